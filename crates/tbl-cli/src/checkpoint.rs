@@ -0,0 +1,205 @@
+use crate::TblCliError;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// record of outputs completed by a batch edit, used to resume interrupted runs
+///
+/// the file starts with the command that wrote it, followed by one line per completed
+/// output holding its size, modification time, the path it was written to, and its planned
+/// path, which differ when outputs are renamed after writing
+pub(crate) struct Checkpoint {
+    path: PathBuf,
+    file: File,
+    completed: HashMap<PathBuf, (u64, u64, PathBuf)>,
+}
+
+impl Checkpoint {
+    /// open checkpoint, keeping previously completed outputs only when resuming
+    pub(crate) fn open(
+        path: &Path,
+        command: &str,
+        resume: bool,
+    ) -> Result<Checkpoint, TblCliError> {
+        let mut completed = HashMap::new();
+        if resume && path.exists() {
+            let contents = std::fs::read_to_string(path)?;
+            let mut lines = contents.lines();
+            if lines.next() != Some(format!("command\t{}", command).as_str()) {
+                return Err(TblCliError::Arg(format!(
+                    "checkpoint {} was written by a different command",
+                    path.to_string_lossy()
+                )));
+            }
+            // a partially written last line is ignored
+            for line in lines {
+                let mut parts = line.splitn(4, '\t');
+                if let (Some(size), Some(modified), Some(written), Some(output)) =
+                    (parts.next(), parts.next(), parts.next(), parts.next())
+                {
+                    if let (Ok(size), Ok(modified)) = (size.parse(), modified.parse()) {
+                        let written = PathBuf::from(written);
+                        completed.insert(PathBuf::from(output), (size, modified, written));
+                    }
+                }
+            }
+        }
+
+        // rewrite checkpoint so that it only lists outputs that are still valid
+        let mut file = File::create(path)?;
+        writeln!(file, "command\t{}", command)?;
+        for (output, (size, modified, written)) in completed.iter() {
+            writeln!(
+                file,
+                "{}\t{}\t{}\t{}",
+                size,
+                modified,
+                written.to_string_lossy(),
+                output.to_string_lossy()
+            )?;
+        }
+        file.sync_data()?;
+
+        Ok(Checkpoint {
+            path: path.to_path_buf(),
+            file,
+            completed,
+        })
+    }
+
+    /// whether planned output was completed by a previous run and has not changed since
+    pub(crate) fn is_complete(&self, output: &Path) -> bool {
+        match self.completed.get(output) {
+            Some((size, modified, written)) => {
+                get_file_stamp(written).is_ok_and(|current| current == (*size, *modified))
+            }
+            None => false,
+        }
+    }
+
+    /// record planned output as completed, having been written to written
+    pub(crate) fn record(&mut self, output: &Path, written: &Path) -> Result<(), TblCliError> {
+        let (size, modified) = get_file_stamp(written)?;
+        writeln!(
+            self.file,
+            "{}\t{}\t{}\t{}",
+            size,
+            modified,
+            written.to_string_lossy(),
+            output.to_string_lossy()
+        )?;
+        self.file.sync_data()?;
+        Ok(())
+    }
+
+    /// remove checkpoint after every output is complete
+    pub(crate) fn finish(self) -> Result<(), TblCliError> {
+        std::fs::remove_file(&self.path)?;
+        Ok(())
+    }
+}
+
+/// size and modification time of file, in seconds since the unix epoch
+fn get_file_stamp(path: &Path) -> Result<(u64, u64), TblCliError> {
+    let metadata = std::fs::metadata(path)?;
+    let modified = metadata
+        .modified()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    Ok((metadata.len(), modified))
+}
+
+/// default checkpoint path in dir, named by a hash of the command so that unrelated edits
+/// writing to the same directory keep separate checkpoints
+pub(crate) fn get_default_checkpoint_path(command: &str, dir: &Path) -> PathBuf {
+    let hash = tbl_core::parquet::fnv1a(tbl_core::parquet::FNV_OFFSET_BASIS, command.as_bytes());
+    dir.join(format!(".tbl_checkpoint_{:016x}", hash))
+}
+
+/// command line identifying a batch edit, excluding flags that do not change its outputs
+pub(crate) fn get_checkpoint_command() -> String {
+    let mut args = std::env::args().skip(1);
+    let mut kept = Vec::new();
+    while let Some(arg) = args.next() {
+        if arg == "--resume" || arg == "--confirm" {
+            continue;
+        } else if arg == "--checkpoint" {
+            args.next();
+        } else if !arg.starts_with("--checkpoint=") {
+            kept.push(arg);
+        }
+    }
+    kept.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_resume_keeps_unchanged_outputs() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("checkpoint");
+        let output = temp_dir.path().join("a.parquet");
+        let renamed = temp_dir.path().join("a_1234.parquet");
+        let unfinished = temp_dir.path().join("b.parquet");
+        std::fs::write(&renamed, "data").unwrap();
+
+        let mut checkpoint = Checkpoint::open(&path, "tbl a b", false).unwrap();
+        checkpoint.record(&output, &renamed).unwrap();
+        drop(checkpoint);
+        // a partially written last line is ignored
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"12\t")
+            .unwrap();
+
+        let checkpoint = Checkpoint::open(&path, "tbl a b", true).unwrap();
+        assert!(checkpoint.is_complete(&output));
+        assert!(!checkpoint.is_complete(&renamed));
+        assert!(!checkpoint.is_complete(&unfinished));
+
+        // outputs are forgotten unless resuming
+        let checkpoint = Checkpoint::open(&path, "tbl a b", false).unwrap();
+        assert!(!checkpoint.is_complete(&output));
+    }
+
+    #[test]
+    fn test_changed_outputs_are_not_complete() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("checkpoint");
+        let output = temp_dir.path().join("a.parquet");
+        std::fs::write(&output, "data").unwrap();
+        let mut checkpoint = Checkpoint::open(&path, "tbl a", false).unwrap();
+        checkpoint.record(&output, &output).unwrap();
+        drop(checkpoint);
+
+        std::fs::write(&output, "rewritten").unwrap();
+        let checkpoint = Checkpoint::open(&path, "tbl a", true).unwrap();
+        assert!(!checkpoint.is_complete(&output));
+    }
+
+    #[test]
+    fn test_resume_rejects_other_commands() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("checkpoint");
+        Checkpoint::open(&path, "tbl a", false).unwrap();
+        assert!(matches!(
+            Checkpoint::open(&path, "tbl b", true),
+            Err(TblCliError::Arg(_))
+        ));
+    }
+
+    #[test]
+    fn test_default_checkpoint_path() {
+        let dir = Path::new("outputs");
+        let path = get_default_checkpoint_path("tbl a", dir);
+        assert_eq!(path.parent(), Some(dir));
+        assert_ne!(path, get_default_checkpoint_path("tbl b", dir));
+    }
+}
@@ -1,12 +1,119 @@
 use super::subcommands::*;
 use crate::TblCliError;
-use clap::{Parser, Subcommand};
-use color_print::cstr;
+use clap::error::{ContextKind, ContextValue, ErrorKind};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use std::path::PathBuf;
 
-pub(crate) async fn run_cli() -> Result<(), TblCliError> {
-    let args = Cli::parse();
+/// parse arguments, choosing colors from --color and --theme first so that help messages
+/// use the same colors as everything else, exits if arguments are invalid
+pub(crate) fn parse_cli() -> Cli {
+    let raw_args: Vec<String> = std::env::args().collect();
+    let globals = parse_global_args(&raw_args);
+    let parsed =
+        crate::styles::init_styles(globals.color.as_deref(), globals.theme).and_then(|()| {
+            let matches = Cli::command()
+                .color(crate::styles::get_color_choice())
+                .try_get_matches_from(&raw_args)
+                .and_then(|matches| Cli::from_arg_matches(&matches));
+            match matches {
+                Ok(args) => Ok(args),
+                // report argument errors as json when requested, help and version are printed as usual
+                Err(e) if e.use_stderr() && globals.json_errors => {
+                    Err(TblCliError::Arg(e.render().to_string().trim().to_string()))
+                }
+                Err(e) => e.exit(),
+            }
+        });
+    match parsed {
+        Ok(args) => args,
+        Err(e) => {
+            let exit_code = e.exit_code();
+            e.report(globals.json_errors, exit_code);
+            std::process::exit(exit_code)
+        }
+    }
+}
+
+/// global options that are needed before arguments are fully parsed
+struct GlobalArgs {
+    pub(crate) color: Option<String>,
+    pub(crate) theme: Option<String>,
+    json_errors: bool,
+}
+
+/// parse global options leniently, ignoring help flags and argument errors, so that they
+/// can style help messages and errors of the full parse
+fn parse_global_args(raw_args: &[String]) -> GlobalArgs {
+    let command = lenient_command(Cli::command(), false);
+    let mut args = raw_args.to_vec();
+    // parsing stops at an unknown argument or invalid value, so these are dropped until the
+    // rest parse, other errors are ignored after the arguments that came before them
+    let matches = loop {
+        let e = match command.clone().try_get_matches_from(&args) {
+            Ok(matches) => break Some(matches),
+            Err(e) => e,
+        };
+        let invalid = match e.kind() {
+            ErrorKind::UnknownArgument => e.get(ContextKind::InvalidArg),
+            ErrorKind::InvalidValue | ErrorKind::ValueValidation => {
+                e.get(ContextKind::InvalidValue)
+            }
+            _ => None,
+        };
+        let position = match invalid {
+            Some(ContextValue::String(invalid)) => {
+                args.iter().skip(1).position(|arg| arg == invalid)
+            }
+            _ => None,
+        };
+        match position {
+            Some(position) => {
+                args.remove(position + 1);
+            }
+            None => {
+                break lenient_command(Cli::command(), true)
+                    .try_get_matches_from(&args)
+                    .ok()
+            }
+        }
+    };
+    let get = |name: &str| {
+        matches
+            .as_ref()
+            .and_then(|matches| matches.get_one::<String>(name).cloned())
+    };
+    GlobalArgs {
+        color: get("color"),
+        theme: get("theme"),
+        json_errors: get("error_format").as_deref() == Some("json"),
+    }
+}
+
+/// command and its subcommands with help flags parsed as plain flags
+fn lenient_command(command: clap::Command, ignore_errors: bool) -> clap::Command {
+    let command = if command.get_arguments().any(|arg| arg.get_id() == "help") {
+        command.mut_arg("help", |arg| arg.action(clap::ArgAction::SetTrue))
+    } else {
+        let help_flag = clap::Arg::new("help")
+            .short('h')
+            .long("help")
+            .action(clap::ArgAction::SetTrue);
+        command.disable_help_flag(true).arg(help_flag)
+    };
+    let names: Vec<String> = command
+        .get_subcommands()
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect();
+    names
+        .iter()
+        .fold(command.ignore_errors(ignore_errors), |command, name| {
+            command.mut_subcommand(name, |subcommand| {
+                lenient_command(subcommand, ignore_errors)
+            })
+        })
+}
 
+pub(crate) async fn run_cli(args: Cli) -> Result<(), TblCliError> {
     if args.version {
         let version = env!("GIT_DESCRIPTION");
         if version.is_empty() {
@@ -21,6 +128,43 @@ pub(crate) async fn run_cli() -> Result<(), TblCliError> {
         Some(Subcommands::Ls(args)) => ls_command(args).await,
         Some(Subcommands::Schema(args)) => schema_command(args).await,
         Some(Subcommands::Schemas(args)) => schemas_command(args).await,
+        Some(Subcommands::Delete(args)) => delete_command(args).await,
+        Some(Subcommands::Update(args)) => update_command(args).await,
+        Some(Subcommands::Flatten(args)) => flatten_command(args).await,
+        Some(Subcommands::ParseJson(args)) => parse_json_command(args).await,
+        Some(Subcommands::Encrypt(args)) => encrypt_command(args).await,
+        Some(Subcommands::Decrypt(args)) => decrypt_command(args).await,
+        Some(Subcommands::ScanPii(args)) => scan_pii_command(args).await,
+        Some(Subcommands::Hist(args)) => hist_command(args).await,
+        Some(Subcommands::Plot(args)) => plot_command(args).await,
+        Some(Subcommands::Corr(args)) => corr_command(args).await,
+        Some(Subcommands::Run(args)) => run_command(args).await,
+        Some(Subcommands::Serve(args)) => serve_command(args).await,
+        Some(Subcommands::Remote(args)) => remote_command(args).await,
+        Some(Subcommands::New(args)) => new_command(args).await,
+        Some(Subcommands::Import(args)) => import_command(args).await,
+        Some(Subcommands::Plan(args)) => plan_command(args).await,
+        Some(Subcommands::Bench(args)) => bench_command(args).await,
+        Some(Subcommands::Gen(args)) => gen_command(args).await,
+        Some(Subcommands::Check(args)) => check_command(args).await,
+        Some(Subcommands::Deadcols(args)) => deadcols_command(args).await,
+        Some(Subcommands::Dupes(args)) => dupes_command(args).await,
+        Some(Subcommands::Cmp(args)) => cmp_command(args).await,
+        Some(Subcommands::NormalizeNames(args)) => normalize_names_command(args).await,
+        Some(Subcommands::Align(args)) => align_command(args).await,
+        Some(Subcommands::Conform(args)) => conform_command(args).await,
+        Some(Subcommands::Grep(args)) => grep_command(args).await,
+        Some(Subcommands::Stats(args)) => stats_command(args).await,
+        Some(Subcommands::Tail(args)) => tail_command(args).await,
+        Some(Subcommands::TouchMetadata(args)) => touch_metadata_command(args).await,
+        Some(Subcommands::Protect(args)) => protect_command(args).await,
+        Some(Subcommands::SizeEstimate(args)) => size_estimate_command(args).await,
+        Some(Subcommands::Cast(args)) => cast_command(args).await,
+        Some(Subcommands::Dtypes(args)) => dtypes_command(args).await,
+        Some(Subcommands::Lineage(args)) => lineage_command(args).await,
+        Some(Subcommands::Reconcile(args)) => reconcile_command(args).await,
+        Some(Subcommands::Mv(args)) => mv_command(args).await,
+        Some(Subcommands::Export(args)) => export_command(args).await,
         _ => data_command(args.data_args).await,
     }
 }
@@ -29,21 +173,21 @@ pub(crate) async fn run_cli() -> Result<(), TblCliError> {
 #[derive(Clone, Parser)]
 #[clap(
     author,
-    about = cstr!("<white><bold>tbl</bold></white> is a tool for reading and editing tabular data files"),
-    override_usage = cstr!("<white><bold>tbl</bold></white> has two modes
-1. Summary mode: <white><bold>tbl [ls | schema] [SUMMARY_OPTIONS]</bold></white>
-2. Data mode:    <white><bold>tbl [DATA_OPTIONS]</bold></white>
+    about = crate::styles::styled_help("`tbl` is a tool for reading and editing tabular data files"),
+    override_usage = crate::styles::styled_help("`tbl` has two modes
+1. Summary mode: `tbl [ls | schema] [SUMMARY_OPTIONS]`
+2. Data mode:    `tbl [DATA_OPTIONS]`
 
-Get help with <white><bold>SUMMARY_OPTIONS</bold></white> using <white><bold>tbl [ls | schema] -h</bold></white>
+Get help with `SUMMARY_OPTIONS` using `tbl [ls | schema] -h`
 
-Data mode is the default mode. <white><bold>DATA_OPTIONS</bold></white> are documented below
+Data mode is the default mode. `DATA_OPTIONS` are documented below
 "),
-    after_help = cstr!("<rgb(0,225,0)><bold>Output Modes:</bold></rgb(0,225,0)>
-<white><bold>1.</bold></white> output results in <white><bold>single file</bold></white>   <white><bold>--output-file</bold></white> /path/to/file.parquet
-<white><bold>2.</bold></white> modify each file <white><bold>inplace</bold></white>        <white><bold>--inplace</bold></white>
-<white><bold>3.</bold></white> copy files into a <white><bold>new dir</bold></white>       <white><bold>--output-dir</bold></white> /path/to/dir
-<white><bold>4.</bold></white> load as <white><bold>interactive</bold></white> python      <white><bold>--df | --lf</bold></white>
-<white><bold>5.</bold></white> output data to <white><bold>stdout</bold></white>           (default behavior)"),
+    after_help = crate::styles::styled_help("## Output Modes:
+`1.` output results in `single file`   `--output-file` /path/to/file.parquet
+`2.` modify each file `inplace`        `--inplace`
+`3.` copy files into a `new dir`       `--output-dir` /path/to/dir
+`4.` load as `interactive` python      `--df | --lf`
+`5.` output data to `stdout`           (default behavior)"),
     long_about = None,
     disable_help_subcommand = true,
     disable_help_flag = true,
@@ -56,21 +200,44 @@ pub(crate) struct Cli {
     #[clap(subcommand)]
     pub(crate) command: Option<Subcommands>,
 
-    ///                   display help message
-    #[clap(short, long, verbatim_doc_comment, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
     help: Option<bool>,
 
-    ///                   display version
+    /// display version
+    #[clap(short = 'V', long, help_heading = "General Options")]
+    version: bool,
+
+    /// when to use colors: auto, always, or never
     #[clap(
-        short = 'V',
         long,
-        verbatim_doc_comment,
+                global = true,
+        value_name = "WHEN",
+        value_parser = ["auto", "always", "never"],
         help_heading = "General Options"
     )]
-    version: bool,
+    color: Option<String>,
+
+    /// color theme: dark, light, or a theme in the config file
+    #[clap(long, global = true, help_heading = "General Options")]
+    theme: Option<String>,
+
+    /// error output format: text or json
+    #[clap(
+        long,
+                global = true,
+        value_name = "FORMAT",
+        value_parser = ["text", "json"],
+        help_heading = "General Options"
+    )]
+    pub(crate) error_format: Option<String>,
+
+    /// exit successfully when inputs match no files
+    #[clap(long, global = true, help_heading = "General Options")]
+    pub(crate) allow_empty: bool,
 
     #[clap(flatten)]
-    data_args: DataArgs,
+    pub(crate) data_args: DataArgs,
 }
 
 /// Define your subcommands as an enum
@@ -87,6 +254,117 @@ pub(crate) enum Subcommands {
     #[command(hide = true)]
     Schemas(SchemasArgs),
 
+    /// Delete rows matching an expression from each file
+    Delete(DeleteArgs),
+
+    /// Update column values, optionally only in rows matching an expression
+    Update(UpdateArgs),
+
+    /// Flatten struct columns into top-level columns
+    Flatten(FlattenArgs),
+
+    /// Parse json string columns into struct or list columns
+    ParseJson(ParseJsonArgs),
+
+    /// Encrypt column values with a key taken from an environment variable
+    Encrypt(EncryptArgs),
+
+    /// Decrypt columns that were encrypted with `tbl encrypt`
+    Decrypt(DecryptArgs),
+
+    /// Scan string columns for values that look like personal data or secrets
+    ScanPii(ScanPiiArgs),
+
+    /// Display histogram and quantiles of a numeric column
+    Hist(HistArgs),
+
+    /// Plot aggregated values of one column against another
+    Plot(PlotArgs),
+
+    /// Display correlation matrix of numeric columns
+    Corr(CorrArgs),
+
+    /// Run a pipeline of transformations described in a yaml, toml, or json file
+    Run(RunArgs),
+
+    /// Serve read-only queries of the selected files over http
+    Serve(ServeArgs),
+
+    /// Read datasets of a remote `tbl serve` over http
+    Remote(RemoteArgs),
+
+    /// Create a new parquet file from a schema
+    New(NewArgs),
+
+    /// Convert csv files to parquet, optionally overriding inferred types with a schema file
+    Import(ImportArgs),
+
+    /// Print the optimized query plan, scanned files, and projected columns without running it
+    Plan(PlanArgs),
+
+    /// Compare file size, write time, and scan time across codecs and row group sizes
+    Bench(BenchArgs),
+
+    /// Generate a parquet file of synthetic data from a schema spec
+    Gen(GenArgs),
+
+    /// Check data against quality rules, exiting with an error if any rule fails
+    Check(CheckArgs),
+
+    /// Find columns that are entirely null or constant, optionally dropping them
+    Deadcols(DeadcolsArgs),
+
+    /// Find files with identical content or overlapping key ranges, optionally removing them
+    Dupes(DupesArgs),
+
+    /// Compare two datasets by key, reporting added, removed, and changed rows
+    Cmp(CmpArgs),
+
+    /// Rename columns across files to a consistent naming convention
+    NormalizeNames(NormalizeNamesArgs),
+
+    /// Rewrite files with drifted schemas to their union schema
+    Align(AlignArgs),
+
+    /// Coerce files to exactly a declared schema
+    Conform(ConformArgs),
+
+    /// Search columns for a literal or regex, reporting files and rows that match
+    Grep(GrepArgs),
+
+    /// Display approximate quantiles of numeric columns
+    Stats(StatsArgs),
+
+    /// Print last rows of dataset, optionally following new rows
+    Tail(TailArgs),
+
+    /// Rewrite footer metadata of files without touching their data pages
+    TouchMetadata(TouchMetadataArgs),
+
+    /// Protect directories from edits, so that outputs must be written elsewhere
+    Protect(ProtectArgs),
+
+    /// Estimate output size of dropping, selecting, or casting columns from column sizes
+    SizeEstimate(SizeEstimateArgs),
+
+    /// Suggest tighter dtypes of columns with their estimated savings, optionally applying them
+    Cast(CastArgs),
+
+    /// Count columns of each dtype, and list files that disagree on the dtype of a column
+    Dtypes(DtypesArgs),
+
+    /// Show the sources and commands that parquet files written with --lineage were derived from
+    Lineage(LineageArgs),
+
+    /// Compare row counts of two datasets per partition key, flagging partitions that diverge
+    Reconcile(ReconcileArgs),
+
+    /// Rename files from a template of values computed from their content
+    Mv(MvArgs),
+
+    /// Export datasets to chunked, optionally gzipped csv files for tools that cannot read parquet
+    Export(ExportArgs),
+
     /// Load, transform, and output file data [default subcommand]
     #[command(hide = true)]
     Data,
@@ -111,6 +389,10 @@ pub(crate) struct LsArgs {
     #[clap(long)]
     pub(crate) absolute: bool,
 
+    /// show rows, bytes, and schema fingerprint of each file
+    #[clap(short, long)]
+    pub(crate) long: bool,
+
     /// display bytes stats
     #[clap(long, hide = true)]
     pub(crate) bytes: bool,
@@ -126,79 +408,1392 @@ pub(crate) struct LsArgs {
     /// sort by number of rows, files, or bytes
     #[clap(long, default_value = "bytes")]
     pub(crate) sort: String,
+
+    /// only list files that contain these column(s)
+    #[clap(long, value_name = "COLUMN", num_args(1..))]
+    pub(crate) has_column: Option<Vec<String>>,
+
+    /// only list files where column has dtype, syntax COLUMN=DTYPE
+    #[clap(long, value_name = "COLUMN=DTYPE", num_args(1..))]
+    pub(crate) dtype: Option<Vec<String>>,
+
+    /// show min and max of key column in each file, from parquet statistics
+    #[clap(long, value_name = "COLUMN")]
+    pub(crate) key_range: Option<String>,
+
+    /// flag files whose key ranges overlap another file, sorting files by key
+    #[clap(long, requires = "key_range")]
+    pub(crate) overlaps: bool,
+}
+
+/// Arguments for the `schema` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct SchemaArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively list all files in tree
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// display bytes stats
+    #[clap(long, hide = true)]
+    pub(crate) bytes: bool,
+
+    /// display stats of each schema group
+    #[clap(long, hide = true)]
+    pub(crate) stats: bool,
+
+    /// columns to print
+    #[clap(long)]
+    pub(crate) columns: Option<Vec<String>>,
+
+    /// number of schemas to print
+    #[clap(long)]
+    pub(crate) n: Option<usize>,
+
+    /// show examples
+    #[clap(long)]
+    pub(crate) examples: bool,
+
+    /// show absolute paths in examples
+    #[clap(long)]
+    pub(crate) absolute: bool,
+
+    /// sort by number of rows, files, or bytes
+    #[clap(long, default_value = "bytes")]
+    pub(crate) sort: String,
+
+    /// show which schemas live in each directory and where each schema first appears
+    #[clap(long)]
+    pub(crate) by_dir: bool,
+
+    /// append current schemas to the schema history file
+    #[clap(long)]
+    pub(crate) record: bool,
+
+    /// report columns that appeared, disappeared, or changed dtype over recorded runs
+    #[clap(long)]
+    pub(crate) changes: bool,
+
+    /// schema history file used by --record and --changes
+    #[clap(long, value_name = "PATH", default_value = ".tbl_schema_history")]
+    pub(crate) history: PathBuf,
+
+    /// browse schema groups interactively, searching them by column name
+    #[clap(short, long)]
+    pub(crate) interactive: bool,
+
+    /// compare schemas of datasets, each path given as PATH or LABEL=PATH
+    #[clap(long)]
+    pub(crate) compare: bool,
 }
 
 /// Arguments for the `schema` subcommand
 #[derive(Clone, Parser)]
-pub(crate) struct SchemaArgs {
+pub(crate) struct SchemasArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively list all files in tree
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// sort by number of rows, files, or bytes
+    #[clap(long, default_value = "bytes")]
+    pub(crate) sort: String,
+}
+
+/// Arguments for the `delete` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct DeleteArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// delete rows matching this SQL expression, e.g. "status == 'failed'"
+    #[clap(long = "where", value_name = "EXPR")]
+    pub(crate) where_: String,
+
+    #[clap(flatten)]
+    pub(crate) output_args: EditOutputArgs,
+}
+
+/// Arguments for the `update` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct UpdateArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// set column to SQL expression, syntax "COLUMN = EXPR", such as "value = wei_to_eth(value)"
+    #[clap(long, value_name = "COLUMN = EXPR", num_args(1..), required = true)]
+    pub(crate) set: Vec<String>,
+
+    /// only update rows matching this SQL expression
+    #[clap(long = "where", value_name = "EXPR")]
+    pub(crate) where_: Option<String>,
+
+    #[clap(flatten)]
+    pub(crate) output_args: EditOutputArgs,
+}
+
+/// Arguments for the `flatten` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct FlattenArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// struct columns to flatten [default: all struct columns]
+    #[clap(short, long, num_args(1..))]
+    pub(crate) columns: Option<Vec<String>>,
+
+    /// separator between struct and field names
+    #[clap(long, default_value = "_")]
+    pub(crate) separator: String,
+
+    /// also flatten structs nested inside structs
+    #[clap(short, long)]
+    pub(crate) recursive: bool,
+
+    #[clap(flatten)]
+    pub(crate) output_args: EditOutputArgs,
+}
+
+/// Arguments for the `parse-json` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct ParseJsonArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// json string column(s) to parse
+    #[clap(short, long, num_args(1..), required = true)]
+    pub(crate) column: Vec<String>,
+
+    /// number of rows per file used to infer the schema [default: all rows]
+    #[clap(long, value_name = "N")]
+    pub(crate) infer_rows: Option<usize>,
+
+    #[clap(flatten)]
+    pub(crate) output_args: EditOutputArgs,
+}
+
+/// Arguments for the `encrypt` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct EncryptArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// string or binary column(s) to encrypt
+    #[clap(long, value_name = "COLUMN", num_args(1..), required = true)]
+    pub(crate) column: Vec<String>,
+
+    /// environment variable holding a 32 byte hex key
+    #[clap(long, value_name = "VAR", required = true)]
+    pub(crate) key_env: String,
+
+    /// key id stored in file metadata [default: name of key env var]
+    #[clap(long)]
+    pub(crate) key_id: Option<String>,
+
+    #[clap(flatten)]
+    pub(crate) output_args: EditOutputArgs,
+}
+
+/// Arguments for the `decrypt` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct DecryptArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// column(s) to decrypt [default: all encrypted columns]
+    #[clap(long, value_name = "COLUMN", num_args(1..))]
+    pub(crate) column: Option<Vec<String>>,
+
+    /// environment variable holding a 32 byte hex key
+    #[clap(long, value_name = "VAR", required = true)]
+    pub(crate) key_env: String,
+
+    #[clap(flatten)]
+    pub(crate) output_args: EditOutputArgs,
+}
+
+/// Arguments for the `scan-pii` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct ScanPiiArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively scan all files in tree
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// number of rows to sample from each file, spread evenly across its rows
+    #[clap(long, default_value_t = 1000)]
+    pub(crate) sample: u32,
+
+    /// minimum fraction of sampled values that must match to flag a column
+    #[clap(long, default_value_t = 0.05)]
+    pub(crate) min_fraction: f64,
+}
+
+/// Arguments for the `hist` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct HistArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// numeric column to summarize
+    #[clap()]
+    pub(crate) column: String,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// number of histogram bins
+    #[clap(long, default_value_t = 20)]
+    pub(crate) bins: usize,
+
+    /// width of the longest histogram bar
+    #[clap(long, default_value_t = 50)]
+    pub(crate) width: usize,
+}
+
+/// Arguments for the `plot` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct PlotArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// column to use for x axis, e.g. a timestamp
+    #[clap(short, long, required = true)]
+    pub(crate) x: String,
+
+    /// numeric column to use for y axis
+    #[clap(short, long, required = true)]
+    pub(crate) y: String,
+
+    /// aggregation of y values within each x bucket: mean, sum, min, max, median, or count
+    #[clap(long, default_value = "mean")]
+    pub(crate) agg: String,
+
+    /// chart width in characters [default: terminal width]
+    #[clap(long)]
+    pub(crate) width: Option<usize>,
+
+    /// chart height in characters
+    #[clap(long, default_value_t = 20)]
+    pub(crate) height: usize,
+}
+
+/// Arguments for the `corr` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct CorrArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// columns to correlate [default: all numeric columns], arguments that are existing paths are used as inputs
+    #[clap(value_name = "COLUMNS_OR_PATHS")]
+    pub(crate) inputs: Vec<String>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// correlation method, pearson or spearman
+    #[clap(long, default_value = "pearson")]
+    pub(crate) method: String,
+}
+
+/// Arguments for the `run` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct RunArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// pipeline file to run
+    #[clap()]
+    pub(crate) pipeline: PathBuf,
+
+    /// skip printing a summary
+    #[clap(long)]
+    pub(crate) no_summary: bool,
+
+    /// confirm that files should be edited
+    #[clap(long)]
+    pub(crate) confirm: bool,
+
+    /// dry run without editing files
+    #[clap(long)]
+    pub(crate) dry: bool,
+}
+
+/// Arguments for the `serve` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct ServeArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// protocol to serve, currently only http
+    #[clap(value_name = "PROTOCOL")]
+    pub(crate) protocol: String,
+
+    /// input path(s) to serve
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively serve all files in tree
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// address to listen on
+    #[clap(long, default_value = "127.0.0.1")]
+    pub(crate) host: String,
+
+    /// port to listen on
+    #[clap(long, default_value_t = 8080)]
+    pub(crate) port: u16,
+
+    /// maximum number of rows returned by a query
+    #[clap(long, default_value_t = 10_000)]
+    pub(crate) max_rows: usize,
+}
+
+/// Arguments for the `remote` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct RemoteArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// what to read from the server
+    #[clap(value_name = "ACTION", value_parser = ["ls", "schema", "count", "cat"])]
+    pub(crate) action: String,
+
+    /// address of server running `tbl serve http`, syntax HOST:PORT
+    #[clap(value_name = "HOST:PORT")]
+    pub(crate) address: String,
+
+    /// filter rows by SQL expression
+    #[clap(long = "where", value_name = "EXPR")]
+    pub(crate) where_: Option<String>,
+
+    /// filter rows by values, syntax COLUMN=VALUE
+    #[clap(long, num_args(1..))]
+    pub(crate) filter: Option<Vec<String>>,
+
+    /// columns to print with cat
+    #[clap(short, long, num_args(1..))]
+    pub(crate) columns: Option<Vec<String>>,
+
+    /// columns to sort rows by with cat
+    #[clap(long, num_args(1..))]
+    pub(crate) sort: Option<Vec<String>>,
+
+    /// number of rows to print with cat, limited by the max rows of the server
+    #[clap(short, long, default_value_t = 20)]
+    pub(crate) n: usize,
+
+    /// skip the first rows with cat
+    #[clap(long)]
+    pub(crate) offset: Option<usize>,
+
+    /// print rows as csv
+    #[clap(long)]
+    pub(crate) csv: bool,
+}
+
+/// Arguments for the `new` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct NewArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// path of file to create
+    #[clap()]
+    pub(crate) path: PathBuf,
+
+    /// columns of file, syntax NAME:TYPE
+    #[clap(value_name = "NAME:TYPE")]
+    pub(crate) columns: Vec<String>,
+
+    /// take columns from a schema file, either NAME:TYPE lines or an existing parquet file
+    #[clap(long, value_name = "PATH")]
+    pub(crate) schema_file: Option<PathBuf>,
+
+    /// number of rows to fill with default values
+    #[clap(long, default_value_t = 0)]
+    pub(crate) rows: usize,
+
+    /// overwrite file if it already exists
+    #[clap(long)]
+    pub(crate) overwrite: bool,
+}
+
+/// Arguments for the `import` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct ImportArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// csv file(s) to convert
+    #[clap(required = true)]
+    pub(crate) paths: Vec<PathBuf>,
+
+    /// yaml, toml, or json file with column dtypes, null markers, date formats, and decimal separators
+    #[clap(long, value_name = "PATH")]
+    pub(crate) schema: Option<PathBuf>,
+
+    /// only report values that do not match the schema, without writing files
+    #[clap(long)]
+    pub(crate) validate: bool,
+
+    /// write parquet files into this directory instead of next to each csv file
+    #[clap(long, value_name = "DIR_PATH")]
+    pub(crate) output_dir: Option<PathBuf>,
+
+    /// write .xlsx files instead of parquet files
+    #[clap(long)]
+    pub(crate) xlsx: bool,
+
+    /// name of sheet of .xlsx output files [default: output file name]
+    #[clap(long, value_name = "NAME", requires = "xlsx")]
+    pub(crate) sheet_name: Option<String>,
+
+    /// fail instead of writing .xlsx output files with more rows than this
+    #[clap(
+        long,
+        value_name = "ROWS",
+        requires = "xlsx",
+        default_value_t = tbl_core::xlsx::XLSX_MAX_ROWS - 1
+    )]
+    pub(crate) max_xlsx_rows: usize,
+
+    /// overwrite output files that already exist
+    #[clap(long)]
+    pub(crate) overwrite: bool,
+
+    /// skip csv files whose output files are newer than the csv files
+    #[clap(long)]
+    pub(crate) incremental: bool,
+
+    /// write rows with invalid values to this directory and convert the remaining rows
+    #[clap(long, value_name = "DIR_PATH")]
+    pub(crate) quarantine_dir: Option<PathBuf>,
+
+    /// number of files to process at once
+    #[clap(long, default_value_t = 1)]
+    pub(crate) jobs: usize,
+}
+
+/// Arguments for the `plan` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct PlanArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// data mode arguments of the query to plan
+    #[clap(flatten)]
+    pub(crate) data_args: Box<DataArgs>,
+}
+
+/// Arguments for the `bench` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct BenchArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to sample
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// compression codecs to compare
+    #[clap(
+        long,
+        value_delimiter = ',',
+        default_value = "uncompressed,snappy,lz4,zstd,gzip"
+    )]
+    pub(crate) codecs: Vec<String>,
+
+    /// row group sizes to compare, in rows
+    #[clap(long, value_delimiter = ',', default_value = "10000,100000,1000000")]
+    pub(crate) row_group_sizes: Vec<usize>,
+
+    /// number of rows to sample from the inputs
+    #[clap(long, default_value_t = 1_000_000)]
+    pub(crate) sample_rows: u32,
+}
+
+/// Arguments for the `gen` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct GenArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// yaml, toml, or json file with columns, distributions, and null rates
+    #[clap()]
+    pub(crate) spec: PathBuf,
+
+    /// path of file to create
+    #[clap()]
+    pub(crate) path: PathBuf,
+
+    /// number of rows to generate, overriding the spec
+    #[clap(long)]
+    pub(crate) rows: Option<usize>,
+
+    /// random seed, overriding the spec
+    #[clap(long)]
+    pub(crate) seed: Option<u64>,
+
+    /// overwrite file if it already exists
+    #[clap(long)]
+    pub(crate) overwrite: bool,
+}
+
+/// Arguments for the `check` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct CheckArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// yaml, toml, or json file of rules
+    #[clap()]
+    pub(crate) rules: PathBuf,
+
+    /// input path(s) to check
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// write rows that violate rules to this directory, with a reason column
+    #[clap(long, value_name = "DIR_PATH")]
+    pub(crate) quarantine_dir: Option<PathBuf>,
+}
+
+/// Arguments for the `deadcols` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct DeadcolsArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// scan every column instead of trusting parquet statistics
+    #[clap(long)]
+    pub(crate) scan: bool,
+
+    /// drop dead columns, writing files according to output options
+    #[clap(long)]
+    pub(crate) drop: bool,
+
+    #[clap(flatten)]
+    pub(crate) output_args: EditOutputArgs,
+}
+
+/// Arguments for the `dupes` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct DupesArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// also find files whose min to max range of this column overlaps
+    #[clap(long, value_name = "COLUMN")]
+    pub(crate) key: Option<String>,
+
+    /// delete files with identical content, keeping the first of each group, after comparing
+    /// their rows
+    #[clap(long)]
+    pub(crate) delete: bool,
+
+    /// merge the rows of files with overlapping key ranges into the first of each group, sorted
+    /// by key
+    #[clap(long, requires = "key")]
+    pub(crate) merge: bool,
+
+    /// confirm deleting or merging without prompting
+    #[clap(long)]
+    pub(crate) confirm: bool,
+}
+
+/// Arguments for the `cmp` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct CmpArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// first dataset, a file or directory
+    #[clap()]
+    pub(crate) a: PathBuf,
+
+    /// second dataset, a file or directory
+    #[clap()]
+    pub(crate) b: PathBuf,
+
+    /// column(s) identifying each row, values should be unique within each dataset
+    #[clap(long, num_args(1..), required = true)]
+    pub(crate) key: Vec<String>,
+
+    /// recursively use all files in tree of each directory
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// number of example rows to show for each kind of difference
+    #[clap(short, long, default_value_t = 10)]
+    pub(crate) n: u32,
+
+    /// write every differing row to only_in_a, only_in_b, and changed files in this directory
+    #[clap(long, value_name = "DIR_PATH")]
+    pub(crate) output_dir: Option<PathBuf>,
+}
+
+/// Arguments for the `normalize-names` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct NormalizeNamesArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// naming convention of columns
+    #[clap(long, default_value = "snake", value_parser = ["snake", "lower", "upper", "camel"])]
+    pub(crate) case: String,
+
+    /// replacement for spaces and special characters
+    #[clap(long, default_value = "_", value_name = "STR")]
+    pub(crate) replace_invalid: String,
+
+    #[clap(flatten)]
+    pub(crate) output_args: EditOutputArgs,
+}
+
+/// Arguments for the `align` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct AlignArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// allow promotions that lose precision, such as i64 with f64 to f64, or u64 with i64 to i64
+    #[clap(long)]
+    pub(crate) allow_lossy: bool,
+
+    #[clap(flatten)]
+    pub(crate) output_args: EditOutputArgs,
+}
+
+/// Arguments for the `conform` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct ConformArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// yaml, toml, or json file listing columns in order, or a parquet file to copy
+    #[clap(long, value_name = "PATH")]
+    pub(crate) schema: PathBuf,
+
+    /// what to do with columns not in the schema
+    #[clap(long, default_value = "drop", value_parser = ["drop", "error"])]
+    pub(crate) on_extra: String,
+
+    /// what to do with schema columns missing from a file
+    #[clap(long, default_value = "null", value_parser = ["null", "error"])]
+    pub(crate) on_missing: String,
+
+    /// what to do with columns of another dtype: cast only when lossless, always cast, or error,
+    /// casts of 64-bit integers to floats and of u64 to i64 are not lossless
+    #[clap(long, default_value = "promote", value_parser = ["promote", "cast", "error"])]
+    pub(crate) on_mismatch: String,
+
+    #[clap(flatten)]
+    pub(crate) output_args: EditOutputArgs,
+}
+
+/// Arguments for the `grep` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct GrepArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// value to search for, binary columns are searched by their hex encoding
+    #[clap()]
+    pub(crate) pattern: String,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// columns or re:/glob:/dtype: selectors to search [default: all columns]
+    #[clap(long, num_args(1..))]
+    pub(crate) columns: Option<Vec<String>>,
+
+    /// interpret pattern as a regex instead of a literal
+    #[clap(short = 'e', long)]
+    pub(crate) regex: bool,
+
+    /// match case insensitively
+    #[clap(short, long)]
+    pub(crate) ignore_case: bool,
+
+    /// print matching rows of each file
+    #[clap(long)]
+    pub(crate) rows: bool,
+
+    /// number of matching rows to print per file
+    #[clap(short, long, default_value_t = 10)]
+    pub(crate) n: u32,
+}
+
+/// Arguments for the `stats` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct StatsArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// columns or re:/glob:/dtype: selectors to summarize [default: all numeric columns]
+    #[clap(long, num_args(1..))]
+    pub(crate) columns: Option<Vec<String>>,
+
+    /// sketch accuracy parameter, larger values use more memory for smaller rank error
+    #[clap(short, long, default_value_t = 400)]
+    pub(crate) k: usize,
+
+    /// read every file instead of reusing profiles of unchanged files from the cache
+    #[clap(long)]
+    pub(crate) no_cache: bool,
+}
+
+/// Arguments for the `tail` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct TailArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// number of last rows to print
+    #[clap(short, long, default_value_t = 10)]
+    pub(crate) n: usize,
+
+    /// skip this many rows at the end before printing the last n rows
+    #[clap(long)]
+    pub(crate) offset: Option<usize>,
+
+    /// print rows in range instead of the last n rows, syntax START..END or START..=END, either
+    /// side optional, with -N.. for the last N rows
+    #[clap(
+        long,
+        value_name = "RANGE",
+        allow_hyphen_values = true,
+        conflicts_with_all = ["n", "offset"]
+    )]
+    pub(crate) rows: Option<String>,
+
+    /// keep printing rows of new files and of files that grow
+    #[clap(short, long)]
+    pub(crate) follow: bool,
+
+    /// seconds between checks for new rows
+    #[clap(long, default_value_t = 1.0, requires = "follow")]
+    pub(crate) interval: f64,
+
+    /// print rows as csv
+    #[clap(long)]
+    pub(crate) csv: bool,
+
+    /// print rows as json lines
+    #[clap(long, conflicts_with = "csv")]
+    pub(crate) jsonl: bool,
+}
+
+/// Arguments for the `touch-metadata` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct TouchMetadataArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// set created_by string of footers
+    #[clap(long, value_name = "STRING")]
+    pub(crate) created_by: Option<String>,
+
+    /// set key value metadata, syntax KEY=VALUE
+    #[clap(long, num_args(1..), value_name = "KEY=VALUE")]
+    pub(crate) set: Vec<String>,
+
+    /// remove key value metadata
+    #[clap(long, num_args(1..), value_name = "KEY")]
+    pub(crate) remove: Vec<String>,
+
+    /// set key value metadata of this key to the current time
+    #[clap(long, value_name = "KEY")]
+    pub(crate) timestamp: Option<String>,
+
+    /// dry run without editing files
+    #[clap(long)]
+    pub(crate) dry: bool,
+
+    /// confirm editing without prompting
+    #[clap(long)]
+    pub(crate) confirm: bool,
+}
+
+/// Arguments for the `protect` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct ProtectArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// directories to protect
+    #[clap(required = true)]
+    pub(crate) dirs: Vec<PathBuf>,
+
+    /// remove protection of directories
+    #[clap(long)]
+    pub(crate) remove: bool,
+}
+
+/// Arguments for the `size-estimate` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct SizeEstimateArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// columns that would be dropped
+    #[clap(long, num_args(1..), value_name = "COLUMN")]
+    pub(crate) drop: Option<Vec<String>>,
+
+    /// columns that would be selected
+    #[clap(short = 'c', long, num_args(1..), value_name = "COLUMN", conflicts_with = "drop")]
+    pub(crate) select: Option<Vec<String>>,
+
+    /// columns that would be cast, syntax COLUMN=TYPE
+    #[clap(long, num_args(1..), value_name = "COLUMN=TYPE")]
+    pub(crate) cast: Option<Vec<String>>,
+}
+
+/// Arguments for the `cast` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct CastArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// suggest tighter dtypes of columns
+    #[clap(long)]
+    pub(crate) suggest: bool,
+
+    /// apply suggested casts, writing files according to output options
+    #[clap(long, requires = "suggest")]
+    pub(crate) apply: bool,
+
+    /// number of rows sampled to detect categorical and date strings
+    #[clap(long, default_value_t = 100_000, value_name = "N_ROWS")]
+    pub(crate) sample: u32,
+
+    #[clap(flatten)]
+    pub(crate) output_args: EditOutputArgs,
+}
+
+/// Arguments for the `dtypes` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct DtypesArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+}
+
+/// Arguments for the `lineage` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct LineageArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// what to do with lineage
+    #[clap(value_name = "ACTION", value_parser = ["show"])]
+    pub(crate) action: String,
+
+    /// parquet files to show lineage of
+    #[clap(required = true)]
+    pub(crate) paths: Vec<PathBuf>,
+}
+
+/// Arguments for the `reconcile` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct ReconcileArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// first dataset, a file or directory
+    #[clap(long, value_name = "PATH", required = true)]
+    pub(crate) left: PathBuf,
+
+    /// second dataset, a file or directory
+    #[clap(long, value_name = "PATH", required = true)]
+    pub(crate) right: PathBuf,
+
+    /// column(s) defining each partition, including hive partition keys
+    #[clap(long, num_args(1..), required = true)]
+    pub(crate) key: Vec<String>,
+
+    /// recursively use all files in tree of each directory
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// largest allowed difference of row counts, relative to the larger count, e.g. 0.01
+    #[clap(long, default_value_t = 0.0)]
+    pub(crate) threshold: f64,
+
+    /// show every partition, not only partitions that diverge
+    #[clap(long)]
+    pub(crate) all: bool,
+}
+
+/// Arguments for the `mv` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct MvArgs {
+    /// display help message
+    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
+    help: Option<bool>,
+
+    /// input path(s) to use
+    #[clap()]
+    pub(crate) paths: Option<Vec<PathBuf>>,
+
+    /// recursively use all files in tree as inputs
+    #[clap(short, long)]
+    pub(crate) tree: bool,
+
+    /// template of new file names, using {stem}, {ext}, {part}, {row_count}, partition keys,
+    /// and {min(COLUMN)}, {max(COLUMN)}, {first(COLUMN)}, or {last(COLUMN)}, e.g.
+    /// "{min(ts):%Y-%m-%d}_{row_count}.parquet"
+    #[clap(long, required = true)]
+    pub(crate) template: String,
+
+    /// dry run without renaming files
+    #[clap(long)]
+    pub(crate) dry: bool,
+
+    /// confirm renaming without prompting
+    #[clap(long)]
+    pub(crate) confirm: bool,
+}
+
+/// Arguments for the `export` subcommand
+#[derive(Clone, Parser)]
+pub(crate) struct ExportArgs {
     /// display help message
     #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
     help: Option<bool>,
 
+    /// format to export to
+    #[clap(value_name = "FORMAT", value_parser = ["csv"])]
+    pub(crate) format: String,
+
     /// input path(s) to use
     #[clap()]
     pub(crate) paths: Option<Vec<PathBuf>>,
 
-    /// recursively list all files in tree
+    /// recursively use all files in tree as inputs
     #[clap(short, long)]
     pub(crate) tree: bool,
 
-    /// display bytes stats
-    #[clap(long, hide = true)]
-    pub(crate) bytes: bool,
+    /// directory to write exported files into
+    #[clap(long, value_name = "DIR_PATH", required = true)]
+    pub(crate) output_dir: PathBuf,
 
-    /// display stats of each schema group
-    #[clap(long, hide = true)]
-    pub(crate) stats: bool,
+    /// prefix of exported file names, followed by the number of each file
+    #[clap(long, value_name = "PRE-FIX", default_value = "part")]
+    pub(crate) output_prefix: String,
 
-    /// columns to print
-    #[clap(long)]
-    pub(crate) columns: Option<Vec<String>>,
+    /// start a new file after this many rows, e.g. 5_000_000
+    #[clap(long, value_name = "N")]
+    pub(crate) max_rows_per_file: Option<String>,
 
-    /// number of schemas to print
+    /// compress exported files with gzip
     #[clap(long)]
-    pub(crate) n: Option<usize>,
+    pub(crate) gzip: bool,
 
-    /// show examples
+    /// overwrite exported files that already exist
     #[clap(long)]
-    pub(crate) examples: bool,
+    pub(crate) overwrite: bool,
 
-    /// show absolute paths in examples
-    #[clap(long)]
-    pub(crate) absolute: bool,
+    /// character separating fields
+    #[clap(long, default_value_t = ',', help_heading = "CSV Options")]
+    pub(crate) delimiter: char,
 
-    /// sort by number of rows, files, or bytes
-    #[clap(long, default_value = "bytes")]
-    pub(crate) sort: String,
+    /// character quoting fields
+    #[clap(long, default_value_t = '"', help_heading = "CSV Options")]
+    pub(crate) quote_char: char,
+
+    /// which fields to quote
+    #[clap(
+        long,
+        default_value = "necessary",
+        value_parser = ["necessary", "always", "non-numeric", "never"],
+        help_heading = "CSV Options"
+    )]
+    pub(crate) quote_style: String,
+
+    /// text written for null values
+    #[clap(long, default_value = "", help_heading = "CSV Options")]
+    pub(crate) null_value: String,
+
+    /// omit the header row from each file
+    #[clap(long, help_heading = "CSV Options")]
+    pub(crate) no_header: bool,
 }
 
-/// Arguments for the `schema` subcommand
+/// Output arguments shared by subcommands that edit files
 #[derive(Clone, Parser)]
-pub(crate) struct SchemasArgs {
-    /// display help message
-    #[clap(short, long, action = clap::ArgAction::HelpLong, help_heading = "General Options")]
-    help: Option<bool>,
+pub(crate) struct EditOutputArgs {
+    /// modify files in place
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) inplace: bool,
 
-    /// input path(s) to use
-    #[clap()]
-    pub(crate) paths: Option<Vec<PathBuf>>,
+    /// rewrite all files into this output directory
+    #[clap(long, help_heading = "Output Options", value_name = "DIR_PATH")]
+    pub(crate) output_dir: Option<PathBuf>,
 
-    /// recursively list all files in tree
-    #[clap(short, long)]
-    pub(crate) tree: bool,
+    /// prefix to add to output filenames
+    #[clap(long, help_heading = "Output Options", value_name = "PRE-FIX")]
+    pub(crate) output_prefix: Option<String>,
 
-    /// sort by number of rows, files, or bytes
-    #[clap(long, default_value = "bytes")]
-    pub(crate) sort: String,
+    /// postfix to add to output filenames
+    #[clap(long, help_heading = "Output Options", value_name = "POST-FIX")]
+    pub(crate) output_postfix: Option<String>,
+
+    /// name outputs by template, variables are {stem}, {ext}, {date}, {part:04}, {hash:8},
+    /// and partition keys of input directories
+    #[clap(
+        long,
+        help_heading = "Output Options",
+        value_name = "TEMPLATE",
+        requires = "output_dir",
+        conflicts_with_all = ["output_prefix", "output_postfix"]
+    )]
+    pub(crate) output_template: Option<String>,
+
+    /// with --tree, write outputs directly into --output-dir instead of their relative subpaths
+    #[clap(long, help_heading = "Output Options", requires = "output_dir")]
+    pub(crate) flat_output: bool,
+
+    /// skip printing a summary
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) no_summary: bool,
+
+    /// print summary of completed edits as a json line
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) summary_json: bool,
+
+    /// confirm that files should be edited
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) confirm: bool,
+
+    /// dry run without editing files
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) dry: bool,
+
+    /// resume an interrupted edit, skipping outputs recorded in its checkpoint
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) resume: bool,
+
+    /// checkpoint file recording completed outputs [default: .tbl_checkpoint_HASH in the output
+    /// directory, or next to the first input of inplace edits, where HASH identifies the command]
+    #[clap(long, help_heading = "Output Options", value_name = "FILE_PATH")]
+    pub(crate) checkpoint: Option<PathBuf>,
+
+    /// skip inputs whose outputs are newer than the inputs or record their content hash
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) incremental: bool,
+
+    /// keep processing remaining files when a file fails, then list the failures
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) keep_going: bool,
+
+    /// stop at the first file that fails [default]
+    #[clap(long, help_heading = "Output Options", conflicts_with = "keep_going")]
+    pub(crate) fail_fast: bool,
+
+    /// number of files to process at once
+    #[clap(long, help_heading = "Output Options", default_value_t = 1)]
+    pub(crate) jobs: usize,
+
+    /// read and decode the next files while writing the current file, holding up to N decoded
+    /// files in memory, cannot be combined with edits that write while reading
+    #[clap(
+        long,
+        help_heading = "Output Options",
+        value_name = "N",
+        conflicts_with = "jobs"
+    )]
+    pub(crate) prefetch: Option<usize>,
+
+    /// adjust parquet outputs to load cleanly into an older engine: spark, athena, and legacy
+    /// (hive, impala) write int96 timestamps, fixed-length decimals, and version 1 pages;
+    /// bigquery writes microsecond timestamps; every profile sanitizes column names
+    #[clap(
+        long,
+        help_heading = "Output Options",
+        value_name = "PROFILE",
+        value_parser = ["spark", "athena", "bigquery", "legacy"]
+    )]
+    pub(crate) compat: Option<String>,
+
+    /// write a .tblindex sidecar of row count and schema next to each output
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) write_index: bool,
+
+    /// column whose min and max are recorded in index sidecars
+    #[clap(
+        long,
+        help_heading = "Output Options",
+        value_name = "COLUMN",
+        requires = "write_index"
+    )]
+    pub(crate) index_key: Option<String>,
+
+    /// record source fingerprints, command line, and tbl version in parquet outputs, see
+    /// `tbl lineage show`
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) lineage: bool,
+}
+
+impl EditOutputArgs {
+    /// convert into data mode args that edit the given inputs
+    pub(crate) fn into_data_args(self, paths: Option<Vec<PathBuf>>, tree: bool) -> DataArgs {
+        DataArgs {
+            paths,
+            tree,
+            inplace: self.inplace,
+            output_dir: self.output_dir,
+            output_prefix: self.output_prefix,
+            output_postfix: self.output_postfix,
+            output_template: self.output_template,
+            flat_output: self.flat_output,
+            no_summary: self.no_summary,
+            summary_json: self.summary_json,
+            confirm: self.confirm,
+            dry: self.dry,
+            resume: self.resume,
+            checkpoint: self.checkpoint,
+            incremental: self.incremental,
+            keep_going: self.keep_going,
+            fail_fast: self.fail_fast,
+            jobs: self.jobs,
+            prefetch: self.prefetch,
+            compat: self.compat,
+            write_index: self.write_index,
+            index_key: self.index_key,
+            lineage: self.lineage,
+            ..Default::default()
+        }
+    }
 }
 
 /// Arguments for the `data` subcommand
-#[derive(Clone, Parser)]
+#[derive(Clone, Parser, PartialEq)]
 pub(crate) struct DataArgs {
     //
     // // input options
     //
-    ///                      input path(s) to use
+    ///     input path(s) to use
     #[clap(
         verbatim_doc_comment,
         help_heading = "Input Options",
@@ -206,10 +1801,28 @@ pub(crate) struct DataArgs {
     )]
     pub(crate) paths: Option<Vec<PathBuf>>,
 
-    ///                  recursively use all files in tree as inputs
-    #[clap(short, long, verbatim_doc_comment, help_heading = "Input Options")]
+    /// recursively use all files in tree as inputs
+    #[clap(short, long, help_heading = "Input Options")]
     pub(crate) tree: bool,
 
+    /// add columns extracted from file paths, syntax NAME:REGEX
+    #[clap(
+        long,
+        help = crate::styles::styled_help("add columns extracted from file paths, syntax `NAME:REGEX`"),
+        help_heading = "Input Options",
+        value_name = "NAME:REGEX",
+        num_args(1..)
+    )]
+    pub(crate) path_column: Option<Vec<String>>,
+
+    /// add a row_index column numbering rows across all input files
+    #[clap(long, help_heading = "Input Options")]
+    pub(crate) with_row_index: bool,
+
+    /// add a source column with the file path of each row
+    #[clap(long, help_heading = "Input Options")]
+    pub(crate) with_source: bool,
+
     //
     // // transform options
     //
@@ -217,22 +1830,22 @@ pub(crate) struct DataArgs {
     #[clap(
         short,
         long,
-        help = cstr!("select only these columns [alias <white><bold>--select</bold></white>]"),
+        help = crate::styles::styled_help("select only these columns or `re:`/`glob:`/`dtype:` selectors, pick interactively if none given [alias `--select`]"),
         help_heading = "Transform Options",
         aliases = ["select"],
         value_name="COLUMN",
-        num_args(1..)
+        num_args(0..)
     )]
     pub(crate) columns: Option<Vec<String>>,
 
-    /// drop column(s)
-    #[clap(short, long, help_heading = "Transform Options", num_args(1..))]
+    /// drop column(s) or re:/glob:/dtype: selectors, pick interactively if none given
+    #[clap(short, long, help_heading = "Transform Options", num_args(0..))]
     pub(crate) drop: Option<Vec<String>>,
 
-    /// add new columns, syntax NAME:TYPE [alias --with]
+    /// add new columns, syntax NAME:TYPE, NAME:TYPE:default=VALUE, or NAME:TYPE:expr=EXPR [alias --with]
     #[clap(
         long,
-        help = cstr!("insert columns, syntax <white><bold>NAME:TYPE</bold></white> [alias <white><bold>--with</bold></white>]"),
+        help = crate::styles::styled_help("insert columns, syntax `NAME:TYPE`, `NAME:TYPE:default=VALUE`, or `NAME:TYPE:expr=EXPR` [alias `--with`]"),
         help_heading = "Transform Options",
         value_name="NEW_COL",
         num_args(1..),
@@ -244,7 +1857,7 @@ pub(crate) struct DataArgs {
     #[clap(
         short,
         long,
-        help = cstr!("rename column(s), syntax <white><bold>OLD_NAME=NEW_NAME</bold></white>"),
+        help = crate::styles::styled_help("rename column(s), syntax `OLD_NAME=NEW_NAME`"),
         help_heading = "Transform Options",
         num_args(1..)
     )]
@@ -253,16 +1866,24 @@ pub(crate) struct DataArgs {
     /// change column type(s), syntax COLUMN=TYPE
     #[clap(
         long,
-        help = cstr!("change column type(s), syntax <white><bold>COLUMN=TYPE</bold></white>"),
+        help = crate::styles::styled_help("change column type(s), syntax `COLUMN=TYPE`"),
         help_heading = "Transform Options",
         num_args(1..)
     )]
     pub(crate) cast: Option<Vec<String>>,
 
+    /// timezone of datetime casts, naive timestamps are localized and others converted
+    #[clap(long, help_heading = "Transform Options", value_name = "TZ")]
+    pub(crate) timezone: Option<String>,
+
+    /// format used to parse string columns cast to datetime, such as %Y-%m-%d %H:%M:%S
+    #[clap(long, help_heading = "Transform Options", value_name = "FORMAT")]
+    pub(crate) time_format: Option<String>,
+
     /// set column values
     #[clap(
         long,
-        help = cstr!("set column value, syntax <white><bold>COLUMN=VALUE</bold></white>"),
+        help = crate::styles::styled_help("set column value, syntax `COLUMN=VALUE`"),
         help_heading = "Transform Options",
         value_name="COLUMN",
         num_args(1..)
@@ -278,31 +1899,59 @@ pub(crate) struct DataArgs {
     )]
     pub(crate) nullify: Option<Vec<String>>,
 
+    /// keep only rows matching a SQL expression
+    #[clap(
+        long = "where",
+        help = crate::styles::styled_help("keep only rows matching SQL expression, e.g. `\"fee > 100\"`"),
+        help_heading = "Transform Options",
+        value_name = "EXPR"
+    )]
+    pub(crate) where_: Option<String>,
+
     /// filter rows by values, syntax COLUMN=VALUE
     #[clap(
         short,
         long,
-        help = cstr!("filter rows by values, syntax <white><bold>COLUMN=VALUE</bold></white>
-    or <white><bold>COLUMN.is_null</bold></white> or <white><bold>COLUMN.is_not_null</bold></white>"),
+        help = crate::styles::styled_help("filter rows by values, syntax `COLUMN=VALUE`
+    or `COLUMN.is_null` or `COLUMN.is_not_null`"),
         help_heading = "Transform Options",
         num_args(1..)
     )]
     pub(crate) filter: Option<Vec<String>>,
 
-    /// sort rows, syntax COLUMN[:desc]
+    /// sort rows stably by keys in order, syntax COLUMN[:asc|:desc]
     #[clap(
         short,
         long,
-        help = cstr!("sort rows, syntax <white><bold>COLUMN[:desc]</bold></white>"),
+        help = crate::styles::styled_help("sort rows stably by keys in order, syntax `COLUMN[:asc|:desc]`, ties keep input order"),
         help_heading = "Transform Options",
         num_args(1..)
     )]
     pub(crate) sort: Option<Vec<String>>,
 
+    /// place nulls of sort columns first or last [default: first]
+    #[clap(
+        long,
+        help_heading = "Transform Options",
+        value_name = "POSITION",
+        value_parser = ["first", "last"],
+        requires = "sort"
+    )]
+    pub(crate) nulls: Option<String>,
+
+    /// sort all inputs with bounded memory by spilling sorted runs to disk
+    #[clap(
+        long,
+        help = crate::styles::styled_help("sort datasets larger than memory, requires `--sort` and `--output-file` and no other transforms"),
+        help_heading = "Transform Options",
+        requires_all = ["sort", "output_file"]
+    )]
+    pub(crate) global: bool,
+
     /// keep only the first n rows [alias --limit]
     #[clap(
         long,
-        help = cstr!("keep only the first n rows [alias <white><bold>--limit</bold></white>]"),
+        help = crate::styles::styled_help("keep only the first n rows [alias `--limit`]"),
         help_heading = "Transform Options",
         aliases = ["limit"]
     )]
@@ -316,10 +1965,117 @@ pub(crate) struct DataArgs {
     #[clap(long, help_heading = "Transform Options")]
     pub(crate) offset: Option<usize>,
 
+    /// keep only rows in range, syntax START..END or START..=END, either side optional, with
+    /// -N.. for the last N rows
+    #[clap(
+        long,
+        help_heading = "Transform Options",
+        value_name = "RANGE",
+        allow_hyphen_values = true
+    )]
+    pub(crate) rows: Option<String>,
+
+    /// take rows from each input file in turn, so previews show rows of every file
+    #[clap(long, help_heading = "Transform Options")]
+    pub(crate) interleave: bool,
+
+    /// combine input files in order of their min value of this column instead of by path
+    #[clap(long, help_heading = "Transform Options", value_name = "COLUMN")]
+    pub(crate) order_by_file_key: Option<String>,
+
+    /// after reading, report files, row groups, and bytes skipped by --where and --filter
+    #[clap(long, help_heading = "Transform Options")]
+    pub(crate) pruning_stats: bool,
+
+    /// update columns to SQL expressions, syntax COLUMN = EXPR, set by the update subcommand
+    #[clap(skip)]
+    pub(crate) update: Option<Vec<String>>,
+
+    /// only apply update to rows matching this SQL expression, set by the update subcommand
+    #[clap(skip)]
+    pub(crate) update_where: Option<String>,
+
+    /// flatten these struct columns, or all struct columns if none given, set by the flatten
+    /// subcommand
+    #[clap(skip)]
+    pub(crate) flatten: Option<Vec<String>>,
+
+    /// separator between struct and field names of flattened columns, set by the flatten
+    /// subcommand
+    #[clap(skip)]
+    pub(crate) flatten_separator: Option<String>,
+
+    /// also flatten structs nested inside structs, set by the flatten subcommand
+    #[clap(skip)]
+    pub(crate) flatten_recursive: bool,
+
+    /// encrypt these columns, set by the encrypt subcommand
+    #[clap(skip)]
+    pub(crate) encrypt: Option<Vec<String>>,
+
+    /// decrypt these columns, or all encrypted columns if empty, set by the decrypt subcommand
+    #[clap(skip)]
+    pub(crate) decrypt: Option<Vec<String>>,
+
+    /// environment variable holding the encryption key, set by the encrypt and decrypt
+    /// subcommands
+    #[clap(skip)]
+    pub(crate) key_env: Option<String>,
+
+    /// key id to store in metadata of encrypted files, set by the encrypt subcommand
+    #[clap(skip)]
+    pub(crate) key_id: Option<String>,
+
+    /// json string columns to parse, with the type inferred across all inputs
+    #[clap(skip)]
+    pub(crate) parse_json: Vec<(String, polars::prelude::DataType)>,
+
+    /// normalize every column name to a case, replacing invalid characters
+    #[clap(skip)]
+    pub(crate) normalize_names: Option<(tbl_core::names::NameCase, String)>,
+
+    /// schema to align every file to, filling missing columns with nulls
+    #[clap(skip)]
+    pub(crate) align_schema: Option<polars::prelude::SchemaRef>,
+
+    /// transformations applied in order after all other transformations
+    #[clap(skip)]
+    pub(crate) pipeline_steps: Vec<DataArgs>,
+
     /// compute value counts of column(s)
     #[clap(long, help_heading = "Transform Options", value_name = "COLUMN")]
     pub(crate) value_counts: Option<String>,
 
+    /// keep only the k most frequent values of value counts
+    #[clap(
+        long,
+        help = crate::styles::styled_help("keep only the k most frequent values of `--value-counts`"),
+        help_heading = "Transform Options",
+        value_name = "K",
+        requires = "value_counts"
+    )]
+    pub(crate) top_k: Option<usize>,
+
+    /// sum this column as the count of each value instead of counting rows
+    #[clap(
+        long,
+        help = crate::styles::styled_help("sum this column as the count of each value of `--value-counts` instead of counting rows"),
+        help_heading = "Transform Options",
+        value_name = "COLUMN",
+        requires = "value_counts",
+        conflicts_with = "approx"
+    )]
+    pub(crate) weight_column: Option<String>,
+
+    /// approximate top k value counts using bounded memory
+    #[clap(
+        long,
+        help = crate::styles::styled_help("approximate `--top-k` using bounded memory, reports error bounds"),
+        help_heading = "Transform Options",
+        requires = "top_k"
+    )]
+    pub(crate) approx: bool,
+
     //
     // // output options
     //
@@ -327,15 +2083,51 @@ pub(crate) struct DataArgs {
     #[clap(long, help_heading = "Output Options")]
     pub(crate) no_summary: bool,
 
+    /// print summary of completed edits as a json line
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) summary_json: bool,
+
     /// number of rows to print in stdout, all for all
     #[clap(
         short,
         long,
-        help = cstr!("number of rows to print in stdout, <white><bold>all</bold></white> for all"),
+        help = crate::styles::styled_help("number of rows to print in stdout, `all` for all"),
         help_heading = "Output Options"
     )]
     pub(crate) n: Option<String>,
 
+    /// print rows of each input file separately instead of concatenating files
+    #[clap(
+        long,
+        help_heading = "Output Options",
+        conflicts_with_all = ["inplace", "output_file", "output_dir", "partition", "df", "lf"]
+    )]
+    pub(crate) per_file: bool,
+
+    /// truncate string values in printed tables to this many characters
+    #[clap(long, help_heading = "Output Options", value_name = "CHARS")]
+    pub(crate) max_col_width: Option<usize>,
+
+    /// print full string values and all columns without truncating
+    #[clap(
+        long,
+        help_heading = "Output Options",
+        conflicts_with = "max_col_width"
+    )]
+    pub(crate) no_truncate: bool,
+
+    /// maximum number of columns to print
+    #[clap(long, help_heading = "Output Options", value_name = "N")]
+    pub(crate) max_columns: Option<usize>,
+
+    /// print table wider than terminal, e.g. for scrolling with less -S
+    #[clap(
+        long,
+        help = crate::styles::styled_help("print table wider than terminal, for scrolling with `less -S`"),
+        help_heading = "Output Options"
+    )]
+    pub(crate) wide: bool,
+
     /// output data as csv
     #[clap(long, help_heading = "Output Options")]
     pub(crate) csv: bool,
@@ -348,10 +2140,24 @@ pub(crate) struct DataArgs {
     #[clap(long, help_heading = "Output Options")]
     pub(crate) jsonl: bool,
 
+    /// output data as a markdown, html, or latex table
+    #[clap(
+        long,
+        help_heading = "Output Options",
+        value_name = "FORMAT",
+        value_parser = ["markdown", "html", "latex"],
+        conflicts_with_all = ["csv", "json", "jsonl"]
+    )]
+    pub(crate) format: Option<String>,
+
     /// encode binary columns as hex for output
     #[clap(long, help_heading = "Output Options")]
     pub(crate) hex: bool,
 
+    /// display binary columns as hex, base64, 0x, or len [default: 0x for fixed-size columns]
+    #[clap(long, help_heading = "Output Options", value_name = "MODE")]
+    pub(crate) binary: Option<String>,
+
     /// modify files in place
     #[clap(long, help_heading = "Output Options")]
     pub(crate) inplace: bool,
@@ -372,6 +2178,49 @@ pub(crate) struct DataArgs {
     #[clap(long, help_heading = "Output Options", value_name = "POST-FIX")]
     pub(crate) output_postfix: Option<String>,
 
+    /// name outputs by template, variables are {stem}, {ext}, {date}, {part:04}, {hash:8},
+    /// and partition keys of input directories
+    #[clap(
+        long,
+        help_heading = "Output Options",
+        value_name = "TEMPLATE",
+        requires = "output_dir",
+        conflicts_with_all = ["output_prefix", "output_postfix"]
+    )]
+    pub(crate) output_template: Option<String>,
+
+    /// with --tree, write outputs directly into --output-dir instead of their relative subpaths
+    #[clap(long, help_heading = "Output Options", requires = "output_dir")]
+    pub(crate) flat_output: bool,
+
+    /// store decimals as fixed-length byte arrays at their own precision for legacy engines
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) legacy_decimals: bool,
+
+    /// adjust parquet outputs to load cleanly into an older engine: spark, athena, and legacy
+    /// (hive, impala) write int96 timestamps, fixed-length decimals, and version 1 pages;
+    /// bigquery writes microsecond timestamps; every profile sanitizes column names
+    #[clap(
+        long,
+        help_heading = "Output Options",
+        value_name = "PROFILE",
+        value_parser = ["spark", "athena", "bigquery", "legacy"]
+    )]
+    pub(crate) compat: Option<String>,
+
+    /// name of sheet of .xlsx output file [default: output file name]
+    #[clap(long, help_heading = "Output Options", value_name = "NAME")]
+    pub(crate) sheet_name: Option<String>,
+
+    /// fail instead of writing .xlsx output files with more rows than this
+    #[clap(
+        long,
+        help_heading = "Output Options",
+        value_name = "ROWS",
+        default_value_t = tbl_core::xlsx::XLSX_MAX_ROWS - 1
+    )]
+    pub(crate) max_xlsx_rows: usize,
+
     /// partition output over this column
     #[clap(
         long,
@@ -419,7 +2268,7 @@ pub(crate) struct DataArgs {
     /// python executable to use with --df or --lf
     #[clap(
         long,
-        help = cstr!("python executable to use with <white><bold>--df</bold></white> or <white><bold>--lf</bold></white>"),
+        help = crate::styles::styled_help("python executable to use with `--df` or `--lf`"),
         help_heading = "Output Options"
     )]
     pub(crate) executable: Option<String>,
@@ -431,4 +2280,108 @@ pub(crate) struct DataArgs {
     /// dry run without editing files
     #[clap(long, help_heading = "Output Options")]
     pub(crate) dry: bool,
+
+    /// resume an interrupted edit, skipping outputs recorded in its checkpoint
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) resume: bool,
+
+    /// checkpoint file recording completed outputs [default: .tbl_checkpoint_HASH in the output
+    /// directory, or next to the first input of inplace edits, where HASH identifies the command]
+    #[clap(long, help_heading = "Output Options", value_name = "FILE_PATH")]
+    pub(crate) checkpoint: Option<PathBuf>,
+
+    /// skip inputs whose outputs are newer than the inputs or record their content hash
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) incremental: bool,
+
+    /// keep processing remaining files when a file fails, then list the failures
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) keep_going: bool,
+
+    /// stop at the first file that fails [default]
+    #[clap(long, help_heading = "Output Options", conflicts_with = "keep_going")]
+    pub(crate) fail_fast: bool,
+
+    /// number of files to process at once
+    #[clap(long, help_heading = "Output Options", default_value_t = 1)]
+    pub(crate) jobs: usize,
+
+    /// read and decode the next files while writing the current file, holding up to N decoded
+    /// files in memory, cannot be combined with edits that write while reading
+    #[clap(
+        long,
+        help_heading = "Output Options",
+        value_name = "N",
+        conflicts_with = "jobs"
+    )]
+    pub(crate) prefetch: Option<usize>,
+
+    /// write a .tblindex sidecar of row count and schema next to each output
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) write_index: bool,
+
+    /// column whose min and max are recorded in index sidecars
+    #[clap(
+        long,
+        help_heading = "Output Options",
+        value_name = "COLUMN",
+        requires = "write_index"
+    )]
+    pub(crate) index_key: Option<String>,
+
+    /// record source fingerprints, command line, and tbl version in parquet outputs, see
+    /// `tbl lineage show`, not available with --global, encrypt, or decrypt
+    #[clap(long, help_heading = "Output Options", conflicts_with = "global")]
+    pub(crate) lineage: bool,
+
+    /// write rows that cannot be cast to this directory, with a reason column, instead of failing
+    #[clap(long, help_heading = "Output Options", value_name = "DIR_PATH")]
+    pub(crate) quarantine_dir: Option<PathBuf>,
+
+    /// copy compressed column chunks of parquet inputs without decoding them, even when row
+    /// groups are small, dropping bloom filters and key value metadata of all but the first input
+    /// [default: only when row groups are large and nothing would be dropped]
+    #[clap(long, help_heading = "Output Options")]
+    pub(crate) splice: bool,
+}
+
+/// defaults are those of the command line, so that args built in code match parsed args
+impl Default for DataArgs {
+    fn default() -> Self {
+        DataArgs::try_parse_from(["tbl"]).expect("data args parse without arguments")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_args_default_matches_cli_defaults() {
+        let args = DataArgs::default();
+        assert_eq!(args.max_xlsx_rows, tbl_core::xlsx::XLSX_MAX_ROWS - 1);
+        assert_eq!(args.jobs, 1);
+    }
+
+    #[test]
+    fn test_parse_global_args() {
+        let parse = |args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            parse_global_args(&args)
+        };
+
+        let globals = parse(&["tbl", "ls", "--theme=light", "--color", "never", "-h"]);
+        assert_eq!(globals.theme.as_deref(), Some("light"));
+        assert_eq!(globals.color.as_deref(), Some("never"));
+
+        // values and arguments after -- are not options
+        let globals = parse(&["tbl", "ls", "--has-column", "--color", "--", "--theme=x"]);
+        assert_eq!(globals.color, None);
+        assert_eq!(globals.theme, None);
+
+        // options after invalid arguments are still read
+        let globals = parse(&["tbl", "ls", "--bogus", "--n", "abc", "--error-format=json"]);
+        assert!(globals.json_errors);
+        assert!(!parse(&["tbl", "ls", "--error-format", "text"]).json_errors);
+    }
 }
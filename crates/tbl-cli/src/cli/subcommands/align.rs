@@ -0,0 +1,63 @@
+use crate::{AlignArgs, TblCliError};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub(crate) async fn align_command(args: AlignArgs) -> Result<(), TblCliError> {
+    let input_paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if input_paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+    let schemas = tbl_core::parquet::get_parquet_schemas(&input_paths).await?;
+    let union = Arc::new(tbl_core::schemas::union_schemas(
+        &schemas,
+        args.allow_lossy,
+    )?);
+
+    // describe how each union column appears across files
+    let rows: Vec<[String; 4]> = union
+        .iter()
+        .map(|(name, dtype)| {
+            let mut source_dtypes: Vec<String> = Vec::new();
+            let mut n_files = 0;
+            for schema in schemas.iter() {
+                if let Some(source) = schema.get(name) {
+                    n_files += 1;
+                    let source = source.to_string();
+                    if !source_dtypes.contains(&source) {
+                        source_dtypes.push(source);
+                    }
+                }
+            }
+            [
+                name.to_string(),
+                dtype.to_string(),
+                format!("{} / {}", n_files, schemas.len()),
+                source_dtypes.join(", "),
+            ]
+        })
+        .collect();
+    tbl_core::formats::print_header("Union schema");
+    super::print_table(&["column", "dtype", "files", "source dtypes"], &rows)?;
+    println!();
+
+    let misaligned: Vec<PathBuf> = input_paths
+        .iter()
+        .zip(schemas.iter())
+        .filter(|(_, schema)| schema.as_ref() != union.as_ref())
+        .map(|(path, _)| path.clone())
+        .collect();
+    if misaligned.is_empty() {
+        println!("[all {} files already match]", input_paths.len());
+        return Ok(());
+    }
+
+    // in place edits only need to rewrite files that differ from the union schema
+    let paths = if args.output_args.inplace {
+        Some(misaligned)
+    } else {
+        args.paths
+    };
+    let mut data_args = args.output_args.into_data_args(paths, args.tree);
+    data_args.align_schema = Some(union);
+    super::data_command(data_args).await
+}
@@ -0,0 +1,103 @@
+use crate::styles::FontStyle;
+use crate::{BenchArgs, TblCliError};
+use polars::prelude::*;
+use std::time::{Duration, Instant};
+
+pub(crate) async fn bench_command(args: BenchArgs) -> Result<(), TblCliError> {
+    let input_paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if input_paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+    let codecs = args
+        .codecs
+        .iter()
+        .map(|codec| Ok((codec.clone(), parse_compression(codec)?)))
+        .collect::<Result<Vec<_>, TblCliError>>()?;
+
+    // sample rows from the start of the dataset
+    let mut df = tbl_core::parquet::create_lazyframe(&input_paths)?
+        .slice(0, args.sample_rows)
+        .collect()?;
+    println!(
+        "benchmarking {} sampled rows from {} files",
+        tbl_core::formats::format_with_commas(df.height() as u64),
+        input_paths.len()
+    );
+    println!();
+
+    // rewrite sample under each combination
+    let bench_path = std::env::temp_dir().join(format!("tbl_bench_{}.parquet", std::process::id()));
+    let mut rows: Vec<[String; 5]> = Vec::new();
+    for (name, compression) in codecs.iter() {
+        for row_group_size in args.row_group_sizes.iter() {
+            let start = Instant::now();
+            let file = std::fs::File::create(&bench_path)?;
+            ParquetWriter::new(file)
+                .with_compression(*compression)
+                .with_row_group_size(Some(*row_group_size))
+                .finish(&mut df)?;
+            let write_time = start.elapsed();
+            let n_bytes = std::fs::metadata(&bench_path)?.len();
+
+            let start = Instant::now();
+            LazyFrame::scan_parquet(&bench_path, ScanArgsParquet::default())?.collect()?;
+            let scan_time = start.elapsed();
+
+            rows.push([
+                name.clone(),
+                tbl_core::formats::format_with_commas(*row_group_size as u64),
+                tbl_core::formats::format_bytes(n_bytes),
+                format_duration(write_time),
+                format_duration(scan_time),
+            ]);
+        }
+    }
+    let _ = std::fs::remove_file(&bench_path);
+
+    // build table
+    let labels = ["codec", "row group size", "size", "write time", "scan time"];
+    let mut table = toolstr::Table::new();
+    for (i, label) in labels.iter().enumerate() {
+        table.add_column(
+            *label,
+            rows.iter().map(|row| row[i].clone()).collect::<Vec<_>>(),
+        )?;
+    }
+
+    // create format
+    let mut format = toolstr::TableFormat {
+        label_font_style: Some("".colorize_title().into()),
+        border_font_style: Some("".colorize_comment().into()),
+        ..Default::default()
+    };
+    for (i, label) in labels.iter().enumerate() {
+        let mut column = toolstr::ColumnFormatShorthand::default().name(*label);
+        column.font_style = if i < 2 {
+            Some("".colorize_variable().into())
+        } else {
+            Some("".colorize_constant().into())
+        };
+        format.add_column(column);
+    }
+    format.print(table)?;
+    Ok(())
+}
+
+fn parse_compression(codec: &str) -> Result<ParquetCompression, TblCliError> {
+    match codec {
+        "uncompressed" => Ok(ParquetCompression::Uncompressed),
+        "snappy" => Ok(ParquetCompression::Snappy),
+        "lz4" => Ok(ParquetCompression::Lz4Raw),
+        "zstd" => Ok(ParquetCompression::Zstd(None)),
+        "gzip" => Ok(ParquetCompression::Gzip(None)),
+        "brotli" => Ok(ParquetCompression::Brotli(None)),
+        _ => Err(TblCliError::Arg(format!(
+            "unknown codec {}, use uncompressed, snappy, lz4, zstd, gzip, or brotli",
+            codec
+        ))),
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.1} ms", duration.as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,340 @@
+use crate::{CastArgs, TblCliError};
+use polars::prelude::*;
+use std::collections::HashMap;
+use tbl_core::formats::{format_bytes, print_bullet, print_header};
+
+/// tighter dtype suggested for a column
+struct CastSuggestion {
+    column: String,
+    dtype: DataType,
+    target: String,
+    reason: String,
+    n_bytes_saved: Option<u64>,
+}
+
+pub(crate) async fn cast_command(args: CastArgs) -> Result<(), TblCliError> {
+    if !args.suggest {
+        return Err(TblCliError::Arg(
+            "use --suggest to find tighter dtypes, or cast columns with `tbl --cast COLUMN=TYPE`"
+                .to_string(),
+        ));
+    }
+    if args.apply && !args.output_args.inplace && args.output_args.output_dir.is_none() {
+        return Err(TblCliError::Arg(
+            "--apply needs --inplace or --output-dir".to_string(),
+        ));
+    }
+    let input_paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if input_paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+
+    let lf = tbl_core::parquet::create_lazyframe(&input_paths)?;
+    let schema = lf.clone().schema()?;
+    let column_sizes = get_column_sizes(&input_paths).await?;
+    let mut suggestions = suggest_numeric_casts(&lf, &schema, &column_sizes)?;
+    let sample = lf.clone().limit(args.sample).collect()?;
+    suggestions.extend(suggest_string_casts(&sample, &column_sizes)?);
+
+    if suggestions.is_empty() {
+        println!(
+            "[no tighter dtypes found among {} columns of {} files]",
+            schema.len(),
+            input_paths.len()
+        );
+        return Ok(());
+    }
+    let rows: Vec<[String; 5]> = suggestions
+        .iter()
+        .map(|suggestion| {
+            [
+                suggestion.column.clone(),
+                suggestion.dtype.to_string(),
+                suggestion.target.clone(),
+                suggestion.reason.clone(),
+                suggestion
+                    .n_bytes_saved
+                    .map(format_bytes)
+                    .unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+    super::print_table(
+        &["column", "dtype", "suggested", "because", "savings"],
+        &rows,
+    )?;
+    let n_bytes_saved: u64 = suggestions.iter().filter_map(|s| s.n_bytes_saved).sum();
+    let n_bytes_total: u64 = column_sizes.values().sum();
+    println!();
+    print_header("Cast suggestions");
+    print_bullet("n_suggestions", suggestions.len().to_string());
+    print_bullet(
+        "estimated savings",
+        format!(
+            "{} of {}",
+            format_bytes(n_bytes_saved),
+            format_bytes(n_bytes_total)
+        ),
+    );
+
+    if args.apply {
+        let cast: Vec<String> = suggestions
+            .iter()
+            .map(|suggestion| format!("{}={}", suggestion.column, suggestion.target))
+            .collect();
+        check_casts(&lf, &schema, &cast)?;
+        println!();
+        let mut data_args = args.output_args.into_data_args(args.paths, args.tree);
+        data_args.cast = Some(cast);
+        super::data_command(data_args).await?;
+    }
+    Ok(())
+}
+
+/// check that casts keep every value of every row, since string casts are suggested from a sample
+fn check_casts(lf: &LazyFrame, schema: &Schema, cast: &[String]) -> Result<(), TblCliError> {
+    let failures = crate::transform::get_cast_failures(schema, cast, None)?;
+    if failures.is_empty() {
+        return Ok(());
+    }
+    let exprs: Vec<Expr> = failures
+        .iter()
+        .enumerate()
+        .map(|(i, (failed, _))| failed.clone().sum().alias(&format!("failed_{}", i)))
+        .collect();
+    let counts = lf.clone().select(exprs).with_streaming(true).collect()?;
+    let mut messages = Vec::new();
+    for (i, (_, description)) in failures.iter().enumerate() {
+        let n_failed = counts
+            .column(&format!("failed_{}", i))?
+            .cast(&DataType::UInt64)?
+            .u64()?
+            .get(0)
+            .unwrap_or(0);
+        if n_failed > 0 {
+            messages.push(format!("{} ({} values)", description, n_failed));
+        }
+    }
+    if messages.is_empty() {
+        Ok(())
+    } else {
+        Err(TblCliError::Error(format!(
+            "not applying casts, values outside the sample would become null: {}",
+            messages.join(", ")
+        )))
+    }
+}
+
+/// compressed bytes of each column across files
+async fn get_column_sizes(
+    paths: &[std::path::PathBuf],
+) -> Result<HashMap<String, u64>, TblCliError> {
+    let mut sizes = HashMap::new();
+    for path in paths.iter() {
+        if path.extension().and_then(|ext| ext.to_str()) != Some("parquet") {
+            continue;
+        }
+        for (name, compressed, _) in tbl_core::parquet::get_parquet_top_level_column_sizes(path)
+            .await?
+            .into_iter()
+        {
+            *sizes.entry(name).or_insert(0) += compressed;
+        }
+    }
+    Ok(sizes)
+}
+
+/// narrower integer types that hold every value, and floats that survive a round trip to f32
+///
+/// these casts must be lossless, so every row is checked rather than a sample
+fn suggest_numeric_casts(
+    lf: &LazyFrame,
+    schema: &Schema,
+    column_sizes: &HashMap<String, u64>,
+) -> Result<Vec<CastSuggestion>, TblCliError> {
+    let mut exprs = Vec::new();
+    for (name, dtype) in schema.iter() {
+        if dtype.is_integer() {
+            exprs.push(
+                col(name)
+                    .min()
+                    .cast(DataType::Float64)
+                    .alias(&format!("{}_min", name)),
+            );
+            exprs.push(
+                col(name)
+                    .max()
+                    .cast(DataType::Float64)
+                    .alias(&format!("{}_max", name)),
+            );
+        } else if dtype == &DataType::Float64 {
+            let round_trip = col(name).cast(DataType::Float32).cast(DataType::Float64);
+            exprs.push(
+                (round_trip
+                    .eq(col(name))
+                    .or(col(name).is_null())
+                    .or(col(name).is_nan()))
+                .all(true)
+                .alias(&format!("{}_f32", name)),
+            );
+        }
+    }
+    if exprs.is_empty() {
+        return Ok(Vec::new());
+    }
+    let stats = lf.clone().select(exprs).collect()?;
+
+    let mut suggestions = Vec::new();
+    for (name, dtype) in schema.iter() {
+        let target = if dtype.is_integer() {
+            let min = stats.column(&format!("{}_min", name))?.f64()?.get(0);
+            let max = stats.column(&format!("{}_max", name))?.f64()?.get(0);
+            match (min, max) {
+                (Some(min), Some(max)) => smallest_integer_dtype(min, max)
+                    .filter(|target| integer_width(target) < integer_width(dtype))
+                    .map(|target| (target, format!("values in {} to {}", min, max))),
+                _ => None,
+            }
+        } else if dtype == &DataType::Float64 {
+            match stats.column(&format!("{}_f32", name))?.bool()?.get(0) {
+                Some(true) => Some((DataType::Float32, "lossless as f32".to_string())),
+                _ => None,
+            }
+        } else {
+            None
+        };
+        if let Some((target, reason)) = target {
+            let n_bytes_saved = column_sizes.get(name.as_str()).map(|n_bytes| {
+                let ratio = super::cast_size_ratio(dtype, &target);
+                n_bytes - (*n_bytes as f64 * ratio) as u64
+            });
+            suggestions.push(CastSuggestion {
+                column: name.to_string(),
+                dtype: dtype.clone(),
+                target: dtype_name(&target),
+                reason,
+                n_bytes_saved,
+            });
+        }
+    }
+    Ok(suggestions)
+}
+
+/// strings that parse as dates or datetimes, and strings with few distinct values
+fn suggest_string_casts(
+    sample: &DataFrame,
+    column_sizes: &HashMap<String, u64>,
+) -> Result<Vec<CastSuggestion>, TblCliError> {
+    let mut suggestions = Vec::new();
+    for series in sample.get_columns() {
+        if series.dtype() != &DataType::String || series.len() == series.null_count() {
+            continue;
+        }
+        let name = series.name();
+        let n_values = series.len() - series.null_count();
+        let options = StrptimeOptions {
+            strict: false,
+            ..Default::default()
+        };
+        // formats are inferred from the first value, failing queries when none fits
+        let parses = |expr: Expr| {
+            sample
+                .clone()
+                .lazy()
+                .select([expr.alias("parsed")])
+                .collect()
+                .ok()
+                .and_then(|parsed| parsed.column("parsed").ok().map(|p| p.null_count()))
+                .is_some_and(|n_null| series.len() - n_null == n_values)
+        };
+        let is_date = parses(col(name).str().to_date(options.clone()));
+        let is_datetime = !is_date
+            && parses(col(name).str().to_datetime(
+                Some(TimeUnit::Microseconds),
+                None,
+                options,
+                lit("raise"),
+            ));
+        let mean_len = series
+            .str()?
+            .into_iter()
+            .flatten()
+            .map(|value| value.len() as f64)
+            .sum::<f64>()
+            / n_values as f64;
+        let stored_as = |width: f64| {
+            let n_bytes = *column_sizes.get(name)?;
+            let ratio = (width / mean_len.max(1.0)).min(1.0);
+            Some(n_bytes - (n_bytes as f64 * ratio) as u64)
+        };
+        let (target, reason, n_bytes_saved) = if is_date {
+            ("date", "strings are dates".to_string(), stored_as(4.0))
+        } else if is_datetime {
+            (
+                "datetime",
+                "strings are timestamps".to_string(),
+                stored_as(8.0),
+            )
+        } else {
+            let n_unique = series.n_unique()?;
+            if n_unique > 1000 || n_unique * 2 > n_values {
+                continue;
+            }
+            let reason = format!("{} distinct values in sample", n_unique);
+            ("categorical", reason, None)
+        };
+        suggestions.push(CastSuggestion {
+            column: name.to_string(),
+            dtype: DataType::String,
+            target: target.to_string(),
+            reason,
+            n_bytes_saved,
+        });
+    }
+    Ok(suggestions)
+}
+
+/// smallest integer dtype that holds every value from min to max
+fn smallest_integer_dtype(min: f64, max: f64) -> Option<DataType> {
+    let candidates = if min >= 0.0 {
+        [
+            (DataType::UInt8, u8::MAX as f64),
+            (DataType::UInt16, u16::MAX as f64),
+            (DataType::UInt32, u32::MAX as f64),
+        ]
+    } else {
+        [
+            (DataType::Int8, i8::MAX as f64),
+            (DataType::Int16, i16::MAX as f64),
+            (DataType::Int32, i32::MAX as f64),
+        ]
+    };
+    candidates
+        .into_iter()
+        .find(|(_, limit)| max <= *limit && -min <= *limit + 1.0)
+        .map(|(dtype, _)| dtype)
+}
+
+fn integer_width(dtype: &DataType) -> usize {
+    match dtype {
+        DataType::Int8 | DataType::UInt8 => 1,
+        DataType::Int16 | DataType::UInt16 => 2,
+        DataType::Int32 | DataType::UInt32 => 4,
+        _ => 8,
+    }
+}
+
+/// name of dtype as accepted by --cast
+fn dtype_name(dtype: &DataType) -> String {
+    match dtype {
+        DataType::Int8 => "i8",
+        DataType::Int16 => "i16",
+        DataType::Int32 => "i32",
+        DataType::UInt8 => "u8",
+        DataType::UInt16 => "u16",
+        DataType::UInt32 => "u32",
+        DataType::Float32 => "f32",
+        _ => "str",
+    }
+    .to_string()
+}
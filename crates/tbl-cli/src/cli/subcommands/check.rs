@@ -0,0 +1,363 @@
+use crate::styles::FontStyle;
+use crate::{CheckArgs, TblCliError};
+use polars::prelude::*;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// data quality rules evaluated across all inputs
+///
+/// ```yaml
+/// rules:
+///   - not_null: [id, fee]
+///   - unique: [id]
+///   - regex: {column: email, pattern: '^[^@]+@[^@]+$'}
+///   - range: {column: fee, min: 0, max: 10000}
+///   - referential: {column: user_id, path: users.parquet, ref_column: id}
+///   - freshness: {column: timestamp, max_age: 24h}
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Rules {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+enum Rule {
+    /// each column has no nulls
+    NotNull(Vec<String>),
+    /// the columns together form a key that appears once across all inputs
+    Unique(Vec<String>),
+    /// string values match a regex
+    Regex { column: String, pattern: String },
+    /// values are within inclusive bounds
+    Range {
+        column: String,
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    /// values exist in a column of another file
+    Referential {
+        column: String,
+        path: PathBuf,
+        ref_column: Option<String>,
+    },
+    /// latest timestamp is no older than an age such as 30m, 24h, or 7d
+    Freshness { column: String, max_age: String },
+}
+
+/// rule compiled into an expression over rows of the inputs
+enum Check {
+    /// expression that is true for rows violating the rule
+    Rows { name: String, violations: Expr },
+    /// expression of row timestamps in milliseconds, whose maximum must be recent
+    Freshness {
+        name: String,
+        timestamps: Expr,
+        max_age_ms: i64,
+    },
+}
+
+impl Check {
+    fn name(&self) -> &str {
+        match self {
+            Check::Rows { name, .. } | Check::Freshness { name, .. } => name,
+        }
+    }
+}
+
+const SOURCE_COLUMN: &str = "_source";
+
+pub(crate) async fn check_command(args: CheckArgs) -> Result<(), TblCliError> {
+    let rules: Rules = super::load_spec_file(&args.rules, "rules")?;
+    let input_paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if input_paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+
+    // compile rules, resolving referenced files against the directory of the rules file
+    let base_dir = args.rules.parent().unwrap_or(Path::new("")).to_path_buf();
+    let mut checks = Vec::new();
+    for rule in rules.rules.into_iter() {
+        checks.extend(compile_rule(rule, &base_dir)?);
+    }
+
+    // evaluate every check for each file in a single pass
+    let source = tbl_core::parquet::PathColumn::full_path(SOURCE_COLUMN);
+    let lf = tbl_core::parquet::create_lazyframe_with_path_columns(&input_paths, &[source])?;
+    let mut row_exprs = vec![col(SOURCE_COLUMN)];
+    let mut agg_exprs = vec![len().alias("_rows")];
+    for (i, check) in checks.iter().enumerate() {
+        let alias = format!("_check_{}", i);
+        match check {
+            Check::Rows { violations, .. } => {
+                row_exprs.push(violations.clone().alias(&alias));
+                agg_exprs.push(col(&alias).sum().cast(DataType::Int64).alias(&alias));
+            }
+            Check::Freshness { timestamps, .. } => {
+                row_exprs.push(timestamps.clone().alias(&alias));
+                agg_exprs.push(col(&alias).max().cast(DataType::Int64).alias(&alias));
+            }
+        }
+    }
+    let results = lf
+        .clone()
+        .select(row_exprs)
+        .group_by([col(SOURCE_COLUMN)])
+        .agg(agg_exprs)
+        .sort([SOURCE_COLUMN], Default::default())
+        .collect()?;
+
+    // write rows violating row rules next to the report
+    if let Some(dir) = &args.quarantine_dir {
+        let failures = checks
+            .iter()
+            .filter_map(|check| match check {
+                Check::Rows { name, violations } => Some((violations.clone(), name.clone())),
+                Check::Freshness { .. } => None,
+            })
+            .collect();
+        if let Some((mask, reason)) = crate::quarantine::create_failure_exprs(failures) {
+            let failed = lf
+                .filter(mask)
+                .with_column(reason.alias(crate::quarantine::REASON_COLUMN))
+                .collect()?;
+            let mut n_quarantined = 0;
+            for input_path in input_paths.iter() {
+                let mut df = failed
+                    .clone()
+                    .lazy()
+                    .filter(col(SOURCE_COLUMN).eq(lit(input_path.to_string_lossy().to_string())))
+                    .drop([SOURCE_COLUMN])
+                    .collect()?;
+                n_quarantined += crate::quarantine::write_quarantine(
+                    &mut df,
+                    dir,
+                    std::slice::from_ref(input_path),
+                )?;
+            }
+            if n_quarantined > 0 {
+                println!();
+            }
+        }
+    }
+
+    // collect failures of each check
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let sources = results.column(SOURCE_COLUMN)?.str()?.clone();
+    let mut summary_rows: Vec<[String; 4]> = Vec::new();
+    let mut failure_rows: Vec<[String; 3]> = Vec::new();
+    let mut n_failed_checks = 0;
+    for (i, check) in checks.iter().enumerate() {
+        let values = results.column(&format!("_check_{}", i))?.i64()?.clone();
+        let mut n_failed_files = 0;
+        let detail = match check {
+            Check::Rows { .. } => {
+                let mut n_violations = 0;
+                for (source, count) in sources.into_iter().zip(&values) {
+                    let count = count.unwrap_or(0);
+                    if count > 0 {
+                        n_failed_files += 1;
+                        n_violations += count;
+                        failure_rows.push([
+                            source.unwrap_or_default().to_string(),
+                            check.name().to_string(),
+                            format!(
+                                "{} rows",
+                                tbl_core::formats::format_with_commas(count as u64)
+                            ),
+                        ]);
+                    }
+                }
+                format!(
+                    "{} rows",
+                    tbl_core::formats::format_with_commas(n_violations as u64)
+                )
+            }
+            Check::Freshness { max_age_ms, .. } => {
+                // freshness holds for the dataset when its newest timestamp is recent
+                let is_stale =
+                    |latest: Option<i64>| latest.is_none_or(|t| now_ms - t > *max_age_ms);
+                let latest = values.into_iter().flatten().max();
+                if is_stale(latest) {
+                    for (source, file_latest) in sources.into_iter().zip(&values) {
+                        n_failed_files += 1;
+                        failure_rows.push([
+                            source.unwrap_or_default().to_string(),
+                            check.name().to_string(),
+                            format_age(file_latest.map(|t| now_ms - t)),
+                        ]);
+                    }
+                }
+                format!("latest {}", format_age(latest.map(|t| now_ms - t)))
+            }
+        };
+        let status = if n_failed_files > 0 {
+            n_failed_checks += 1;
+            "fail"
+        } else {
+            "pass"
+        };
+        summary_rows.push([
+            check.name().to_string(),
+            status.to_string(),
+            format!("{} / {}", n_failed_files, results.height()),
+            detail,
+        ]);
+    }
+
+    // print reports
+    tbl_core::formats::print_header("Rules");
+    print_table(
+        &["rule", "status", "failed files", "violations"],
+        &summary_rows,
+    )?;
+    if !failure_rows.is_empty() {
+        println!();
+        tbl_core::formats::print_header("Failures");
+        print_table(&["file", "rule", "violations"], &failure_rows)?;
+    }
+
+    if n_failed_checks > 0 {
+        Err(TblCliError::CheckFailed(format!(
+            "{} of {} rules failed",
+            n_failed_checks,
+            checks.len()
+        )))
+    } else {
+        println!();
+        println!("[all {} rules passed]", checks.len());
+        Ok(())
+    }
+}
+
+fn compile_rule(rule: Rule, base_dir: &Path) -> Result<Vec<Check>, TblCliError> {
+    let checks = match rule {
+        Rule::NotNull(columns) => columns
+            .iter()
+            .map(|column| Check::Rows {
+                name: format!("not_null({})", column),
+                violations: col(column).is_null(),
+            })
+            .collect(),
+        Rule::Unique(columns) => {
+            let key: Vec<Expr> = columns.iter().map(|column| col(column)).collect();
+            vec![Check::Rows {
+                name: format!("unique({})", columns.join(", ")),
+                violations: len().over(key).gt(lit(1)),
+            }]
+        }
+        Rule::Regex { column, pattern } => {
+            vec![Check::Rows {
+                name: format!("regex({})", column),
+                violations: col(&column)
+                    .cast(DataType::String)
+                    .str()
+                    .contains(lit(pattern), true)
+                    .not(),
+            }]
+        }
+        Rule::Range { column, min, max } => {
+            let mut violations = lit(false);
+            if let Some(min) = min {
+                violations = violations.or(col(&column).lt(lit(min)));
+            }
+            if let Some(max) = max {
+                violations = violations.or(col(&column).gt(lit(max)));
+            }
+            vec![Check::Rows {
+                name: format!("range({})", column),
+                violations,
+            }]
+        }
+        Rule::Referential {
+            column,
+            path,
+            ref_column,
+        } => {
+            let path = base_dir.join(path);
+            let ref_column = ref_column.unwrap_or_else(|| column.clone());
+            let values = tbl_core::parquet::create_lazyframe(std::slice::from_ref(&path))?
+                .select([col(&ref_column).unique()])
+                .collect()?;
+            let values = values.column(&ref_column)?.clone();
+            vec![Check::Rows {
+                name: format!("referential({} in {})", column, path.to_string_lossy()),
+                violations: col(&column)
+                    .is_in(lit(values))
+                    .not()
+                    .and(col(&column).is_not_null()),
+            }]
+        }
+        Rule::Freshness { column, max_age } => vec![Check::Freshness {
+            name: format!("freshness({})", column),
+            timestamps: col(&column).dt().timestamp(TimeUnit::Milliseconds),
+            max_age_ms: parse_age(&max_age)?,
+        }],
+    };
+    Ok(checks)
+}
+
+/// parse age such as 30s, 30m, 24h, or 7d into milliseconds
+fn parse_age(age: &str) -> Result<i64, TblCliError> {
+    let invalid = || {
+        TblCliError::Arg(format!(
+            "invalid age {}, use a number with s, m, h, or d",
+            age
+        ))
+    };
+    let split = age.len().checked_sub(1).ok_or_else(invalid)?;
+    let (number, unit) = age.split_at(split);
+    let number: i64 = number.parse().map_err(|_| invalid())?;
+    let unit_ms = match unit {
+        "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        "d" => 86_400_000,
+        _ => return Err(invalid()),
+    };
+    Ok(number * unit_ms)
+}
+
+fn format_age(age_ms: Option<i64>) -> String {
+    match age_ms {
+        Some(age_ms) if age_ms >= 86_400_000 => {
+            format!("{:.1} days old", age_ms as f64 / 86_400_000.0)
+        }
+        Some(age_ms) if age_ms >= 3_600_000 => {
+            format!("{:.1} hours old", age_ms as f64 / 3_600_000.0)
+        }
+        Some(age_ms) => format!("{:.1} minutes old", age_ms as f64 / 60_000.0),
+        None => "no timestamps".to_string(),
+    }
+}
+
+/// print rows as a table, styling the first column as a name and the rest as values
+pub(crate) fn print_table<const N: usize>(
+    labels: &[&str; N],
+    rows: &[[String; N]],
+) -> Result<(), TblCliError> {
+    let mut table = toolstr::Table::new();
+    for (i, label) in labels.iter().enumerate() {
+        table.add_column(
+            *label,
+            rows.iter().map(|row| row[i].clone()).collect::<Vec<_>>(),
+        )?;
+    }
+    let mut format = toolstr::TableFormat {
+        label_font_style: Some("".colorize_title().into()),
+        border_font_style: Some("".colorize_comment().into()),
+        ..Default::default()
+    };
+    for (i, label) in labels.iter().enumerate() {
+        let mut column = toolstr::ColumnFormatShorthand::default().name(*label);
+        column.font_style = if i == 0 {
+            Some("".colorize_variable().into())
+        } else {
+            Some("".colorize_constant().into())
+        };
+        format.add_column(column);
+    }
+    format.print(table)?;
+    Ok(())
+}
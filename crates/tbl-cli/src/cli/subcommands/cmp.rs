@@ -0,0 +1,258 @@
+use crate::{CmpArgs, TblCliError};
+use polars::prelude::*;
+use std::path::{Path, PathBuf};
+
+const A_PREFIX: &str = "a.";
+const B_PREFIX: &str = "b.";
+const IN_A: &str = "_in_a";
+const IN_B: &str = "_in_b";
+const DIFFERING_COLUMNS: &str = "differing_columns";
+
+pub(crate) async fn cmp_command(args: CmpArgs) -> Result<(), TblCliError> {
+    let a_paths = get_dataset_paths(&args.a, args.tree)?;
+    let b_paths = get_dataset_paths(&args.b, args.tree)?;
+    let mut lf_a = tbl_core::parquet::create_lazyframe(&a_paths)?;
+    let mut lf_b = tbl_core::parquet::create_lazyframe(&b_paths)?;
+    let schema_a = lf_a.schema()?;
+    let schema_b = lf_b.schema()?;
+    for key in args.key.iter() {
+        if !schema_a.contains(key) || !schema_b.contains(key) {
+            return Err(TblCliError::Arg(format!(
+                "key column {} must exist in both datasets",
+                key
+            )));
+        }
+    }
+
+    // compare columns present in both datasets, casting b to the dtypes of a
+    let is_value = |name: &str| !args.key.iter().any(|key| key == name);
+    let values: Vec<(String, DataType)> = schema_a
+        .iter()
+        .filter(|(name, _)| is_value(name) && schema_b.contains(name))
+        .map(|(name, dtype)| (name.to_string(), dtype.clone()))
+        .collect();
+    let only_a_columns: Vec<String> = schema_a
+        .iter_names()
+        .filter(|name| !schema_b.contains(name))
+        .map(|name| name.to_string())
+        .collect();
+    let only_b_columns: Vec<String> = schema_b
+        .iter_names()
+        .filter(|name| !schema_a.contains(name))
+        .map(|name| name.to_string())
+        .collect();
+
+    // prefix value columns so that both sides survive the join
+    let keys: Vec<Expr> = args.key.iter().map(|key| col(key)).collect();
+    let mut a_exprs = keys.clone();
+    let mut b_exprs = keys.clone();
+    for (name, dtype) in values.iter() {
+        a_exprs.push(col(name).alias(&format!("{}{}", A_PREFIX, name)));
+        b_exprs.push(
+            col(name)
+                .cast(dtype.clone())
+                .alias(&format!("{}{}", B_PREFIX, name)),
+        );
+    }
+    a_exprs.push(lit(true).alias(IN_A));
+    b_exprs.push(lit(true).alias(IN_B));
+    let a = lf_a.select(a_exprs);
+    let b = lf_b.select(b_exprs);
+
+    // left joins stream their left side but hold a hash table of their right side in memory,
+    // all of b for the first join and only the keys of a for the second
+    let a_joined = a.clone().join(
+        b.clone(),
+        keys.clone(),
+        keys.clone(),
+        JoinArgs::new(JoinType::Left),
+    );
+    let b_joined = b.join(
+        a.select(keys.iter().cloned().chain([col(IN_A)]).collect::<Vec<_>>()),
+        keys.clone(),
+        keys.clone(),
+        JoinArgs::new(JoinType::Left),
+    );
+    let diffs: Vec<Expr> = values
+        .iter()
+        .map(|(name, _)| {
+            col(&format!("{}{}", A_PREFIX, name))
+                .neq_missing(col(&format!("{}{}", B_PREFIX, name)))
+                .and(col(IN_B).is_not_null())
+        })
+        .collect();
+    let any_diff = diffs
+        .iter()
+        .cloned()
+        .fold(lit(false), |acc, diff| acc.or(diff));
+    let differing_columns = concat_str(
+        values
+            .iter()
+            .zip(diffs.iter())
+            .map(|((name, _), diff)| {
+                when(diff.clone())
+                    .then(lit(name.clone()))
+                    .otherwise(lit(NULL).cast(DataType::String))
+            })
+            .chain([lit(NULL).cast(DataType::String)])
+            .collect::<Vec<_>>(),
+        ", ",
+        true,
+    );
+
+    // count differences in one pass over each join
+    let mut count_exprs = vec![
+        len().alias("n_a"),
+        col(IN_B).is_null().sum().alias("n_only_a"),
+        col(IN_B).is_not_null().sum().alias("n_matched"),
+        any_diff.clone().sum().alias("n_changed"),
+    ];
+    for (i, diff) in diffs.iter().enumerate() {
+        count_exprs.push(diff.clone().sum().alias(&format!("n_diff_{}", i)));
+    }
+    let a_counts = a_joined
+        .clone()
+        .select(count_exprs)
+        .with_streaming(true)
+        .collect()?;
+    let b_counts = b_joined
+        .clone()
+        .select([
+            len().alias("n_b"),
+            col(IN_A).is_null().sum().alias("n_only_b"),
+        ])
+        .with_streaming(true)
+        .collect()?;
+    let get_count = |df: &DataFrame, name: &str| -> Result<u64, TblCliError> {
+        Ok(df
+            .column(name)?
+            .cast(&DataType::UInt64)?
+            .u64()?
+            .get(0)
+            .unwrap_or(0))
+    };
+    let n_changed = get_count(&a_counts, "n_changed")?;
+    let n_only_a = get_count(&a_counts, "n_only_a")?;
+    let n_only_b = get_count(&b_counts, "n_only_b")?;
+
+    // summary
+    let format_count = |n: u64| tbl_core::formats::format_with_commas(n);
+    tbl_core::formats::print_header("Comparison");
+    tbl_core::formats::print_bullet("a", args.a.to_string_lossy());
+    tbl_core::formats::print_bullet("b", args.b.to_string_lossy());
+    tbl_core::formats::print_bullet("key", args.key.join(", "));
+    tbl_core::formats::print_bullet("rows in a", format_count(get_count(&a_counts, "n_a")?));
+    tbl_core::formats::print_bullet("rows in b", format_count(get_count(&b_counts, "n_b")?));
+    tbl_core::formats::print_bullet("only in a", format_count(n_only_a));
+    tbl_core::formats::print_bullet("only in b", format_count(n_only_b));
+    tbl_core::formats::print_bullet("changed", format_count(n_changed));
+    tbl_core::formats::print_bullet(
+        "unchanged",
+        format_count(get_count(&a_counts, "n_matched")? - n_changed),
+    );
+    if !only_a_columns.is_empty() {
+        tbl_core::formats::print_bullet("columns only in a", only_a_columns.join(", "));
+    }
+    if !only_b_columns.is_empty() {
+        tbl_core::formats::print_bullet("columns only in b", only_b_columns.join(", "));
+    }
+
+    if n_changed > 0 {
+        println!();
+        tbl_core::formats::print_header("Changed columns");
+        for (i, (name, _)) in values.iter().enumerate() {
+            let n_diff = get_count(&a_counts, &format!("n_diff_{}", i))?;
+            if n_diff > 0 {
+                tbl_core::formats::print_bullet(name, format!("{} rows", format_count(n_diff)));
+            }
+        }
+    }
+
+    // frames of each kind of difference, with value columns of their own dataset
+    let unprefix = |prefix: &str| -> Vec<Expr> {
+        keys.iter()
+            .cloned()
+            .chain(
+                values
+                    .iter()
+                    .map(|(name, _)| col(&format!("{}{}", prefix, name)).alias(name)),
+            )
+            .collect()
+    };
+    let only_a = a_joined
+        .clone()
+        .filter(col(IN_B).is_null())
+        .select(unprefix(A_PREFIX));
+    let only_b = b_joined
+        .filter(col(IN_A).is_null())
+        .select(unprefix(B_PREFIX));
+    let changed = a_joined
+        .filter(any_diff)
+        .with_column(differing_columns.alias(DIFFERING_COLUMNS));
+
+    let examples = [
+        ("Only in a", n_only_a, only_a.clone()),
+        ("Only in b", n_only_b, only_b.clone()),
+        (
+            "Changed rows",
+            n_changed,
+            changed.clone().select(
+                keys.iter()
+                    .cloned()
+                    .chain([col(DIFFERING_COLUMNS)])
+                    .collect::<Vec<_>>(),
+            ),
+        ),
+    ];
+    for (title, n_rows, lf) in examples.into_iter() {
+        if n_rows == 0 {
+            continue;
+        }
+        let df = lf.limit(args.n).with_streaming(true).collect()?;
+        println!();
+        tbl_core::formats::print_header(format!(
+            "{} (first {} of {})",
+            title,
+            df.height(),
+            format_count(n_rows)
+        ));
+        println!("{}", df);
+    }
+
+    if let Some(output_dir) = &args.output_dir {
+        std::fs::create_dir_all(output_dir)?;
+        let changed_columns: Vec<Expr> = keys
+            .iter()
+            .cloned()
+            .chain([col(DIFFERING_COLUMNS)])
+            .chain(values.iter().flat_map(|(name, _)| {
+                [
+                    col(&format!("{}{}", A_PREFIX, name)),
+                    col(&format!("{}{}", B_PREFIX, name)),
+                ]
+            }))
+            .collect();
+        let outputs = [
+            ("only_in_a.parquet", only_a),
+            ("only_in_b.parquet", only_b),
+            ("changed.parquet", changed.select(changed_columns)),
+        ];
+        println!();
+        for (file_name, lf) in outputs.into_iter() {
+            let path = output_dir.join(file_name);
+            lf.sink_parquet(path.clone(), ParquetWriteOptions::default())?;
+            tbl_core::formats::print_bullet("wrote", path.to_string_lossy());
+        }
+    }
+
+    Ok(())
+}
+
+fn get_dataset_paths(path: &Path, tree: bool) -> Result<Vec<PathBuf>, TblCliError> {
+    let inputs = Some(vec![path.to_path_buf()]);
+    let paths = tbl_core::filesystem::get_input_paths(&inputs, tree, true)?;
+    if paths.is_empty() {
+        return Err(TblCliError::no_inputs(&inputs, tree));
+    }
+    Ok(paths)
+}
@@ -0,0 +1,142 @@
+use crate::{ConformArgs, TblCliError};
+use polars::prelude::*;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// columns of a canonical schema, in output order
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CanonicalSchema {
+    columns: Vec<CanonicalColumn>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CanonicalColumn {
+    name: String,
+    dtype: String,
+}
+
+pub(crate) async fn conform_command(args: ConformArgs) -> Result<(), TblCliError> {
+    let schema = Arc::new(load_canonical_schema(&args.schema).await?);
+    let input_paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if input_paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+    let schemas = tbl_core::parquet::get_parquet_schemas(&input_paths).await?;
+
+    // find every difference from the canonical schema and the action each policy takes
+    let mut rows: Vec<[String; 4]> = Vec::new();
+    let mut n_errors = 0;
+    let mut nonconforming: Vec<PathBuf> = Vec::new();
+    for (path, file_schema) in input_paths.iter().zip(schemas.iter()) {
+        let mut differences: Vec<(String, String, bool)> = Vec::new();
+        for name in file_schema.iter_names() {
+            if !schema.contains(name) {
+                differences.push((
+                    name.to_string(),
+                    "extra".to_string(),
+                    args.on_extra == "error",
+                ));
+            }
+        }
+        for (name, dtype) in schema.iter() {
+            match file_schema.get(name) {
+                None => differences.push((
+                    name.to_string(),
+                    "missing".to_string(),
+                    args.on_missing == "error",
+                )),
+                Some(current) if current != dtype => {
+                    let lossless = tbl_core::schemas::promote_dtypes(current, dtype, false)
+                        .as_ref()
+                        == Some(dtype);
+                    let fails = match args.on_mismatch.as_str() {
+                        "cast" => false,
+                        "promote" => !lossless,
+                        _ => true,
+                    };
+                    differences.push((
+                        name.to_string(),
+                        format!("{} -> {}", current, dtype),
+                        fails,
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+        // files with the right columns can still differ in column order
+        if differences.is_empty() && file_schema.as_ref() != schema.as_ref() {
+            differences.push(("".to_string(), "column order".to_string(), false));
+        }
+        if differences.is_empty() {
+            continue;
+        }
+        nonconforming.push(path.clone());
+        for (column, difference, fails) in differences.into_iter() {
+            if fails {
+                n_errors += 1;
+            }
+            rows.push([
+                path.to_string_lossy().to_string(),
+                column,
+                difference,
+                if fails { "error" } else { "fix" }.to_string(),
+            ]);
+        }
+    }
+    if nonconforming.is_empty() {
+        println!(
+            "[all {} files conform to {}]",
+            input_paths.len(),
+            args.schema.to_string_lossy()
+        );
+        return Ok(());
+    }
+    tbl_core::formats::print_header("Differences");
+    super::print_table(&["file", "column", "difference", "action"], &rows)?;
+    println!();
+    if n_errors > 0 {
+        return Err(TblCliError::Arg(format!(
+            "{} differences are not allowed by the --on-extra, --on-missing, and --on-mismatch policies",
+            n_errors
+        )));
+    }
+
+    // in place edits only need to rewrite files that differ from the canonical schema
+    let paths = if args.output_args.inplace {
+        Some(nonconforming)
+    } else {
+        args.paths
+    };
+    let mut data_args = args.output_args.into_data_args(paths, args.tree);
+    data_args.align_schema = Some(schema);
+    super::data_command(data_args).await
+}
+
+async fn load_canonical_schema(path: &Path) -> Result<Schema, TblCliError> {
+    if path.extension().and_then(|s| s.to_str()) == Some("parquet") {
+        let schema = tbl_core::parquet::get_parquet_schema(path).await?;
+        return Ok(schema.as_ref().clone());
+    }
+    let spec: CanonicalSchema = super::load_spec_file(path, "schema")?;
+    let mut schema = Schema::new();
+    for column in spec.columns.into_iter() {
+        let dtype = crate::transform::parse_dtype(&column.dtype).map_err(|_| {
+            TblCliError::Arg(format!(
+                "invalid dtype {} for {}",
+                column.dtype, column.name
+            ))
+        })?;
+        if schema
+            .with_column(column.name.as_str().into(), dtype)
+            .is_some()
+        {
+            return Err(TblCliError::Arg(format!(
+                "column {} is declared more than once",
+                column.name
+            )));
+        }
+    }
+    Ok(schema)
+}
@@ -0,0 +1,136 @@
+use crate::styles::FontStyle;
+use crate::{CorrArgs, TblCliError};
+use polars::prelude::*;
+use std::path::PathBuf;
+use toolstr::Colorize;
+
+pub(crate) async fn corr_command(args: CorrArgs) -> Result<(), TblCliError> {
+    // split positional arguments into input paths and column names
+    let (paths, columns): (Vec<String>, Vec<String>) = args
+        .inputs
+        .into_iter()
+        .partition(|input| std::path::Path::new(input).exists());
+    let inputs: Option<Vec<PathBuf>> = if paths.is_empty() {
+        None
+    } else {
+        Some(paths.into_iter().map(PathBuf::from).collect())
+    };
+    let paths = tbl_core::filesystem::get_input_paths(&inputs, args.tree, true)?;
+    if paths.is_empty() {
+        return Err(TblCliError::no_inputs(&inputs, args.tree));
+    }
+
+    let mut lf = tbl_core::parquet::create_lazyframe(&paths)?;
+    let columns = if columns.is_empty() {
+        lf.schema()?
+            .iter()
+            .filter(|(_, dtype)| dtype.is_numeric())
+            .map(|(name, _)| name.to_string())
+            .collect()
+    } else {
+        columns
+    };
+    if columns.len() < 2 {
+        return Err(TblCliError::Arg(
+            "need at least 2 numeric columns to correlate".to_string(),
+        ));
+    }
+
+    // compute each pair of columns in a single pass
+    let mut exprs = Vec::new();
+    for (i, a) in columns.iter().enumerate() {
+        for (j, b) in columns.iter().enumerate().skip(i + 1) {
+            let (a_expr, b_expr) = (
+                col(a).cast(DataType::Float64),
+                col(b).cast(DataType::Float64),
+            );
+            let expr = match args.method.as_str() {
+                "pearson" => pearson_corr(a_expr, b_expr, 1),
+                "spearman" => spearman_rank_corr(a_expr, b_expr, 1, false),
+                other => {
+                    return Err(TblCliError::Arg(format!(
+                        "unknown method {}, use pearson or spearman",
+                        other
+                    )))
+                }
+            };
+            exprs.push(expr.alias(&format!("{}_{}", i, j)));
+        }
+    }
+    let df = lf.select(exprs).collect()?;
+    let n = columns.len();
+    let matrix: Vec<Vec<Option<f64>>> = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| match i.cmp(&j) {
+                    std::cmp::Ordering::Equal => Some(1.0),
+                    _ => df
+                        .column(&format!("{}_{}", i.min(j), i.max(j)))
+                        .ok()
+                        .and_then(|s| s.f64().ok()?.get(0)),
+                })
+                .collect()
+        })
+        .collect();
+
+    tbl_core::formats::print_header(format!("{} correlation", args.method));
+    print_matrix(&columns, &matrix);
+    Ok(())
+}
+
+fn print_matrix(columns: &[String], matrix: &[Vec<Option<f64>>]) {
+    let name_width = columns.iter().map(|c| c.len()).max().unwrap_or(0);
+    let cell_width = columns.iter().map(|c| c.len()).max().unwrap_or(0).max(6);
+    let header: Vec<String> = columns
+        .iter()
+        .map(|c| format!("{:>width$}", c, width = cell_width))
+        .collect();
+    println!(
+        "{}  {}",
+        " ".repeat(name_width),
+        header.join(" ").as_str().colorize_title()
+    );
+    for (name, row) in columns.iter().zip(matrix.iter()) {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|value| {
+                let text = match value {
+                    Some(value) if value.is_finite() => {
+                        format!("{:>width$.3}", value, width = cell_width)
+                    }
+                    _ => format!("{:>width$}", "-", width = cell_width),
+                };
+                let (r, g, b) = heat_color(*value);
+                let (text_r, text_g, text_b) = crate::styles::theme().text;
+                text.truecolor(text_r, text_g, text_b)
+                    .on_truecolor(r, g, b)
+                    .to_string()
+            })
+            .collect();
+        println!(
+            "{}  {}",
+            format!("{:>width$}", name, width = name_width)
+                .as_str()
+                .colorize_variable(),
+            cells.join(" ")
+        );
+    }
+}
+
+/// blend background toward green for positive and red for negative correlation
+fn heat_color(value: Option<f64>) -> (u8, u8, u8) {
+    let (r, g, b) = crate::styles::theme().background;
+    let background = (r as f64, g as f64, b as f64);
+    let (target, strength) = match value {
+        Some(value) if value.is_finite() && value >= 0.0 => ((30.0, 160.0, 70.0), value),
+        Some(value) if value.is_finite() => ((190.0, 50.0, 50.0), -value),
+        _ => return (r, g, b),
+    };
+    let strength = strength.min(1.0);
+    let blend = |from: f64, to: f64| (from + (to - from) * strength).round() as u8;
+    (
+        blend(background.0, target.0),
+        blend(background.1, target.1),
+        blend(background.2, target.2),
+    )
+}
@@ -1,33 +1,424 @@
+use crate::checkpoint::Checkpoint;
 use crate::{DataArgs, OutputMode, TblCliError};
-use std::path::PathBuf;
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tbl_core::filesystem::{get_input_paths, get_output_paths, OutputPathSpec};
 
-pub(crate) async fn data_command(args: DataArgs) -> Result<(), TblCliError> {
+pub(crate) async fn data_command(mut args: DataArgs) -> Result<(), TblCliError> {
     inquire::set_global_render_config(crate::styles::get_render_config());
 
+    // slice row ranges through --offset and --head
+    crate::transform::resolve_row_range(&mut args)?;
+
     // decide output mode
     let output_mode = decide_output_mode(&args)?;
 
+    // check encryption key before any files are selected
+    if args.encrypt.is_some() || args.decrypt.is_some() {
+        if args.lineage {
+            return Err(TblCliError::Arg(
+                "--lineage is not available with encrypt or decrypt".to_string(),
+            ));
+        }
+        crate::output::read_encryption_key(&args)?;
+    }
+
+    // these write outputs while reading, which --prefetch runs ahead of the current write
+    let writes_while_reading = args.global
+        || args.encrypt.is_some()
+        || args.decrypt.is_some()
+        || args.quarantine_dir.is_some()
+        || args.splice;
+    if args.prefetch.is_some() && writes_while_reading {
+        return Err(TblCliError::Arg(
+            "--prefetch cannot be combined with --global, encryption, --quarantine-dir, or --splice"
+                .to_string(),
+        ));
+    }
+
     // create input output pairs
     let io = gather_inputs_and_outputs(&output_mode, &args)?;
+    if io.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+    let io = match &args.order_by_file_key {
+        Some(key) => order_inputs_by_file_key(io, key, !args.no_summary).await?,
+        None => io,
+    };
+
+    // refuse to write into protected directories
+    if output_mode.writes_to_disk() {
+        let mut written: Vec<PathBuf> =
+            io.iter().filter_map(|(_, output)| output.clone()).collect();
+        written.extend(args.output_dir.iter().cloned());
+        written.extend(args.output_file.iter().cloned());
+        crate::protection::check_not_protected(&written)?;
+    }
+
+    // skip outputs that are newer than their inputs
+    let io = if args.incremental {
+        let n_total = io.len();
+        let io = skip_up_to_date(io, &output_mode, &args).await?;
+        if n_total > 0 && io.is_empty() {
+            if !args.no_summary {
+                println!("[all {} outputs up to date]", n_total);
+            }
+            return Ok(());
+        }
+        if !args.no_summary {
+            println!(
+                "[skipping {} of {} files with up to date outputs]",
+                n_total - io.len(),
+                n_total
+            );
+        }
+        io
+    } else {
+        io
+    };
+
+    // pick columns interactively if flags given without columns
+    pick_columns_if_needed(&mut args, &io).await?;
 
     // print data summary
     if !args.no_summary {
         crate::summary::print_summary(&io, &output_mode, &args).await?;
     }
 
+    // show rows affected by updates before a dry run exits
+    if args.dry && args.update.is_some() {
+        print_update_diff(&io, &args)?;
+    }
+
     // exit early as needed
-    exit_early_if_needed(args.dry, args.confirm, !args.no_summary, &output_mode, &io);
+    if exit_early_if_needed(&io, &output_mode, &args)? {
+        return Ok(());
+    }
+
+    // record completed outputs so that interrupted edits can be resumed
+    let mut checkpoint = open_checkpoint(&io, &output_mode, &args)?;
+    let io = match &checkpoint {
+        Some(checkpoint) if args.resume => {
+            let n_total = io.len();
+            let io: Vec<_> = io
+                .into_iter()
+                .filter(|(_, output_path)| match output_path {
+                    Some(output_path) => !checkpoint.is_complete(output_path),
+                    None => true,
+                })
+                .collect();
+            println!(
+                "[resuming, skipping {} of {} files already completed]",
+                n_total - io.len(),
+                n_total
+            );
+            io
+        }
+        _ => io,
+    };
 
     // process each input output pair
-    for (input_paths, output_path) in io.into_iter() {
-        process_io(input_paths, output_path, &output_mode, &args)?
+    let mut failures = Vec::new();
+    let n_total = io.len();
+    let mut edit_stats = if output_mode.writes_to_disk() && (!args.no_summary || args.summary_json)
+    {
+        Some(crate::summary::EditStats::start())
+    } else {
+        None
+    };
+    // inputs are measured before any of them can be overwritten
+    if let Some(edit_stats) = edit_stats.as_mut() {
+        for (input_paths, _) in io.iter() {
+            edit_stats.add_inputs(input_paths).await?;
+        }
+    }
+    let options = tbl_core::executor::ExecutorOptions {
+        max_concurrent: args.jobs,
+        ..Default::default()
+    };
+    let keep_going = args.keep_going;
+    let interrupt = if output_mode.writes_to_disk() {
+        Some(crate::interrupt::Interrupt::install(checkpoint.is_some()))
+    } else {
+        None
+    };
+    let cancel = match &interrupt {
+        Some(interrupt) => interrupt.cancel.clone(),
+        None => tbl_core::executor::CancelToken::new(),
+    };
+    let output_mode = Arc::new(output_mode);
+    let args = Arc::new(args);
+    let mut output_paths = Vec::new();
+    let mut progress_line = crate::summary::ProgressLine::new(if output_mode.writes_to_disk() {
+        n_total
+    } else {
+        0
+    });
+    let on_complete =
+        |progress, result: Result<(Vec<PathBuf>, Option<PathBuf>, PairResult), TblCliError>| {
+            progress_line.update(&progress);
+            match (result?, checkpoint.as_mut()) {
+                ((_, planned_path, Ok(output_path)), checkpoint) => {
+                    if let (Some(planned_path), Some(output_path), Some(checkpoint)) =
+                        (&planned_path, &output_path, checkpoint)
+                    {
+                        checkpoint.record(planned_path, output_path)?;
+                    }
+                    output_paths.extend(output_path);
+                }
+                ((input_paths, _, Err(e)), _) if keep_going => {
+                    if let Some(edit_stats) = edit_stats.as_mut() {
+                        edit_stats.add_failure();
+                    }
+                    failures.push((input_paths, e))
+                }
+                ((_, _, Err(e)), _) => return Err(e),
+            }
+            Ok(())
+        };
+    let executed = match args.prefetch {
+        // decoding of the next files overlaps with encoding of the current file
+        Some(depth) => {
+            let read = {
+                let output_mode = output_mode.clone();
+                let args = args.clone();
+                let interrupt = interrupt.clone();
+                move |(input_paths, output_path): (Vec<PathBuf>, Option<PathBuf>)| {
+                    let output_mode = output_mode.clone();
+                    let args = args.clone();
+                    let interrupt = interrupt.clone();
+                    async move {
+                        if let (Some(interrupt), Some(output_path)) = (&interrupt, &output_path) {
+                            interrupt.start(output_path);
+                        }
+                        let read =
+                            read_pair(&input_paths, output_path.clone(), &output_mode, &args, true)
+                                .await;
+                        Ok::<_, TblCliError>((input_paths, output_path, read))
+                    }
+                }
+            };
+            let write = |(input_paths, output_path, read): (
+                Vec<PathBuf>,
+                Option<PathBuf>,
+                Result<ReadPair, TblCliError>,
+            )| {
+                let output_mode = output_mode.clone();
+                let args = args.clone();
+                let interrupt = interrupt.clone();
+                async move {
+                    let result = match read {
+                        Ok(read) => {
+                            write_pair(read, output_path.clone(), &output_mode, &args).await
+                        }
+                        Err(e) => Err(e),
+                    };
+                    if let (Some(interrupt), Some(output_path)) = (&interrupt, &output_path) {
+                        interrupt.finish(output_path);
+                    }
+                    Ok::<_, TblCliError>((input_paths, output_path, result))
+                }
+            };
+            tbl_core::executor::run_pipelined(
+                io,
+                depth,
+                &options,
+                &cancel,
+                read,
+                write,
+                on_complete,
+            )
+            .await?
+        }
+        None => {
+            let op = {
+                let output_mode = output_mode.clone();
+                let args = args.clone();
+                let interrupt = interrupt.clone();
+                move |(input_paths, output_path): (Vec<PathBuf>, Option<PathBuf>)| {
+                    let output_mode = output_mode.clone();
+                    let args = args.clone();
+                    let interrupt = interrupt.clone();
+                    async move {
+                        if let (Some(interrupt), Some(output_path)) = (&interrupt, &output_path) {
+                            interrupt.start(output_path);
+                        }
+                        let result =
+                            process_pair(&input_paths, output_path.clone(), &output_mode, &args)
+                                .await;
+                        if let (Some(interrupt), Some(output_path)) = (&interrupt, &output_path) {
+                            interrupt.finish(output_path);
+                        }
+                        Ok::<_, TblCliError>((input_paths, output_path, result))
+                    }
+                }
+            };
+            tbl_core::executor::run_per_file(io, &options, &cancel, op, on_complete).await?
+        }
+    };
+    progress_line.finish();
+    if let Some(edit_stats) = edit_stats.as_mut() {
+        for output_path in output_paths.iter() {
+            edit_stats.add_output(output_path).await?;
+        }
+    }
+    if let Some(edit_stats) = edit_stats {
+        edit_stats.print(args.summary_json)?;
+    }
+
+    // files in progress finished before stopping, so only unstarted files are left to resume
+    if cancel.is_cancelled() {
+        if !failures.is_empty() {
+            print_failures(&failures)?;
+        }
+        eprintln!(
+            "[stopped with {} of {} files not started]",
+            executed.n_cancelled, n_total
+        );
+        crate::interrupt::print_resume_hint(checkpoint.is_some());
+        return Err(TblCliError::Aborted);
+    }
+
+    // keep the checkpoint of failed edits so that they can be resumed
+    if !failures.is_empty() {
+        print_failures(&failures)?;
+        return Err(TblCliError::PartialFailure(format!(
+            "{} of {} files failed",
+            failures.len(),
+            n_total
+        )));
+    }
+    if let Some(checkpoint) = checkpoint {
+        checkpoint.finish()?;
     }
 
     Ok(())
 }
 
-fn decide_output_mode(args: &DataArgs) -> Result<OutputMode, TblCliError> {
+/// path that the output of an input output pair was written to, or its error
+type PairResult = Result<Option<PathBuf>, TblCliError>;
+
+/// process input output pair, returning the path its output was written to
+async fn process_pair(
+    input_paths: &[PathBuf],
+    output_path: Option<PathBuf>,
+    output_mode: &OutputMode,
+    args: &DataArgs,
+) -> Result<Option<PathBuf>, TblCliError> {
+    let read = read_pair(input_paths, output_path.clone(), output_mode, args, false).await?;
+    write_pair(read, output_path, output_mode, args).await
+}
+
+/// input output pair that has been read but not yet written
+struct ReadPair {
+    pending: Option<PendingOutput>,
+}
+
+/// read and transform input output pair, decoding its output frame if collect is set
+async fn read_pair(
+    input_paths: &[PathBuf],
+    output_path: Option<PathBuf>,
+    output_mode: &OutputMode,
+    args: &DataArgs,
+    collect: bool,
+) -> Result<ReadPair, TblCliError> {
+    // inputs are fingerprinted before inplace edits overwrite them
+    let writes_parquet = output_mode.writes_to_disk()
+        && output_path
+            .as_ref()
+            .and_then(|path| path.extension())
+            .is_some_and(|ext| ext == "parquet");
+    let lineage = if args.lineage && writes_parquet {
+        Some(crate::lineage::Lineage::from_inputs(input_paths)?.to_key_value()?)
+    } else {
+        None
+    };
+    let input_hash = if args.incremental && writes_parquet {
+        Some((
+            crate::lineage::INPUT_HASH_KEY.to_string(),
+            crate::lineage::get_input_hash(input_paths)?,
+        ))
+    } else {
+        None
+    };
+
+    // tree outputs keep the subdirectories of their inputs
+    if let (OutputMode::SaveToDirectory, Some(Some(parent))) =
+        (output_mode, output_path.as_ref().map(|path| path.parent()))
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    let key_values: Vec<(String, String)> = lineage.into_iter().chain(input_hash).collect();
+    let pending = prepare_io(
+        input_paths.to_vec(),
+        output_path,
+        output_mode,
+        args,
+        key_values,
+    )
+    .await?;
+    let pending = match pending {
+        Some(mut pending) => {
+            if collect {
+                let lf = pending.lf;
+                let df = tokio::task::spawn_blocking(move || lf.collect())
+                    .await
+                    .map_err(tbl_core::TblError::from)??;
+                pending.lf = df.lazy();
+            }
+            Some(pending)
+        }
+        None => None,
+    };
+    Ok(ReadPair { pending })
+}
+
+/// write output of input output pair, returning the path its output was written to
+async fn write_pair(
+    read: ReadPair,
+    output_path: Option<PathBuf>,
+    output_mode: &OutputMode,
+    args: &DataArgs,
+) -> Result<Option<PathBuf>, TblCliError> {
+    if let Some(pending) = read.pending {
+        finish_io(pending, output_mode, args).await?;
+    }
+    let output_path = match output_path {
+        Some(output_path)
+            if args
+                .output_template
+                .as_deref()
+                .is_some_and(|t| t.contains("{hash")) =>
+        {
+            Some(rename_by_content_hash(output_path).await?)
+        }
+        output_path => output_path,
+    };
+    if let (true, Some(output_path)) = (args.write_index, &output_path) {
+        tbl_core::parquet::write_parquet_index(output_path, args.index_key.as_deref()).await?;
+    }
+    Ok(output_path)
+}
+
+fn print_failures(failures: &[(Vec<PathBuf>, TblCliError)]) -> Result<(), TblCliError> {
+    let rows: Vec<[String; 2]> = failures
+        .iter()
+        .map(|(input_paths, e)| {
+            let paths: Vec<String> = input_paths
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect();
+            [paths.join(", "), e.to_string()]
+        })
+        .collect();
+    println!();
+    tbl_core::formats::print_header("Failed files");
+    super::print_table(&["file", "error"], &rows)
+}
+
+pub(crate) fn decide_output_mode(args: &DataArgs) -> Result<OutputMode, TblCliError> {
     match (
         args.inplace,
         &args.output_file,
@@ -49,6 +440,172 @@ fn decide_output_mode(args: &DataArgs) -> Result<OutputMode, TblCliError> {
     }
 }
 
+#[allow(clippy::type_complexity)]
+async fn skip_up_to_date(
+    io: Vec<(Vec<PathBuf>, Option<PathBuf>)>,
+    output_mode: &OutputMode,
+    args: &DataArgs,
+) -> Result<Vec<(Vec<PathBuf>, Option<PathBuf>)>, TblCliError> {
+    if !matches!(
+        output_mode,
+        OutputMode::SaveToDirectory | OutputMode::SaveToSingleFile
+    ) {
+        return Err(TblCliError::Arg(
+            "--incremental can only be used with --output-dir or --output-file".to_string(),
+        ));
+    }
+    let hashed = args
+        .output_template
+        .as_deref()
+        .is_some_and(|t| t.contains("{hash"));
+    let mut stale = Vec::new();
+    for (input_paths, output_path) in io.into_iter() {
+        let up_to_date = match &output_path {
+            Some(output_path) => is_up_to_date(&input_paths, output_path, hashed).await?,
+            None => false,
+        };
+        if !up_to_date {
+            stale.push((input_paths, output_path));
+        }
+    }
+    Ok(stale)
+}
+
+/// whether output is newer than its inputs, or records the content hash of its inputs
+///
+/// outputs named by {hash} are not known until written, so every file matching their name is
+/// checked for the content hash of the inputs
+async fn is_up_to_date(
+    input_paths: &[PathBuf],
+    output_path: &Path,
+    hashed: bool,
+) -> Result<bool, TblCliError> {
+    let candidates = match hashed_output_candidates(output_path, hashed)? {
+        Some(candidates) => candidates,
+        None if tbl_core::filesystem::is_output_up_to_date(input_paths, output_path) => {
+            return Ok(true)
+        }
+        None => vec![output_path.to_path_buf()],
+    };
+    let mut input_hash = None;
+    for candidate in candidates.iter() {
+        let Some(recorded) = crate::lineage::read_input_hash(candidate).await else {
+            continue;
+        };
+        let current = match &input_hash {
+            Some(current) => current,
+            None => input_hash.insert(crate::lineage::get_input_hash(input_paths)?),
+        };
+        if &recorded == current {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// files that an output named by a {hash} template could have been renamed to
+fn hashed_output_candidates(
+    output_path: &Path,
+    hashed: bool,
+) -> Result<Option<Vec<PathBuf>>, TblCliError> {
+    if !hashed {
+        return Ok(None);
+    }
+    let file_name = output_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let template = tbl_core::filesystem::OutputTemplate::parse(&file_name)?;
+    if !template.uses("hash") {
+        return Ok(None);
+    }
+    let marker = "\u{0}";
+    let values = HashMap::from([("hash".to_string(), marker.to_string())]);
+    let pattern = template.render(&values, &[])?;
+    let pieces: Vec<&str> = pattern.split(marker).collect();
+    let parent = output_path.parent().unwrap_or(Path::new("."));
+    let mut candidates = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(parent) {
+        for entry in entries {
+            let path = entry?.path();
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if matches_pieces(&name, &pieces) {
+                candidates.push(path);
+            }
+        }
+    }
+    Ok(Some(candidates))
+}
+
+/// whether name is the pieces in order, with any text between consecutive pieces
+fn matches_pieces(name: &str, pieces: &[&str]) -> bool {
+    let Some((first, rest)) = pieces.split_first() else {
+        return false;
+    };
+    let Some(mut remaining) = name.strip_prefix(first) else {
+        return false;
+    };
+    let Some((last, middle)) = rest.split_last() else {
+        return remaining.is_empty();
+    };
+    let Some(stripped) = remaining.strip_suffix(last) else {
+        return false;
+    };
+    remaining = stripped;
+    for piece in middle.iter() {
+        match remaining.find(piece) {
+            Some(index) => remaining = &remaining[index + piece.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// open checkpoint for edits that write one output per input file
+///
+/// the default checkpoint is kept with the outputs, and edits continue without one if it
+/// cannot be created there, unless a checkpoint was requested
+fn open_checkpoint(
+    io: &[(Vec<PathBuf>, Option<PathBuf>)],
+    output_mode: &OutputMode,
+    args: &DataArgs,
+) -> Result<Option<Checkpoint>, TblCliError> {
+    match output_mode {
+        OutputMode::ModifyInplace | OutputMode::SaveToDirectory => {
+            let command = crate::checkpoint::get_checkpoint_command();
+            if let Some(path) = &args.checkpoint {
+                return Ok(Some(Checkpoint::open(path, &command, args.resume)?));
+            }
+            let dir = match (output_mode, &args.output_dir) {
+                (OutputMode::SaveToDirectory, Some(output_dir)) => output_dir.clone(),
+                _ => io
+                    .iter()
+                    .find_map(|(_, output_path)| output_path.as_ref()?.parent())
+                    .map(|parent| parent.to_path_buf())
+                    .unwrap_or_default(),
+            };
+            let path = crate::checkpoint::get_default_checkpoint_path(&command, &dir);
+            let opened = std::fs::create_dir_all(&dir)
+                .map_err(TblCliError::from)
+                .and_then(|_| Checkpoint::open(&path, &command, args.resume));
+            match opened {
+                Ok(checkpoint) => Ok(Some(checkpoint)),
+                Err(e) if args.resume => Err(e),
+                Err(e) => {
+                    if !args.no_summary {
+                        println!("[continuing without checkpoint, {}]", e);
+                    }
+                    Ok(None)
+                }
+            }
+        }
+        _ if args.resume || args.checkpoint.is_some() => Err(TblCliError::Arg(
+            "checkpoints can only be used with --inplace or --output-dir".to_string(),
+        )),
+        _ => Ok(None),
+    }
+}
+
 #[allow(clippy::type_complexity)]
 fn gather_inputs_and_outputs(
     output_mode: &OutputMode,
@@ -57,6 +614,12 @@ fn gather_inputs_and_outputs(
     // parse input output pairs
     let mut io = Vec::new();
     match output_mode {
+        OutputMode::PrintToStdout if args.per_file => {
+            let input_paths = get_input_paths(&args.paths, args.tree, true)?;
+            for input_path in input_paths.into_iter() {
+                io.push(([input_path].to_vec(), None))
+            }
+        }
         OutputMode::PrintToStdout
         | OutputMode::Partition
         | OutputMode::InteractiveLf
@@ -85,8 +648,13 @@ fn gather_inputs_and_outputs(
                 file_prefix: args.output_prefix.clone(),
                 file_postfix: args.output_postfix.clone(),
                 sort: true,
+                flat: args.flat_output,
             };
             let (input_paths, output_paths) = get_output_paths(output_spec)?;
+            let output_paths = match &args.output_template {
+                Some(template) => apply_output_template(template, &input_paths, output_paths)?,
+                None => output_paths,
+            };
             for (input_path, output_path) in input_paths.into_iter().zip(output_paths) {
                 io.push(([input_path].to_vec(), Some(output_path)))
             }
@@ -102,62 +670,728 @@ fn gather_inputs_and_outputs(
     Ok(io)
 }
 
-fn exit_early_if_needed(
-    dry: bool,
-    confirm: bool,
+/// order inputs combined into each output by the min value of key in each file
+///
+/// files without values of key are combined last, and files whose range overlaps the range of
+/// an earlier file are reported, since their rows will be interleaved in key order
+#[allow(clippy::type_complexity)]
+async fn order_inputs_by_file_key(
+    io: Vec<(Vec<PathBuf>, Option<PathBuf>)>,
+    key: &str,
     summary: bool,
-    output_mode: &OutputMode,
+) -> Result<Vec<(Vec<PathBuf>, Option<PathBuf>)>, TblCliError> {
+    let mut ordered = Vec::new();
+    for (input_paths, output_path) in io.into_iter() {
+        if input_paths.len() < 2 {
+            ordered.push((input_paths, output_path));
+            continue;
+        }
+
+        // read ranges from statistics, scanning files whose statistics are missing
+        let stats_ranges = tbl_core::parquet::get_parquet_key_ranges(&input_paths, key).await?;
+        let mut ranges = Vec::new();
+        for (path, range) in input_paths.into_iter().zip(stats_ranges) {
+            let range = match range {
+                Some(range) => Some(range),
+                None => super::scan_key_range(&path, key)?,
+            };
+            // temporal values are compared by their physical values
+            let range = range.map(|(min, max)| (to_physical_value(min), to_physical_value(max)));
+            ranges.push((range, path));
+        }
+        ranges.sort_by(|a, b| match (&a.0, &b.0) {
+            (Some(a), Some(b)) => a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+
+        // count files starting before an earlier file ends
+        let mut n_overlapping = 0;
+        let mut max_so_far: Option<AnyValue<'static>> = None;
+        for (range, _) in ranges.iter() {
+            if let Some((min, max)) = range {
+                if max_so_far
+                    .as_ref()
+                    .is_some_and(|max_so_far| min < max_so_far)
+                {
+                    n_overlapping += 1;
+                }
+                if max_so_far
+                    .as_ref()
+                    .is_none_or(|max_so_far| max > max_so_far)
+                {
+                    max_so_far = Some(max.clone());
+                }
+            }
+        }
+        if n_overlapping > 0 && summary {
+            println!(
+                "[{} of {} files overlap the {} range of an earlier file, use --sort {} to order their rows]",
+                n_overlapping,
+                ranges.len(),
+                key,
+                key
+            );
+        }
+        ordered.push((
+            ranges.into_iter().map(|(_, path)| path).collect(),
+            output_path,
+        ));
+    }
+    Ok(ordered)
+}
+
+fn to_physical_value(value: AnyValue<'static>) -> AnyValue<'static> {
+    match value {
+        AnyValue::Datetime(value, _, _) | AnyValue::Duration(value, _) => AnyValue::Int64(value),
+        AnyValue::Date(value) => AnyValue::Int32(value),
+        AnyValue::Time(value) => AnyValue::Int64(value),
+        value => value,
+    }
+}
+
+/// rename output files by template, leaving {hash} to be filled in once outputs are written
+fn apply_output_template(
+    template: &str,
+    input_paths: &[PathBuf],
+    output_paths: Vec<PathBuf>,
+) -> Result<Vec<PathBuf>, TblCliError> {
+    let template = tbl_core::filesystem::OutputTemplate::parse(template)?;
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let mut renamed = Vec::new();
+    for (part, (input_path, output_path)) in input_paths.iter().zip(output_paths).enumerate() {
+        let mut values: HashMap<String, String> =
+            tbl_core::parquet::get_hive_partitions(input_path)
+                .into_iter()
+                .collect();
+        let stem = input_path.file_stem().unwrap_or_default().to_string_lossy();
+        let ext = input_path.extension().unwrap_or_default().to_string_lossy();
+        values.insert("stem".to_string(), stem.to_string());
+        values.insert("ext".to_string(), ext.to_string());
+        values.insert("date".to_string(), date.clone());
+        values.insert("part".to_string(), part.to_string());
+        let mut file_name = template.render(&values, &["hash"])?;
+        // outputs without an extension keep the format of their input
+        if !file_name.contains('.') && !ext.is_empty() {
+            file_name = format!("{}.{}", file_name, ext);
+        }
+        if template.uses("hash") && !file_name.ends_with(".parquet") {
+            return Err(TblCliError::Arg(
+                "{hash} can only name .parquet outputs".to_string(),
+            ));
+        }
+        renamed.push(output_path.with_file_name(file_name));
+    }
+
+    tbl_core::filesystem::check_output_collisions(input_paths, &renamed)?;
+    Ok(renamed)
+}
+
+/// fill in the {hash} variable of an output name with the content hash of the output
+async fn rename_by_content_hash(output_path: PathBuf) -> Result<PathBuf, TblCliError> {
+    let file_name = output_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let template = tbl_core::filesystem::OutputTemplate::parse(&file_name)?;
+    if !template.uses("hash") {
+        return Ok(output_path);
+    }
+    let hash = tbl_core::parquet::parquet_content_hash(&output_path, 8192).await?;
+    let values = HashMap::from([("hash".to_string(), hash.hash)]);
+    let renamed = output_path.with_file_name(template.render(&values, &[])?);
+    // an existing file of the same name is kept if it holds the same rows, hashes can collide
+    if renamed != output_path && renamed.exists() {
+        if !same_rows(&output_path, &renamed)? {
+            std::fs::remove_file(&output_path)?;
+            return Err(TblCliError::Error(format!(
+                "{} already exists with different rows of the same hash",
+                renamed.to_string_lossy()
+            )));
+        }
+        std::fs::remove_file(&output_path)?;
+        return Ok(renamed);
+    }
+    std::fs::rename(&output_path, &renamed)?;
+    Ok(renamed)
+}
+
+/// whether two parquet files hold the same schema and rows in the same order
+fn same_rows(path: &Path, other: &Path) -> Result<bool, TblCliError> {
+    let df = tbl_core::parquet::create_lazyframe(&[path.to_path_buf()])?.collect()?;
+    let other = tbl_core::parquet::create_lazyframe(&[other.to_path_buf()])?.collect()?;
+    Ok(df.schema() == other.schema() && df.equals_missing(&other))
+}
+
+async fn pick_columns_if_needed(
+    args: &mut DataArgs,
     io: &[(Vec<PathBuf>, Option<PathBuf>)],
-) {
+) -> Result<(), TblCliError> {
+    let pick_drop = matches!(&args.drop, Some(columns) if columns.is_empty());
+    let pick_select = matches!(&args.columns, Some(columns) if columns.is_empty());
+    if !pick_drop && !pick_select {
+        return Ok(());
+    }
+
+    let input_paths: Vec<PathBuf> = io.iter().flat_map(|(inputs, _)| inputs.clone()).collect();
+    let columns = get_column_union(&input_paths).await?;
+    if columns.is_empty() {
+        return Err(TblCliError::Error("no columns to pick from".to_string()));
+    }
+
+    if pick_drop {
+        args.drop = Some(prompt_columns("select columns to drop:", &columns)?);
+    }
+    if pick_select {
+        args.columns = Some(prompt_columns("select columns to keep:", &columns)?);
+    }
+    Ok(())
+}
+
+/// union of columns across files as (name, dtype, disk bytes)
+async fn get_column_union(paths: &[PathBuf]) -> Result<Vec<(String, String, u64)>, TblCliError> {
+    let summaries = tbl_core::parquet::get_parquet_summaries(paths).await?;
+    let mut columns: Vec<(String, String, u64)> = Vec::new();
+    for summary in summaries.iter() {
+        for ((name, dtype), column) in summary.schema.iter().zip(summary.columns.iter()) {
+            match columns
+                .iter_mut()
+                .find(|(other, _, _)| other == name.as_str())
+            {
+                Some((_, other_dtype, n_bytes)) => {
+                    let dtype = dtype.to_string();
+                    if !other_dtype.split(" | ").any(|d| d == dtype) {
+                        other_dtype.push_str(&format!(" | {}", dtype));
+                    }
+                    *n_bytes += column.n_bytes_compressed;
+                }
+                None => columns.push((
+                    name.to_string(),
+                    dtype.to_string(),
+                    column.n_bytes_compressed,
+                )),
+            }
+        }
+    }
+    Ok(columns)
+}
+
+fn prompt_columns(
+    prompt: &str,
+    columns: &[(String, String, u64)],
+) -> Result<Vec<String>, TblCliError> {
+    let options: Vec<String> = columns
+        .iter()
+        .map(|(name, dtype, n_bytes)| {
+            format!(
+                "{} ({}, {})",
+                name,
+                dtype,
+                tbl_core::formats::format_bytes(*n_bytes)
+            )
+        })
+        .collect();
+    match inquire::MultiSelect::new(prompt, options).raw_prompt() {
+        Ok(selected) if !selected.is_empty() => Ok(selected
+            .into_iter()
+            .map(|option| columns[option.index].0.clone())
+            .collect()),
+        Ok(_) => {
+            println!("[no columns selected]");
+            Err(TblCliError::Aborted)
+        }
+        Err(_) => Err(TblCliError::Aborted),
+    }
+}
+
+fn print_update_diff(
+    io: &[(Vec<PathBuf>, Option<PathBuf>)],
+    args: &DataArgs,
+) -> Result<(), TblCliError> {
+    if !args.no_summary {
+        println!();
+        println!();
+    }
+    tbl_core::formats::print_header("Affected rows");
+    let condition = match &args.update_where {
+        Some(condition) => crate::transform::parse_sql_expr(condition)?,
+        None => lit(true),
+    };
+    for input_path in io.iter().flat_map(|(input_paths, _)| input_paths.iter()) {
+        let lf = tbl_core::parquet::create_lazyframe(std::slice::from_ref(input_path))?;
+        let counts = lf
+            .select([
+                len().alias("n_rows"),
+                condition.clone().sum().alias("n_affected"),
+            ])
+            .collect()?;
+        let n_rows = counts
+            .column("n_rows")?
+            .cast(&DataType::UInt64)?
+            .u64()?
+            .get(0);
+        let n_affected = counts
+            .column("n_affected")?
+            .cast(&DataType::UInt64)?
+            .u64()?
+            .get(0);
+        tbl_core::formats::print_bullet(
+            input_path.to_string_lossy(),
+            format!(
+                "{} of {} rows",
+                tbl_core::formats::format_with_commas(n_affected.unwrap_or(0)),
+                tbl_core::formats::format_with_commas(n_rows.unwrap_or(0)),
+            ),
+        );
+    }
+    Ok(())
+}
+
+/// whether to stop before writing, for dry runs, or an error if user declines to continue
+fn exit_early_if_needed(
+    io: &[(Vec<PathBuf>, Option<PathBuf>)],
+    output_mode: &OutputMode,
+    args: &DataArgs,
+) -> Result<bool, TblCliError> {
+    let summary = !args.no_summary;
+
     // exit if performing dry run
-    if dry {
+    if args.dry {
         if summary {
             println!();
             println!();
             tbl_core::formats::print_header("Data")
         }
         println!("[dry run, exiting]");
-        std::process::exit(0);
+        return Ok(true);
     }
 
-    // exit if no files selected
-    if io.is_empty() {
+    // exit if user does not confirm write operations, previewing changes on request
+    if output_mode.writes_to_disk() & !args.confirm {
         if summary {
             println!();
             println!();
-            tbl_core::formats::print_header("Data")
         }
-        println!("[no tabular files selected]");
-        std::process::exit(0)
+        let prompt = "continue? [y/N, d to preview changes]";
+        loop {
+            match inquire::Text::new(prompt).prompt() {
+                Ok(answer) if answer.trim().eq_ignore_ascii_case("d") => {
+                    if let Err(e) = print_edit_preview(io, args) {
+                        println!("[could not preview changes: {}]", e);
+                    }
+                    println!();
+                }
+                Ok(answer) if matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") => break,
+                _ => return Err(TblCliError::Aborted),
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// print schema before and after the edit, and sample rows before and after, for an example file
+fn print_edit_preview(
+    io: &[(Vec<PathBuf>, Option<PathBuf>)],
+    args: &DataArgs,
+) -> Result<(), TblCliError> {
+    let n_sample_rows = 5;
+    let example_path = match io.first().and_then(|(input_paths, _)| input_paths.first()) {
+        Some(example_path) => example_path,
+        None => return Ok(()),
     };
+    let before = create_input_lazyframe(std::slice::from_ref(example_path), args)?;
+    let schema_before = before.clone().schema()?;
+    let schema_after = crate::transform::apply_transformations(before.clone(), args)?.schema()?;
 
-    // exit if user does not confirm write operations
-    if output_mode.writes_to_disk() & !confirm {
-        if summary {
-            println!();
-            println!();
-        }
-        let prompt = "continue? ";
-        if let Ok(true) = inquire::Confirm::new(prompt).with_default(false).prompt() {
-        } else {
-            println!("[exiting]");
-            std::process::exit(0)
+    // columns in output order, followed by dropped columns
+    let mut rows = Vec::new();
+    for (name, dtype) in schema_after.iter() {
+        let (dtype_before, change) = match schema_before.get(name) {
+            Some(dtype_before) if dtype_before == dtype => (dtype_before.to_string(), ""),
+            Some(dtype_before) => (dtype_before.to_string(), "cast"),
+            None => ("-".to_string(), "added"),
+        };
+        rows.push([
+            name.to_string(),
+            dtype_before,
+            dtype.to_string(),
+            change.to_string(),
+        ]);
+    }
+    for (name, dtype) in schema_before.iter() {
+        if !schema_after.contains(name) {
+            rows.push([
+                name.to_string(),
+                dtype.to_string(),
+                "-".to_string(),
+                "dropped".to_string(),
+            ]);
         }
     }
+    println!();
+    tbl_core::formats::print_header(format!("Schema of {}", example_path.to_string_lossy()));
+    super::print_table(&["column", "before", "after", "change"], &rows)?;
+
+    // sample rows that the edit outputs, preferring rows that updates apply to
+    let index_column = "__tbl_preview_row";
+    let indexed = before.with_row_index(index_column, None);
+    let selected = crate::transform::apply_row_selection(indexed.clone(), args)?;
+    let selected = match &args.update_where {
+        Some(condition) => selected.filter(crate::transform::parse_sql_expr(condition)?),
+        None => selected,
+    };
+    let sample_before = selected
+        .select([col(index_column)])
+        .limit(n_sample_rows)
+        .join(
+            indexed,
+            [col(index_column)],
+            [col(index_column)],
+            JoinArgs::new(JoinType::Left),
+        )
+        .drop([index_column])
+        .collect()?;
+
+    // transform sampled rows without choosing rows again, which rows of later pipeline steps
+    // and value counts can only approximate
+    let row_wise_args = crate::transform::row_wise_args(args);
+    let sample_after =
+        crate::transform::apply_transformations(sample_before.clone().lazy(), &row_wise_args)?
+            .collect()?;
+    let approximate = crate::transform::selects_rows(&row_wise_args);
+    println!();
+    tbl_core::formats::print_header("Sample rows before");
+    println!("{}", sample_before);
+    println!();
+    tbl_core::formats::print_header(if approximate {
+        "Sample rows after (approximate)"
+    } else {
+        "Sample rows after"
+    });
+    println!("{}", sample_after);
+    Ok(())
+}
+
+/// output frame of input output pair that has not been written yet
+struct PendingOutput {
+    lf: LazyFrame,
+    input_paths: Vec<PathBuf>,
+    output_path: Option<PathBuf>,
+    n_input_rows: Option<u64>,
+    pruning_stats: crate::pruning::PruningStats,
+    pruned_args: Option<DataArgs>,
+    /// key value metadata written into parquet outputs, such as lineage
+    key_values: Vec<(String, String)>,
 }
 
-fn process_io(
+/// create output frame of input output pair, or write outputs that bypass frames
+async fn prepare_io(
     input_paths: Vec<PathBuf>,
     output_path: Option<PathBuf>,
     output_mode: &OutputMode,
     args: &DataArgs,
+    key_values: Vec<(String, String)>,
+) -> Result<Option<PendingOutput>, TblCliError> {
+    if args.global {
+        crate::output::save_globally_sorted(&input_paths, output_path, output_mode, args).await?;
+        return Ok(None);
+    }
+    if args.encrypt.is_some() || args.decrypt.is_some() {
+        crate::output::save_encrypted(&input_paths, output_path, args).await?;
+        return Ok(None);
+    }
+
+    // files without rows contribute nothing to merged outputs
+    let (input_paths, empty_skipped) =
+        crate::pruning::skip_empty_files(input_paths, output_mode).await?;
+    if !empty_skipped.is_empty() && !args.no_summary {
+        println!(
+            "[skipping {} of {} input files with zero rows]",
+            empty_skipped.len(),
+            input_paths.len() + empty_skipped.len()
+        );
+    }
+
+    // dropping whole columns copies the remaining column chunks without decoding them
+    if crate::output::can_splice(&input_paths, output_path.as_deref(), output_mode, args)? {
+        crate::output::save_spliced(&input_paths, output_path, &key_values, args).await?;
+        return Ok(None);
+    }
+
+    // skip files that index sidecars show cannot contribute rows
+    let all_input_paths = if args.pruning_stats {
+        input_paths.clone()
+    } else {
+        Vec::new()
+    };
+    let (input_paths, pruned_args) =
+        crate::pruning::prune_with_indexes(input_paths, output_mode, args)?;
+    let args = pruned_args.as_ref().unwrap_or(args);
+    let index_skipped: Vec<PathBuf> = all_input_paths
+        .into_iter()
+        .filter(|path| !input_paths.contains(path))
+        .chain(empty_skipped.into_iter().filter(|_| args.pruning_stats))
+        .collect();
+    let (input_paths, mut pruning_stats) =
+        crate::pruning::prune_with_statistics(input_paths, output_mode, args).await?;
+    pruning_stats.add_skipped_files(&index_skipped).await?;
+
+    let lf = if args.approx {
+        // approximate counts are streamed from files instead of using a frame
+        crate::transform::approx_value_counts(&input_paths, args).await?
+    } else {
+        // create lazy frame
+        let lf = create_input_lazyframe(&input_paths, args)?;
+
+        // set aside rows that cannot be cast instead of failing or nulling them
+        let lf = match (&args.quarantine_dir, &args.cast) {
+            (Some(dir), Some(cast)) => quarantine_cast_failures(lf, cast, dir, &input_paths, args)?,
+            (None, Some(cast)) => {
+                crate::transform::check_datetime_parsing(&lf, cast, args.time_format.as_deref())?;
+                lf
+            }
+            _ => lf,
+        };
+
+        // transform into output frames
+        let lf = crate::transform::apply_transformations(lf, args)?;
+
+        // files rewritten in place of their inputs keep partition values in directory names,
+        // while flattened outputs store them as columns
+        if matches!(
+            output_mode,
+            OutputMode::ModifyInplace | OutputMode::SaveToDirectory
+        ) && !args.flat_output
+        {
+            drop_hive_columns(lf, &input_paths)?
+        } else {
+            lf
+        }
+    };
+
+    // count input rows before they are overwritten, to report rows removed by row filters
+    let report_rows = output_mode.writes_to_disk()
+        && output_path.is_some()
+        && !args.summary_json
+        && std::iter::once(args)
+            .chain(args.pipeline_steps.iter())
+            .any(|args| args.where_.is_some() || args.filter.is_some());
+    let n_input_rows = if report_rows {
+        let input_refs: Vec<&std::path::Path> = input_paths.iter().map(|p| p.as_path()).collect();
+        Some(
+            tbl_core::parquet::get_parquet_row_counts(&input_refs)
+                .await?
+                .iter()
+                .sum(),
+        )
+    } else {
+        None
+    };
+
+    Ok(Some(PendingOutput {
+        lf,
+        input_paths,
+        output_path,
+        n_input_rows,
+        pruning_stats,
+        pruned_args: pruned_args.clone(),
+        key_values,
+    }))
+}
+
+/// write output frame of input output pair
+async fn finish_io(
+    pending: PendingOutput,
+    output_mode: &OutputMode,
+    args: &DataArgs,
 ) -> Result<(), TblCliError> {
-    // create lazy frame
-    let lf = tbl_core::parquet::create_lazyframe(&input_paths)?;
+    let PendingOutput {
+        lf,
+        input_paths,
+        output_path,
+        n_input_rows,
+        pruning_stats,
+        pruned_args,
+        key_values,
+    } = pending;
+    let args = pruned_args.as_ref().unwrap_or(args);
+    // decoding and encoding run on the blocking pool to keep other files moving
+    let (output_mode, write_args, write_path) = (*output_mode, args.clone(), output_path.clone());
+    tokio::task::spawn_blocking(move || {
+        crate::output::output_lazyframe(
+            lf,
+            input_paths,
+            write_path,
+            &output_mode,
+            &key_values,
+            &write_args,
+        )
+    })
+    .await
+    .map_err(tbl_core::TblError::from)??;
 
-    // transform into output frames
-    let lf = crate::transform::apply_transformations(lf, args)?;
+    if let (Some(n_input_rows), Some(output_path)) = (n_input_rows, output_path) {
+        report_removed_rows(n_input_rows, &output_path).await?;
+    }
+    if args.pruning_stats {
+        pruning_stats.print();
+    }
+
+    Ok(())
+}
+
+fn quarantine_cast_failures(
+    mut lf: LazyFrame,
+    cast: &[String],
+    dir: &std::path::Path,
+    input_paths: &[PathBuf],
+    args: &DataArgs,
+) -> Result<LazyFrame, TblCliError> {
+    let schema = lf.schema()?;
+    let failures = crate::transform::get_cast_failures(&schema, cast, args.time_format.as_deref())?;
+    match crate::quarantine::create_failure_exprs(failures) {
+        Some((mask, reason)) => {
+            let mut failed = lf
+                .clone()
+                .filter(mask.clone())
+                .with_column(reason.alias(crate::quarantine::REASON_COLUMN))
+                .collect()?;
+            crate::quarantine::write_quarantine(&mut failed, dir, input_paths)?;
+            Ok(lf.filter(mask.not()))
+        }
+        None => Ok(lf),
+    }
+}
+
+/// create lazy frame of inputs, with the path, source, and row index columns requested
+pub(crate) fn create_input_lazyframe(
+    input_paths: &[PathBuf],
+    args: &DataArgs,
+) -> Result<LazyFrame, TblCliError> {
+    let mut path_columns = args
+        .path_column
+        .iter()
+        .flatten()
+        .map(|spec| tbl_core::parquet::PathColumn::parse(spec))
+        .collect::<Result<Vec<_>, _>>()?;
+    if args.with_source {
+        path_columns.push(tbl_core::parquet::PathColumn::full_path("source"));
+    }
+    let mut lf = if args.interleave {
+        interleave_inputs(input_paths, &path_columns, args)?
+    } else {
+        tbl_core::parquet::create_lazyframe_with_path_columns(input_paths, &path_columns)?
+    };
+
+    // index and source lead each row so rows can be traced back to their file
+    if args.with_source {
+        lf = lf.select([col("source"), all().exclude(["source"])]);
+    }
+    if args.with_row_index {
+        lf = lf.with_row_index("row_index", None);
+    }
+    Ok(lf)
+}
 
-    // output data
-    crate::output::output_lazyframe(lf, input_paths, output_path, output_mode, args)
+/// lazyframe taking rows from each input file in turn
+fn interleave_inputs(
+    input_paths: &[PathBuf],
+    path_columns: &[tbl_core::parquet::PathColumn],
+    args: &DataArgs,
+) -> Result<LazyFrame, TblCliError> {
+    let lfs = input_paths
+        .iter()
+        .map(|path| {
+            tbl_core::parquet::create_lazyframe_with_path_columns(
+                std::slice::from_ref(path),
+                path_columns,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    // files only need as many rows as are kept, unless rows are removed before slicing
+    let removes_rows = args.where_.is_some()
+        || args.filter.is_some()
+        || args.tail.is_some()
+        || args.quarantine_dir.is_some();
+    let limit = match args.head {
+        Some(head) if !removes_rows => Some(head + args.offset.unwrap_or(0)),
+        _ => None,
+    };
+    Ok(tbl_core::parquet::interleave_lazyframes(lfs, limit)?)
+}
+
+async fn report_removed_rows(
+    n_input_rows: u64,
+    output_path: &std::path::Path,
+) -> Result<(), TblCliError> {
+    let n_output_rows = tbl_core::parquet::get_parquet_row_count(output_path).await?;
+    tbl_core::formats::print_bullet(
+        output_path.to_string_lossy(),
+        format!(
+            "removed {} of {} rows",
+            tbl_core::formats::format_with_commas(n_input_rows.saturating_sub(n_output_rows)),
+            tbl_core::formats::format_with_commas(n_input_rows),
+        ),
+    );
+    Ok(())
+}
+
+fn drop_hive_columns(mut lf: LazyFrame, input_paths: &[PathBuf]) -> Result<LazyFrame, TblCliError> {
+    if !tbl_core::parquet::has_hive_partitions(input_paths) {
+        return Ok(lf);
+    }
+    let schema = lf.schema()?;
+    let keys: Vec<String> = tbl_core::parquet::get_hive_partitions(&input_paths[0])
+        .into_iter()
+        .map(|(key, _)| key)
+        .filter(|key| schema.contains(key))
+        .collect();
+    Ok(lf.drop(keys))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_incremental_splice_records_input_hash() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let input_path = temp_dir.path().join("input.parquet");
+        let mut df = df!("a" => [1i64, 2, 3], "b" => ["x", "y", "z"]).unwrap();
+        ParquetWriter::new(std::fs::File::create(&input_path).unwrap())
+            .finish(&mut df)
+            .unwrap();
+        let output_dir = temp_dir.path().join("output");
+        std::fs::create_dir(&output_dir).unwrap();
+        let args = DataArgs {
+            drop: Some(vec!["b".to_string()]),
+            splice: true,
+            incremental: true,
+            output_dir: Some(output_dir.clone()),
+            output_template: Some("{hash}".to_string()),
+            no_summary: true,
+            ..DataArgs::default()
+        };
+        let output_mode = OutputMode::SaveToDirectory;
+        let io = vec![(
+            vec![input_path.clone()],
+            Some(output_dir.join("{hash}.parquet")),
+        )];
+        assert!(
+            crate::output::can_splice(&io[0].0, io[0].1.as_deref(), &output_mode, &args).unwrap()
+        );
+
+        let stale = skip_up_to_date(io.clone(), &output_mode, &args)
+            .await
+            .unwrap();
+        assert_eq!(stale.len(), 1);
+        let (input_paths, output_path) = stale.into_iter().next().unwrap();
+        process_pair(&input_paths, output_path, &output_mode, &args)
+            .await
+            .unwrap();
+
+        let stale = skip_up_to_date(io, &output_mode, &args).await.unwrap();
+        assert!(stale.is_empty());
+    }
 }
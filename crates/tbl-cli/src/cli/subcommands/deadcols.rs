@@ -0,0 +1,175 @@
+use crate::styles::FontStyle;
+use crate::{DeadcolsArgs, TblCliError};
+use polars::prelude::*;
+use tbl_core::parquet::ColumnLiveness;
+use toolstr::Colorize;
+
+pub(crate) async fn deadcols_command(args: DeadcolsArgs) -> Result<(), TblCliError> {
+    let input_paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if input_paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+    if args.drop && !args.output_args.inplace && args.output_args.output_dir.is_none() {
+        return Err(TblCliError::Arg(
+            "--drop needs --inplace or --output-dir".to_string(),
+        ));
+    }
+
+    // use statistics where they are conclusive
+    let mut lf = tbl_core::parquet::create_lazyframe(&input_paths)?;
+    let schema = lf.schema()?;
+    let mut columns = if args.scan {
+        schema
+            .iter_names()
+            .map(|name| (name.to_string(), ColumnLiveness::Unknown))
+            .collect()
+    } else {
+        tbl_core::parquet::get_parquet_column_liveness(&input_paths).await?
+    };
+
+    // scan columns whose statistics are missing or inexact
+    let unknown: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, liveness))| *liveness == ColumnLiveness::Unknown)
+        .map(|(i, _)| i)
+        .collect();
+    if !unknown.is_empty() {
+        let mut exprs = vec![len().cast(DataType::UInt64).alias("_rows")];
+        for i in unknown.iter() {
+            let name = &columns[*i].0;
+            exprs.push(
+                col(name)
+                    .null_count()
+                    .cast(DataType::UInt64)
+                    .alias(&format!("_nulls_{}", i)),
+            );
+            exprs.push(
+                col(name)
+                    .n_unique()
+                    .cast(DataType::UInt64)
+                    .alias(&format!("_unique_{}", i)),
+            );
+        }
+        let counts = lf.clone().select(exprs).collect()?;
+        let get_count = |name: &str| -> Result<u64, TblCliError> {
+            Ok(counts.column(name)?.u64()?.get(0).unwrap_or(0))
+        };
+        let n_rows = get_count("_rows")?;
+        for i in unknown.iter() {
+            let n_nulls = get_count(&format!("_nulls_{}", i))?;
+            let n_unique = get_count(&format!("_unique_{}", i))?;
+            columns[*i].1 = if n_nulls == n_rows {
+                ColumnLiveness::AllNull
+            } else if n_nulls == 0 && n_unique == 1 {
+                ColumnLiveness::Constant(Vec::new())
+            } else {
+                ColumnLiveness::Live
+            };
+        }
+    }
+
+    let dead: Vec<(&String, &ColumnLiveness)> = columns
+        .iter()
+        .filter(|(_, liveness)| liveness.is_dead())
+        .map(|(name, liveness)| (name, liveness))
+        .collect();
+    if dead.is_empty() {
+        println!(
+            "[no dead columns among {} columns of {} files]",
+            columns.len(),
+            input_paths.len()
+        );
+        return Ok(());
+    }
+
+    // read the value of each constant column from its first row
+    let constant_columns: Vec<Expr> = dead
+        .iter()
+        .filter(|(_, liveness)| matches!(liveness, ColumnLiveness::Constant(_)))
+        .map(|(name, _)| col(name))
+        .collect();
+    let first_row = if constant_columns.is_empty() {
+        DataFrame::empty()
+    } else {
+        lf.clone().select(constant_columns).limit(1).collect()?
+    };
+
+    let mut names = Vec::new();
+    let mut dtypes = Vec::new();
+    let mut kinds = Vec::new();
+    let mut values = Vec::new();
+    for (name, liveness) in dead.iter() {
+        names.push(name.to_string());
+        dtypes.push(
+            schema
+                .get(name)
+                .map(|dtype| dtype.to_string())
+                .unwrap_or_default(),
+        );
+        match liveness {
+            ColumnLiveness::Constant(_) => {
+                kinds.push("constant".to_string());
+                values.push(first_row.column(name)?.get(0)?.to_string());
+            }
+            _ => {
+                kinds.push("all null".to_string());
+                values.push("null".to_string());
+            }
+        }
+    }
+    print_dead_columns(names, dtypes, kinds, values)?;
+    println!();
+    println!(
+        "{} of {} columns are dead, {} needed a scan",
+        dead.len().to_string().colorize_constant().bold(),
+        columns.len().to_string().colorize_constant().bold(),
+        unknown.len().to_string().colorize_constant().bold(),
+    );
+
+    if args.drop {
+        let drop = dead.iter().map(|(name, _)| name.to_string()).collect();
+        println!();
+        let mut data_args = args.output_args.into_data_args(args.paths, args.tree);
+        data_args.drop = Some(drop);
+        super::data_command(data_args).await?;
+    }
+    Ok(())
+}
+
+fn print_dead_columns(
+    names: Vec<String>,
+    dtypes: Vec<String>,
+    kinds: Vec<String>,
+    values: Vec<String>,
+) -> Result<(), TblCliError> {
+    // build table
+    let mut table = toolstr::Table::new();
+    table.add_column("column", names)?;
+    table.add_column("dtype", dtypes)?;
+    table.add_column("dead because", kinds)?;
+    table.add_column("value", values)?;
+
+    // create format
+    let mut name_column = toolstr::ColumnFormatShorthand::default()
+        .name("column")
+        .left_justify();
+    let mut dtype_column = toolstr::ColumnFormatShorthand::default().name("dtype");
+    let mut kind_column = toolstr::ColumnFormatShorthand::default().name("dead because");
+    let mut value_column = toolstr::ColumnFormatShorthand::default().name("value");
+    name_column.font_style = Some("".colorize_variable().into());
+    dtype_column.font_style = Some("".colorize_function().into());
+    kind_column.font_style = Some("".colorize_string().into());
+    value_column.font_style = Some("".colorize_constant().into());
+    let mut format = toolstr::TableFormat {
+        label_font_style: Some("".colorize_title().into()),
+        border_font_style: Some("".colorize_comment().into()),
+        ..Default::default()
+    };
+    format.add_column(name_column);
+    format.add_column(dtype_column);
+    format.add_column(kind_column);
+    format.add_column(value_column);
+    format.print(table)?;
+    Ok(())
+}
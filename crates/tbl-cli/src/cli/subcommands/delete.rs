@@ -0,0 +1,8 @@
+use crate::{DeleteArgs, TblCliError};
+
+pub(crate) async fn delete_command(args: DeleteArgs) -> Result<(), TblCliError> {
+    let mut data_args = args.output_args.into_data_args(args.paths, args.tree);
+    // rows where the predicate is null are kept, only rows where it is true are deleted
+    data_args.where_ = Some(format!("NOT coalesce(({}), false)", args.where_));
+    super::data_command(data_args).await
+}
@@ -0,0 +1,80 @@
+use crate::{DtypesArgs, TblCliError};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tbl_core::formats::{format_with_commas, print_header};
+
+pub(crate) async fn dtypes_command(args: DtypesArgs) -> Result<(), TblCliError> {
+    let paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    let paths: Vec<_> = paths
+        .into_iter()
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("parquet"))
+        .collect();
+    if paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+    let summaries = tbl_core::parquet::get_parquet_summaries(&paths).await?;
+
+    // files having each dtype of each column, with columns in order of first appearance
+    let mut columns: Vec<(String, BTreeMap<String, Vec<PathBuf>>)> = Vec::new();
+    for (path, summary) in paths.iter().zip(summaries.iter()) {
+        for (name, dtype) in summary.schema.iter() {
+            let index = match columns
+                .iter()
+                .position(|(column, _)| column == name.as_str())
+            {
+                Some(index) => index,
+                None => {
+                    columns.push((name.to_string(), BTreeMap::new()));
+                    columns.len() - 1
+                }
+            };
+            columns[index]
+                .1
+                .entry(dtype.to_string())
+                .or_default()
+                .push(path.clone());
+        }
+    }
+
+    let mut dtype_counts: BTreeMap<String, u64> = BTreeMap::new();
+    for (_, dtypes) in columns.iter() {
+        for dtype in dtypes.keys() {
+            *dtype_counts.entry(dtype.clone()).or_default() += 1;
+        }
+    }
+    let mut dtype_counts: Vec<(String, u64)> = dtype_counts.into_iter().collect();
+    dtype_counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    print_header(format!(
+        "{} columns in {} files",
+        format_with_commas(columns.len() as u64),
+        format_with_commas(paths.len() as u64)
+    ));
+    let rows: Vec<[String; 2]> = dtype_counts
+        .iter()
+        .map(|(dtype, count)| [dtype.clone(), format_with_commas(*count)])
+        .collect();
+    super::print_table(&["dtype", "n_columns"], &rows)?;
+
+    // the most common dtype of a column is taken as its expected dtype
+    let mut rows: Vec<[String; 4]> = Vec::new();
+    for (name, dtypes) in columns.iter().filter(|(_, dtypes)| dtypes.len() > 1) {
+        let mut dtypes: Vec<(&String, &Vec<PathBuf>)> = dtypes.iter().collect();
+        dtypes.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(b.0)));
+        for (dtype, files) in dtypes.iter() {
+            rows.push([
+                name.clone(),
+                dtype.to_string(),
+                format_with_commas(files.len() as u64),
+                files[0].to_string_lossy().to_string(),
+            ]);
+        }
+    }
+    println!();
+    if rows.is_empty() {
+        println!("[all files agree on the dtype of every column]");
+    } else {
+        print_header("Dtype disagreements");
+        super::print_table(&["column", "dtype", "n_files", "first file"], &rows)?;
+    }
+    Ok(())
+}
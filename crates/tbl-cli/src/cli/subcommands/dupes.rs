@@ -0,0 +1,212 @@
+use crate::{DupesArgs, TblCliError};
+use polars::prelude::*;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+const BATCH_SIZE: usize = 8192;
+
+pub(crate) async fn dupes_command(args: DupesArgs) -> Result<(), TblCliError> {
+    let input_paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if input_paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+
+    // group files by hash of their logical content
+    let mut by_hash: BTreeMap<tbl_core::parquet::ContentHash, Vec<PathBuf>> = BTreeMap::new();
+    for path in input_paths.iter() {
+        let hash = tbl_core::parquet::parquet_content_hash(path, BATCH_SIZE).await?;
+        by_hash.entry(hash).or_default().push(path.clone());
+    }
+    let identical: Vec<(tbl_core::parquet::ContentHash, Vec<PathBuf>)> = by_hash
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .collect();
+
+    tbl_core::formats::print_header("Identical content");
+    if identical.is_empty() {
+        println!("[no files with identical content]");
+    }
+    for (hash, paths) in identical.iter() {
+        print_group(
+            format!(
+                "{} files with {} rows",
+                paths.len(),
+                tbl_core::formats::format_with_commas(hash.n_rows)
+            ),
+            &hash.hash,
+            paths,
+        );
+    }
+
+    // group files whose key ranges overlap, ignoring files that are identical to a kept file
+    let mut overlapping = Vec::new();
+    if let Some(key) = &args.key {
+        let duplicates: Vec<&PathBuf> = identical
+            .iter()
+            .flat_map(|(_, paths)| paths.iter().skip(1))
+            .collect();
+        let paths: Vec<PathBuf> = input_paths
+            .iter()
+            .filter(|path| !duplicates.contains(path))
+            .cloned()
+            .collect();
+        overlapping = get_overlapping_groups(&paths, key)?;
+
+        println!();
+        tbl_core::formats::print_header(format!("Overlapping {} ranges", key));
+        if overlapping.is_empty() {
+            println!("[no files with overlapping {} ranges]", key);
+        }
+        for (min, max, paths) in overlapping.iter() {
+            print_group(
+                format!("{} files", paths.len()),
+                &format!("{} to {}", min, max),
+                paths,
+            );
+        }
+    }
+
+    let deletes = args.delete && !identical.is_empty();
+    let merges = args.merge && !overlapping.is_empty();
+    if !deletes && !merges {
+        return Ok(());
+    }
+    let mut edited = Vec::new();
+    if deletes {
+        edited.extend(
+            identical
+                .iter()
+                .flat_map(|(_, paths)| paths.iter().cloned()),
+        );
+    }
+    if merges {
+        edited.extend(
+            overlapping
+                .iter()
+                .flat_map(|(_, _, paths)| paths.iter().cloned()),
+        );
+    }
+    crate::protection::check_not_protected(&edited)?;
+    println!();
+    if !args.confirm {
+        let prompt = "continue? ";
+        if let Ok(true) = inquire::Confirm::new(prompt).with_default(false).prompt() {
+        } else {
+            return Err(TblCliError::Aborted);
+        }
+    }
+
+    // delete all but the first file of each identical group, once their rows are compared
+    if args.delete {
+        let mut n_deleted = 0;
+        for (_, paths) in identical.iter() {
+            let kept = read_sorted_rows(&paths[0])?;
+            for path in paths.iter().skip(1) {
+                if !read_sorted_rows(path)?.equals_missing(&kept) {
+                    println!(
+                        "[kept {}, its rows differ from {} despite equal hashes]",
+                        path.to_string_lossy(),
+                        paths[0].to_string_lossy()
+                    );
+                    continue;
+                }
+                std::fs::remove_file(path)?;
+                n_deleted += 1;
+            }
+        }
+        println!("[deleted {} duplicate files]", n_deleted);
+    }
+
+    // rewrite the first file of each overlapping group with the rows of the group, files that
+    // are identical to a kept file were excluded from groups, so rows of each file are all kept
+    if let (true, Some(key)) = (args.merge, &args.key) {
+        let mut n_merged = 0;
+        for (_, _, paths) in overlapping.iter() {
+            let mut df = tbl_core::parquet::create_lazyframe(paths)?
+                .sort(
+                    [key],
+                    SortMultipleOptions::default().with_maintain_order(true),
+                )
+                .collect()?;
+            let tmp_path = tbl_core::parquet::create_tmp_target(&paths[0]);
+            let file = std::fs::File::create(&tmp_path)?;
+            ParquetWriter::new(file).finish(&mut df)?;
+            std::fs::rename(&tmp_path, &paths[0])?;
+            for path in paths.iter().skip(1) {
+                std::fs::remove_file(path)?;
+            }
+            n_merged += paths.len();
+        }
+        println!(
+            "[merged {} files into {} files]",
+            n_merged,
+            overlapping.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// rows of file in order of all columns, so that files with rows in different orders compare equal
+///
+/// columns that cannot be sorted leave rows in file order
+fn read_sorted_rows(path: &std::path::Path) -> Result<DataFrame, TblCliError> {
+    let df = tbl_core::parquet::create_lazyframe(&[path.to_path_buf()])?.collect()?;
+    let columns: Vec<&str> = df.get_column_names();
+    match df.sort(
+        columns,
+        SortMultipleOptions::default().with_maintain_order(true),
+    ) {
+        Ok(sorted) => Ok(sorted),
+        Err(_) => Ok(df),
+    }
+}
+
+/// group files whose inclusive min to max range of key overlaps, returning groups of two or more
+fn get_overlapping_groups(
+    paths: &[PathBuf],
+    key: &str,
+) -> Result<Vec<(AnyValue<'static>, AnyValue<'static>, Vec<PathBuf>)>, TblCliError> {
+    let mut ranges = Vec::new();
+    for path in paths.iter() {
+        let df = tbl_core::parquet::create_lazyframe(std::slice::from_ref(path))?
+            .select([col(key).min().alias("min"), col(key).max().alias("max")])
+            .collect()?;
+        let min = df.column("min")?.get(0)?.into_static()?;
+        let max = df.column("max")?.get(0)?.into_static()?;
+        // files without non-null keys cannot overlap
+        if !min.is_null() {
+            ranges.push((min, max, path.clone()));
+        }
+    }
+    ranges.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    // sweep files in order of their minimum, extending the current group while ranges overlap
+    let mut groups: Vec<(AnyValue<'static>, AnyValue<'static>, Vec<PathBuf>)> = Vec::new();
+    for (min, max, path) in ranges.into_iter() {
+        match groups.last_mut() {
+            Some((_, group_max, group)) if min <= *group_max => {
+                if max > *group_max {
+                    *group_max = max;
+                }
+                group.push(path);
+            }
+            _ => groups.push((min, max, vec![path])),
+        }
+    }
+    Ok(groups
+        .into_iter()
+        .filter(|(_, _, group)| group.len() > 1)
+        .collect())
+}
+
+fn print_group(label: String, detail: &str, paths: &[PathBuf]) {
+    tbl_core::formats::print_bullet(label, detail);
+    for (i, path) in paths.iter().enumerate() {
+        if i == 0 {
+            tbl_core::formats::print_bullet_indent(path.to_string_lossy(), "kept", 4);
+        } else {
+            tbl_core::formats::print_bullet_key_indent(path.to_string_lossy(), 4);
+        }
+    }
+}
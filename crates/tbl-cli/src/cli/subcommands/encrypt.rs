@@ -0,0 +1,16 @@
+use crate::{DecryptArgs, EncryptArgs, TblCliError};
+
+pub(crate) async fn encrypt_command(args: EncryptArgs) -> Result<(), TblCliError> {
+    let mut data_args = args.output_args.into_data_args(args.paths, args.tree);
+    data_args.encrypt = Some(args.column);
+    data_args.key_id = Some(args.key_id.unwrap_or(args.key_env.clone()));
+    data_args.key_env = Some(args.key_env);
+    super::data_command(data_args).await
+}
+
+pub(crate) async fn decrypt_command(args: DecryptArgs) -> Result<(), TblCliError> {
+    let mut data_args = args.output_args.into_data_args(args.paths, args.tree);
+    data_args.decrypt = Some(args.column.unwrap_or_default());
+    data_args.key_env = Some(args.key_env);
+    super::data_command(data_args).await
+}
@@ -0,0 +1,237 @@
+use crate::{ExportArgs, TblCliError};
+use flate2::write::GzEncoder;
+use polars::prelude::*;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tbl_core::formats::format_with_commas;
+
+/// rows read from an input file at a time, bounding memory of the export
+const BATCH_ROWS: u64 = 100_000;
+
+pub(crate) async fn export_command(args: ExportArgs) -> Result<(), TblCliError> {
+    let max_rows_per_file = parse_max_rows(&args.max_rows_per_file)?;
+    let options = CsvOptions::from_args(&args)?;
+    let paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+
+    // every input must have the columns of the first, since each csv file has one header
+    let schema = tbl_core::parquet::create_lazyframe(&paths[..1])?.schema()?;
+    for path in paths.iter().skip(1) {
+        let file_schema =
+            tbl_core::parquet::create_lazyframe(std::slice::from_ref(path))?.schema()?;
+        if file_schema != schema {
+            return Err(TblCliError::Error(format!(
+                "columns of {} differ from columns of {}, use tbl conform to align them first",
+                path.to_string_lossy(),
+                paths[0].to_string_lossy()
+            )));
+        }
+    }
+
+    // the number of files follows from row counts in metadata
+    let path_refs: Vec<&Path> = paths.iter().map(|path| path.as_path()).collect();
+    let row_counts = tbl_core::parquet::get_parquet_row_counts(&path_refs).await?;
+    let total_rows: u64 = row_counts.iter().sum();
+    let n_outputs = match max_rows_per_file {
+        Some(max_rows) => total_rows.div_ceil(max_rows).max(1),
+        None => 1,
+    };
+    let extension = if args.gzip { "csv.gz" } else { "csv" };
+    let output_paths: Vec<PathBuf> = (0..n_outputs)
+        .map(|part| {
+            args.output_dir
+                .join(format!("{}_{:05}.{}", args.output_prefix, part, extension))
+        })
+        .collect();
+    if !args.overwrite {
+        if let Some(existing) = output_paths.iter().find(|path| path.exists()) {
+            return Err(TblCliError::Error(format!(
+                "{} already exists, use --overwrite to replace it",
+                existing.to_string_lossy()
+            )));
+        }
+    }
+    crate::protection::check_not_protected(&output_paths)?;
+    std::fs::create_dir_all(&args.output_dir)?;
+
+    // stream batches of each input into the current output, starting a new output when full
+    let mut outputs = output_paths.iter();
+    let mut current: Option<(CsvOutput, u64)> = None;
+    for (path, n_rows) in paths.iter().zip(row_counts) {
+        let lf = tbl_core::parquet::create_lazyframe(std::slice::from_ref(path))?;
+        let mut offset = 0;
+        while offset < n_rows {
+            let (output, written) = match current.as_mut() {
+                Some(current) => current,
+                None => {
+                    let output_path = outputs.next().ok_or_else(|| {
+                        TblCliError::Error("more rows than expected from metadata".to_string())
+                    })?;
+                    current.insert((CsvOutput::create(output_path, args.gzip)?, 0))
+                }
+            };
+            let room = max_rows_per_file.map_or(BATCH_ROWS, |max_rows| max_rows - *written);
+            let n_batch = BATCH_ROWS.min(room).min(n_rows - offset);
+            let mut df = lf.clone().slice(offset as i64, n_batch as u32).collect()?;
+            options.write(output, &mut df, *written == 0)?;
+            offset += n_batch;
+            *written += n_batch;
+            if max_rows_per_file == Some(*written) {
+                if let Some((output, _)) = current.take() {
+                    output.finish()?;
+                }
+            }
+        }
+    }
+    match current {
+        Some((output, _)) => output.finish()?,
+        // an empty dataset still gets a file with its header
+        None if total_rows == 0 => {
+            let mut output = CsvOutput::create(&output_paths[0], args.gzip)?;
+            options.write(
+                &mut output,
+                &mut DataFrame::empty_with_schema(&schema),
+                true,
+            )?;
+            output.finish()?;
+        }
+        None => {}
+    }
+
+    println!(
+        "[exported {} rows from {} files into {} {} files in {}]",
+        format_with_commas(total_rows),
+        format_with_commas(paths.len() as u64),
+        format_with_commas(n_outputs),
+        extension,
+        args.output_dir.to_string_lossy()
+    );
+    Ok(())
+}
+
+fn parse_max_rows(value: &Option<String>) -> Result<Option<u64>, TblCliError> {
+    let value = match value {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    match value.replace('_', "").parse::<u64>() {
+        Ok(max_rows) if max_rows > 0 => Ok(Some(max_rows)),
+        _ => Err(TblCliError::Arg(format!(
+            "--max-rows-per-file must be a positive integer, got {}",
+            value
+        ))),
+    }
+}
+
+/// quoting and formatting of csv fields
+struct CsvOptions {
+    separator: u8,
+    quote_char: u8,
+    quote_style: QuoteStyle,
+    null_value: String,
+    header: bool,
+}
+
+impl CsvOptions {
+    fn from_args(args: &ExportArgs) -> Result<CsvOptions, TblCliError> {
+        let quote_style = match args.quote_style.as_str() {
+            "necessary" => QuoteStyle::Necessary,
+            "always" => QuoteStyle::Always,
+            "non-numeric" => QuoteStyle::NonNumeric,
+            "never" => QuoteStyle::Never,
+            other => return Err(TblCliError::Arg(format!("invalid quote style: {}", other))),
+        };
+        Ok(CsvOptions {
+            separator: ascii_byte("--delimiter", args.delimiter)?,
+            quote_char: ascii_byte("--quote-char", args.quote_char)?,
+            quote_style,
+            null_value: args.null_value.clone(),
+            header: !args.no_header,
+        })
+    }
+
+    fn write(
+        &self,
+        output: &mut CsvOutput,
+        df: &mut DataFrame,
+        first_batch: bool,
+    ) -> Result<(), TblCliError> {
+        CsvWriter::new(output)
+            .include_header(self.header && first_batch)
+            .with_separator(self.separator)
+            .with_quote_char(self.quote_char)
+            .with_quote_style(self.quote_style)
+            .with_null_value(self.null_value.clone())
+            .finish(df)?;
+        Ok(())
+    }
+}
+
+fn ascii_byte(flag: &str, value: char) -> Result<u8, TblCliError> {
+    if value.is_ascii() {
+        Ok(value as u8)
+    } else {
+        Err(TblCliError::Arg(format!(
+            "{} must be a single ascii character",
+            flag
+        )))
+    }
+}
+
+/// csv file being written to a temporary path, moved into place when finished
+struct CsvOutput {
+    writer: CsvWriterTarget,
+    path: PathBuf,
+    tmp_path: PathBuf,
+}
+
+enum CsvWriterTarget {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl CsvOutput {
+    fn create(path: &Path, gzip: bool) -> Result<CsvOutput, TblCliError> {
+        let tmp_path = crate::output::get_tmp_path(path);
+        let file = BufWriter::new(File::create(&tmp_path)?);
+        let writer = if gzip {
+            CsvWriterTarget::Gzip(GzEncoder::new(file, flate2::Compression::default()))
+        } else {
+            CsvWriterTarget::Plain(file)
+        };
+        Ok(CsvOutput {
+            writer,
+            path: path.to_path_buf(),
+            tmp_path,
+        })
+    }
+
+    fn finish(self) -> Result<(), TblCliError> {
+        let mut file = match self.writer {
+            CsvWriterTarget::Plain(file) => file,
+            CsvWriterTarget::Gzip(encoder) => encoder.finish()?,
+        };
+        file.flush()?;
+        std::fs::rename(&self.tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+impl Write for CsvOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match &mut self.writer {
+            CsvWriterTarget::Plain(file) => file.write(buf),
+            CsvWriterTarget::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.writer {
+            CsvWriterTarget::Plain(file) => file.flush(),
+            CsvWriterTarget::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
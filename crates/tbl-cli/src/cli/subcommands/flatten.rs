@@ -0,0 +1,9 @@
+use crate::{FlattenArgs, TblCliError};
+
+pub(crate) async fn flatten_command(args: FlattenArgs) -> Result<(), TblCliError> {
+    let mut data_args = args.output_args.into_data_args(args.paths, args.tree);
+    data_args.flatten = Some(args.columns.unwrap_or_default());
+    data_args.flatten_separator = Some(args.separator);
+    data_args.flatten_recursive = args.recursive;
+    super::data_command(data_args).await
+}
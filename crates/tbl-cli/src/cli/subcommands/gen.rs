@@ -0,0 +1,232 @@
+use crate::{GenArgs, TblCliError};
+use polars::prelude::*;
+use rand::distributions::{Alphanumeric, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, Normal};
+use serde::Deserialize;
+
+/// columns of generated data
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GenSpec {
+    /// number of rows to generate
+    #[serde(default)]
+    rows: Option<usize>,
+    /// random seed, chosen at random if not given
+    #[serde(default)]
+    seed: Option<u64>,
+    columns: Vec<ColumnSpec>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ColumnSpec {
+    name: String,
+    dtype: String,
+    /// uniform, normal, sequence, or choice
+    #[serde(default)]
+    distribution: Option<String>,
+    /// lower bound of uniform values, or first value of a sequence
+    #[serde(default)]
+    min: Option<f64>,
+    /// upper bound of uniform values
+    #[serde(default)]
+    max: Option<f64>,
+    #[serde(default)]
+    mean: Option<f64>,
+    #[serde(default)]
+    std: Option<f64>,
+    /// increment between sequence values
+    #[serde(default)]
+    step: Option<f64>,
+    /// values drawn by the choice distribution
+    #[serde(default)]
+    values: Vec<serde_json::Value>,
+    /// relative weights of choice values
+    #[serde(default)]
+    weights: Vec<f64>,
+    /// length of random strings and binary values
+    #[serde(default)]
+    length: Option<usize>,
+    /// fraction of values that are null
+    #[serde(default)]
+    null_rate: f64,
+}
+
+pub(crate) async fn gen_command(args: GenArgs) -> Result<(), TblCliError> {
+    if args.path.exists() && !args.overwrite {
+        return Err(TblCliError::Arg(format!(
+            "{} already exists, use --overwrite to replace it",
+            args.path.to_string_lossy()
+        )));
+    }
+    let spec: GenSpec = super::load_spec_file(&args.spec, "spec")?;
+    let n_rows = args.rows.or(spec.rows).unwrap_or(1000);
+    let seed = args.seed.or(spec.seed).unwrap_or_else(rand::random);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let series = spec
+        .columns
+        .iter()
+        .map(|column| generate_column(column, n_rows, &mut rng))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut df = DataFrame::new(series)?;
+
+    let file = std::fs::File::create(&args.path)?;
+    ParquetWriter::new(file).finish(&mut df)?;
+    println!(
+        "generated {} with {} columns and {} rows using seed {}",
+        args.path.to_string_lossy(),
+        df.width(),
+        df.height(),
+        seed
+    );
+    Ok(())
+}
+
+fn generate_column(spec: &ColumnSpec, n: usize, rng: &mut StdRng) -> Result<Series, TblCliError> {
+    let dtype = crate::transform::parse_dtype(&spec.dtype)
+        .map_err(|_| TblCliError::Arg(format!("invalid dtype {} for {}", spec.dtype, spec.name)))?;
+    if !(0.0..=1.0).contains(&spec.null_rate) {
+        return Err(TblCliError::Arg(format!(
+            "null_rate of {} must be between 0 and 1",
+            spec.name
+        )));
+    }
+    let null_rate = spec.null_rate;
+    let default_distribution = if spec.values.is_empty() {
+        "uniform"
+    } else {
+        "choice"
+    };
+    let distribution = spec.distribution.as_deref().unwrap_or(default_distribution);
+    let is_integer = dtype.is_integer() || dtype.is_temporal();
+
+    let series = match (distribution, &dtype) {
+        ("choice", _) => {
+            if spec.values.is_empty() {
+                return Err(TblCliError::Arg(format!(
+                    "choice distribution of {} needs values",
+                    spec.name
+                )));
+            }
+            let values: Vec<String> = spec
+                .values
+                .iter()
+                .map(|value| match value {
+                    serde_json::Value::String(value) => value.clone(),
+                    value => value.to_string(),
+                })
+                .collect();
+            let weights = if spec.weights.is_empty() {
+                vec![1.0; values.len()]
+            } else {
+                spec.weights.clone()
+            };
+            let index = WeightedIndex::new(&weights).map_err(|e| {
+                TblCliError::Arg(format!("invalid weights of {}: {}", spec.name, e))
+            })?;
+            let data = with_nulls(rng, n, null_rate, |rng| values[index.sample(rng)].clone());
+            Series::new(&spec.name, data).strict_cast(&dtype)?
+        }
+        ("uniform", DataType::String) => {
+            let length = spec.length.unwrap_or(8);
+            let data = with_nulls(rng, n, null_rate, |rng| {
+                (0..length)
+                    .map(|_| rng.sample(Alphanumeric) as char)
+                    .collect::<String>()
+            });
+            Series::new(&spec.name, data)
+        }
+        ("uniform", DataType::Binary) => {
+            let length = spec.length.unwrap_or(32);
+            let data = with_nulls(rng, n, null_rate, |rng| {
+                (0..length).map(|_| rng.gen::<u8>()).collect::<Vec<u8>>()
+            });
+            Series::new(&spec.name, data)
+        }
+        ("uniform", DataType::Boolean) => {
+            let data = with_nulls(rng, n, null_rate, |rng| rng.gen_bool(0.5));
+            Series::new(&spec.name, data)
+        }
+        ("uniform", dtype) if is_integer => {
+            let (min, max) = get_bounds(spec, 0.0, 100.0)?;
+            let (min, max) = (min.round() as i64, max.round() as i64);
+            let data = with_nulls(rng, n, null_rate, |rng| rng.gen_range(min..=max));
+            Series::new(&spec.name, data).strict_cast(dtype)?
+        }
+        ("uniform", dtype) if dtype.is_numeric() => {
+            let (min, max) = get_bounds(spec, 0.0, 1.0)?;
+            let data = with_nulls(rng, n, null_rate, |rng| rng.gen_range(min..=max));
+            Series::new(&spec.name, data).strict_cast(dtype)?
+        }
+        ("normal", dtype) if dtype.is_numeric() => {
+            let normal = Normal::new(spec.mean.unwrap_or(0.0), spec.std.unwrap_or(1.0))
+                .map_err(|e| TblCliError::Arg(format!("invalid std of {}: {}", spec.name, e)))?;
+            let round = dtype.is_integer();
+            let data = with_nulls(rng, n, null_rate, |rng| {
+                let value = normal.sample(rng);
+                if round {
+                    value.round()
+                } else {
+                    value
+                }
+            });
+            Series::new(&spec.name, data).strict_cast(dtype)?
+        }
+        ("sequence", dtype) if is_integer || dtype.is_numeric() => {
+            let start = spec.min.unwrap_or(0.0);
+            let step = spec.step.unwrap_or(1.0);
+            let mut i = 0;
+            let data = with_nulls(rng, n, null_rate, |_| {
+                i += 1;
+                start + step * (i - 1) as f64
+            });
+            let series = Series::new(&spec.name, data);
+            let series = if is_integer {
+                series.strict_cast(&DataType::Int64)?
+            } else {
+                series
+            };
+            series.strict_cast(dtype)?
+        }
+        (distribution, dtype) => {
+            return Err(TblCliError::Arg(format!(
+                "distribution {} cannot generate {} values for {}",
+                distribution, dtype, spec.name
+            )))
+        }
+    };
+    Ok(series)
+}
+
+/// draw n values, replacing a fraction of them with nulls
+fn with_nulls<T>(
+    rng: &mut StdRng,
+    n: usize,
+    null_rate: f64,
+    mut sample: impl FnMut(&mut StdRng) -> T,
+) -> Vec<Option<T>> {
+    (0..n)
+        .map(|_| {
+            let value = sample(rng);
+            if null_rate > 0.0 && rng.gen_bool(null_rate) {
+                None
+            } else {
+                Some(value)
+            }
+        })
+        .collect()
+}
+
+fn get_bounds(spec: &ColumnSpec, min: f64, max: f64) -> Result<(f64, f64), TblCliError> {
+    let (min, max) = (spec.min.unwrap_or(min), spec.max.unwrap_or(max));
+    if min > max {
+        return Err(TblCliError::Arg(format!(
+            "min of {} is greater than max",
+            spec.name
+        )));
+    }
+    Ok((min, max))
+}
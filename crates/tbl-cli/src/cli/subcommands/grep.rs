@@ -0,0 +1,150 @@
+use crate::{GrepArgs, TblCliError};
+use polars::prelude::*;
+use std::path::PathBuf;
+
+const N_MATCHING: &str = "_n_matching";
+
+pub(crate) async fn grep_command(args: GrepArgs) -> Result<(), TblCliError> {
+    let input_paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if input_paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+
+    // literals are searched as escaped regexes so that both share case handling
+    let pattern = if args.regex {
+        args.pattern.clone()
+    } else {
+        regex_escape(&args.pattern)
+    };
+    let pattern = if args.ignore_case {
+        format!("(?i){}", pattern)
+    } else {
+        pattern
+    };
+    // hex encodings of binary values have no 0x prefix and use lowercase digits
+    let binary_pattern = match args.pattern.strip_prefix("0x") {
+        Some(hex) if !args.regex => format!("(?i){}", regex_escape(hex)),
+        _ => pattern.clone(),
+    };
+
+    let mut rows: Vec<[String; 3]> = Vec::new();
+    let mut matches: Vec<(PathBuf, LazyFrame, Expr)> = Vec::new();
+    let mut n_rows_total = 0;
+    for path in input_paths.iter() {
+        let mut lf = tbl_core::parquet::create_lazyframe(std::slice::from_ref(path))?;
+        let schema = lf.schema()?;
+        let columns = match &args.columns {
+            Some(selectors) => tbl_core::schemas::resolve_column_selectors(selectors, &schema)?,
+            None => schema.iter_names().map(|name| name.to_string()).collect(),
+        };
+
+        // search each column through its string representation, skipping nested columns
+        let mut searched: Vec<(String, Expr)> = Vec::new();
+        for name in columns.iter() {
+            let expr = match schema.get(name) {
+                Some(DataType::String) => col(name).str().contains(lit(pattern.clone()), true),
+                Some(DataType::Binary) => col(name)
+                    .binary()
+                    .hex_encode()
+                    .str()
+                    .contains(lit(binary_pattern.clone()), true),
+                Some(dtype) if !dtype.is_nested() => col(name)
+                    .cast(DataType::String)
+                    .str()
+                    .contains(lit(pattern.clone()), true),
+                _ => continue,
+            };
+            searched.push((name.clone(), expr.fill_null(lit(false))));
+        }
+        if searched.is_empty() {
+            continue;
+        }
+
+        let any_match = searched
+            .iter()
+            .map(|(_, expr)| expr.clone())
+            .reduce(|acc, expr| acc.or(expr))
+            .unwrap_or(lit(false));
+        let mut count_exprs = vec![any_match.clone().sum().alias(N_MATCHING)];
+        for (name, expr) in searched.iter() {
+            count_exprs.push(expr.clone().sum().alias(name));
+        }
+        let counts = lf
+            .clone()
+            .select(count_exprs)
+            .with_streaming(true)
+            .collect()?;
+        let get_count = |name: &str| -> Result<u64, TblCliError> {
+            Ok(counts
+                .column(name)?
+                .cast(&DataType::UInt64)?
+                .u64()?
+                .get(0)
+                .unwrap_or(0))
+        };
+        let n_rows = get_count(N_MATCHING)?;
+        if n_rows == 0 {
+            continue;
+        }
+        let mut matching_columns = Vec::new();
+        for (name, _) in searched.iter() {
+            if get_count(name)? > 0 {
+                matching_columns.push(name.clone());
+            }
+        }
+        n_rows_total += n_rows;
+        rows.push([
+            path.to_string_lossy().to_string(),
+            tbl_core::formats::format_with_commas(n_rows),
+            matching_columns.join(", "),
+        ]);
+        matches.push((path.clone(), lf, any_match));
+    }
+
+    tbl_core::formats::print_header("Matches");
+    tbl_core::formats::print_bullet("pattern", &args.pattern);
+    tbl_core::formats::print_bullet(
+        "files matching",
+        format!(
+            "{} / {}",
+            tbl_core::formats::format_with_commas(matches.len() as u64),
+            tbl_core::formats::format_with_commas(input_paths.len() as u64)
+        ),
+    );
+    tbl_core::formats::print_bullet(
+        "rows matching",
+        tbl_core::formats::format_with_commas(n_rows_total),
+    );
+    if rows.is_empty() {
+        return Ok(());
+    }
+    println!();
+    super::print_table(&["file", "rows", "columns"], &rows)?;
+
+    if args.rows {
+        for (path, lf, any_match) in matches.into_iter() {
+            let df = lf
+                .filter(any_match)
+                .limit(args.n)
+                .with_streaming(true)
+                .collect()?;
+            println!();
+            tbl_core::formats::print_header(path.to_string_lossy());
+            println!("{}", df);
+        }
+    }
+
+    Ok(())
+}
+
+/// escape regex metacharacters so that text matches literally
+fn regex_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$#&-~".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
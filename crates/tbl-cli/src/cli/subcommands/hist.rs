@@ -0,0 +1,119 @@
+use crate::styles::FontStyle;
+use crate::{HistArgs, TblCliError};
+use tbl_core::sketches::KllSketch;
+use toolstr::Colorize;
+
+pub(crate) async fn hist_command(args: HistArgs) -> Result<(), TblCliError> {
+    let paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+
+    let k = 400;
+    let batch_size = 8192;
+    let sketch =
+        tbl_core::parquet::parquet_quantile_sketch(&paths, &args.column, k, batch_size).await?;
+    if sketch.n_items() == 0 {
+        println!("[no non-null values in {}]", args.column);
+        return Ok(());
+    }
+
+    tbl_core::formats::print_header(format!("Histogram of {}", args.column));
+    print_histogram(&sketch, args.bins.max(1), args.width.max(1));
+    println!();
+    println!(
+        "{} values in {} files, bin counts are approximate",
+        tbl_core::formats::format_with_commas(sketch.n_items())
+            .colorize_constant()
+            .bold(),
+        paths.len().to_string().colorize_constant().bold(),
+    );
+    println!();
+    println!();
+    tbl_core::formats::print_header("Quantiles");
+    print_quantiles(&sketch)?;
+    Ok(())
+}
+
+fn print_histogram(sketch: &KllSketch, n_bins: usize, width: usize) {
+    let bins = sketch.histogram(n_bins);
+    let max_count = bins.iter().map(|(_, _, count)| *count).max().unwrap_or(0);
+    let lowers: Vec<String> = bins
+        .iter()
+        .map(|(lower, _, _)| tbl_core::formats::format_float(*lower))
+        .collect();
+    let uppers: Vec<String> = bins
+        .iter()
+        .map(|(_, upper, _)| tbl_core::formats::format_float(*upper))
+        .collect();
+    let lower_width = lowers.iter().map(|s| s.len()).max().unwrap_or(0);
+    let upper_width = uppers.iter().map(|s| s.len()).max().unwrap_or(0);
+    for ((lower, upper), (_, _, count)) in lowers.iter().zip(uppers.iter()).zip(bins.iter()) {
+        let bar = render_bar(*count, max_count, width);
+        println!(
+            "{} {} {} {} {} {}",
+            format!("{:>width$}", lower, width = lower_width).colorize_variable(),
+            "-".colorize_comment(),
+            format!("{:>width$}", upper, width = upper_width).colorize_variable(),
+            "│".colorize_comment(),
+            bar.colorize_title(),
+            tbl_core::formats::format_with_commas(*count).colorize_constant(),
+        );
+    }
+}
+
+/// render bar using eighth blocks for sub-character resolution
+fn render_bar(count: u64, max_count: u64, width: usize) -> String {
+    if max_count == 0 {
+        return String::new();
+    }
+    let eighths = (count as f64 / max_count as f64 * (width * 8) as f64).round() as usize;
+    let partials = ["", "▏", "▎", "▍", "▌", "▋", "▊", "▉"];
+    let mut bar = "█".repeat(eighths / 8);
+    bar.push_str(partials[eighths % 8]);
+    bar
+}
+
+fn print_quantiles(sketch: &KllSketch) -> Result<(), TblCliError> {
+    let quantiles = [
+        ("min", 0.0),
+        ("p1", 0.01),
+        ("p5", 0.05),
+        ("p25", 0.25),
+        ("p50", 0.5),
+        ("p75", 0.75),
+        ("p95", 0.95),
+        ("p99", 0.99),
+        ("max", 1.0),
+    ];
+    let names: Vec<String> = quantiles.iter().map(|(name, _)| name.to_string()).collect();
+    let values: Vec<String> = quantiles
+        .iter()
+        .map(|(_, q)| {
+            sketch
+                .quantile(*q)
+                .map(tbl_core::formats::format_float)
+                .unwrap_or_default()
+        })
+        .collect();
+
+    // build table
+    let mut table = toolstr::Table::new();
+    table.add_column("quantile", names)?;
+    table.add_column("value", values)?;
+
+    // create format
+    let mut name_column = toolstr::ColumnFormatShorthand::default().name("quantile");
+    let mut value_column = toolstr::ColumnFormatShorthand::default().name("value");
+    name_column.font_style = Some("".colorize_variable().into());
+    value_column.font_style = Some("".colorize_constant().into());
+    let mut format = toolstr::TableFormat {
+        label_font_style: Some("".colorize_title().into()),
+        border_font_style: Some("".colorize_comment().into()),
+        ..Default::default()
+    };
+    format.add_column(name_column);
+    format.add_column(value_column);
+    format.print(table)?;
+    Ok(())
+}
@@ -0,0 +1,355 @@
+use crate::{ImportArgs, TblCliError};
+use polars::prelude::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// overrides applied to inferred csv types
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CsvSchema {
+    /// values read as null in every column
+    #[serde(default)]
+    null_values: Vec<String>,
+    #[serde(default)]
+    columns: BTreeMap<String, ColumnSpec>,
+}
+
+/// either a bare dtype or a table of column options
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ColumnSpec {
+    Dtype(String),
+    Options(ColumnOptions),
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ColumnOptions {
+    dtype: String,
+    /// strftime format of date and datetime columns
+    #[serde(default)]
+    format: Option<String>,
+    /// character used as decimal point, such as `,`
+    #[serde(default)]
+    decimal_separator: Option<String>,
+    /// values read as null in this column
+    #[serde(default)]
+    null_values: Vec<String>,
+}
+
+impl ColumnSpec {
+    fn options(&self) -> ColumnOptions {
+        match self {
+            ColumnSpec::Dtype(dtype) => ColumnOptions {
+                dtype: dtype.clone(),
+                format: None,
+                decimal_separator: None,
+                null_values: Vec::new(),
+            },
+            ColumnSpec::Options(options) => options.clone(),
+        }
+    }
+}
+
+pub(crate) async fn import_command(args: ImportArgs) -> Result<(), TblCliError> {
+    let schema: CsvSchema = match &args.schema {
+        Some(path) => super::load_spec_file(path, "schema")?,
+        None => CsvSchema::default(),
+    };
+    let columns = schema
+        .columns
+        .iter()
+        .map(|(name, spec)| {
+            let options = spec.options();
+            let dtype = crate::transform::parse_dtype(&options.dtype).map_err(|_| {
+                TblCliError::Arg(format!("invalid dtype {} for {}", options.dtype, name))
+            })?;
+            Ok((name.clone(), dtype, options))
+        })
+        .collect::<Result<Vec<_>, TblCliError>>()?;
+
+    if args.validate {
+        tbl_core::formats::print_header("Invalid values");
+    } else {
+        // refuse to write into protected directories
+        let mut written: Vec<PathBuf> = args
+            .paths
+            .iter()
+            .map(|path| get_output_path(path, &args.output_dir, args.xlsx))
+            .collect();
+        written.extend(args.output_dir.iter().cloned());
+        crate::protection::check_not_protected(&written)?;
+        if let Some(output_dir) = &args.output_dir {
+            std::fs::create_dir_all(output_dir)?;
+        }
+    }
+    let n_total = args.paths.len();
+    let options = tbl_core::executor::ExecutorOptions {
+        max_concurrent: args.jobs.max(1),
+        ..Default::default()
+    };
+    let cancel = tbl_core::executor::CancelToken::new();
+    let shared = Arc::new((args.clone(), schema, columns));
+    let op = move |path: PathBuf| {
+        let shared = shared.clone();
+        async move {
+            tokio::task::spawn_blocking(move || {
+                let (args, schema, columns) = shared.as_ref();
+                import_file(&path, args, schema, columns).map(|outcome| (path, outcome))
+            })
+            .await
+            .map_err(tbl_core::TblError::from)?
+        }
+    };
+    let mut n_invalid_total = 0;
+    let mut progress_line = crate::summary::ProgressLine::new(n_total);
+    let on_complete = |progress, result: Result<(PathBuf, ImportOutcome), TblCliError>| {
+        let (path, outcome) = result?;
+        progress_line.clear();
+        match outcome {
+            ImportOutcome::UpToDate => {
+                tbl_core::formats::print_bullet(path.to_string_lossy(), "up to date")
+            }
+            ImportOutcome::Validated { invalid, n_rows } => {
+                for (name, count) in invalid.into_iter() {
+                    n_invalid_total += count;
+                    tbl_core::formats::print_bullet(
+                        format!("{}: {}", path.to_string_lossy(), name),
+                        format!(
+                            "{} of {} rows",
+                            tbl_core::formats::format_with_commas(count),
+                            tbl_core::formats::format_with_commas(n_rows)
+                        ),
+                    );
+                }
+            }
+            ImportOutcome::Written {
+                n_written,
+                output_path,
+            } => tbl_core::formats::print_bullet(
+                path.to_string_lossy(),
+                format!(
+                    "{} rows written to {}",
+                    tbl_core::formats::format_with_commas(n_written as u64),
+                    output_path.to_string_lossy()
+                ),
+            ),
+        }
+        progress_line.update(&progress);
+        Ok(())
+    };
+    tbl_core::executor::run_per_file(args.paths.clone(), &options, &cancel, op, on_complete)
+        .await?;
+    progress_line.finish();
+    if args.validate && n_invalid_total == 0 {
+        println!("[all values match schema]");
+    }
+    Ok(())
+}
+
+/// result of importing one csv file
+enum ImportOutcome {
+    UpToDate,
+    /// number of invalid values of each column with invalid values
+    Validated {
+        invalid: Vec<(String, u64)>,
+        n_rows: u64,
+    },
+    Written {
+        n_written: usize,
+        output_path: PathBuf,
+    },
+}
+
+/// validate or convert one csv file
+fn import_file(
+    path: &PathBuf,
+    args: &ImportArgs,
+    schema: &CsvSchema,
+    columns: &[(String, DataType, ColumnOptions)],
+) -> Result<ImportOutcome, TblCliError> {
+    let output_path = get_output_path(path, &args.output_dir, args.xlsx);
+    if args.incremental
+        && !args.validate
+        && tbl_core::filesystem::is_output_up_to_date(std::slice::from_ref(path), &output_path)
+    {
+        return Ok(ImportOutcome::UpToDate);
+    }
+    if !args.validate && output_path.exists() && !args.overwrite {
+        return Err(TblCliError::Arg(format!(
+            "{} already exists, use --overwrite to replace it",
+            output_path.to_string_lossy()
+        )));
+    }
+    let mut lf = read_csv(path, schema, columns)?;
+
+    // count values that are present in the csv but fail to convert
+    let n_rows = lf.clone().select([len()]).collect()?;
+    let n_rows = n_rows.get_columns()[0]
+        .cast(&DataType::UInt64)?
+        .u64()?
+        .get(0);
+    let mut failures = Vec::new();
+    let mut checks = Vec::new();
+    for (name, dtype, options) in columns.iter() {
+        let raw = clean_raw_column(name, options);
+        let invalid = raw
+            .clone()
+            .is_not_null()
+            .and(convert_column(raw, dtype, options).is_null());
+        checks.push(invalid.clone().sum().alias(name));
+        failures.push((invalid, format!("invalid {} value in {}", dtype, name)));
+    }
+    let invalid = if checks.is_empty() {
+        DataFrame::default()
+    } else {
+        lf.clone().select(checks).collect()?
+    };
+    let mut invalid_columns = Vec::new();
+    for series in invalid.get_columns() {
+        let count = series.cast(&DataType::UInt64)?.u64()?.get(0).unwrap_or(0);
+        if count > 0 {
+            invalid_columns.push((series.name().to_string(), count));
+        }
+    }
+    if args.validate {
+        return Ok(ImportOutcome::Validated {
+            invalid: invalid_columns,
+            n_rows: n_rows.unwrap_or(0),
+        });
+    }
+    let n_invalid: u64 = invalid_columns.iter().map(|(_, count)| count).sum();
+    if n_invalid > 0 {
+        let dir = args.quarantine_dir.as_ref().ok_or_else(|| {
+            TblCliError::Error(format!(
+                "{} has {} values that do not match the schema, use --validate to inspect",
+                path.to_string_lossy(),
+                n_invalid
+            ))
+        })?;
+
+        // set aside invalid rows and convert the rest
+        if let Some((mask, reason)) = crate::quarantine::create_failure_exprs(failures) {
+            let mut failed = lf
+                .clone()
+                .filter(mask.clone())
+                .with_column(reason.alias(crate::quarantine::REASON_COLUMN))
+                .collect()?;
+            crate::quarantine::write_quarantine(&mut failed, dir, std::slice::from_ref(path))?;
+            lf = lf.filter(mask.not());
+        }
+    }
+
+    // convert
+    let exprs: Vec<Expr> = columns
+        .iter()
+        .map(|(name, dtype, options)| {
+            convert_column(clean_raw_column(name, options), dtype, options).alias(name)
+        })
+        .collect();
+    let lf = lf.with_columns(exprs);
+
+    // an interrupted import leaves no output that --incremental would take as up to date
+    let tmp_path = tbl_core::parquet::create_tmp_target(&output_path);
+    let written = if args.xlsx {
+        crate::output::save_xlsx(
+            lf,
+            &output_path,
+            &tmp_path,
+            args.sheet_name.as_deref(),
+            args.max_xlsx_rows,
+        )
+    } else {
+        (|| {
+            let mut df = lf.collect()?;
+            let file = std::fs::File::create(&tmp_path)?;
+            ParquetWriter::new(file).finish(&mut df)?;
+            Ok(df.height())
+        })()
+    };
+    let n_written = match written {
+        Ok(n_written) => n_written,
+        Err(e) => {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(e);
+        }
+    };
+    std::fs::rename(&tmp_path, &output_path)?;
+    Ok(ImportOutcome::Written {
+        n_written,
+        output_path,
+    })
+}
+
+/// read csv, keeping overridden columns as strings until they are converted
+fn read_csv(
+    path: &Path,
+    schema: &CsvSchema,
+    columns: &[(String, DataType, ColumnOptions)],
+) -> Result<LazyFrame, TblCliError> {
+    let overrides: Schema = columns
+        .iter()
+        .map(|(name, _, _)| Field::new(name, DataType::String))
+        .collect();
+    let mut reader =
+        LazyCsvReader::new(path).with_dtype_overwrite(Some(Arc::new(overrides.clone())));
+    if !schema.null_values.is_empty() {
+        reader = reader.with_null_values(Some(NullValues::AllColumns(schema.null_values.clone())));
+    }
+    let mut lf = reader.finish()?;
+    let csv_schema = lf.schema()?;
+    for name in overrides.iter_names() {
+        if !csv_schema.contains(name) {
+            return Err(TblCliError::MissingSchemaError(format!(
+                "column {} not found in {}",
+                name,
+                path.to_string_lossy()
+            )));
+        }
+    }
+    Ok(lf)
+}
+
+/// apply null markers and decimal separator to raw string column
+fn clean_raw_column(name: &str, options: &ColumnOptions) -> Expr {
+    let mut expr = col(name);
+    for null_value in options.null_values.iter() {
+        expr = when(expr.clone().eq(lit(null_value.clone())))
+            .then(lit(NULL).cast(DataType::String))
+            .otherwise(expr);
+    }
+    if let Some(separator) = &options.decimal_separator {
+        expr = expr
+            .str()
+            .replace_all(lit(separator.clone()), lit("."), true);
+    }
+    expr
+}
+
+/// convert raw string column into dtype, producing nulls for invalid values
+fn convert_column(raw: Expr, dtype: &DataType, options: &ColumnOptions) -> Expr {
+    let strptime_options = StrptimeOptions {
+        format: options.format.clone(),
+        strict: false,
+        ..Default::default()
+    };
+    match dtype {
+        DataType::Date => raw.str().to_date(strptime_options),
+        DataType::Datetime(time_unit, time_zone) => raw.str().to_datetime(
+            Some(*time_unit),
+            time_zone.clone(),
+            strptime_options,
+            lit("raise"),
+        ),
+        dtype => raw.cast(dtype.clone()),
+    }
+}
+
+fn get_output_path(path: &Path, output_dir: &Option<PathBuf>, xlsx: bool) -> PathBuf {
+    let output_path = path.with_extension(if xlsx { "xlsx" } else { "parquet" });
+    match (output_dir, output_path.file_name()) {
+        (Some(output_dir), Some(file_name)) => output_dir.join(file_name),
+        _ => output_path,
+    }
+}
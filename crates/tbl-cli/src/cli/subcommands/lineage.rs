@@ -0,0 +1,58 @@
+use crate::lineage::Lineage;
+use crate::{LineageArgs, TblCliError};
+use tbl_core::formats::{print_bullet_indent, print_header};
+
+pub(crate) async fn lineage_command(args: LineageArgs) -> Result<(), TblCliError> {
+    for (i, path) in args.paths.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        print_header(format!("Lineage of {}", path.to_string_lossy()));
+        match crate::lineage::read_lineage(path).await? {
+            Some(lineage) => {
+                let mut visited = std::collections::HashSet::new();
+                print_lineage(&lineage, 0, &mut visited).await?
+            }
+            None => println!("[no lineage recorded, write outputs with --lineage to record it]"),
+        }
+    }
+    Ok(())
+}
+
+/// print lineage and the lineage of its sources, indenting each generation
+///
+/// each file stores one generation, earlier generations are read from sources that still have
+/// the fingerprints they had when they were read
+async fn print_lineage(
+    lineage: &Lineage,
+    indent: usize,
+    visited: &mut std::collections::HashSet<std::path::PathBuf>,
+) -> Result<(), TblCliError> {
+    print_bullet_indent(
+        "written",
+        format!("{} by tbl {}", lineage.time, lineage.tbl_version),
+        indent,
+    );
+    print_bullet_indent("command", &lineage.command, indent);
+    for source in lineage.sources.iter() {
+        let unchanged = source.path.exists()
+            && tbl_core::parquet::parquet_footer_fingerprint(&source.path)
+                .is_ok_and(|fingerprint| fingerprint == source.fingerprint);
+        let status = match (source.path.exists(), unchanged) {
+            (false, _) => "missing",
+            (true, true) => "unchanged",
+            (true, false) => "changed since",
+        };
+        print_bullet_indent(
+            "source",
+            format!("{} [{}]", source.path.to_string_lossy(), status),
+            indent,
+        );
+        if unchanged && visited.insert(source.path.clone()) {
+            if let Ok(Some(source_lineage)) = crate::lineage::read_lineage(&source.path).await {
+                Box::pin(print_lineage(&source_lineage, indent + 4, visited)).await?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,17 +1,32 @@
+use crate::styles::FontStyle;
 use crate::{LsArgs, TblCliError};
+use polars::prelude::*;
+use std::path::PathBuf;
 use toolstr::Colorize;
 
 pub(crate) async fn ls_command(ls_args: LsArgs) -> Result<(), TblCliError> {
     // get paths
     let paths = tbl_core::filesystem::get_input_paths(&ls_args.paths, ls_args.tree, true)?;
 
-    if paths.is_empty() {
-        println!("[no tabular paths]");
-        return Ok(());
+    // filter paths by schema contents
+    let paths = filter_by_columns(paths, &ls_args.has_column, &ls_args.dtype).await?;
+
+    if paths.is_empty() && ls_args.has_column.is_none() && ls_args.dtype.is_none() {
+        return Err(TblCliError::no_inputs(&ls_args.paths, ls_args.tree));
+    } else if paths.is_empty() {
+        return Err(TblCliError::NoInputs(
+            "no inputs found with matching columns".to_string(),
+        ));
     }
 
     // print file names
-    print_file_names(&paths, ls_args.n, ls_args.absolute)?;
+    if let Some(key) = &ls_args.key_range {
+        print_key_ranges(&paths, key, ls_args.overlaps, ls_args.n, ls_args.absolute).await?;
+    } else if ls_args.long {
+        print_file_details(&paths, ls_args.n, ls_args.absolute).await?;
+    } else {
+        print_file_names(&paths, ls_args.n, ls_args.absolute)?;
+    }
 
     // print stats
     print_stats(&paths).await?;
@@ -19,25 +34,73 @@ pub(crate) async fn ls_command(ls_args: LsArgs) -> Result<(), TblCliError> {
     Ok(())
 }
 
-fn print_file_names(
-    paths: &[std::path::PathBuf],
-    n: Option<usize>,
-    absolute: bool,
-) -> Result<(), TblCliError> {
+async fn filter_by_columns(
+    paths: Vec<PathBuf>,
+    has_column: &Option<Vec<String>>,
+    dtype: &Option<Vec<String>>,
+) -> Result<Vec<PathBuf>, TblCliError> {
+    if has_column.is_none() && dtype.is_none() {
+        return Ok(paths);
+    }
+
+    // parse required columns and dtypes
+    let mut required: Vec<(String, Option<String>)> = Vec::new();
+    for column in has_column.iter().flatten() {
+        required.push((column.clone(), None))
+    }
+    for spec in dtype.iter().flatten() {
+        match spec.split_once('=') {
+            Some((column, dtype)) => {
+                required.push((column.to_string(), Some(dtype.to_lowercase())))
+            }
+            None => {
+                return Err(TblCliError::Arg(
+                    "--dtype syntax is COLUMN=DTYPE".to_string(),
+                ))
+            }
+        }
+    }
+
+    let schemas = tbl_core::parquet::get_parquet_schemas(&paths).await?;
+    let filtered = paths
+        .into_iter()
+        .zip(schemas)
+        .filter(|(_, schema)| {
+            required
+                .iter()
+                .all(|(column, dtype)| match (schema.get(column), dtype) {
+                    (Some(actual), Some(dtype)) => dtype_matches(actual, dtype),
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                })
+        })
+        .map(|(path, _)| path)
+        .collect();
+    Ok(filtered)
+}
+
+/// match dtype against name as displayed by `tbl schema`, e.g. `binary` or `datetime`
+fn dtype_matches(dtype: &DataType, name: &str) -> bool {
+    let dtype = dtype.to_string().to_lowercase();
+    dtype == name || dtype.starts_with(&format!("{}[", name))
+}
+
+fn get_display_paths(paths: &[PathBuf], absolute: bool) -> Result<Vec<PathBuf>, TblCliError> {
     // clear common prefix
-    let display_paths = if absolute || (paths.len() == 1) {
-        paths.to_vec()
+    if absolute || (paths.len() == 1) {
+        Ok(paths.to_vec())
     } else {
         let common_prefix = tbl_core::filesystem::get_common_prefix(paths)?;
         let mut new_paths = Vec::new();
         for path in paths.iter() {
             new_paths.push(path.strip_prefix(&common_prefix)?.to_owned())
         }
-        new_paths
-    };
+        Ok(new_paths)
+    }
+}
 
-    // decide number of files to print
-    let n_print = match n {
+fn get_n_print(n: Option<usize>) -> usize {
+    match n {
         Some(n) => n,
         None => {
             if let Some((_, height)) = term_size::dimensions() {
@@ -50,26 +113,236 @@ fn print_file_names(
                 100
             }
         }
-    };
-
-    // print out file names or paths
-    for path in display_paths.iter().take(n_print) {
-        println!("{}", path.to_string_lossy().purple())
     }
-    if n_print < paths.len() {
+}
+
+fn print_n_not_shown(n_print: usize, n_paths: usize) {
+    if n_print < n_paths {
         println!(
             "{}",
             format!(
                 "... {} files not shown",
-                tbl_core::formats::format_with_commas((paths.len() - n_print) as u64).bold()
+                tbl_core::formats::format_with_commas((n_paths - n_print) as u64).bold()
             )
-            .truecolor(150, 150, 150)
+            .as_str()
+            .colorize_comment()
+        );
+    }
+}
+
+fn print_file_names(
+    paths: &[std::path::PathBuf],
+    n: Option<usize>,
+    absolute: bool,
+) -> Result<(), TblCliError> {
+    let display_paths = get_display_paths(paths, absolute)?;
+
+    // decide number of files to print
+    let n_print = get_n_print(n);
+
+    // print out file names or paths
+    for path in display_paths.iter().take(n_print) {
+        println!("{}", path.to_string_lossy().purple())
+    }
+    print_n_not_shown(n_print, paths.len());
+
+    Ok(())
+}
+
+async fn print_file_details(
+    paths: &[PathBuf],
+    n: Option<usize>,
+    absolute: bool,
+) -> Result<(), TblCliError> {
+    let display_paths = get_display_paths(paths, absolute)?;
+    let n_print = get_n_print(n);
+
+    // gather per-file summaries
+    let summaries =
+        tbl_core::parquet::get_parquet_summaries(&paths[..n_print.min(paths.len())]).await?;
+    let mut fingerprints = Vec::new();
+    let mut rows = Vec::new();
+    let mut bytes = Vec::new();
+    let mut names = Vec::new();
+    for (path, summary) in display_paths.iter().zip(summaries.iter()) {
+        fingerprints.push(tbl_core::parquet::schema_fingerprint(&summary.schema));
+        rows.push(tbl_core::formats::format_with_commas(summary.n_rows));
+        bytes.push(tbl_core::formats::format_bytes(summary.n_bytes_compressed));
+        names.push(path.to_string_lossy().to_string());
+    }
+
+    // build table
+    let mut table = toolstr::Table::new();
+    table.add_column("schema", fingerprints)?;
+    table.add_column("rows", rows)?;
+    table.add_column("disk size", bytes)?;
+    table.add_column("path", names)?;
+
+    // create format
+    let mut schema_column = toolstr::ColumnFormatShorthand::default().name("schema");
+    let mut rows_column = toolstr::ColumnFormatShorthand::default().name("rows");
+    let mut bytes_column = toolstr::ColumnFormatShorthand::default().name("disk size");
+    let mut path_column = toolstr::ColumnFormatShorthand::default()
+        .name("path")
+        .left_justify();
+    schema_column.font_style = Some("".colorize_variable().into());
+    rows_column.font_style = Some("".colorize_constant().into());
+    bytes_column.font_style = Some("".colorize_constant().into());
+    path_column.font_style = Some("".colorize_function().into());
+    let mut format = toolstr::TableFormat {
+        label_font_style: Some("".colorize_title().into()),
+        border_font_style: Some("".colorize_comment().into()),
+        ..Default::default()
+    };
+    format.add_column(schema_column);
+    format.add_column(rows_column);
+    format.add_column(bytes_column);
+    format.add_column(path_column);
+    format.print(table)?;
+
+    print_n_not_shown(n_print, paths.len());
+
+    Ok(())
+}
+
+async fn print_key_ranges(
+    paths: &[PathBuf],
+    key: &str,
+    overlaps: bool,
+    n: Option<usize>,
+    absolute: bool,
+) -> Result<(), TblCliError> {
+    let display_paths = get_display_paths(paths, absolute)?;
+
+    // read ranges from statistics, scanning files whose statistics are missing
+    let stats_ranges = tbl_core::parquet::get_parquet_key_ranges(paths, key).await?;
+    let mut ranges: Vec<(Option<tbl_core::parquet::KeyBounds>, PathBuf)> = Vec::new();
+    for ((path, range), display_path) in paths.iter().zip(stats_ranges).zip(display_paths) {
+        let range = match range {
+            Some(range) => Some(range),
+            None => scan_key_range(path, key)?,
+        };
+        ranges.push((range, display_path));
+    }
+
+    // flag files whose range overlaps a range starting before it ends
+    let mut flags = vec![false; ranges.len()];
+    if overlaps {
+        ranges.sort_by(|a, b| match (&a.0, &b.0) {
+            (Some(a), Some(b)) => a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        let mut previous: Option<(AnyValue<'static>, usize)> = None;
+        for (i, (range, _)) in ranges.iter().enumerate() {
+            if let Some((min, max)) = range {
+                if let Some((previous_max, previous_i)) = &previous {
+                    if min <= previous_max {
+                        flags[i] = true;
+                        flags[*previous_i] = true;
+                    }
+                }
+                match &previous {
+                    Some((previous_max, _)) if max <= previous_max => {}
+                    _ => previous = Some((max.clone(), i)),
+                }
+            }
+        }
+    }
+
+    // build table
+    let n_print = get_n_print(n);
+    let display = |value: &AnyValue<'_>| match value {
+        AnyValue::String(value) => value.to_string(),
+        AnyValue::StringOwned(value) => value.to_string(),
+        value => value.to_string(),
+    };
+    let mut mins = Vec::new();
+    let mut maxs = Vec::new();
+    let mut names = Vec::new();
+    for (range, path) in ranges.iter().take(n_print) {
+        let (min, max) = match range {
+            Some((min, max)) => (display(min), display(max)),
+            None => (String::new(), String::new()),
+        };
+        mins.push(min);
+        maxs.push(max);
+        names.push(path.to_string_lossy().to_string());
+    }
+    let mut table = toolstr::Table::new();
+    table.add_column("min", mins)?;
+    table.add_column("max", maxs)?;
+    if overlaps {
+        let overlap_flags: Vec<String> = flags
+            .iter()
+            .take(n_print)
+            .map(|flag| if *flag { "overlap" } else { "" }.to_string())
+            .collect();
+        table.add_column("overlaps", overlap_flags)?;
+    }
+    table.add_column("path", names)?;
+
+    // create format
+    let mut min_column = toolstr::ColumnFormatShorthand::default().name("min");
+    let mut max_column = toolstr::ColumnFormatShorthand::default().name("max");
+    let mut path_column = toolstr::ColumnFormatShorthand::default()
+        .name("path")
+        .left_justify();
+    min_column.font_style = Some("".colorize_constant().into());
+    max_column.font_style = Some("".colorize_constant().into());
+    path_column.font_style = Some("".colorize_function().into());
+    let mut format = toolstr::TableFormat {
+        label_font_style: Some("".colorize_title().into()),
+        border_font_style: Some("".colorize_comment().into()),
+        ..Default::default()
+    };
+    format.add_column(min_column);
+    format.add_column(max_column);
+    if overlaps {
+        let mut overlaps_column = toolstr::ColumnFormatShorthand::default().name("overlaps");
+        overlaps_column.font_style = Some("".colorize_variable().into());
+        format.add_column(overlaps_column);
+    }
+    format.add_column(path_column);
+    format.print(table)?;
+
+    print_n_not_shown(n_print, paths.len());
+    if overlaps {
+        let n_overlapping = flags.iter().filter(|flag| **flag).count();
+        println!(
+            "{} files have key ranges overlapping another file",
+            tbl_core::formats::format_with_commas(n_overlapping as u64)
+                .as_str()
+                .colorize_constant()
+                .bold()
         );
     }
 
     Ok(())
 }
 
+/// min and max of key column computed by scanning file, none if all values are null
+pub(crate) fn scan_key_range(
+    path: &PathBuf,
+    key: &str,
+) -> Result<Option<tbl_core::parquet::KeyBounds>, TblCliError> {
+    let schema = tbl_core::parquet::create_lazyframe(std::slice::from_ref(path))?.schema()?;
+    if schema.get(key).is_none() {
+        return Ok(None);
+    }
+    let df = tbl_core::parquet::create_lazyframe(std::slice::from_ref(path))?
+        .select([col(key).min().alias("min"), col(key).max().alias("max")])
+        .collect()?;
+    let min = tbl_core::parquet::into_static_value(df.column("min")?.get(0)?)?;
+    let max = tbl_core::parquet::into_static_value(df.column("max")?.get(0)?)?;
+    if min.is_null() {
+        Ok(None)
+    } else {
+        Ok(Some((min, max)))
+    }
+}
+
 async fn print_stats(paths: &[std::path::PathBuf]) -> Result<(), TblCliError> {
     // get total file size
     let mut total_size: u64 = 0;
@@ -87,11 +360,16 @@ async fn print_stats(paths: &[std::path::PathBuf]) -> Result<(), TblCliError> {
     println!(
         "{} rows stored in {} across {} tabular files",
         tbl_core::formats::format_with_commas(row_counts.iter().sum())
-            .green()
+            .as_str()
+            .colorize_constant()
+            .bold(),
+        tbl_core::formats::format_bytes(total_size)
+            .as_str()
+            .colorize_constant()
             .bold(),
-        tbl_core::formats::format_bytes(total_size).green().bold(),
         tbl_core::formats::format_with_commas(paths.len() as u64)
-            .green()
+            .as_str()
+            .colorize_constant()
             .bold()
     );
 
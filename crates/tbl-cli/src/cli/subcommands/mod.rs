@@ -1,11 +1,119 @@
+mod align;
+pub(crate) use align::*;
+
+mod bench;
+pub(crate) use bench::*;
+
+mod cast;
+pub(crate) use cast::*;
+
+mod check;
+pub(crate) use check::*;
+
+mod cmp;
+pub(crate) use cmp::*;
+
+mod conform;
+pub(crate) use conform::*;
+
+mod corr;
+pub(crate) use corr::*;
+
 mod data;
 pub(crate) use data::*;
 
+mod deadcols;
+pub(crate) use deadcols::*;
+
+mod delete;
+pub(crate) use delete::*;
+
+mod dtypes;
+pub(crate) use dtypes::*;
+
+mod dupes;
+pub(crate) use dupes::*;
+
+mod encrypt;
+pub(crate) use encrypt::*;
+
+mod export;
+pub(crate) use export::*;
+
+mod flatten;
+pub(crate) use flatten::*;
+
+mod gen;
+pub(crate) use gen::*;
+
+mod grep;
+pub(crate) use grep::*;
+
+mod hist;
+pub(crate) use hist::*;
+
+mod import;
+pub(crate) use import::*;
+
+mod lineage;
+pub(crate) use lineage::*;
+
 mod ls;
 pub(crate) use ls::*;
 
+mod mv;
+pub(crate) use mv::*;
+
+mod new;
+pub(crate) use new::*;
+
+mod normalize_names;
+pub(crate) use normalize_names::*;
+
+mod parse_json;
+pub(crate) use parse_json::*;
+
+mod plan;
+pub(crate) use plan::*;
+
+mod plot;
+pub(crate) use plot::*;
+
+mod protect;
+pub(crate) use protect::*;
+
+mod reconcile;
+pub(crate) use reconcile::*;
+
+mod remote;
+pub(crate) use remote::*;
+
+mod run;
+pub(crate) use run::*;
+
+mod scan_pii;
+pub(crate) use scan_pii::*;
+
 mod schema;
 pub(crate) use schema::*;
 
 mod schemas;
 pub(crate) use schemas::*;
+
+mod serve;
+pub(crate) use serve::*;
+
+mod size_estimate;
+pub(crate) use size_estimate::*;
+
+mod stats;
+pub(crate) use stats::*;
+
+mod tail;
+pub(crate) use tail::*;
+
+mod touch_metadata;
+pub(crate) use touch_metadata::*;
+
+mod update;
+pub(crate) use update::*;
@@ -0,0 +1,159 @@
+use super::print_table;
+use crate::{MvArgs, TblCliError};
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tbl_core::filesystem::OutputTemplate;
+use tbl_core::formats::format_with_commas;
+
+pub(crate) async fn mv_command(args: MvArgs) -> Result<(), TblCliError> {
+    let template = OutputTemplate::parse(&args.template)?;
+    let paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+
+    // render new name of each file from its content
+    let mut renamed = Vec::new();
+    for (part, path) in paths.iter().enumerate() {
+        let values = get_template_values(&template, path, part).await?;
+        let ext = path.extension().unwrap_or_default().to_string_lossy();
+        let mut file_name = template.render(&values, &[])?;
+        // names without an extension keep the extension of the file
+        if !file_name.contains('.') && !ext.is_empty() {
+            file_name = format!("{}.{}", file_name, ext);
+        }
+        if file_name.contains('/') {
+            return Err(TblCliError::Arg(format!(
+                "new name of {} contains a slash: {}",
+                path.to_string_lossy(),
+                file_name
+            )));
+        }
+        renamed.push(path.with_file_name(file_name));
+    }
+    tbl_core::filesystem::check_output_collisions(&paths, &renamed)?;
+    for target in renamed.iter() {
+        if target.exists() && !paths.contains(target) {
+            return Err(TblCliError::Error(format!(
+                "cannot rename to {}, file already exists",
+                target.to_string_lossy()
+            )));
+        }
+    }
+
+    // list renames
+    let renames: Vec<(PathBuf, PathBuf)> = paths
+        .into_iter()
+        .zip(renamed)
+        .filter(|(path, target)| path != target)
+        .collect();
+    if renames.is_empty() {
+        println!("[all files already match template]");
+        return Ok(());
+    }
+    let sources: Vec<PathBuf> = renames.iter().map(|(path, _)| path.clone()).collect();
+    crate::protection::check_not_protected(&sources)?;
+    tbl_core::formats::print_header("Renames");
+    let rows: Vec<[String; 2]> = renames
+        .iter()
+        .map(|(path, target)| {
+            [
+                path.to_string_lossy().to_string(),
+                target
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+            ]
+        })
+        .collect();
+    print_table(&["file", "new name"], &rows)?;
+    println!();
+    if args.dry {
+        println!("[dry run, exiting]");
+        return Ok(());
+    }
+    if !args.confirm {
+        let prompt = "continue? ";
+        if let Ok(true) = inquire::Confirm::new(prompt).with_default(false).prompt() {
+        } else {
+            return Err(TblCliError::Aborted);
+        }
+    }
+
+    // index sidecars follow their files
+    for (path, target) in renames.iter() {
+        std::fs::rename(path, target)?;
+        let index_path = tbl_core::parquet::index_path(path);
+        if index_path.exists() {
+            std::fs::rename(&index_path, tbl_core::parquet::index_path(target))?;
+        }
+    }
+    println!(
+        "[renamed {} files]",
+        format_with_commas(renames.len() as u64)
+    );
+    Ok(())
+}
+
+/// values of template variables for file, computing aggregates of columns in one scan
+async fn get_template_values(
+    template: &OutputTemplate,
+    path: &Path,
+    part: usize,
+) -> Result<HashMap<String, String>, TblCliError> {
+    let mut values: HashMap<String, String> = tbl_core::parquet::get_hive_partitions(path)
+        .into_iter()
+        .collect();
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = path.extension().unwrap_or_default().to_string_lossy();
+    values.insert("stem".to_string(), stem.to_string());
+    values.insert("ext".to_string(), ext.to_string());
+    values.insert("part".to_string(), part.to_string());
+
+    let mut exprs = Vec::new();
+    for (name, spec) in template.variables().iter() {
+        if name == "row_count" {
+            let n_rows = tbl_core::parquet::get_parquet_row_count(path).await?;
+            values.insert(name.clone(), n_rows.to_string());
+            continue;
+        }
+        let (function, column) = match name.strip_suffix(')').and_then(|name| name.split_once('('))
+        {
+            Some(aggregate) => aggregate,
+            None => continue,
+        };
+        let expr = match function {
+            "min" => col(column).min(),
+            "max" => col(column).max(),
+            "first" => col(column).first(),
+            "last" => col(column).last(),
+            _ => {
+                return Err(TblCliError::Arg(format!(
+                    "unknown function in template variable {{{}}}, use min, max, first, or last",
+                    name
+                )))
+            }
+        };
+        let (expr, key) = match spec {
+            Some(spec) if spec.contains('%') => {
+                (expr.dt().to_string(spec), format!("{}:{}", name, spec))
+            }
+            _ => (expr.cast(DataType::String), name.clone()),
+        };
+        exprs.push(expr.alias(&key));
+    }
+    if exprs.is_empty() {
+        return Ok(values);
+    }
+
+    let df = tbl_core::parquet::create_lazyframe(&[path.to_path_buf()])?
+        .select(exprs)
+        .collect()?;
+    for column in df.get_columns().iter() {
+        let value = column.str()?.get(0).unwrap_or("null").to_string();
+        values.insert(column.name().to_string(), value);
+    }
+    Ok(values)
+}
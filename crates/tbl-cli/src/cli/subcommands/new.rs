@@ -0,0 +1,87 @@
+use crate::{NewArgs, TblCliError};
+use polars::prelude::*;
+
+pub(crate) async fn new_command(args: NewArgs) -> Result<(), TblCliError> {
+    if args.path.exists() && !args.overwrite {
+        return Err(TblCliError::Arg(format!(
+            "{} already exists, use --overwrite to replace it",
+            args.path.to_string_lossy()
+        )));
+    }
+
+    // gather columns from schema file and from arguments
+    let mut columns: Vec<(String, DataType)> = Vec::new();
+    if let Some(schema_file) = &args.schema_file {
+        if schema_file.extension().and_then(|s| s.to_str()) == Some("parquet") {
+            let schema = tbl_core::parquet::get_parquet_schema(schema_file).await?;
+            for (name, dtype) in schema.iter() {
+                columns.push((name.to_string(), dtype.clone()));
+            }
+        } else {
+            let contents = std::fs::read_to_string(schema_file)?;
+            for line in contents.lines() {
+                let line = line.trim();
+                if !line.is_empty() && !line.starts_with('#') {
+                    columns.push(parse_column_spec(line)?);
+                }
+            }
+        }
+    }
+    for spec in args.columns.iter() {
+        columns.push(parse_column_spec(spec)?);
+    }
+    if columns.is_empty() {
+        return Err(TblCliError::Arg(
+            "specify columns as NAME:TYPE or with --schema-file".to_string(),
+        ));
+    }
+
+    // build frame
+    let series = columns
+        .iter()
+        .map(|(name, dtype)| create_default_series(name, dtype, args.rows))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut df = DataFrame::new(series)?;
+
+    // write file
+    let file = std::fs::File::create(&args.path)?;
+    ParquetWriter::new(file).finish(&mut df)?;
+    println!(
+        "created {} with {} columns and {} rows",
+        args.path.to_string_lossy(),
+        df.width(),
+        df.height()
+    );
+    Ok(())
+}
+
+fn parse_column_spec(spec: &str) -> Result<(String, DataType), TblCliError> {
+    match spec.split_once(':') {
+        Some((name, dtype)) if !name.trim().is_empty() => Ok((
+            name.trim().to_string(),
+            crate::transform::parse_dtype(dtype.trim())
+                .map_err(|_| TblCliError::Arg(format!("invalid data type in {}", spec)))?,
+        )),
+        _ => Err(TblCliError::Arg(format!(
+            "invalid column {}, syntax is NAME:TYPE",
+            spec
+        ))),
+    }
+}
+
+/// create column of zero values, empty strings, or false
+fn create_default_series(name: &str, dtype: &DataType, n: usize) -> Result<Series, TblCliError> {
+    if n == 0 {
+        return Ok(Series::new_empty(name, dtype));
+    }
+    let series = match dtype {
+        DataType::String => Series::new(name, vec![""; n]),
+        DataType::Boolean => Series::new(name, vec![false; n]),
+        DataType::Binary => Series::new(name, vec![&[] as &[u8]; n]),
+        dtype if dtype.is_numeric() || dtype.is_temporal() => {
+            Series::new(name, vec![0i32; n]).cast(dtype)?
+        }
+        dtype => Series::full_null(name, n, dtype),
+    };
+    Ok(series)
+}
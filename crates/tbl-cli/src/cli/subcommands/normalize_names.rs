@@ -0,0 +1,76 @@
+use crate::{NormalizeNamesArgs, TblCliError};
+use std::collections::BTreeMap;
+use tbl_core::names::NameCase;
+
+pub(crate) async fn normalize_names_command(args: NormalizeNamesArgs) -> Result<(), TblCliError> {
+    let case: NameCase = args.case.parse()?;
+    let input_paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if input_paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+
+    // plan renames of every file, finding names that would collide within a file
+    let mut renames: BTreeMap<(String, String), usize> = BTreeMap::new();
+    let mut collisions: Vec<[String; 3]> = Vec::new();
+    for path in input_paths.iter() {
+        let schema = tbl_core::parquet::get_parquet_schema(path).await?;
+        let mut targets: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for name in schema.iter_names() {
+            let normalized = tbl_core::names::normalize_name(name, case, &args.replace_invalid);
+            if normalized.is_empty() {
+                collisions.push([
+                    path.to_string_lossy().to_string(),
+                    "(empty)".to_string(),
+                    name.to_string(),
+                ]);
+            } else if normalized != name.as_str() {
+                *renames
+                    .entry((name.to_string(), normalized.clone()))
+                    .or_insert(0) += 1;
+            }
+            targets
+                .entry(normalized)
+                .or_default()
+                .push(name.to_string());
+        }
+        for (normalized, names) in targets.into_iter() {
+            if names.len() > 1 && !normalized.is_empty() {
+                collisions.push([
+                    path.to_string_lossy().to_string(),
+                    normalized,
+                    names.join(", "),
+                ]);
+            }
+        }
+    }
+
+    if renames.is_empty() && collisions.is_empty() {
+        println!(
+            "[column names of {} files already follow {} case]",
+            input_paths.len(),
+            case
+        );
+        return Ok(());
+    }
+
+    tbl_core::formats::print_header("Renames");
+    let rows: Vec<[String; 3]> = renames
+        .into_iter()
+        .map(|((name, normalized), n_files)| [name, normalized, n_files.to_string()])
+        .collect();
+    super::print_table(&["column", "renamed to", "files"], &rows)?;
+
+    if !collisions.is_empty() {
+        println!();
+        tbl_core::formats::print_header("Collisions");
+        super::print_table(&["file", "name", "columns"], &collisions)?;
+        return Err(TblCliError::Arg(format!(
+            "{} names would collide, rename those columns first",
+            collisions.len()
+        )));
+    }
+
+    let mut data_args = args.output_args.into_data_args(args.paths, args.tree);
+    data_args.normalize_names = Some((case, args.replace_invalid));
+    super::data_command(data_args).await
+}
@@ -0,0 +1,193 @@
+use crate::{ParseJsonArgs, TblCliError};
+use polars::prelude::*;
+use std::path::{Path, PathBuf};
+
+pub(crate) async fn parse_json_command(args: ParseJsonArgs) -> Result<(), TblCliError> {
+    let paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+
+    // infer a single type from the values of every file so that all rewritten files match,
+    // reading and parsing files in parallel and merging their types in path order
+    let options = tbl_core::executor::ExecutorOptions {
+        max_concurrent: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        ..Default::default()
+    };
+    let columns = Arc::new(args.column.clone());
+    let infer_rows = args.infer_rows;
+    let op = move |path: PathBuf| {
+        let columns = columns.clone();
+        async move {
+            tokio::task::spawn_blocking(move || infer_file_types(&path, &columns, infer_rows))
+                .await
+                .map_err(tbl_core::TblError::from)?
+        }
+    };
+    let mut file_types = vec![Vec::new(); paths.len()];
+    let on_complete = |progress: tbl_core::executor::FileProgress,
+                       result: Result<Vec<JsonType>, TblCliError>| {
+        file_types[progress.index] = result?;
+        Ok(())
+    };
+    let cancel = tbl_core::executor::CancelToken::new();
+    tbl_core::executor::run_per_file(paths, &options, &cancel, op, on_complete).await?;
+    let mut types = vec![JsonType::Null; args.column.len()];
+    for file_types in file_types.into_iter() {
+        for (json_type, file_type) in types.iter_mut().zip(file_types) {
+            *json_type = std::mem::replace(json_type, JsonType::Null).merge(file_type);
+        }
+    }
+    let parse_json = args
+        .column
+        .iter()
+        .cloned()
+        .zip(types.iter().map(JsonType::to_dtype))
+        .collect();
+
+    let mut data_args = args.output_args.into_data_args(args.paths, args.tree);
+    data_args.parse_json = parse_json;
+    super::data_command(data_args).await
+}
+
+/// type of each json column of a file, from its first infer_rows rows or all rows
+fn infer_file_types(
+    path: &Path,
+    columns: &[String],
+    infer_rows: Option<usize>,
+) -> Result<Vec<JsonType>, TblCliError> {
+    let lf = tbl_core::parquet::create_lazyframe(&[path.to_path_buf()])?
+        .select(columns.iter().map(|column| col(column)).collect::<Vec<_>>());
+    let lf = match infer_rows {
+        Some(n) => lf.limit(n as u32),
+        None => lf,
+    };
+    let values = lf.collect()?;
+    let mut types = Vec::new();
+    for (column, series) in columns.iter().zip(values.get_columns()) {
+        let mut json_type = JsonType::Null;
+        for value in series.str()?.into_iter().flatten() {
+            let value: serde_json::Value = serde_json::from_str(value).map_err(|e| {
+                TblCliError::Error(format!(
+                    "invalid json in column {} of {}: {}",
+                    column,
+                    path.to_string_lossy(),
+                    e
+                ))
+            })?;
+            json_type = json_type.merge(JsonType::of(&value));
+        }
+        types.push(json_type);
+    }
+    Ok(types)
+}
+
+/// type of json values, tracking the sign and size of integers to choose an integer dtype
+#[derive(Clone, Debug, PartialEq)]
+enum JsonType {
+    Null,
+    Boolean,
+    /// integers, noting whether any are negative and whether any exceed i64::MAX
+    Integer {
+        negative: bool,
+        beyond_i64: bool,
+    },
+    Float,
+    String,
+    List(Box<JsonType>),
+    Struct(Vec<(String, JsonType)>),
+}
+
+impl JsonType {
+    fn of(value: &serde_json::Value) -> JsonType {
+        match value {
+            serde_json::Value::Null => JsonType::Null,
+            serde_json::Value::Bool(_) => JsonType::Boolean,
+            serde_json::Value::Number(number) if number.is_i64() || number.is_u64() => {
+                JsonType::Integer {
+                    negative: number.as_i64().is_some_and(|n| n < 0),
+                    beyond_i64: !number.is_i64(),
+                }
+            }
+            serde_json::Value::Number(_) => JsonType::Float,
+            serde_json::Value::String(_) => JsonType::String,
+            serde_json::Value::Array(items) => JsonType::List(Box::new(
+                items
+                    .iter()
+                    .map(JsonType::of)
+                    .fold(JsonType::Null, JsonType::merge),
+            )),
+            serde_json::Value::Object(fields) => JsonType::Struct(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), JsonType::of(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// combine types of two json values, keeping every struct field seen in either
+    fn merge(self, other: JsonType) -> JsonType {
+        match (self, other) {
+            (JsonType::Null, other) | (other, JsonType::Null) => other,
+            (
+                JsonType::Integer {
+                    negative,
+                    beyond_i64,
+                },
+                JsonType::Integer {
+                    negative: other_negative,
+                    beyond_i64: other_beyond_i64,
+                },
+            ) => JsonType::Integer {
+                negative: negative || other_negative,
+                beyond_i64: beyond_i64 || other_beyond_i64,
+            },
+            (JsonType::Integer { .. }, JsonType::Float)
+            | (JsonType::Float, JsonType::Integer { .. }) => JsonType::Float,
+            (JsonType::List(left), JsonType::List(right)) => {
+                JsonType::List(Box::new(left.merge(*right)))
+            }
+            (JsonType::Struct(mut fields), JsonType::Struct(right_fields)) => {
+                for (name, right_type) in right_fields.into_iter() {
+                    match fields.iter_mut().find(|(field, _)| *field == name) {
+                        Some((_, field_type)) => {
+                            let merged = std::mem::replace(field_type, JsonType::Null);
+                            *field_type = merged.merge(right_type);
+                        }
+                        None => fields.push((name, right_type)),
+                    }
+                }
+                JsonType::Struct(fields)
+            }
+            (left, right) if left == right => left,
+            // values with conflicting types fall back to strings
+            _ => JsonType::String,
+        }
+    }
+
+    /// dtype holding every value, integers beyond i64::MAX are UInt64 unless some are
+    /// negative, in which case no integer dtype fits them and they are kept as strings
+    fn to_dtype(&self) -> DataType {
+        match self {
+            JsonType::Null => DataType::Null,
+            JsonType::Boolean => DataType::Boolean,
+            JsonType::Integer {
+                beyond_i64: false, ..
+            } => DataType::Int64,
+            JsonType::Integer {
+                negative: false, ..
+            } => DataType::UInt64,
+            JsonType::Integer { .. } => DataType::String,
+            JsonType::Float => DataType::Float64,
+            JsonType::String => DataType::String,
+            JsonType::List(item) => DataType::List(Box::new(item.to_dtype())),
+            JsonType::Struct(fields) => DataType::Struct(
+                fields
+                    .iter()
+                    .map(|(name, field_type)| Field::new(name, field_type.to_dtype()))
+                    .collect(),
+            ),
+        }
+    }
+}
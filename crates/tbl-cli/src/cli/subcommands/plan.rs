@@ -0,0 +1,98 @@
+use crate::{PlanArgs, TblCliError};
+use polars_plan::plans::{ArenaLpIter, IR};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+pub(crate) async fn plan_command(args: PlanArgs) -> Result<(), TblCliError> {
+    let args = *args.data_args;
+    let input_paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if input_paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+
+    // skip files the same way data mode would, counting what remains to be scanned
+    let n_inputs = input_paths.len();
+    let output_mode = super::decide_output_mode(&args)?;
+    let (kept_paths, pruned_args) =
+        crate::pruning::prune_with_indexes(input_paths.clone(), &output_mode, &args)?;
+    let mut args = pruned_args.unwrap_or(args);
+    args.pruning_stats = true;
+    let index_skipped: Vec<PathBuf> = input_paths
+        .into_iter()
+        .filter(|path| !kept_paths.contains(path))
+        .collect();
+    let (input_paths, mut pruning_stats) =
+        crate::pruning::prune_with_statistics(kept_paths, &output_mode, &args).await?;
+    pruning_stats.add_skipped_files(&index_skipped).await?;
+
+    // build the same frame that data mode would collect
+    let lf = super::create_input_lazyframe(&input_paths, &args)?;
+    let lf = crate::transform::apply_transformations(lf, &args)?;
+
+    tbl_core::formats::print_header("Optimized plan");
+    println!("{}", lf.describe_optimized_plan()?);
+
+    // group scans that read the same columns with the same predicate
+    let plan = lf.to_alp_optimized()?;
+    let mut scans: BTreeMap<(String, String), Vec<PathBuf>> = BTreeMap::new();
+    for (_, ir) in (&plan.lp_arena).iter(plan.lp_top) {
+        if let IR::Scan {
+            paths,
+            file_info,
+            predicate,
+            file_options,
+            ..
+        } = ir
+        {
+            let n_columns = file_info.schema.len();
+            let projection = match &file_options.with_columns {
+                Some(columns) => format!(
+                    "{} ({} of {} columns)",
+                    columns.join(", "),
+                    columns.len(),
+                    n_columns
+                ),
+                None => format!("all {} columns", n_columns),
+            };
+            let predicate = match predicate {
+                Some(predicate) => predicate.display(&plan.expr_arena).to_string(),
+                None => "none".to_string(),
+            };
+            scans
+                .entry((projection, predicate))
+                .or_default()
+                .extend(paths.iter().cloned());
+        }
+    }
+
+    println!();
+    tbl_core::formats::print_header("Scans");
+    for ((projection, predicate), paths) in scans.iter() {
+        tbl_core::formats::print_bullet("files", paths.len().to_string());
+        tbl_core::formats::print_bullet("projected columns", projection);
+        tbl_core::formats::print_bullet("pushed down predicate", predicate);
+    }
+
+    // list the files and row groups that statistics show can match
+    let mut scanned: Vec<&PathBuf> = scans.values().flatten().collect();
+    scanned.sort();
+    scanned.dedup();
+    println!();
+    tbl_core::formats::print_header(format!("Scanned files ({} of {})", scanned.len(), n_inputs));
+    for path in scanned.into_iter() {
+        let (_, file_stats) =
+            crate::pruning::prune_with_statistics(vec![path.clone()], &output_mode, &args).await?;
+        tbl_core::formats::print_bullet(
+            path.to_string_lossy(),
+            format!(
+                "{} of {} row groups, {}",
+                file_stats.n_row_groups - file_stats.n_skipped_row_groups,
+                file_stats.n_row_groups,
+                tbl_core::formats::format_bytes(file_stats.n_bytes - file_stats.n_skipped_bytes)
+            ),
+        );
+    }
+    pruning_stats.print();
+
+    Ok(())
+}
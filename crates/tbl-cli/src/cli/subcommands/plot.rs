@@ -0,0 +1,219 @@
+use crate::styles::FontStyle;
+use crate::{PlotArgs, TblCliError};
+use polars::prelude::*;
+
+pub(crate) async fn plot_command(args: PlotArgs) -> Result<(), TblCliError> {
+    let paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+
+    // leave room for y axis labels
+    let label_width = 10;
+    let width = match args.width {
+        Some(width) => width,
+        None => term_size::dimensions()
+            .map(|(width, _)| width.saturating_sub(label_width + 3))
+            .unwrap_or(70),
+    }
+    .clamp(10, 400);
+    let height = args.height.clamp(2, 200);
+
+    // aggregate y values into one bucket per horizontal dot
+    let lf = tbl_core::parquet::create_lazyframe(&paths)?
+        .select([col(&args.x), col(&args.y)])
+        .drop_nulls(None);
+    let bounds = lf
+        .clone()
+        .select([
+            col(&args.x).min().alias("x_min"),
+            col(&args.x).max().alias("x_max"),
+            col(&args.x)
+                .cast(DataType::Float64)
+                .min()
+                .alias("x_min_f64"),
+            col(&args.x)
+                .cast(DataType::Float64)
+                .max()
+                .alias("x_max_f64"),
+        ])
+        .collect()?;
+    let (x_min, x_max) = match (
+        bounds.column("x_min_f64")?.f64()?.get(0),
+        bounds.column("x_max_f64")?.f64()?.get(0),
+    ) {
+        (Some(x_min), Some(x_max)) => (x_min, x_max),
+        _ => {
+            println!("[no rows with both {} and {}]", args.x, args.y);
+            return Ok(());
+        }
+    };
+    let n_buckets = width * 2;
+    let span = (x_max - x_min).max(f64::MIN_POSITIVE);
+    let bucket = ((col(&args.x).cast(DataType::Float64) - lit(x_min)) / lit(span)
+        * lit((n_buckets - 1) as f64))
+    .floor()
+    .cast(DataType::Int64)
+    .alias("bucket");
+    let y = col(&args.y).cast(DataType::Float64);
+    let agg = match args.agg.as_str() {
+        "mean" => y.mean(),
+        "sum" => y.sum(),
+        "min" => y.min(),
+        "max" => y.max(),
+        "median" => y.median(),
+        "count" => y.count().cast(DataType::Float64),
+        other => {
+            return Err(TblCliError::Arg(format!(
+                "unknown aggregation {}, use mean, sum, min, max, median, or count",
+                other
+            )))
+        }
+    };
+    let points = lf
+        .select([bucket, col(&args.y)])
+        .group_by([col("bucket")])
+        .agg([agg.alias("y")])
+        .sort(["bucket"], Default::default())
+        .collect()?;
+    let buckets: Vec<i64> = points
+        .column("bucket")?
+        .i64()?
+        .into_no_null_iter()
+        .collect();
+    let values: Vec<Option<f64>> = points.column("y")?.f64()?.into_iter().collect();
+    let points: Vec<(usize, f64)> = buckets
+        .into_iter()
+        .zip(values)
+        .filter_map(|(bucket, value)| value.map(|value| (bucket as usize, value)))
+        .collect();
+
+    // render chart
+    let y_min = points.iter().map(|(_, y)| *y).fold(f64::INFINITY, f64::min);
+    let y_max = points
+        .iter()
+        .map(|(_, y)| *y)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let mut canvas = BrailleCanvas::new(width, height);
+    let to_dot_y = |value: f64| {
+        let fraction = if y_max > y_min {
+            (value - y_min) / (y_max - y_min)
+        } else {
+            0.5
+        };
+        ((1.0 - fraction) * (canvas.dot_height() - 1) as f64).round() as usize
+    };
+    let dots: Vec<(usize, usize)> = points.iter().map(|(x, y)| (*x, to_dot_y(*y))).collect();
+    if let [(x, y)] = dots.as_slice() {
+        canvas.set(*x, *y);
+    }
+    for pair in dots.windows(2) {
+        canvas.line(pair[0], pair[1]);
+    }
+
+    tbl_core::formats::print_header(format!("{}({}) by {}", args.agg, args.y, args.x));
+    let rows = canvas.rows();
+    for (i, row) in rows.iter().enumerate() {
+        let label = if i == 0 {
+            tbl_core::formats::format_float(y_max)
+        } else if i + 1 == rows.len() {
+            tbl_core::formats::format_float(y_min)
+        } else {
+            String::new()
+        };
+        println!(
+            "{} {} {}",
+            format!("{:>width$}", label, width = label_width).colorize_constant(),
+            "┤".colorize_comment(),
+            row.colorize_title(),
+        );
+    }
+    println!(
+        "{} {}",
+        " ".repeat(label_width),
+        format!("└{}", "─".repeat(width + 1)).colorize_comment()
+    );
+    let x_min_label = any_value_to_string(bounds.column("x_min")?.get(0)?);
+    let x_max_label = any_value_to_string(bounds.column("x_max")?.get(0)?);
+    let gap = (width + 2).saturating_sub(x_min_label.len() + x_max_label.len());
+    println!(
+        "{} {}{}{}",
+        " ".repeat(label_width),
+        x_min_label.colorize_variable(),
+        " ".repeat(gap),
+        x_max_label.colorize_variable(),
+    );
+    Ok(())
+}
+
+/// grid of braille characters, each holding 2 x 4 dots
+struct BrailleCanvas {
+    width: usize,
+    height: usize,
+    cells: Vec<u8>,
+}
+
+impl BrailleCanvas {
+    fn new(width: usize, height: usize) -> Self {
+        BrailleCanvas {
+            width,
+            height,
+            cells: vec![0; width * height],
+        }
+    }
+
+    fn dot_height(&self) -> usize {
+        self.height * 4
+    }
+
+    fn set(&mut self, x: usize, y: usize) {
+        let (column, row) = (x / 2, y / 4);
+        if column >= self.width || row >= self.height {
+            return;
+        }
+        let bits = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+        self.cells[row * self.width + column] |= bits[y % 4][x % 2];
+    }
+
+    /// draw line between two dots using bresenham's algorithm
+    fn line(&mut self, start: (usize, usize), end: (usize, usize)) {
+        let (mut x, mut y) = (start.0 as i64, start.1 as i64);
+        let (x1, y1) = (end.0 as i64, end.1 as i64);
+        let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+        let (sx, sy) = (if x < x1 { 1 } else { -1 }, if y < y1 { 1 } else { -1 });
+        let mut error = dx + dy;
+        loop {
+            self.set(x as usize, y as usize);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * error;
+            if e2 >= dy {
+                error += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                error += dx;
+                y += sy;
+            }
+        }
+    }
+
+    fn rows(&self) -> Vec<String> {
+        self.cells
+            .chunks(self.width)
+            .map(|row| {
+                row.iter()
+                    .map(|bits| char::from_u32(0x2800 + *bits as u32).unwrap_or(' '))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn any_value_to_string(value: AnyValue<'_>) -> String {
+    match value {
+        AnyValue::String(s) => s.to_string(),
+        value => value.to_string(),
+    }
+}
@@ -0,0 +1,28 @@
+use crate::protection::PROTECT_MARKER;
+use crate::{ProtectArgs, TblCliError};
+
+pub(crate) async fn protect_command(args: ProtectArgs) -> Result<(), TblCliError> {
+    for dir in args.dirs.iter() {
+        if !dir.is_dir() {
+            return Err(TblCliError::Arg(format!(
+                "{} is not a directory",
+                dir.to_string_lossy()
+            )));
+        }
+    }
+    for dir in args.dirs.iter() {
+        let marker = dir.join(PROTECT_MARKER);
+        if args.remove {
+            if marker.exists() {
+                std::fs::remove_file(&marker)?;
+                println!("[unprotected {}]", dir.to_string_lossy());
+            } else {
+                println!("[{} has no protection marker]", dir.to_string_lossy());
+            }
+        } else {
+            std::fs::write(&marker, "")?;
+            println!("[protected {}]", dir.to_string_lossy());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,212 @@
+use super::print_table;
+use crate::{ReconcileArgs, TblCliError};
+use polars::prelude::*;
+use std::path::{Path, PathBuf};
+use tbl_core::formats::format_with_commas;
+
+const N_LEFT: &str = "_n_left";
+const N_RIGHT: &str = "_n_right";
+
+pub(crate) async fn reconcile_command(args: ReconcileArgs) -> Result<(), TblCliError> {
+    if !(0.0..=1.0).contains(&args.threshold) {
+        return Err(TblCliError::Arg(
+            "--threshold must be between 0 and 1".to_string(),
+        ));
+    }
+    let left_paths = get_dataset_paths(&args.left, args.tree)?;
+    let right_paths = get_dataset_paths(&args.right, args.tree)?;
+    let mut lf_left = tbl_core::parquet::create_lazyframe(&left_paths)?;
+    let mut lf_right = tbl_core::parquet::create_lazyframe(&right_paths)?;
+    let schema_left = lf_left.schema()?;
+    let schema_right = lf_right.schema()?;
+    for key in args.key.iter() {
+        if !schema_left.contains(key) || !schema_right.contains(key) {
+            return Err(TblCliError::Arg(format!(
+                "key column {} must exist in both datasets",
+                key
+            )));
+        }
+    }
+
+    let counts = partition_counts(lf_left, lf_right, &schema_left, &args.key)
+        .with_streaming(true)
+        .collect()?;
+
+    // compare counts of each partition
+    let key_columns = args
+        .key
+        .iter()
+        .map(|key| Ok(counts.column(key)?.str()?.clone()))
+        .collect::<Result<Vec<_>, TblCliError>>()?;
+    let n_left = counts.column(N_LEFT)?.u64()?.clone();
+    let n_right = counts.column(N_RIGHT)?.u64()?.clone();
+    let mut rows = Vec::new();
+    let (mut total_left, mut total_right) = (0u64, 0u64);
+    let (mut n_only_left, mut n_only_right, mut n_diverging) = (0usize, 0usize, 0usize);
+    for i in 0..counts.height() {
+        let left = n_left.get(i).unwrap_or(0);
+        let right = n_right.get(i).unwrap_or(0);
+        total_left += left;
+        total_right += right;
+        let difference = left.abs_diff(right);
+        let relative = difference as f64 / std::cmp::max(left, right).max(1) as f64;
+        let status = if right == 0 {
+            n_only_left += 1;
+            "only in left"
+        } else if left == 0 {
+            n_only_right += 1;
+            "only in right"
+        } else if relative > args.threshold {
+            "diverges"
+        } else {
+            ""
+        };
+        let is_diverging = relative > args.threshold;
+        if is_diverging {
+            n_diverging += 1;
+        }
+        if is_diverging || args.all {
+            let partition: Vec<String> = args
+                .key
+                .iter()
+                .zip(key_columns.iter())
+                .map(|(key, values)| format!("{}={}", key, values.get(i).unwrap_or("null")))
+                .collect();
+            rows.push([
+                partition.join(", "),
+                format_with_commas(left),
+                format_with_commas(right),
+                format!("{:.2}%", 100.0 * relative),
+                status.to_string(),
+            ]);
+        }
+    }
+
+    // summary
+    tbl_core::formats::print_header("Reconciliation");
+    tbl_core::formats::print_bullet("left", args.left.to_string_lossy());
+    tbl_core::formats::print_bullet("right", args.right.to_string_lossy());
+    tbl_core::formats::print_bullet("key", args.key.join(", "));
+    tbl_core::formats::print_bullet("rows in left", format_with_commas(total_left));
+    tbl_core::formats::print_bullet("rows in right", format_with_commas(total_right));
+    tbl_core::formats::print_bullet("partitions", format_with_commas(counts.height() as u64));
+    tbl_core::formats::print_bullet("only in left", format_with_commas(n_only_left as u64));
+    tbl_core::formats::print_bullet("only in right", format_with_commas(n_only_right as u64));
+    tbl_core::formats::print_bullet(
+        "diverging",
+        format!(
+            "{} beyond threshold of {}%",
+            format_with_commas(n_diverging as u64),
+            100.0 * args.threshold
+        ),
+    );
+
+    if !rows.is_empty() {
+        println!();
+        tbl_core::formats::print_header(if args.all {
+            "Partitions"
+        } else {
+            "Diverging partitions"
+        });
+        print_table(
+            &[
+                "partition",
+                "left rows",
+                "right rows",
+                "difference",
+                "status",
+            ],
+            &rows,
+        )?;
+    }
+
+    if n_diverging > 0 {
+        Err(TblCliError::CheckFailed(format!(
+            "{} of {} partitions diverge",
+            n_diverging,
+            counts.height()
+        )))
+    } else {
+        println!();
+        println!(
+            "[all {} partitions within threshold]",
+            format_with_commas(counts.height() as u64)
+        );
+        Ok(())
+    }
+}
+
+/// row counts of each partition in left and right, with keys as strings and null keys
+/// matching each other, casting keys of right to the dtypes of left
+fn partition_counts(
+    lf_left: LazyFrame,
+    lf_right: LazyFrame,
+    schema_left: &Schema,
+    key: &[String],
+) -> LazyFrame {
+    let keys: Vec<Expr> = key.iter().map(|key| col(key)).collect();
+    let right_keys: Vec<Expr> = key
+        .iter()
+        .map(|key| col(key).cast(schema_left.get(key).cloned().unwrap_or_default()))
+        .collect();
+    let left_counts = lf_left
+        .group_by(keys.clone())
+        .agg([len().cast(DataType::UInt64).alias(N_LEFT)]);
+    let right_counts = lf_right
+        .select(right_keys)
+        .group_by(keys.clone())
+        .agg([len().cast(DataType::UInt64).alias(N_RIGHT)]);
+    let join_args = JoinArgs {
+        join_nulls: true,
+        ..JoinArgs::new(JoinType::Full).with_coalesce(JoinCoalesce::CoalesceColumns)
+    };
+    left_counts
+        .join(right_counts, keys.clone(), keys.clone(), join_args)
+        .sort(key, SortMultipleOptions::default())
+        .select(
+            keys.iter()
+                .map(|key| key.clone().cast(DataType::String))
+                .chain([col(N_LEFT).fill_null(0), col(N_RIGHT).fill_null(0)])
+                .collect::<Vec<_>>(),
+        )
+}
+
+fn get_dataset_paths(path: &Path, tree: bool) -> Result<Vec<PathBuf>, TblCliError> {
+    let inputs = Some(vec![path.to_path_buf()]);
+    let paths = tbl_core::filesystem::get_input_paths(&inputs, tree, true)?;
+    if paths.is_empty() {
+        return Err(TblCliError::no_inputs(&inputs, tree));
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partition_counts_match_null_keys() {
+        let df = df!("k" => [Some(1i64), None, None, Some(2)]).unwrap();
+        let schema = df.schema();
+        let counts = partition_counts(df.clone().lazy(), df.lazy(), &schema, &["k".to_string()])
+            .collect()
+            .unwrap();
+        assert_eq!(counts.height(), 3);
+        let n_left: Vec<Option<u64>> = counts
+            .column(N_LEFT)
+            .unwrap()
+            .u64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        let n_right: Vec<Option<u64>> = counts
+            .column(N_RIGHT)
+            .unwrap()
+            .u64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(n_left, n_right);
+        assert_eq!(n_left.iter().flatten().sum::<u64>(), 4);
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{RemoteArgs, TblCliError};
+use polars::prelude::*;
+use tbl_core::formats::{format_with_commas, print_bullet, print_header};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// most bytes of a server response that are read
+const MAX_RESPONSE_BYTES: u64 = 1 << 30;
+
+pub(crate) async fn remote_command(args: RemoteArgs) -> Result<(), TblCliError> {
+    match args.action.as_str() {
+        "ls" => {
+            let response = get_json(&args.address, "/files").await?;
+            let files = json_list(&response, "files")?;
+            print_header(format!("{} files at {}", files.len(), args.address));
+            for file in files.iter() {
+                println!("{}", file.as_str().unwrap_or_default());
+            }
+        }
+        "schema" => {
+            let response = get_json(&args.address, "/schema").await?;
+            let rows: Vec<[String; 2]> = json_list(&response, "columns")?
+                .iter()
+                .map(|column| {
+                    let field = |key: &str| column[key].as_str().unwrap_or_default().to_string();
+                    [field("name"), field("dtype")]
+                })
+                .collect();
+            super::print_table(&["column", "dtype"], &rows)?;
+        }
+        "count" => {
+            let target = format!("/count?{}", filter_query(&args));
+            let response = get_json(&args.address, &target).await?;
+            print_bullet(
+                "n_rows",
+                format_with_commas(response["n_rows"].as_u64().unwrap_or(0)),
+            );
+            print_bullet(
+                "n_files",
+                format_with_commas(response["n_files"].as_u64().unwrap_or(0)),
+            );
+        }
+        _ => {
+            let mut params = vec![filter_query(&args)];
+            if let Some(columns) = &args.columns {
+                params.push(format!("columns={}", percent_encode(&columns.join(","))));
+            }
+            if let Some(sort) = &args.sort {
+                params.push(format!("sort={}", percent_encode(&sort.join(","))));
+            }
+            if let Some(offset) = args.offset {
+                params.push(format!("offset={}", offset));
+            }
+            params.push(format!("limit={}", args.n));
+            params.push("format=arrow".to_string());
+            params.retain(|param| !param.is_empty());
+            let target = format!("/query?{}", params.join("&"));
+            let (status, body) = get(&args.address, &target).await?;
+            check_status(status, &body)?;
+            let mut df = IpcStreamReader::new(std::io::Cursor::new(body)).finish()?;
+            if args.csv {
+                CsvWriter::new(std::io::stdout()).finish(&mut df)?;
+            } else {
+                println!("{}", df);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// query parameters of the `where` and `filter` arguments
+fn filter_query(args: &RemoteArgs) -> String {
+    let mut params = Vec::new();
+    if let Some(where_) = &args.where_ {
+        params.push(format!("where={}", percent_encode(where_)));
+    }
+    if let Some(filter) = &args.filter {
+        params.push(format!("filter={}", percent_encode(&filter.join(","))));
+    }
+    params.join("&")
+}
+
+/// send get request to server, returning status code and body
+async fn get(address: &str, target: &str) -> Result<(u16, Vec<u8>), TblCliError> {
+    let mut stream = TcpStream::connect(address)
+        .await
+        .map_err(|e| TblCliError::Error(format!("could not connect to {}: {}", address, e)))?;
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        target, address
+    );
+    stream.write_all(request.as_bytes()).await?;
+
+    // the server closes each connection after its response
+    let mut response = Vec::new();
+    (&mut stream)
+        .take(MAX_RESPONSE_BYTES + 1)
+        .read_to_end(&mut response)
+        .await?;
+    if response.len() as u64 > MAX_RESPONSE_BYTES {
+        return Err(TblCliError::Error(format!(
+            "response from server exceeds {}, request fewer rows or columns",
+            tbl_core::formats::format_bytes(MAX_RESPONSE_BYTES)
+        )));
+    }
+    let header_end = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(|| TblCliError::Error("malformed response from server".to_string()))?;
+    let status = String::from_utf8_lossy(&response[..header_end])
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| TblCliError::Error("malformed response from server".to_string()))?;
+    Ok((status, response[header_end + 4..].to_vec()))
+}
+
+async fn get_json(address: &str, target: &str) -> Result<serde_json::Value, TblCliError> {
+    let (status, body) = get(address, target).await?;
+    check_status(status, &body)?;
+    serde_json::from_slice(&body)
+        .map_err(|e| TblCliError::Error(format!("invalid json from server: {}", e)))
+}
+
+/// convert error responses into errors, keeping the message of the server
+fn check_status(status: u16, body: &[u8]) -> Result<(), TblCliError> {
+    if status == 200 {
+        return Ok(());
+    }
+    let message = serde_json::from_slice::<serde_json::Value>(body)
+        .ok()
+        .and_then(|value| value["error"].as_str().map(|s| s.to_string()))
+        .unwrap_or_else(|| String::from_utf8_lossy(body).to_string());
+    if status == 400 {
+        Err(TblCliError::Arg(message))
+    } else {
+        Err(TblCliError::Error(format!(
+            "server error {}: {}",
+            status, message
+        )))
+    }
+}
+
+fn json_list<'a>(
+    value: &'a serde_json::Value,
+    key: &str,
+) -> Result<&'a Vec<serde_json::Value>, TblCliError> {
+    value[key]
+        .as_array()
+        .ok_or_else(|| TblCliError::Error(format!("server response has no {}", key)))
+}
+
+fn percent_encode(input: &str) -> String {
+    input
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b',' => {
+                (byte as char).to_string()
+            }
+            byte => format!("%{:02X}", byte),
+        })
+        .collect()
+}
@@ -0,0 +1,144 @@
+use crate::{DataArgs, RunArgs, TblCliError};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// pipeline of transformations applied to a set of input files
+///
+/// ```yaml
+/// inputs: [data/]
+/// tree: true
+/// steps:
+///   - filter: ["status=ok"]
+///   - insert: ["source:str:import"]
+///   - cast: ["fee=f32"]
+/// output:
+///   output_dir: cleaned/
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Pipeline {
+    inputs: Vec<PathBuf>,
+    #[serde(default)]
+    tree: bool,
+    #[serde(default)]
+    steps: Vec<PipelineStep>,
+    #[serde(default)]
+    output: PipelineOutput,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+enum PipelineStep {
+    Filter(Vec<String>),
+    Where(String),
+    #[serde(alias = "with_columns")]
+    Insert(Vec<String>),
+    Cast(Vec<String>),
+    Drop(Vec<String>),
+    Select(Vec<String>),
+    Rename(Vec<String>),
+    Set(Vec<String>),
+    Nullify(Vec<String>),
+    Update {
+        set: Vec<String>,
+        #[serde(rename = "where")]
+        where_: Option<String>,
+    },
+    Sort(Vec<String>),
+    Offset(usize),
+    Head(usize),
+    Tail(usize),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PipelineOutput {
+    #[serde(default)]
+    inplace: bool,
+    output_file: Option<PathBuf>,
+    output_dir: Option<PathBuf>,
+    output_prefix: Option<String>,
+    output_postfix: Option<String>,
+    output_template: Option<String>,
+    partition: Option<String>,
+}
+
+pub(crate) async fn run_command(args: RunArgs) -> Result<(), TblCliError> {
+    let pipeline: Pipeline = load_spec_file(&args.pipeline, "pipeline")?;
+
+    // resolve relative paths against the directory of the pipeline file
+    let base_dir = args
+        .pipeline
+        .parent()
+        .map(|dir| dir.to_path_buf())
+        .unwrap_or_default();
+    let resolve = |path: &PathBuf| base_dir.join(path);
+
+    let data_args = DataArgs {
+        paths: Some(pipeline.inputs.iter().map(resolve).collect()),
+        tree: pipeline.tree,
+        inplace: pipeline.output.inplace,
+        output_file: pipeline.output.output_file.as_ref().map(resolve),
+        output_dir: pipeline.output.output_dir.as_ref().map(resolve),
+        output_prefix: pipeline.output.output_prefix,
+        output_postfix: pipeline.output.output_postfix,
+        output_template: pipeline.output.output_template,
+        partition: pipeline.output.partition,
+        no_summary: args.no_summary,
+        confirm: args.confirm,
+        dry: args.dry,
+        pipeline_steps: pipeline.steps.into_iter().map(step_to_data_args).collect(),
+        ..Default::default()
+    };
+    super::data_command(data_args).await
+}
+
+/// load a yaml, toml, or json file, choosing the parser by file extension
+pub(crate) fn load_spec_file<T: serde::de::DeserializeOwned>(
+    path: &Path,
+    kind: &str,
+) -> Result<T, TblCliError> {
+    let contents = std::fs::read_to_string(path)?;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let spec = match extension {
+        "toml" => toml::from_str(&contents).map_err(|e| e.to_string()),
+        "json" => serde_json::from_str(&contents).map_err(|e| e.to_string()),
+        // read yaml through json values so that enums can be written as `- filter: [...]`
+        _ => serde_yaml::from_str::<serde_json::Value>(&contents)
+            .map_err(|e| e.to_string())
+            .and_then(|value| serde_json::from_value(value).map_err(|e| e.to_string())),
+    };
+    spec.map_err(|e| {
+        TblCliError::Arg(format!(
+            "invalid {} file {}: {}",
+            kind,
+            path.to_string_lossy(),
+            e
+        ))
+    })
+}
+
+/// convert step into data args containing only that transformation
+fn step_to_data_args(step: PipelineStep) -> DataArgs {
+    let mut args = DataArgs::default();
+    match step {
+        PipelineStep::Filter(filter) => args.filter = Some(filter),
+        PipelineStep::Where(where_) => args.where_ = Some(where_),
+        PipelineStep::Insert(columns) => args.with_columns = Some(columns),
+        PipelineStep::Cast(cast) => args.cast = Some(cast),
+        PipelineStep::Drop(columns) => args.drop = Some(columns),
+        PipelineStep::Select(columns) => args.columns = Some(columns),
+        PipelineStep::Rename(rename) => args.rename = Some(rename),
+        PipelineStep::Set(set) => args.set = Some(set),
+        PipelineStep::Nullify(columns) => args.nullify = Some(columns),
+        PipelineStep::Update { set, where_ } => {
+            args.update = Some(set);
+            args.update_where = where_;
+        }
+        PipelineStep::Sort(sort) => args.sort = Some(sort),
+        PipelineStep::Offset(n) => args.offset = Some(n),
+        PipelineStep::Head(n) => args.head = Some(n),
+        PipelineStep::Tail(n) => args.tail = Some(n),
+    }
+    args
+}
@@ -0,0 +1,150 @@
+use crate::styles::FontStyle;
+use crate::{ScanPiiArgs, TblCliError};
+use polars::prelude::*;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tbl_core::pii::PiiKind;
+use toolstr::Colorize;
+
+struct PiiFinding {
+    path: PathBuf,
+    column: String,
+    kind: PiiKind,
+    n_matches: u64,
+    n_sampled: u64,
+}
+
+pub(crate) async fn scan_pii_command(args: ScanPiiArgs) -> Result<(), TblCliError> {
+    let paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+
+    let mut findings = Vec::new();
+    for path in paths.iter() {
+        findings.extend(scan_file(path, &args).await?);
+    }
+
+    if findings.is_empty() {
+        println!("[no pii detected in {} files]", paths.len());
+        return Ok(());
+    }
+    print_findings(&findings)?;
+
+    let mut flagged_files: Vec<&PathBuf> = findings.iter().map(|finding| &finding.path).collect();
+    flagged_files.dedup();
+    let mut flagged_columns: Vec<(&PathBuf, &String)> = findings
+        .iter()
+        .map(|finding| (&finding.path, &finding.column))
+        .collect();
+    flagged_columns.dedup();
+    println!();
+    println!(
+        "{} columns in {} of {} files need masking",
+        flagged_columns.len().to_string().colorize_constant().bold(),
+        flagged_files.len().to_string().colorize_constant().bold(),
+        paths.len().to_string().colorize_constant().bold(),
+    );
+    Ok(())
+}
+
+/// sample rows spread evenly across file and count values matching each detector
+async fn scan_file(path: &PathBuf, args: &ScanPiiArgs) -> Result<Vec<PiiFinding>, TblCliError> {
+    let mut lf = tbl_core::parquet::create_lazyframe(std::slice::from_ref(path))?;
+    let columns: Vec<String> = lf
+        .schema()?
+        .iter()
+        .filter(|(_, dtype)| **dtype == DataType::String)
+        .map(|(name, _)| name.to_string())
+        .collect();
+    if columns.is_empty() {
+        return Ok(Vec::new());
+    }
+    let n_rows = tbl_core::parquet::get_parquet_row_count(path).await?;
+    let step = (n_rows / args.sample.max(1) as u64).max(1) as usize;
+    let df = lf
+        .select(
+            columns
+                .iter()
+                .map(|name| {
+                    col(name)
+                        .gather_every(step, 0)
+                        .head(Some(args.sample as usize))
+                })
+                .collect::<Vec<_>>(),
+        )
+        .with_streaming(true)
+        .collect()?;
+
+    let mut findings = Vec::new();
+    for column in columns.into_iter() {
+        let mut counts: BTreeMap<PiiKind, u64> = BTreeMap::new();
+        let mut n_sampled = 0;
+        for value in df.column(&column)?.str()?.into_iter().flatten() {
+            n_sampled += 1;
+            if let Some(kind) = tbl_core::pii::detect_pii(value, &column) {
+                *counts.entry(kind).or_insert(0) += 1;
+            }
+        }
+        for (kind, n_matches) in counts.into_iter() {
+            if n_matches as f64 >= args.min_fraction * n_sampled as f64 {
+                findings.push(PiiFinding {
+                    path: path.clone(),
+                    column: column.clone(),
+                    kind,
+                    n_matches,
+                    n_sampled,
+                })
+            }
+        }
+    }
+    Ok(findings)
+}
+
+fn print_findings(findings: &[PiiFinding]) -> Result<(), TblCliError> {
+    let mut paths = Vec::new();
+    let mut columns = Vec::new();
+    let mut kinds = Vec::new();
+    let mut matches = Vec::new();
+    for finding in findings.iter() {
+        paths.push(finding.path.to_string_lossy().to_string());
+        columns.push(finding.column.clone());
+        kinds.push(finding.kind.to_string());
+        matches.push(format!(
+            "{} of {} ({:.1}%)",
+            tbl_core::formats::format_with_commas(finding.n_matches),
+            tbl_core::formats::format_with_commas(finding.n_sampled),
+            100.0 * finding.n_matches as f64 / finding.n_sampled as f64,
+        ));
+    }
+
+    // build table
+    let mut table = toolstr::Table::new();
+    table.add_column("path", paths)?;
+    table.add_column("column", columns)?;
+    table.add_column("detected", kinds)?;
+    table.add_column("sampled matches", matches)?;
+
+    // create format
+    let mut path_column = toolstr::ColumnFormatShorthand::default()
+        .name("path")
+        .left_justify();
+    let mut column_column = toolstr::ColumnFormatShorthand::default().name("column");
+    let mut kind_column = toolstr::ColumnFormatShorthand::default().name("detected");
+    let mut matches_column = toolstr::ColumnFormatShorthand::default().name("sampled matches");
+    path_column.font_style = Some("".colorize_function().into());
+    column_column.font_style = Some("".colorize_variable().into());
+    kind_column.font_style = Some("".colorize_string().into());
+    matches_column.font_style = Some("".colorize_constant().into());
+    let mut format = toolstr::TableFormat {
+        label_font_style: Some("".colorize_title().into()),
+        border_font_style: Some("".colorize_comment().into()),
+        ..Default::default()
+    };
+    format.add_column(path_column);
+    format.add_column(column_column);
+    format.add_column(kind_column);
+    format.add_column(matches_column);
+    format.print(table)?;
+    Ok(())
+}
@@ -9,9 +9,29 @@ use tbl_core::parquet::{combine_tabular_summaries, summarize_by_schema, TabularS
 use toolstr::Colorize;
 
 pub(crate) async fn schema_command(args: SchemaArgs) -> Result<(), TblCliError> {
+    // report history without reading files unless a run is also recorded
+    if args.changes && !args.record {
+        return print_schema_changes(&args.history);
+    }
+
+    // compare datasets instead of summarizing one set of files
+    if args.compare {
+        return compare_schemas(&args).await;
+    }
+
     // get schemas
     let paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
     let summaries = tbl_core::parquet::get_parquet_summaries(&paths).await?;
+    if args.record {
+        let record = crate::schema_history::SchemaRecord::new(&summaries);
+        crate::schema_history::append_schema_record(&args.history, &record)?;
+        if args.changes {
+            return print_schema_changes(&args.history);
+        }
+    }
     let ref_summaries: Vec<&tbl_core::parquet::TabularSummary> = summaries.iter().collect();
     let by_schema = summarize_by_schema(ref_summaries.as_slice())?;
 
@@ -60,6 +80,27 @@ pub(crate) async fn schema_command(args: SchemaArgs) -> Result<(), TblCliError>
         }
     };
 
+    // browse all schema groups instead of printing the top groups
+    if args.interactive {
+        let mut paths_by_schema = HashMap::<Arc<Schema>, Vec<PathBuf>>::new();
+        for (path, summary) in paths.iter().zip(summaries.iter()) {
+            paths_by_schema
+                .entry(Arc::clone(&summary.schema))
+                .or_default()
+                .push(path.clone());
+        }
+        let n_schemas = by_schema.len();
+        let groups = top_n_schemas(by_schema, n_schemas, sort_by);
+        return navigate_schemas(groups, paths_by_schema, n_example_paths);
+    }
+
+    // group schemas by directory before summaries are consumed
+    let by_dir = if args.by_dir {
+        Some(group_schemas_by_dir(&paths, &summaries))
+    } else {
+        None
+    };
+
     // print output
     print_schemas(
         by_schema,
@@ -69,10 +110,400 @@ pub(crate) async fn schema_command(args: SchemaArgs) -> Result<(), TblCliError>
         n_example_paths,
         example_paths,
     )?;
+    if let Some(by_dir) = by_dir {
+        print_schemas_by_dir(by_dir)?;
+    }
 
     Ok(())
 }
 
+fn print_schema_changes(history: &std::path::Path) -> Result<(), TblCliError> {
+    let records = crate::schema_history::read_schema_history(history)?;
+    tbl_core::formats::print_header("Schema changes");
+    let mut n_changed_runs = 0;
+    for pair in records.windows(2) {
+        let changes = crate::schema_history::schema_changes(&pair[0], &pair[1]);
+        if changes.is_empty() {
+            continue;
+        }
+        n_changed_runs += 1;
+        tbl_core::formats::print_bullet(
+            &pair[1].time,
+            format!(
+                "{} files, {} schemas",
+                pair[1].n_files,
+                pair[1].schemas.len()
+            ),
+        );
+        for change in changes.iter() {
+            println!("    {}", change);
+        }
+    }
+    if n_changed_runs == 0 {
+        println!(
+            "[no schema changes over {} recorded runs]",
+            format_with_commas(records.len() as u64)
+        );
+    }
+    Ok(())
+}
+
+/// columns of a dataset in order of first appearance, with every dtype each column has
+type DatasetColumns = Vec<(String, Vec<DataType>)>;
+
+async fn compare_schemas(args: &SchemaArgs) -> Result<(), TblCliError> {
+    // parse datasets, each given as PATH or LABEL=PATH
+    let specs = match &args.paths {
+        Some(paths) if paths.len() >= 2 => paths,
+        _ => {
+            return Err(TblCliError::Arg(
+                "--compare requires at least two datasets".to_string(),
+            ))
+        }
+    };
+    let mut labels = Vec::new();
+    let mut datasets = Vec::new();
+    for spec in specs.iter() {
+        let spec = spec.to_string_lossy().to_string();
+        let (label, path) = match spec.split_once('=') {
+            Some((label, path)) if !label.is_empty() && !PathBuf::from(&spec).exists() => {
+                (label.to_string(), PathBuf::from(path))
+            }
+            _ => (spec.clone(), PathBuf::from(&spec)),
+        };
+        let inputs = Some(vec![path]);
+        let paths = tbl_core::filesystem::get_input_paths(&inputs, args.tree, true)?;
+        if paths.is_empty() {
+            return Err(TblCliError::no_inputs(&inputs, args.tree));
+        }
+        let schemas = tbl_core::parquet::get_parquet_schemas(&paths).await?;
+        let mut columns: DatasetColumns = Vec::new();
+        for schema in schemas.iter() {
+            for (name, dtype) in schema.iter() {
+                match columns.iter_mut().find(|(other, _)| other == name.as_str()) {
+                    Some((_, dtypes)) if !dtypes.contains(dtype) => dtypes.push(dtype.clone()),
+                    Some(_) => {}
+                    None => columns.push((name.to_string(), vec![dtype.clone()])),
+                }
+            }
+        }
+        labels.push(label);
+        datasets.push(columns);
+    }
+
+    // list columns that are missing from a dataset or whose dtypes differ between datasets
+    let baseline = &datasets[0];
+    let mut names: Vec<&String> = Vec::new();
+    for columns in datasets.iter() {
+        for (name, _) in columns.iter() {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    let mut rows = Vec::new();
+    for name in names.iter() {
+        let dtypes: Vec<Option<&Vec<DataType>>> = datasets
+            .iter()
+            .map(|columns| {
+                columns
+                    .iter()
+                    .find(|(other, _)| other == *name)
+                    .map(|(_, d)| d)
+            })
+            .collect();
+        let matches_first = |dtype: &Option<&Vec<DataType>>| match (dtype, &dtypes[0]) {
+            (Some(dtype), Some(first)) => same_dtypes(dtype, first),
+            (dtype, first) => dtype.is_none() && first.is_none(),
+        };
+        if dtypes.iter().all(matches_first) {
+            continue;
+        }
+        let mut row = vec![name.to_string()];
+        for dtype in dtypes.iter() {
+            row.push(match dtype {
+                Some(dtypes) => dtypes
+                    .iter()
+                    .map(|dtype| dtype.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+                None => "-".to_string(),
+            });
+        }
+        rows.push(row);
+    }
+
+    // print relationship of each dataset to the first
+    tbl_core::formats::print_header("Schema comparison");
+    tbl_core::formats::print_bullet(
+        labels[0].as_str(),
+        format!("{} columns, baseline", baseline.len()),
+    );
+    let mut n_incompatible = 0;
+    for (label, columns) in labels.iter().zip(datasets.iter()).skip(1) {
+        let (is_compatible, relationship) = schema_relationship(baseline, columns);
+        if !is_compatible {
+            n_incompatible += 1;
+        }
+        tbl_core::formats::print_bullet(
+            label.as_str(),
+            format!("{} columns, {}", columns.len(), relationship),
+        );
+    }
+    if !rows.is_empty() {
+        println!();
+        tbl_core::formats::print_header("Differing columns");
+        let mut table = toolstr::Table::new();
+        table.add_column(
+            "column",
+            rows.iter().map(|row| row[0].clone()).collect::<Vec<_>>(),
+        )?;
+        for (i, label) in labels.iter().enumerate() {
+            table.add_column(
+                label,
+                rows.iter()
+                    .map(|row| row[i + 1].clone())
+                    .collect::<Vec<_>>(),
+            )?;
+        }
+        let mut format = toolstr::TableFormat {
+            label_font_style: Some("".colorize_title().into()),
+            border_font_style: Some("".colorize_comment().into()),
+            ..Default::default()
+        };
+        let mut name_column = toolstr::ColumnFormatShorthand::default().name("column");
+        name_column.font_style = Some("".colorize_function().into());
+        format.add_column(name_column);
+        for label in labels.iter() {
+            let mut dtype_column = toolstr::ColumnFormatShorthand::default().name(label);
+            dtype_column.font_style = Some("".colorize_variable().into());
+            format.add_column(dtype_column);
+        }
+        format.print(table)?;
+    }
+
+    if n_incompatible > 0 {
+        Err(TblCliError::CheckFailed(format!(
+            "{} of {} datasets are incompatible with {}",
+            n_incompatible,
+            labels.len() - 1,
+            labels[0]
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// how the columns of a dataset relate to the columns of a baseline dataset
+///
+/// a dataset is compatible when it has every baseline column with the same dtypes
+fn schema_relationship(baseline: &DatasetColumns, columns: &DatasetColumns) -> (bool, String) {
+    let find = |columns: &'_ DatasetColumns, name: &str| {
+        columns
+            .iter()
+            .find(|(other, _)| other == name)
+            .map(|(_, dtypes)| dtypes.clone())
+    };
+    let n_conflicting = baseline
+        .iter()
+        .filter(|(name, dtypes)| {
+            find(columns, name).is_some_and(|other| !same_dtypes(&other, dtypes))
+        })
+        .count();
+    let n_missing = baseline
+        .iter()
+        .filter(|(name, _)| find(columns, name).is_none())
+        .count();
+    let n_added = columns
+        .iter()
+        .filter(|(name, _)| find(baseline, name).is_none())
+        .count();
+    let relationship = if n_conflicting > 0 {
+        format!("conflicting dtypes in {} columns", n_conflicting)
+    } else if n_missing > 0 && n_added > 0 {
+        format!(
+            "divergent, {} columns missing and {} columns added",
+            n_missing, n_added
+        )
+    } else if n_missing > 0 {
+        format!("subset, {} columns missing", n_missing)
+    } else if n_added > 0 {
+        format!("superset, {} columns added", n_added)
+    } else if baseline
+        .iter()
+        .map(|(name, _)| name)
+        .eq(columns.iter().map(|(name, _)| name))
+    {
+        "identical".to_string()
+    } else {
+        "compatible, columns in different order".to_string()
+    };
+    (n_conflicting == 0 && n_missing == 0, relationship)
+}
+
+/// whether columns have the same dtypes, in any order of appearance
+fn same_dtypes(dtypes: &[DataType], other: &[DataType]) -> bool {
+    dtypes.len() == other.len() && dtypes.iter().all(|dtype| other.contains(dtype))
+}
+
+/// fingerprints of schemas in each directory, with the number of files of each
+fn group_schemas_by_dir(
+    paths: &[PathBuf],
+    summaries: &[TabularSummary],
+) -> Vec<(String, Vec<(String, usize)>)> {
+    let mut by_dir: std::collections::BTreeMap<String, Vec<(String, usize)>> =
+        std::collections::BTreeMap::new();
+    for (path, summary) in paths.iter().zip(summaries.iter()) {
+        let dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy().to_string(),
+            _ => ".".to_string(),
+        };
+        let fingerprint = tbl_core::parquet::schema_fingerprint(&summary.schema);
+        let schemas = by_dir.entry(dir).or_default();
+        match schemas.iter_mut().find(|(other, _)| *other == fingerprint) {
+            Some((_, n_files)) => *n_files += 1,
+            None => schemas.push((fingerprint, 1)),
+        }
+    }
+    by_dir.into_iter().collect()
+}
+
+fn print_schemas_by_dir(by_dir: Vec<(String, Vec<(String, usize)>)>) -> Result<(), TblCliError> {
+    // directories are visited in sorted order, e.g. by partition value
+    let mut first_dirs: Vec<(String, String)> = Vec::new();
+    let mut rows = Vec::new();
+    for (dir, schemas) in by_dir.iter() {
+        for (fingerprint, _) in schemas.iter() {
+            if !first_dirs.iter().any(|(other, _)| other == fingerprint) {
+                first_dirs.push((fingerprint.clone(), dir.clone()));
+            }
+        }
+        let n_files: usize = schemas.iter().map(|(_, n_files)| n_files).sum();
+        let fingerprints: Vec<String> = schemas
+            .iter()
+            .map(|(fingerprint, n)| format!("{} ({})", fingerprint, n))
+            .collect();
+        rows.push([
+            dir.clone(),
+            format_with_commas(n_files as u64),
+            fingerprints.join(", "),
+            if schemas.len() > 1 { "yes" } else { "" }.to_string(),
+        ]);
+    }
+
+    println!();
+    println!();
+    tbl_core::formats::print_header("Schemas by directory");
+    super::print_table(&["directory", "files", "schemas (files)", "mixed"], &rows)?;
+    if first_dirs.len() > 1 {
+        println!();
+        for (fingerprint, dir) in first_dirs.iter() {
+            tbl_core::formats::print_bullet(fingerprint, format!("first appears in {}", dir));
+        }
+    }
+    Ok(())
+}
+
+/// choose schema groups to print from a menu, or find the groups that have a column
+fn navigate_schemas(
+    groups: Vec<TabularSummary>,
+    paths_by_schema: HashMap<Arc<Schema>, Vec<PathBuf>>,
+    n_example_paths: usize,
+) -> Result<(), TblCliError> {
+    inquire::set_global_render_config(crate::styles::get_render_config());
+    let labels: Vec<String> = groups
+        .iter()
+        .enumerate()
+        .map(|(i, summary)| {
+            format!(
+                "{}. {}  {} columns, {} files, {} rows, {}",
+                i + 1,
+                tbl_core::parquet::schema_fingerprint(&summary.schema),
+                summary.schema.len(),
+                format_with_commas(summary.n_files),
+                format_with_commas(summary.n_rows),
+                format_bytes(summary.n_bytes_compressed),
+            )
+        })
+        .collect();
+    let search = "[search for column]".to_string();
+    let quit = "[quit]".to_string();
+    loop {
+        let mut options = vec![search.clone()];
+        options.extend(labels.iter().cloned());
+        options.push(quit.clone());
+        let prompt = format!("{} schema groups, type to filter:", groups.len());
+        let choice = match inquire::Select::new(&prompt, options)
+            .with_page_size(15)
+            .raw_prompt()
+        {
+            Ok(choice) => choice,
+            Err(_) => return Ok(()),
+        };
+        let index = if choice.value == search {
+            let query = match inquire::Text::new("column name:").prompt() {
+                Ok(query) if !query.trim().is_empty() => query.trim().to_lowercase(),
+                _ => continue,
+            };
+            let mut matches = Vec::new();
+            for (i, summary) in groups.iter().enumerate() {
+                let columns: Vec<String> = summary
+                    .schema
+                    .iter()
+                    .filter(|(name, _)| name.to_lowercase().contains(&query))
+                    .map(|(name, dtype)| format!("{}: {}", name, dtype))
+                    .collect();
+                if !columns.is_empty() {
+                    matches.push((i, format!("{}  ({})", labels[i], columns.join(", "))));
+                }
+            }
+            if matches.is_empty() {
+                println!("[no schema group has a column matching {}]", query);
+                continue;
+            }
+            let prompt = format!("{} schema groups with matching columns:", matches.len());
+            let options: Vec<String> = matches.iter().map(|(_, label)| label.clone()).collect();
+            match inquire::Select::new(&prompt, options)
+                .with_page_size(15)
+                .raw_prompt()
+            {
+                Ok(choice) => matches[choice.index].0,
+                Err(_) => continue,
+            }
+        } else if choice.value == quit {
+            return Ok(());
+        } else {
+            choice.index - 1
+        };
+
+        // print chosen group, then return to the menu
+        let summary = &groups[index];
+        println!();
+        println!(
+            "{} {} {}",
+            "Schema".colorize_title(),
+            format!("{}", index + 1).as_str().colorize_constant().bold(),
+            tbl_core::parquet::schema_fingerprint(&summary.schema)
+                .as_str()
+                .colorize_variable(),
+        );
+        println!();
+        print_schema(summary.schema.clone(), summary)?;
+        if let Some(paths) = paths_by_schema.get(&summary.schema) {
+            println!();
+            println!("{}", "Example paths".colorize_title());
+            for (i, path) in paths.iter().take(n_example_paths).enumerate() {
+                println!(
+                    "{} {}",
+                    format!("{}.", i + 1).colorize_variable(),
+                    path.to_string_lossy().colorize_comment()
+                );
+            }
+        }
+        println!();
+    }
+}
+
 fn count_unique_schemas(schemas: &Vec<&Arc<Schema>>) -> HashMap<Arc<Schema>, usize> {
     let mut schema_counts = HashMap::new();
 
@@ -108,11 +539,9 @@ fn top_n_schemas(
 ) -> Vec<TabularSummary> {
     let mut summaries: Vec<_> = schema_summaries.values().cloned().collect();
     match sort_by {
-        SortSchemasBy::Rows => summaries.sort_by(|a, b| b.n_rows.cmp(&a.n_rows)),
-        SortSchemasBy::Files => summaries.sort_by(|a, b| b.n_files.cmp(&a.n_files)),
-        SortSchemasBy::Bytes => {
-            summaries.sort_by(|a, b| b.n_bytes_compressed.cmp(&a.n_bytes_compressed))
-        }
+        SortSchemasBy::Rows => summaries.sort_by_key(|s| std::cmp::Reverse(s.n_rows)),
+        SortSchemasBy::Files => summaries.sort_by_key(|s| std::cmp::Reverse(s.n_files)),
+        SortSchemasBy::Bytes => summaries.sort_by_key(|s| std::cmp::Reverse(s.n_bytes_compressed)),
     }
     summaries.into_iter().take(n_to_show).collect()
 }
@@ -131,19 +560,29 @@ fn print_schemas(
     let schema_word = if n_schemas == 1 { "schema" } else { "schemas" };
     println!(
         "{} unique {}, {} rows, {} files, {}",
-        format_with_commas(n_schemas as u64).green().bold(),
+        format_with_commas(n_schemas as u64)
+            .as_str()
+            .colorize_constant()
+            .bold(),
         schema_word,
-        format_with_commas(total_summary.n_rows).green().bold(),
-        format_with_commas(total_summary.n_files).green().bold(),
+        format_with_commas(total_summary.n_rows)
+            .as_str()
+            .colorize_constant()
+            .bold(),
+        format_with_commas(total_summary.n_files)
+            .as_str()
+            .colorize_constant()
+            .bold(),
         format_bytes(total_summary.n_bytes_compressed)
-            .green()
+            .as_str()
+            .colorize_constant()
             .bold(),
     );
     println!();
     if n_schemas > 1 {
         println!(
             "showing top {} schemas by number of {}:",
-            format!("{}", n_to_show).green().bold(),
+            format!("{}", n_to_show).as_str().colorize_constant().bold(),
             sort_by,
         );
         println!();
@@ -173,18 +612,36 @@ fn print_schemas(
         };
         let byte_percent = format.format(byte_percent)?;
 
+        let fingerprint = tbl_core::parquet::schema_fingerprint(&summary.schema);
         if n_schemas > 1 {
             println!(
-                "{} {}{} {} rows ({}), {} files ({}), {} ({})",
+                "{} {} {}{} {} rows ({}), {} files ({}), {} ({})",
                 "Schema".colorize_title(),
-                format!("{}", i + 1).green().bold(),
+                format!("{}", i + 1).as_str().colorize_constant().bold(),
+                fingerprint.as_str().colorize_variable(),
                 ":".colorize_title(),
-                format_with_commas(summary.n_rows).green().bold(),
-                row_percent.green().bold(),
-                format_with_commas(summary.n_files).green().bold(),
-                file_percent.green().bold(),
-                format_bytes(summary.n_bytes_compressed).green().bold(),
-                byte_percent.green().bold(),
+                format_with_commas(summary.n_rows)
+                    .as_str()
+                    .colorize_constant()
+                    .bold(),
+                row_percent.as_str().colorize_constant().bold(),
+                format_with_commas(summary.n_files)
+                    .as_str()
+                    .colorize_constant()
+                    .bold(),
+                file_percent.as_str().colorize_constant().bold(),
+                format_bytes(summary.n_bytes_compressed)
+                    .as_str()
+                    .colorize_constant()
+                    .bold(),
+                byte_percent.as_str().colorize_constant().bold(),
+            );
+            println!();
+        } else {
+            println!(
+                "{} {}",
+                "Schema".colorize_title(),
+                fingerprint.as_str().colorize_variable()
             );
             println!();
         }
@@ -217,7 +674,10 @@ fn print_schemas(
         println!();
         println!(
             "{} more schemas not shown",
-            format!("{}", n_schemas - n_to_show).bold().green()
+            format!("{}", n_schemas - n_to_show)
+                .as_str()
+                .colorize_constant()
+                .bold()
         )
     }
 
@@ -228,19 +688,19 @@ fn print_schema(schema: Arc<Schema>, summary: &TabularSummary) -> Result<(), Tbl
     // gather data
     let names: Vec<String> = schema.iter_names().map(|x| x.to_string()).collect();
     let dtypes: Vec<String> = schema.iter_dtypes().map(|x| x.to_string()).collect();
-    let uncompressed: Vec<_> = summary
+    let mut uncompressed: Vec<_> = summary
         .columns
         .iter()
         .map(|x| format_bytes(x.n_bytes_uncompressed))
         .collect();
-    let compressed: Vec<_> = summary
+    let mut compressed: Vec<_> = summary
         .columns
         .iter()
         .map(|x| format_bytes(x.n_bytes_compressed))
         .collect();
 
     let total_disk_bytes: u64 = summary.columns.iter().map(|x| x.n_bytes_compressed).sum();
-    let percent_disk: Vec<_> = summary
+    let mut percent_disk: Vec<_> = summary
         .columns
         .iter()
         .map(|x| {
@@ -251,6 +711,12 @@ fn print_schema(schema: Arc<Schema>, summary: &TabularSummary) -> Result<(), Tbl
         })
         .collect();
 
+    // hive partition columns are stored in directory names instead of on disk
+    let n_virtual = names.len().saturating_sub(summary.columns.len());
+    for sizes in [&mut uncompressed, &mut compressed, &mut percent_disk] {
+        sizes.extend(std::iter::repeat_n("-".to_string(), n_virtual));
+    }
+
     // build table
     let mut table = toolstr::Table::new();
     table.add_column("column name", names)?;
@@ -0,0 +1,407 @@
+use crate::{ServeArgs, TblCliError};
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+pub(crate) async fn serve_command(args: ServeArgs) -> Result<(), TblCliError> {
+    if args.protocol != "http" {
+        return Err(TblCliError::Arg(format!(
+            "unknown protocol {}, only http is supported",
+            args.protocol
+        )));
+    }
+    let paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+    let state = Arc::new(ServerState {
+        paths,
+        max_rows: args.max_rows,
+    });
+
+    let listener = TcpListener::bind((args.host.as_str(), args.port)).await?;
+    println!(
+        "serving {} files at http://{}",
+        state.paths.len(),
+        listener.local_addr()?
+    );
+    println!("endpoints: /files /schema /count /query");
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, state).await {
+                eprintln!("connection error: {}", e);
+            }
+        });
+    }
+}
+
+struct ServerState {
+    paths: Vec<PathBuf>,
+    max_rows: usize,
+}
+
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn json(status: u16, value: serde_json::Value) -> Response {
+        Response {
+            status,
+            content_type: "application/json",
+            body: value.to_string().into_bytes(),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Response {
+        Response::json(status, serde_json::json!({ "error": message }))
+    }
+}
+
+/// longest request or header line accepted, in bytes
+const MAX_LINE_BYTES: u64 = 8192;
+
+/// most header lines accepted per request
+const MAX_HEADER_LINES: usize = 100;
+
+async fn handle_connection(stream: TcpStream, state: Arc<ServerState>) -> std::io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    let response = match read_request(&mut reader).await? {
+        Ok(request_line) => match parse_request_line(&request_line) {
+            Ok(target) => tokio::task::spawn_blocking(move || route(&target, &state))
+                .await
+                .unwrap_or_else(|e| Response::error(500, &e.to_string())),
+            Err(response) => response,
+        },
+        Err(response) => response,
+    };
+
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        414 => "URI Too Long",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    };
+    let header = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason,
+        response.content_type,
+        response.body.len()
+    );
+    writer.write_all(header.as_bytes()).await?;
+    writer.write_all(&response.body).await?;
+    writer.shutdown().await
+}
+
+/// read the request line and discard the headers, answering 414 or 431 when
+/// a line or the header block exceeds its limit
+async fn read_request<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+) -> std::io::Result<Result<String, Response>> {
+    let request_line = match read_limited_line(reader).await? {
+        Some(line) => line,
+        None => return Ok(Err(Response::error(414, "request line too long"))),
+    };
+    let mut n_headers = 0;
+    loop {
+        let line = match read_limited_line(reader).await? {
+            Some(line) => line,
+            None => return Ok(Err(Response::error(431, "header line too long"))),
+        };
+        if line.trim().is_empty() {
+            return Ok(Ok(request_line));
+        }
+        n_headers += 1;
+        if n_headers > MAX_HEADER_LINES {
+            return Ok(Err(Response::error(431, "too many header lines")));
+        }
+    }
+}
+
+/// read one line of at most MAX_LINE_BYTES, returning None when it is longer
+async fn read_limited_line<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+) -> std::io::Result<Option<String>> {
+    let mut line = Vec::new();
+    let n_read = (&mut *reader)
+        .take(MAX_LINE_BYTES)
+        .read_until(b'\n', &mut line)
+        .await?;
+    if n_read as u64 == MAX_LINE_BYTES && !line.ends_with(b"\n") {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&line).to_string()))
+}
+
+/// extract the target of a GET request line
+fn parse_request_line(request_line: &str) -> Result<String, Response> {
+    let mut parts = request_line.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => Ok(target.to_string()),
+        (Some(_), Some(_)) => Err(Response::error(405, "only GET requests are supported")),
+        _ => Err(Response::error(400, "malformed request")),
+    }
+}
+
+fn route(target: &str, state: &ServerState) -> Response {
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path, parse_query_string(query)),
+        None => (target, HashMap::new()),
+    };
+    let result = match path.trim_end_matches('/') {
+        "" => Ok(Response::json(
+            200,
+            serde_json::json!({ "endpoints": ["/files", "/schema", "/count", "/query"] }),
+        )),
+        "/files" => files_endpoint(state),
+        "/schema" => schema_endpoint(state),
+        "/count" => count_endpoint(state, &query),
+        "/query" => query_endpoint(state, &query),
+        _ => return Response::error(404, "unknown endpoint"),
+    };
+    result.unwrap_or_else(|e| match e {
+        TblCliError::Arg(message) => Response::error(400, &message),
+        e => Response::error(500, &e.to_string()),
+    })
+}
+
+fn files_endpoint(state: &ServerState) -> Result<Response, TblCliError> {
+    let files: Vec<String> = state
+        .paths
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    Ok(Response::json(200, serde_json::json!({ "files": files })))
+}
+
+fn schema_endpoint(state: &ServerState) -> Result<Response, TblCliError> {
+    let schema = tbl_core::parquet::create_lazyframe(&state.paths)?.schema()?;
+    let columns: Vec<serde_json::Value> = schema
+        .iter()
+        .map(|(name, dtype)| serde_json::json!({ "name": name.as_str(), "dtype": dtype.to_string() }))
+        .collect();
+    Ok(Response::json(
+        200,
+        serde_json::json!({ "columns": columns }),
+    ))
+}
+
+fn count_endpoint(
+    state: &ServerState,
+    query: &HashMap<String, String>,
+) -> Result<Response, TblCliError> {
+    let lf = filtered_lazyframe(state, query)?;
+    let df = lf.select([len()]).collect()?;
+    let n_rows = df.get_columns()[0]
+        .cast(&DataType::UInt64)?
+        .u64()?
+        .get(0)
+        .unwrap_or(0);
+    Ok(Response::json(
+        200,
+        serde_json::json!({ "n_rows": n_rows, "n_files": state.paths.len() }),
+    ))
+}
+
+fn query_endpoint(
+    state: &ServerState,
+    query: &HashMap<String, String>,
+) -> Result<Response, TblCliError> {
+    let lf = filtered_lazyframe(state, query)?;
+    let columns = split_list(query.get("columns"));
+    let lf = crate::transform::apply_select(lf, columns.as_deref())?;
+    let sort = split_list(query.get("sort"));
+    let lf = crate::transform::apply_sort(lf, sort.as_deref(), false)?;
+    let offset = parse_number(query.get("offset"), "offset")?;
+    let lf = crate::transform::apply_offset(lf, offset)?;
+    let limit = parse_number(query.get("limit"), "limit")?
+        .unwrap_or(state.max_rows)
+        .min(state.max_rows);
+    let mut df = crate::transform::apply_head(lf, Some(limit))?.collect()?;
+
+    let mut body = Vec::new();
+    let content_type = match query.get("format").map(|s| s.as_str()) {
+        None | Some("json") => {
+            JsonWriter::new(&mut body)
+                .with_json_format(JsonFormat::Json)
+                .finish(&mut df)?;
+            "application/json"
+        }
+        Some("csv") => {
+            CsvWriter::new(&mut body).finish(&mut df)?;
+            "text/csv"
+        }
+        Some("arrow") => {
+            IpcStreamWriter::new(&mut body).finish(&mut df)?;
+            "application/vnd.apache.arrow.stream"
+        }
+        Some(other) => {
+            return Err(TblCliError::Arg(format!(
+                "unknown format {}, use json, csv, or arrow",
+                other
+            )))
+        }
+    };
+    Ok(Response {
+        status: 200,
+        content_type,
+        body,
+    })
+}
+
+/// apply the `where` and `filter` query parameters shared by endpoints
+fn filtered_lazyframe(
+    state: &ServerState,
+    query: &HashMap<String, String>,
+) -> Result<LazyFrame, TblCliError> {
+    let lf = tbl_core::parquet::create_lazyframe(&state.paths)?;
+    let lf = crate::transform::apply_where(lf, query.get("where").map(|s| s.as_str()))?;
+    let filters = split_list(query.get("filter"));
+    crate::transform::apply_filter(lf, filters.as_deref())
+}
+
+fn split_list(value: Option<&String>) -> Option<Vec<String>> {
+    value.map(|value| value.split(',').map(|s| s.trim().to_string()).collect())
+}
+
+fn parse_number(value: Option<&String>, name: &str) -> Result<Option<usize>, TblCliError> {
+    value
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| TblCliError::Arg(format!("{} must be a number", name)))
+        })
+        .transpose()
+}
+
+fn parse_query_string(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => (percent_decode(key), percent_decode(value)),
+            None => (percent_decode(pair), String::new()),
+        })
+        .collect()
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => output.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        output.push(byte);
+                        i += 2;
+                    }
+                    Err(_) => output.push(b'%'),
+                }
+            }
+            byte => output.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&output).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body_json(response: &Response) -> serde_json::Value {
+        serde_json::from_slice(&response.body).unwrap()
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("a%20b+c"), "a b c");
+        assert_eq!(percent_decode("x%3D1%26y"), "x=1&y");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+        assert_eq!(percent_decode("%C3%A9"), "\u{e9}");
+    }
+
+    #[test]
+    fn test_parse_request_line() {
+        assert_eq!(
+            parse_request_line("GET /count?where=a%3E1 HTTP/1.1\r\n").ok(),
+            Some("/count?where=a%3E1".to_string())
+        );
+        assert_eq!(
+            parse_request_line("POST /query HTTP/1.1\r\n")
+                .err()
+                .map(|r| r.status),
+            Some(405)
+        );
+        assert_eq!(
+            parse_request_line("\r\n").err().map(|r| r.status),
+            Some(400)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_request_limits() {
+        let request = b"GET /files HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        let result = read_request(&mut &request[..]).await.unwrap();
+        assert_eq!(result.ok().as_deref(), Some("GET /files HTTP/1.1\r\n"));
+
+        let long_line = format!(
+            "GET /{} HTTP/1.1\r\n\r\n",
+            "a".repeat(MAX_LINE_BYTES as usize)
+        );
+        let result = read_request(&mut long_line.as_bytes()).await.unwrap();
+        assert_eq!(result.err().map(|r| r.status), Some(414));
+
+        let long_header = format!(
+            "GET / HTTP/1.1\r\nX-Long: {}\r\n\r\n",
+            "a".repeat(MAX_LINE_BYTES as usize)
+        );
+        let result = read_request(&mut long_header.as_bytes()).await.unwrap();
+        assert_eq!(result.err().map(|r| r.status), Some(431));
+
+        let many_headers = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X-Header: a\r\n".repeat(MAX_HEADER_LINES + 1)
+        );
+        let result = read_request(&mut many_headers.as_bytes()).await.unwrap();
+        assert_eq!(result.err().map(|r| r.status), Some(431));
+    }
+
+    #[test]
+    fn test_count_endpoint() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("data.parquet");
+        let mut df = df!("a" => [1i64, 2, 3]).unwrap();
+        ParquetWriter::new(std::fs::File::create(&path).unwrap())
+            .finish(&mut df)
+            .unwrap();
+        let state = ServerState {
+            paths: vec![path],
+            max_rows: 10,
+        };
+
+        let response = route("/count", &state);
+        assert_eq!(response.status, 200);
+        assert_eq!(body_json(&response)["n_rows"], 3);
+        assert_eq!(body_json(&response)["n_files"], 1);
+
+        assert_eq!(route("/missing", &state).status, 404);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{SizeEstimateArgs, TblCliError};
+use polars::prelude::*;
+use std::collections::HashMap;
+use tbl_core::formats::{format_bytes, format_with_commas, print_bullet, print_header};
+
+/// estimated size of one column across files
+struct ColumnEstimate {
+    name: String,
+    dtype: String,
+    action: String,
+    n_bytes_before: u64,
+    n_bytes_after: u64,
+}
+
+pub(crate) async fn size_estimate_command(args: SizeEstimateArgs) -> Result<(), TblCliError> {
+    let paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    let paths: Vec<_> = paths
+        .into_iter()
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("parquet"))
+        .collect();
+    if paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+
+    let mut columns: Vec<ColumnEstimate> = Vec::new();
+    let mut n_bytes_before = 0;
+    let mut n_bytes_other = 0;
+    for path in paths.iter() {
+        let schema = tbl_core::parquet::get_parquet_schema(path).await?;
+        let sizes = tbl_core::parquet::get_parquet_top_level_column_sizes(path).await?;
+        let n_bytes = std::fs::metadata(path)?.len();
+        n_bytes_before += n_bytes;
+
+        // footers and page indexes are assumed to keep their size
+        let n_bytes_columns: u64 = sizes.iter().map(|(_, compressed, _)| compressed).sum();
+        n_bytes_other += n_bytes.saturating_sub(n_bytes_columns);
+
+        let kept = get_kept_columns(&schema, &args)?;
+        let casts = get_casts(&schema, &args)?;
+        for (name, compressed, _) in sizes.into_iter() {
+            let dtype = schema.get(&name).cloned().unwrap_or(DataType::Null);
+            let (action, n_bytes_after) = match (kept.contains(&name), casts.get(&name)) {
+                (false, _) => ("drop".to_string(), 0),
+                (true, Some(target)) => (
+                    format!("cast to {}", target),
+                    (compressed as f64 * cast_size_ratio(&dtype, target)) as u64,
+                ),
+                (true, None) => ("keep".to_string(), compressed),
+            };
+            match columns.iter_mut().find(|column| column.name == name) {
+                Some(column) => {
+                    column.n_bytes_before += compressed;
+                    column.n_bytes_after += n_bytes_after;
+                }
+                None => columns.push(ColumnEstimate {
+                    name,
+                    dtype: dtype.to_string(),
+                    action,
+                    n_bytes_before: compressed,
+                    n_bytes_after,
+                }),
+            }
+        }
+    }
+
+    let rows: Vec<[String; 5]> = columns
+        .iter()
+        .map(|column| {
+            [
+                column.name.clone(),
+                column.dtype.clone(),
+                column.action.clone(),
+                format_bytes(column.n_bytes_before),
+                format_bytes(column.n_bytes_after),
+            ]
+        })
+        .collect();
+    super::print_table(&["column", "dtype", "action", "before", "after"], &rows)?;
+
+    let n_bytes_after = n_bytes_other
+        + columns
+            .iter()
+            .map(|column| column.n_bytes_after)
+            .sum::<u64>();
+    println!();
+    print_header("Size estimate");
+    print_bullet("n_files", format_with_commas(paths.len() as u64));
+    print_bullet("size before", format_bytes(n_bytes_before));
+    let change = if n_bytes_before == 0 {
+        String::new()
+    } else {
+        let change = 100.0 * (n_bytes_after as f64 / n_bytes_before as f64 - 1.0);
+        format!(" ({:+.1}%)", change)
+    };
+    print_bullet(
+        "estimated size after",
+        format!("{}{}", format_bytes(n_bytes_after), change),
+    );
+    print_bullet(
+        "estimated savings",
+        format_bytes(n_bytes_before.saturating_sub(n_bytes_after)),
+    );
+    if args.cast.is_some() {
+        println!();
+        println!("[cast columns are scaled by the width of their types, compression may differ]");
+    }
+    Ok(())
+}
+
+/// columns of file kept by the selections of args
+fn get_kept_columns(schema: &Schema, args: &SizeEstimateArgs) -> Result<Vec<String>, TblCliError> {
+    let mut kept: Vec<String> = match &args.select {
+        Some(select) => tbl_core::schemas::resolve_column_selectors(select, schema)?,
+        None => schema.iter_names().map(|name| name.to_string()).collect(),
+    };
+    if let Some(drop) = &args.drop {
+        let dropped = tbl_core::schemas::resolve_column_selectors(drop, schema)?;
+        kept.retain(|name| !dropped.contains(name));
+    }
+    Ok(kept)
+}
+
+/// target dtype of each cast column of file
+fn get_casts(
+    schema: &Schema,
+    args: &SizeEstimateArgs,
+) -> Result<HashMap<String, DataType>, TblCliError> {
+    let mut casts = HashMap::new();
+    if let Some(cast) = &args.cast {
+        for c in crate::transform::resolve_cast_columns(schema, cast)?.iter() {
+            match c.split_once('=') {
+                Some((column, dtype)) => {
+                    casts.insert(column.to_string(), crate::transform::parse_dtype(dtype)?);
+                }
+                None => {
+                    return Err(TblCliError::Arg(format!(
+                        "invalid cast {}, use COLUMN=TYPE",
+                        c
+                    )))
+                }
+            }
+        }
+    }
+    Ok(casts)
+}
+
+/// ratio of the widths of fixed width types, or 1 if either type has no fixed width
+pub(crate) fn cast_size_ratio(source: &DataType, target: &DataType) -> f64 {
+    match (fixed_width(source), fixed_width(target)) {
+        (Some(source), Some(target)) => target as f64 / source as f64,
+        _ => 1.0,
+    }
+}
+
+fn fixed_width(dtype: &DataType) -> Option<usize> {
+    match dtype {
+        DataType::Int8 | DataType::UInt8 => Some(1),
+        DataType::Int16 | DataType::UInt16 => Some(2),
+        DataType::Int32 | DataType::UInt32 | DataType::Float32 | DataType::Date => Some(4),
+        DataType::Int64
+        | DataType::UInt64
+        | DataType::Float64
+        | DataType::Datetime(_, _)
+        | DataType::Duration(_)
+        | DataType::Time => Some(8),
+        DataType::Decimal(_, _) => Some(16),
+        _ => None,
+    }
+}
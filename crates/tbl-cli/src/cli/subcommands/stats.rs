@@ -0,0 +1,101 @@
+use crate::{StatsArgs, TblCliError};
+use polars::prelude::*;
+
+const QUANTILES: [(&str, f64); 7] = [
+    ("min", 0.0),
+    ("p1", 0.01),
+    ("p25", 0.25),
+    ("p50", 0.5),
+    ("p75", 0.75),
+    ("p99", 0.99),
+    ("max", 1.0),
+];
+
+pub(crate) async fn stats_command(args: StatsArgs) -> Result<(), TblCliError> {
+    let paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+
+    // columns of all files in order of first appearance, keeping the first dtype of each
+    let mut schema = Schema::new();
+    for file_schema in tbl_core::parquet::get_parquet_schemas(&paths).await?.iter() {
+        for (name, dtype) in file_schema.iter() {
+            if !schema.contains(name) {
+                schema.with_column(name.clone(), dtype.clone());
+            }
+        }
+    }
+    let columns: Vec<String> = match &args.columns {
+        Some(selectors) => tbl_core::schemas::resolve_column_selectors(selectors, &schema)?,
+        None => schema.iter_names().map(|name| name.to_string()).collect(),
+    };
+    let columns: Vec<(String, DataType)> = columns
+        .into_iter()
+        .filter_map(|name| {
+            let dtype = schema.get(&name)?.clone();
+            dtype.is_numeric().then_some((name, dtype))
+        })
+        .collect();
+    if columns.is_empty() {
+        return Err(TblCliError::Arg(
+            "no numeric columns to summarize".to_string(),
+        ));
+    }
+
+    let names: Vec<String> = columns.iter().map(|(name, _)| name.clone()).collect();
+    let batch_size = 8192;
+    let max_concurrent = 8;
+    let cache = crate::config::open_cache("stats").filter(|_| !args.no_cache);
+    let (profiles, n_cached) = tbl_core::parquet::parquet_column_profiles(
+        &paths,
+        &names,
+        args.k,
+        batch_size,
+        max_concurrent,
+        cache.as_ref(),
+    )
+    .await?;
+
+    let rows: Vec<[String; 11]> = columns
+        .iter()
+        .zip(profiles.iter())
+        .map(|((name, dtype), profile)| {
+            let mut row: [String; 11] = Default::default();
+            row[0] = name.clone();
+            row[1] = dtype.to_string();
+            row[2] = tbl_core::formats::format_with_commas(profile.quantiles.n_items());
+            row[3] = tbl_core::formats::format_with_commas(profile.distinct.estimate());
+            for (i, (_, q)) in QUANTILES.iter().enumerate() {
+                row[4 + i] = profile
+                    .quantiles
+                    .quantile(*q)
+                    .map(tbl_core::formats::format_float)
+                    .unwrap_or_default();
+            }
+            row
+        })
+        .collect();
+    let mut labels = [
+        "column", "dtype", "values", "distinct", "", "", "", "", "", "", "",
+    ];
+    for (i, (label, _)) in QUANTILES.iter().enumerate() {
+        labels[4 + i] = label;
+    }
+    tbl_core::formats::print_header("Quantiles");
+    super::print_table(&labels, &rows)?;
+    println!();
+    println!(
+        "{} numeric columns in {} files, distinct counts and quantiles are approximate and values exclude nulls",
+        columns.len(),
+        paths.len()
+    );
+    if n_cached > 0 {
+        println!(
+            "[{} of {} files read from cache, use --no-cache to read all files]",
+            n_cached,
+            paths.len()
+        );
+    }
+    Ok(())
+}
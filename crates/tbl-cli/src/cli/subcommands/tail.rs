@@ -0,0 +1,161 @@
+use crate::{TailArgs, TblCliError};
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub(crate) async fn tail_command(args: TailArgs) -> Result<(), TblCliError> {
+    let input_paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    if input_paths.is_empty() && !args.follow {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+    let path_refs: Vec<&Path> = input_paths.iter().map(|path| path.as_path()).collect();
+    let row_counts = tbl_core::parquet::get_parquet_row_counts(&path_refs).await?;
+
+    let mut printer = RowPrinter::new(&args);
+    if let Some(range) = &args.rows {
+        let slice = crate::transform::parse_row_range(range)?;
+        if !input_paths.is_empty() {
+            let lf = tbl_core::parquet::create_lazyframe(&input_paths)?;
+            let lf = crate::transform::apply_offset(lf, slice.offset)?;
+            let lf = crate::transform::apply_head(lf, slice.head)?;
+            let lf = crate::transform::apply_tail(lf, slice.tail)?;
+            printer.print(&lf.collect()?, None)?;
+        }
+    } else {
+        // rows from start to end, counted from the first row, skipping offset rows at the end
+        let n_total: u64 = row_counts.iter().sum();
+        let end = n_total.saturating_sub(args.offset.unwrap_or(0) as u64);
+        let start = end.saturating_sub(args.n as u64);
+
+        // only read the last files, which hold the last rows
+        let mut first = input_paths.len();
+        let mut first_row = n_total;
+        while first > 0 && first_row > start {
+            first -= 1;
+            first_row -= row_counts[first];
+        }
+        if end > start {
+            let df = tbl_core::parquet::create_lazyframe(&input_paths[first..])?
+                .slice((start - first_row) as i64, (end - start) as IdxSize)
+                .collect()?;
+            printer.print(&df, None)?;
+        }
+    }
+    if !args.follow {
+        return Ok(());
+    }
+
+    // poll for files that are new or that were rewritten with a different number of rows,
+    // reading footers only of files whose size or modification time changed
+    let mut seen: HashMap<PathBuf, (u64, Option<FileStamp>)> = input_paths
+        .into_iter()
+        .zip(row_counts)
+        .map(|(path, n_rows)| {
+            let stamp = FileStamp::of(&path);
+            (path, (n_rows, stamp))
+        })
+        .collect();
+    let interval = std::time::Duration::from_secs_f64(args.interval);
+    loop {
+        tokio::time::sleep(interval).await;
+        let paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+        for path in paths.into_iter() {
+            let stamp = FileStamp::of(&path);
+            let n_seen = match seen.get(&path) {
+                Some((_, seen_stamp)) if stamp.is_some() && *seen_stamp == stamp => continue,
+                Some((n_seen, _)) => Some(*n_seen),
+                None => None,
+            };
+            // files still being written have no footer yet, so they are read on a later poll
+            let n_rows = match tbl_core::parquet::get_parquet_row_count(&path).await {
+                Ok(n_rows) => n_rows,
+                Err(_) => continue,
+            };
+            if n_seen == Some(n_rows) {
+                seen.insert(path, (n_rows, stamp));
+                continue;
+            }
+            // files that shrink were replaced, so all of their rows are new
+            let offset = match n_seen {
+                Some(n_seen) if n_seen < n_rows => n_seen,
+                _ => 0,
+            };
+            let df = match tbl_core::parquet::create_lazyframe(std::slice::from_ref(&path))
+                .and_then(|lf| {
+                    Ok(lf
+                        .slice(offset as i64, (n_rows - offset) as IdxSize)
+                        .collect()?)
+                }) {
+                Ok(df) => df,
+                Err(_) => continue,
+            };
+            seen.insert(path.clone(), (n_rows, stamp));
+            if df.height() > 0 {
+                printer.print(&df, Some(&path))?;
+            }
+        }
+    }
+}
+
+/// size and modification time of a file, which change when a file is rewritten
+#[derive(Clone, Copy, PartialEq)]
+struct FileStamp {
+    len: u64,
+    modified: std::time::SystemTime,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Option<FileStamp> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(FileStamp {
+            len: metadata.len(),
+            modified: metadata.modified().ok()?,
+        })
+    }
+}
+
+/// prints batches of rows, writing the csv header only once
+struct RowPrinter {
+    csv: bool,
+    jsonl: bool,
+    wrote_header: bool,
+}
+
+impl RowPrinter {
+    fn new(args: &TailArgs) -> RowPrinter {
+        RowPrinter {
+            csv: args.csv,
+            jsonl: args.jsonl,
+            wrote_header: false,
+        }
+    }
+
+    fn print(&mut self, df: &DataFrame, path: Option<&Path>) -> Result<(), TblCliError> {
+        // text outputs cannot hold raw bytes
+        let text_output = self.csv || self.jsonl;
+        let mut df = if text_output {
+            crate::output::format_binary_columns(df, Some("0x"))?
+        } else {
+            df.clone()
+        };
+        if self.csv {
+            CsvWriter::new(std::io::stdout())
+                .include_header(!self.wrote_header)
+                .finish(&mut df)?;
+            self.wrote_header = true;
+        } else if self.jsonl {
+            JsonWriter::new(std::io::stdout()).finish(&mut df)?;
+        } else {
+            if let Some(path) = path {
+                println!();
+                tbl_core::formats::print_header(format!(
+                    "{} (+{} rows)",
+                    path.to_string_lossy(),
+                    tbl_core::formats::format_with_commas(df.height() as u64)
+                ));
+            }
+            println!("{}", df);
+        }
+        Ok(())
+    }
+}
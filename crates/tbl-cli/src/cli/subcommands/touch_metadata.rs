@@ -0,0 +1,79 @@
+use crate::{TblCliError, TouchMetadataArgs};
+use tbl_core::formats::{format_with_commas, print_bullet, print_header};
+
+pub(crate) async fn touch_metadata_command(args: TouchMetadataArgs) -> Result<(), TblCliError> {
+    let edits = parse_footer_edits(&args)?;
+    let paths = tbl_core::filesystem::get_input_paths(&args.paths, args.tree, true)?;
+    let paths: Vec<_> = paths
+        .into_iter()
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("parquet"))
+        .collect();
+    if paths.is_empty() {
+        return Err(TblCliError::no_inputs(&args.paths, args.tree));
+    }
+    crate::protection::check_not_protected(&paths)?;
+
+    print_header("Footer edits");
+    print_bullet("n_files", format_with_commas(paths.len() as u64));
+    if let Some(created_by) = &edits.created_by {
+        print_bullet("created_by", created_by);
+    }
+    for (key, value) in edits.set.iter() {
+        print_bullet(format!("set {}", key), value);
+    }
+    for key in edits.remove.iter() {
+        print_bullet("remove", key);
+    }
+    println!();
+    if args.dry {
+        println!("[dry run, exiting]");
+        return Ok(());
+    }
+    if !args.confirm {
+        let prompt = "continue? ";
+        if let Ok(true) = inquire::Confirm::new(prompt).with_default(false).prompt() {
+        } else {
+            return Err(TblCliError::Aborted);
+        }
+    }
+
+    for path in paths.iter() {
+        tbl_core::parquet::touch_parquet_metadata(path, &edits)?;
+    }
+    println!(
+        "[updated footer metadata of {} files]",
+        format_with_commas(paths.len() as u64)
+    );
+    Ok(())
+}
+
+fn parse_footer_edits(
+    args: &TouchMetadataArgs,
+) -> Result<tbl_core::parquet::FooterEdits, TblCliError> {
+    let mut set = Vec::new();
+    for pair in args.set.iter() {
+        match pair.split_once('=') {
+            Some((key, value)) if !key.is_empty() => set.push((key.to_string(), value.to_string())),
+            _ => {
+                return Err(TblCliError::Arg(format!(
+                    "invalid --set {}, syntax is KEY=VALUE",
+                    pair
+                )))
+            }
+        }
+    }
+    if let Some(key) = &args.timestamp {
+        let now = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        set.push((key.clone(), now));
+    }
+    if args.created_by.is_none() && set.is_empty() && args.remove.is_empty() {
+        return Err(TblCliError::Arg(
+            "specify --created-by, --set, --remove, or --timestamp".to_string(),
+        ));
+    }
+    Ok(tbl_core::parquet::FooterEdits {
+        created_by: args.created_by.clone(),
+        set,
+        remove: args.remove.clone(),
+    })
+}
@@ -0,0 +1,8 @@
+use crate::{TblCliError, UpdateArgs};
+
+pub(crate) async fn update_command(args: UpdateArgs) -> Result<(), TblCliError> {
+    let mut data_args = args.output_args.into_data_args(args.paths, args.tree);
+    data_args.update = Some(args.set);
+    data_args.update_where = args.where_;
+    super::data_command(data_args).await
+}
@@ -0,0 +1,82 @@
+use crate::TblCliError;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// user configuration, read from `$TBL_CONFIG` or `~/.config/tbl/config.toml`
+#[derive(Clone, Debug, Default, Deserialize)]
+pub(crate) struct Config {
+    /// name of theme to use
+    pub(crate) theme: Option<String>,
+
+    /// custom themes
+    #[serde(default)]
+    pub(crate) themes: HashMap<String, ThemeConfig>,
+
+    /// directories that edits cannot write to
+    #[serde(default)]
+    pub(crate) protected: Vec<PathBuf>,
+}
+
+/// custom theme, colors are hex strings like "#ce93f9"
+#[derive(Clone, Debug, Default, Deserialize)]
+pub(crate) struct ThemeConfig {
+    /// builtin theme to take unspecified colors from
+    pub(crate) base: Option<String>,
+    pub(crate) title: Option<String>,
+    pub(crate) comment: Option<String>,
+    pub(crate) string: Option<String>,
+    pub(crate) constant: Option<String>,
+    pub(crate) function: Option<String>,
+    pub(crate) variable: Option<String>,
+    pub(crate) background: Option<String>,
+    pub(crate) accent: Option<String>,
+    pub(crate) text: Option<String>,
+    pub(crate) muted: Option<String>,
+    pub(crate) error: Option<String>,
+}
+
+pub(crate) fn get_config_path() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("TBL_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let config_dir = match std::env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var("HOME").ok()?).join(".config"),
+    };
+    Some(config_dir.join("tbl").join("config.toml"))
+}
+
+/// directory of cached results, `$XDG_CACHE_HOME/tbl` or `~/.cache/tbl`
+pub(crate) fn get_cache_dir() -> Option<PathBuf> {
+    let cache_dir = match std::env::var("XDG_CACHE_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var("HOME").ok()?).join(".cache"),
+    };
+    Some(cache_dir.join("tbl"))
+}
+
+/// most bytes kept in each cache, beyond which least recently used entries are evicted
+const CACHE_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+/// cache of results named name, inside the cache directory
+pub(crate) fn open_cache(name: &str) -> Option<tbl_core::cache::ResultCache> {
+    let dir = get_cache_dir()?.join(name);
+    Some(tbl_core::cache::ResultCache::new(&dir, CACHE_MAX_BYTES))
+}
+
+/// load config file, using an empty config if the file does not exist
+pub(crate) fn load_config() -> Result<Config, TblCliError> {
+    let path = match get_config_path() {
+        Some(path) if path.exists() => path,
+        _ => return Ok(Config::default()),
+    };
+    let contents = std::fs::read_to_string(&path)?;
+    toml::from_str(&contents).map_err(|e| {
+        TblCliError::Error(format!(
+            "could not parse config file {}: {}",
+            path.to_string_lossy(),
+            e
+        ))
+    })
+}
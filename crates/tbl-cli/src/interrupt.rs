@@ -0,0 +1,103 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tbl_core::executor::CancelToken;
+
+/// handler of ctrl-c during batch edits
+///
+/// the first ctrl-c stops new files from starting and lets files in progress finish, a second
+/// ctrl-c exits immediately after removing the temporary files of files in progress
+#[derive(Clone)]
+pub(crate) struct Interrupt {
+    pub(crate) cancel: CancelToken,
+    in_flight: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+impl Interrupt {
+    /// install signal handler, with resume hint printed if the edit is checkpointed
+    pub(crate) fn install(resumable: bool) -> Interrupt {
+        let interrupt = Interrupt {
+            cancel: CancelToken::new(),
+            in_flight: Arc::new(Mutex::new(HashSet::new())),
+        };
+        let handler = interrupt.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_err() {
+                return;
+            }
+            handler.cancel.cancel();
+            eprintln!(
+                "\n[interrupted, finishing files in progress, press ctrl-c again to stop now]"
+            );
+            if tokio::signal::ctrl_c().await.is_err() {
+                return;
+            }
+            handler.remove_tmp_files();
+            print_resume_hint(resumable);
+            // files in progress cannot be stopped from here, so exit without unwinding
+            std::process::exit(crate::TblCliError::Aborted.exit_code())
+        });
+        interrupt
+    }
+
+    /// mark output as being written
+    pub(crate) fn start(&self, output_path: &Path) {
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            in_flight.insert(output_path.to_path_buf());
+        }
+    }
+
+    /// mark output as no longer being written
+    pub(crate) fn finish(&self, output_path: &Path) {
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            in_flight.remove(output_path);
+        }
+    }
+
+    fn remove_tmp_files(&self) {
+        if let Ok(in_flight) = self.in_flight.lock() {
+            for tmp_path in in_flight.iter().flat_map(|path| tmp_paths(path)) {
+                if tmp_path.exists() && std::fs::remove_file(&tmp_path).is_ok() {
+                    eprintln!("[removed {}]", tmp_path.to_string_lossy());
+                }
+            }
+        }
+    }
+}
+
+/// temporary files that writers of output may create next to it
+///
+/// lazyframe outputs are staged at `file.parquet_tmp` (and `file.parquet_tmp_tmp` for legacy
+/// layouts), while the rewriters of tbl_core stage at `file_tmp.parquet`
+fn tmp_paths(output_path: &Path) -> [PathBuf; 3] {
+    let tmp_path = crate::output::get_tmp_path(output_path);
+    [
+        crate::output::get_tmp_path(&tmp_path),
+        tmp_path,
+        tbl_core::parquet::create_tmp_target(output_path),
+    ]
+}
+
+/// print how to continue an interrupted edit
+pub(crate) fn print_resume_hint(resumable: bool) {
+    if resumable {
+        eprintln!(
+            "[edit interrupted, completed files are checkpointed, rerun with --resume to continue]"
+        );
+    } else {
+        eprintln!("[edit interrupted]");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tmp_paths_cover_all_writers() {
+        let paths = tmp_paths(Path::new("data/file.parquet"));
+        assert!(paths.contains(&PathBuf::from("data/file.parquet_tmp")));
+        assert!(paths.contains(&PathBuf::from("data/file.parquet_tmp_tmp")));
+        assert!(paths.contains(&PathBuf::from("data/file_tmp.parquet")));
+    }
+}
@@ -0,0 +1,125 @@
+use crate::TblCliError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// key of parquet key value metadata that holds the lineage of a file
+pub(crate) const LINEAGE_KEY: &str = "tbl.lineage";
+
+/// key of parquet key value metadata that holds the content hash of the inputs of a file
+pub(crate) const INPUT_HASH_KEY: &str = "tbl.input_hash";
+
+/// how a file was derived, stored as json in its key value metadata
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct Lineage {
+    /// version of tbl that wrote the file
+    pub(crate) tbl_version: String,
+    /// command line that wrote the file
+    pub(crate) command: String,
+    /// time of write in rfc 3339 format
+    pub(crate) time: String,
+    /// files the output was derived from
+    pub(crate) sources: Vec<LineageSource>,
+}
+
+/// input of a derived file
+///
+/// only one generation is stored, earlier generations are read from sources that are unchanged
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct LineageSource {
+    /// path of input when it was read
+    pub(crate) path: PathBuf,
+    /// footer fingerprint of input when it was read
+    pub(crate) fingerprint: String,
+}
+
+impl Lineage {
+    /// lineage of an output derived from input_paths by the current command
+    ///
+    /// inputs are fingerprinted before they can be overwritten by inplace edits
+    pub(crate) fn from_inputs(input_paths: &[PathBuf]) -> Result<Lineage, TblCliError> {
+        let mut sources = Vec::new();
+        for path in input_paths.iter() {
+            let is_parquet = path.extension().and_then(|ext| ext.to_str()) == Some("parquet");
+            let fingerprint = if is_parquet {
+                tbl_core::parquet::parquet_footer_fingerprint(path)?
+            } else {
+                String::new()
+            };
+            sources.push(LineageSource {
+                path: path.canonicalize().unwrap_or_else(|_| path.clone()),
+                fingerprint,
+            });
+        }
+        Ok(Lineage {
+            tbl_version: env!("CARGO_PKG_VERSION").to_string(),
+            command: get_command_line(),
+            time: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            sources,
+        })
+    }
+
+    /// key value metadata entry that stores lineage in a parquet output
+    pub(crate) fn to_key_value(&self) -> Result<(String, String), TblCliError> {
+        let json = serde_json::to_string(self).map_err(|e| TblCliError::Error(e.to_string()))?;
+        Ok((LINEAGE_KEY.to_string(), json))
+    }
+}
+
+/// command line of the current process, quoting arguments that contain whitespace
+fn get_command_line() -> String {
+    let mut args: Vec<String> = std::env::args()
+        .map(|arg| {
+            if arg.is_empty() || arg.contains(char::is_whitespace) || arg.contains('\'') {
+                format!("'{}'", arg.replace('\'', "'\\''"))
+            } else {
+                arg
+            }
+        })
+        .collect();
+    if let Some(program) = args.first_mut() {
+        *program = "tbl".to_string();
+    }
+    args.join(" ")
+}
+
+/// read lineage stored in parquet file, if any
+pub(crate) async fn read_lineage(path: &Path) -> Result<Option<Lineage>, TblCliError> {
+    let metadata = tbl_core::parquet::get_parquet_metadata(path).await?;
+    let value = metadata
+        .file_metadata()
+        .key_value_metadata()
+        .and_then(|key_values| key_values.iter().find(|kv| kv.key == LINEAGE_KEY))
+        .and_then(|kv| kv.value.clone());
+    match value {
+        Some(value) => serde_json::from_str(&value).map(Some).map_err(|e| {
+            TblCliError::Error(format!(
+                "could not parse lineage of {}: {}",
+                path.to_string_lossy(),
+                e
+            ))
+        }),
+        None => Ok(None),
+    }
+}
+
+/// content hash of the bytes of input files, in order
+pub(crate) fn get_input_hash(input_paths: &[PathBuf]) -> Result<String, TblCliError> {
+    let mut hash = tbl_core::parquet::FNV_OFFSET_BASIS;
+    for path in input_paths.iter() {
+        let fingerprint = tbl_core::parquet::file_content_fingerprint(path)?;
+        hash = tbl_core::parquet::fnv1a(hash, fingerprint.as_bytes());
+    }
+    Ok(format!("{:016x}", hash))
+}
+
+/// input hash recorded in parquet file, if file exists and has one
+pub(crate) async fn read_input_hash(path: &Path) -> Option<String> {
+    let metadata = tbl_core::parquet::get_parquet_metadata(path).await.ok()?;
+    metadata
+        .file_metadata()
+        .key_value_metadata()?
+        .iter()
+        .find(|kv| kv.key == INPUT_HASH_KEY)?
+        .value
+        .clone()
+}
@@ -8,23 +8,65 @@
     attr(deny(warnings, rust_2018_idioms), allow(dead_code, unused_variables))
 ))]
 
+mod checkpoint;
+
 mod cli;
 pub(crate) use cli::*;
 
+mod config;
+
+mod interrupt;
+
+mod lineage;
+
 pub(crate) mod styles;
 
 mod types;
 use types::*;
 
+mod nested;
+
+mod protection;
+
+mod pruning;
+
 mod python;
 
+mod quarantine;
+
+mod schema_history;
+
 mod summary;
 
 mod transform;
 
 mod output;
 
-#[tokio::main]
-async fn main() -> Result<(), TblCliError> {
-    cli::run_cli().await
+fn main() {
+    let args = cli::parse_cli();
+
+    // polars reads display options from the environment each time a table is printed, so
+    // they are set before the runtime starts any threads, tail and remote show every row
+    if matches!(
+        args.command,
+        Some(Subcommands::Tail(_) | Subcommands::Remote(_))
+    ) {
+        std::env::set_var("POLARS_FMT_MAX_ROWS", "-1");
+    }
+    output::set_table_format(&args.data_args);
+
+    let json_errors = args.error_format.as_deref() == Some("json");
+    let allow_empty = args.allow_empty;
+    let result = tokio::runtime::Runtime::new()
+        .map_err(TblCliError::from)
+        .and_then(|runtime| runtime.block_on(cli::run_cli(args)));
+    if let Err(e) = result {
+        let exit_code = if matches!(e, TblCliError::NoInputs(_)) && allow_empty {
+            0
+        } else {
+            e.exit_code()
+        };
+        e.report(json_errors, exit_code);
+        std::process::exit(exit_code)
+    }
 }
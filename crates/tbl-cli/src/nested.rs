@@ -0,0 +1,121 @@
+use crate::TblCliError;
+use polars::prelude::*;
+
+/// split a dot path like `payload.gas_used` into a struct column and field names
+///
+/// returns None when the name is a top-level column or does not address a struct field
+pub(crate) fn split_nested_path<'a>(
+    schema: &Schema,
+    name: &'a str,
+) -> Option<(&'a str, Vec<&'a str>)> {
+    if schema.contains(name) {
+        return None;
+    }
+    // column names can themselves contain dots, so try each prefix
+    for (i, _) in name.match_indices('.') {
+        let (root, rest) = (&name[..i], &name[i + 1..]);
+        if let Some(DataType::Struct(_)) = schema.get(root) {
+            return Some((root, rest.split('.').collect()));
+        }
+    }
+    None
+}
+
+/// expression reading a column or a nested struct field
+pub(crate) fn column_expr(schema: &Schema, name: &str) -> Expr {
+    match split_nested_path(schema, name) {
+        Some((root, fields)) => fields
+            .iter()
+            .fold(col(root), |expr, field| expr.struct_().field_by_name(field)),
+        None => col(name),
+    }
+}
+
+/// rebuild a struct, replacing the field at `path` with `edit` applied to it
+///
+/// the field is removed when `edit` returns None
+pub(crate) fn edit_struct_field(
+    expr: Expr,
+    dtype: &DataType,
+    path: &[&str],
+    edit: &dyn Fn(Expr) -> Option<Expr>,
+) -> Result<Option<Expr>, TblCliError> {
+    let (first, rest) = match path.split_first() {
+        Some(split) => split,
+        None => return Ok(edit(expr)),
+    };
+    let fields = match dtype {
+        DataType::Struct(fields) => fields,
+        _ => {
+            return Err(TblCliError::Arg(format!(
+                "{} is not inside a struct",
+                first
+            )))
+        }
+    };
+    if !fields.iter().any(|field| field.name() == first) {
+        return Err(TblCliError::Arg(format!(
+            "struct field {} not found",
+            first
+        )));
+    }
+    let mut exprs = Vec::new();
+    for field in fields.iter() {
+        let field_expr = expr.clone().struct_().field_by_name(field.name());
+        if field.name() == first {
+            if let Some(edited) = edit_struct_field(field_expr, field.data_type(), rest, edit)? {
+                exprs.push(edited.alias(field.name()));
+            }
+        } else {
+            exprs.push(field_expr.alias(field.name()));
+        }
+    }
+    if exprs.is_empty() {
+        return Err(TblCliError::Arg(
+            "cannot remove every field of a struct, drop the struct column instead".to_string(),
+        ));
+    }
+    Ok(Some(as_struct(exprs)))
+}
+
+/// rebuild a struct keeping only the fields at `paths`
+pub(crate) fn project_struct(
+    expr: Expr,
+    dtype: &DataType,
+    paths: &[Vec<&str>],
+) -> Result<Expr, TblCliError> {
+    let fields = match dtype {
+        DataType::Struct(fields) => fields,
+        _ => return Ok(expr),
+    };
+    for path in paths.iter() {
+        if !fields
+            .iter()
+            .any(|field| Some(&field.name().as_str()) == path.first())
+        {
+            return Err(TblCliError::Arg(format!(
+                "struct field {} not found",
+                path.join(".")
+            )));
+        }
+    }
+    let mut exprs = Vec::new();
+    for field in fields.iter() {
+        let sub_paths: Vec<Vec<&str>> = paths
+            .iter()
+            .filter(|path| path.first() == Some(&field.name().as_str()))
+            .map(|path| path[1..].to_vec())
+            .collect();
+        if sub_paths.is_empty() {
+            continue;
+        }
+        let field_expr = expr.clone().struct_().field_by_name(field.name());
+        let field_expr = if sub_paths.iter().any(|path| path.is_empty()) {
+            field_expr
+        } else {
+            project_struct(field_expr, field.data_type(), &sub_paths)?
+        };
+        exprs.push(field_expr.alias(field.name()));
+    }
+    Ok(as_struct(exprs))
+}
@@ -1,8 +1,7 @@
 use crate::styles::FontStyle;
 use crate::{DataArgs, OutputMode, TblCliError};
-use color_print::cstr;
 use polars::prelude::*;
-use std::io::stdout;
+use std::io::{stdout, IsTerminal};
 use std::path::PathBuf;
 use toolstr::Colorize;
 
@@ -11,33 +10,27 @@ pub(crate) fn output_lazyframe(
     input_paths: Vec<PathBuf>,
     output_path: Option<PathBuf>,
     output_mode: &OutputMode,
+    key_values: &[(String, String)],
     args: &DataArgs,
 ) -> Result<(), TblCliError> {
     match output_mode {
-        OutputMode::PrintToStdout => print_lazyframe(lf, args),
-        OutputMode::SaveToSingleFile => save_lf_to_disk(lf, output_path, args),
-        OutputMode::SaveToDirectory => save_lf_to_disk(lf, output_path, args),
-        OutputMode::ModifyInplace => save_lf_to_disk(lf, output_path, args),
+        OutputMode::PrintToStdout => print_lazyframe(lf, &input_paths, args),
+        OutputMode::SaveToSingleFile => save_lf_to_disk(lf, output_path, key_values, args),
+        OutputMode::SaveToDirectory => save_lf_to_disk(lf, output_path, key_values, args),
+        OutputMode::ModifyInplace => save_lf_to_disk(lf, output_path, key_values, args),
         OutputMode::Partition => partition_data(lf, input_paths, args),
         OutputMode::InteractiveLf => enter_interactive_session(lf, input_paths, args),
         OutputMode::InteractiveDf => enter_interactive_session(lf, input_paths, args),
     }
 }
 
-fn print_lazyframe(lf: LazyFrame, args: &DataArgs) -> Result<(), TblCliError> {
+fn print_lazyframe(
+    lf: LazyFrame,
+    input_paths: &[PathBuf],
+    args: &DataArgs,
+) -> Result<(), TblCliError> {
     let df = lf.collect()?;
 
-    let mut df = match args.hex {
-        true => binary_to_hex(&mut df.clone())?,
-        false => df,
-    };
-
-    if !args.no_summary {
-        println!();
-        println!();
-        tbl_core::formats::print_header("Data");
-    };
-
     let n_show = match &args.n {
         Some(n) if n == "all" => df.height(),
         Some(n) => n.parse::<usize>()?,
@@ -49,28 +42,76 @@ fn print_lazyframe(lf: LazyFrame, args: &DataArgs) -> Result<(), TblCliError> {
         0
     };
 
+    // only shown rows are rendered, so that large frames are not formatted in full
+    let df = df.head(Some(n_show));
+
+    // text outputs cannot hold raw bytes, so they render every binary column
+    let text_output = args.csv | args.json | args.jsonl | args.format.is_some();
+    let binary_mode = match (&args.binary, args.hex || text_output) {
+        (Some(mode), _) => Some(mode.as_str()),
+        (None, true) => Some("0x"),
+        (None, false) => None,
+    };
+    let df = format_binary_columns(&df, binary_mode)?;
+
+    if args.per_file {
+        // sections of each file are separated by their path
+        let path = input_paths
+            .first()
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default();
+        println!();
+        println!();
+        tbl_core::formats::print_header(path);
+    } else if !args.no_summary {
+        println!();
+        println!();
+        tbl_core::formats::print_header("Data");
+    };
+
     if args.csv {
-        let df = binary_to_hex(&mut df)?;
         print_dataframe_as_csv(&df, n_show)?;
     } else if args.json | args.jsonl {
-        let df = binary_to_hex(&mut df)?;
         print_dataframe_as_json(&df, n_show, args.jsonl)?;
+    } else if let Some(format) = &args.format {
+        print_dataframe_as_markup(&df, n_show, format)?;
     } else {
-        let df = df.head(Some(n_show));
         println!("{}", df);
+        if args.wide && std::io::stdout().is_terminal() {
+            println!("pipe into {} to scroll horizontally", "less -S".bold());
+        }
     };
 
     if n_missing > 0 {
         println!(
             "{} rows omitted, use {} to show all rows",
             n_missing.to_string().colorize_constant().bold(),
-            cstr!("<white><bold>-n all</bold></white>")
+            "-n all".bold()
         );
     }
 
     Ok(())
 }
 
+/// configure truncation of printed tables through polars formatting variables
+///
+/// called once with the parsed table flags before any threads start
+pub(crate) fn set_table_format(args: &DataArgs) {
+    if let Some(max_col_width) = args.max_col_width {
+        std::env::set_var("POLARS_FMT_STR_LEN", max_col_width.to_string());
+    }
+    if args.no_truncate {
+        std::env::set_var("POLARS_FMT_STR_LEN", "-1");
+    }
+    if args.no_truncate || args.wide {
+        std::env::set_var("POLARS_FMT_MAX_COLS", "-1");
+        std::env::set_var("POLARS_TABLE_WIDTH", u16::MAX.to_string());
+    }
+    if let Some(max_columns) = args.max_columns {
+        std::env::set_var("POLARS_FMT_MAX_COLS", max_columns.to_string());
+    }
+}
+
 fn print_dataframe_as_csv(df: &DataFrame, n: usize) -> Result<(), PolarsError> {
     let mut writer = CsvWriter::new(stdout());
     let df: DataFrame = df.head(Some(n));
@@ -94,42 +135,193 @@ fn print_dataframe_as_json(df: &DataFrame, n: usize, jsonl: bool) -> Result<(),
     result
 }
 
-fn binary_to_hex(df: &mut DataFrame) -> Result<DataFrame, PolarsError> {
-    let mut df = df.clone();
+/// print dataframe as a markdown, html, or latex table
+fn print_dataframe_as_markup(df: &DataFrame, n: usize, format: &str) -> Result<(), PolarsError> {
+    let df: DataFrame = df.head(Some(n));
+    let names: Vec<String> = df
+        .get_column_names()
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    let numeric: Vec<bool> = df
+        .get_columns()
+        .iter()
+        .map(|s| s.dtype().is_numeric())
+        .collect();
+    let mut rows = Vec::with_capacity(df.height());
+    for i in 0..df.height() {
+        let mut row = Vec::with_capacity(names.len());
+        for column in df.get_columns() {
+            row.push(match column.get(i)? {
+                AnyValue::Null => String::new(),
+                AnyValue::String(value) => value.to_string(),
+                AnyValue::StringOwned(value) => value.to_string(),
+                value => value.to_string(),
+            });
+        }
+        rows.push(row);
+    }
+
+    let escape: fn(&str) -> String = match format {
+        "html" => escape_html,
+        "latex" => escape_latex,
+        _ => escape_markdown,
+    };
+    let names: Vec<String> = names.iter().map(|name| escape(name)).collect();
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(|cell| escape(cell)).collect())
+        .collect();
+
+    match format {
+        "html" => {
+            println!("<table>");
+            println!("  <thead>");
+            let header: Vec<String> = names.iter().map(|n| format!("<th>{}</th>", n)).collect();
+            println!("    <tr>{}</tr>", header.join(""));
+            println!("  </thead>");
+            println!("  <tbody>");
+            for row in rows.iter() {
+                let cells: Vec<String> = row.iter().map(|c| format!("<td>{}</td>", c)).collect();
+                println!("    <tr>{}</tr>", cells.join(""));
+            }
+            println!("  </tbody>");
+            println!("</table>");
+        }
+        "latex" => {
+            let alignment: String = numeric
+                .iter()
+                .map(|numeric| if *numeric { 'r' } else { 'l' })
+                .collect();
+            println!("\\begin{{tabular}}{{{}}}", alignment);
+            println!("\\hline");
+            println!("{} \\\\", names.join(" & "));
+            println!("\\hline");
+            for row in rows.iter() {
+                println!("{} \\\\", row.join(" & "));
+            }
+            println!("\\hline");
+            println!("\\end{{tabular}}");
+        }
+        _ => {
+            let separators: Vec<&str> = numeric
+                .iter()
+                .map(|numeric| if *numeric { "---:" } else { "---" })
+                .collect();
+            println!("| {} |", names.join(" | "));
+            println!("| {} |", separators.join(" | "));
+            for row in rows.iter() {
+                println!("| {} |", row.join(" | "));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn escape_markdown(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
 
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+        .replace('\n', "<br>")
+}
+
+fn escape_latex(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// render binary columns as text using a mode of hex, base64, 0x, or len
+///
+/// without a mode, only fixed-size columns such as hashes and addresses are rendered, as 0x hex
+pub(crate) fn format_binary_columns(
+    df: &DataFrame,
+    mode: Option<&str>,
+) -> Result<DataFrame, TblCliError> {
     let binary_columns: Vec<String> = df
         .get_columns()
         .iter()
-        .filter_map(|s| {
-            if matches!(s.dtype(), DataType::Binary) {
-                Some(s.name().to_string())
-            } else {
-                None
-            }
-        })
+        .filter(|s| matches!(s.dtype(), DataType::Binary))
+        .map(|s| s.name().to_string())
         .collect();
 
-    for col_name in binary_columns {
-        let hex_col_with_prefix = df
-            .clone()
-            .lazy()
-            .select(&[
-                concat_str([lit("0x"), col(&col_name).binary().hex_encode()], "", true)
-                    .alias(&col_name),
-            ])
-            .collect()?
-            .column(&col_name)?
-            .clone();
+    let mut exprs = Vec::new();
+    for name in binary_columns.iter() {
+        let column_mode = match mode {
+            Some(mode) => mode,
+            None if is_fixed_size_binary(df, name)? => "0x",
+            None => continue,
+        };
+        let hex = col(name).binary().hex_encode();
+        let expr = match column_mode {
+            "hex" => hex,
+            "0x" => concat_str([lit("0x"), hex], "", true),
+            "base64" => col(name).binary().base64_encode(),
+            "len" => (hex.str().len_bytes() / lit(2u32)).cast(DataType::UInt32),
+            other => {
+                return Err(TblCliError::Arg(format!(
+                    "unknown binary mode {}, use hex, base64, 0x, or len",
+                    other
+                )))
+            }
+        };
+        exprs.push(expr.alias(name));
+    }
 
-        df = df.with_column(hex_col_with_prefix)?.clone();
+    if exprs.is_empty() {
+        Ok(df.clone())
+    } else {
+        Ok(df.clone().lazy().with_columns(exprs).collect()?)
     }
+}
 
-    Ok(df)
+/// whether every value of a binary column has the same number of bytes
+fn is_fixed_size_binary(df: &DataFrame, name: &str) -> Result<bool, TblCliError> {
+    let length = col(name).binary().hex_encode().str().len_bytes();
+    let lengths = df
+        .clone()
+        .lazy()
+        .select([length.clone().min().alias("min"), length.max().alias("max")])
+        .collect()?;
+    let min = lengths.column("min")?.get(0)?;
+    let max = lengths.column("max")?.get(0)?;
+    Ok(!min.is_null() && min == max)
+}
+
+/// temporary path that an output is written to before it is moved into place
+pub(crate) fn get_tmp_path(output_path: &std::path::Path) -> PathBuf {
+    let mut tmp_name = output_path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push("_tmp");
+    output_path.with_file_name(tmp_name)
 }
 
 fn save_lf_to_disk(
     lf: LazyFrame,
     output_path: Option<PathBuf>,
+    key_values: &[(String, String)],
     args: &DataArgs,
 ) -> Result<(), TblCliError> {
     let output_path = match output_path {
@@ -138,14 +330,10 @@ fn save_lf_to_disk(
     };
 
     // Create a temporary path by appending "_tmp" to the original path
-    let tmp_path = output_path.with_file_name(format!(
-        "{}_tmp",
-        output_path
-            .file_name()
-            .ok_or_else(|| TblCliError::Error("File name is missing".to_string()))?
-            .to_str()
-            .ok_or_else(|| TblCliError::Error("File name is not valid UTF-8".to_string()))?
-    ));
+    if output_path.file_name().is_none() {
+        return Err(TblCliError::Error("File name is missing".to_string()));
+    }
+    let tmp_path = get_tmp_path(&output_path);
 
     // Write to the temporary file
     if output_path.ends_with(".csv") | args.csv {
@@ -154,9 +342,39 @@ fn save_lf_to_disk(
     } else if output_path.ends_with(".json") | args.json {
         let options = JsonWriterOptions::default();
         lf.sink_json(&tmp_path, options)?;
+    } else if output_path.extension().and_then(|s| s.to_str()) == Some("xlsx") {
+        save_xlsx(
+            lf,
+            &output_path,
+            &tmp_path,
+            args.sheet_name.as_deref(),
+            args.max_xlsx_rows,
+        )?;
     } else {
-        let options = ParquetWriteOptions::default();
-        lf.sink_parquet(&tmp_path, options)?;
+        let (lf, options) = match &args.compat {
+            Some(profile) => apply_compat_profile(lf, profile)?,
+            None => (lf, ParquetWriteOptions::default()),
+        };
+        match legacy_layout(args) {
+            // polars cannot write legacy layouts, so its output is rewritten before publishing
+            Some(layout) => {
+                let staged_path = get_tmp_path(&tmp_path);
+                lf.sink_parquet(&staged_path, options)?;
+                let result =
+                    tbl_core::parquet::rewrite_parquet_legacy(&staged_path, &tmp_path, &layout);
+                std::fs::remove_file(&staged_path)?;
+                result?;
+            }
+            None => lf.sink_parquet(&tmp_path, options)?,
+        }
+        // polars writers take no key value metadata, so it is added before the output is visible
+        if !key_values.is_empty() {
+            let edits = tbl_core::parquet::FooterEdits {
+                set: key_values.to_vec(),
+                ..Default::default()
+            };
+            tbl_core::parquet::edit_unpublished_parquet_metadata(&tmp_path, &edits)?;
+        }
     };
 
     // Move the temporary file to the final output path
@@ -165,6 +383,282 @@ fn save_lf_to_disk(
     Ok(())
 }
 
+/// write a single sheet workbook to write_path, returning the number of rows written
+///
+/// at most one row past max_rows is collected, so oversized outputs fail without being read
+/// in full. the sheet is named after output_path unless a sheet name is given
+pub(crate) fn save_xlsx(
+    lf: LazyFrame,
+    output_path: &std::path::Path,
+    write_path: &std::path::Path,
+    sheet_name: Option<&str>,
+    max_rows: usize,
+) -> Result<usize, TblCliError> {
+    let sheet_name = match sheet_name {
+        Some(sheet_name) => sheet_name.to_string(),
+        None => tbl_core::xlsx::default_sheet_name(output_path),
+    };
+    tbl_core::xlsx::validate_sheet_name(&sheet_name)?;
+    let max_rows = max_rows.min(tbl_core::xlsx::XLSX_MAX_ROWS - 1);
+    let df = lf.slice(0, (max_rows + 1) as IdxSize).collect()?;
+    if df.height() > max_rows {
+        return Err(TblCliError::Arg(format!(
+            "more than {} rows exceed --max-xlsx-rows, filter or slice rows before writing {}",
+            tbl_core::formats::format_with_commas(max_rows as u64),
+            output_path.to_string_lossy()
+        )));
+    }
+    tbl_core::xlsx::write_xlsx(&df, write_path, &sheet_name)?;
+    Ok(df.height())
+}
+
+/// physical layout of parquet outputs that older engines need, if any
+///
+/// writers store decimals with precision up to 18 as int32 or int64, which some older
+/// engines cannot read, so these are stored as fixed-length byte arrays at their precision.
+/// the spark, athena, and legacy profiles also store timestamps as int96 in version 1 pages
+fn legacy_layout(args: &DataArgs) -> Option<tbl_core::parquet::LegacyLayout> {
+    let int96_timestamps = matches!(args.compat.as_deref(), Some("spark" | "athena" | "legacy"));
+    let fixed_decimals = args.legacy_decimals || int96_timestamps;
+    if !fixed_decimals {
+        return None;
+    }
+    Some(tbl_core::parquet::LegacyLayout {
+        fixed_decimals,
+        int96_timestamps,
+        snappy: args.compat.is_some(),
+    })
+}
+
+/// adapt columns and writer options to what an older engine can read
+///
+/// every profile writes snappy pages and signed integers. bigquery stores nanosecond
+/// timestamps as microseconds, while the other profiles keep them for their int96 layout
+fn apply_compat_profile(
+    mut lf: LazyFrame,
+    profile: &str,
+) -> Result<(LazyFrame, ParquetWriteOptions), TblCliError> {
+    let schema = lf.schema()?;
+    let mut exprs = Vec::new();
+    let mut sanitized: std::collections::HashMap<String, &str> = std::collections::HashMap::new();
+    for (name, dtype) in schema.iter() {
+        let cast = match dtype {
+            DataType::UInt8 => Some(DataType::Int16),
+            DataType::UInt16 => Some(DataType::Int32),
+            DataType::UInt32 | DataType::UInt64 => Some(DataType::Int64),
+            DataType::Datetime(TimeUnit::Nanoseconds, tz) if profile == "bigquery" => {
+                Some(DataType::Datetime(TimeUnit::Microseconds, tz.clone()))
+            }
+            _ => None,
+        };
+        let expr = match cast {
+            Some(dtype) => col(name).strict_cast(dtype),
+            None => col(name),
+        };
+        let new_name = sanitize_column_name(name, profile);
+        if let Some(other) = sanitized.insert(new_name.clone(), name) {
+            return Err(TblCliError::Arg(format!(
+                "columns {} and {} both become {} under {} naming",
+                other, name, new_name, profile
+            )));
+        }
+        exprs.push(expr.alias(&new_name));
+    }
+    let options = ParquetWriteOptions {
+        compression: ParquetCompression::Snappy,
+        ..Default::default()
+    };
+    Ok((lf.select(exprs), options))
+}
+
+/// rewrite column name into characters that the engine of profile accepts
+fn sanitize_column_name(name: &str, profile: &str) -> String {
+    let mut sanitized: String = match profile {
+        "spark" => name
+            .chars()
+            .map(|c| if " ,;{}()\n\t=".contains(c) { '_' } else { c })
+            .collect(),
+        "bigquery" => name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect(),
+        _ => name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect(),
+    };
+    if sanitized.is_empty()
+        || profile == "bigquery" && sanitized.starts_with(|c: char| c.is_ascii_digit())
+    {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+pub(crate) async fn save_globally_sorted(
+    input_paths: &[PathBuf],
+    output_path: Option<PathBuf>,
+    output_mode: &OutputMode,
+    args: &DataArgs,
+) -> Result<(), TblCliError> {
+    let output_path = match (output_mode, output_path) {
+        (OutputMode::SaveToSingleFile, Some(output_path)) => output_path,
+        _ => {
+            return Err(TblCliError::Arg(
+                "--global requires --output-file".to_string(),
+            ))
+        }
+    };
+    if crate::transform::has_non_sort_transformations(args) {
+        return Err(TblCliError::Arg(
+            "--global can only be combined with --sort".to_string(),
+        ));
+    }
+
+    let (columns, descending) =
+        crate::transform::parse_sort_columns(args.sort.as_deref().unwrap_or(&[]));
+    let sort_keys: Vec<_> = columns
+        .into_iter()
+        .zip(descending)
+        .map(|(column, descending)| tbl_core::parquet::SortKey {
+            column,
+            descending,
+            nulls_first: !crate::transform::sorts_nulls_last(args),
+        })
+        .collect();
+    let run_rows = 1_000_000;
+    let batch_size = 8192;
+    tbl_core::parquet::external_sort_parquets(
+        input_paths,
+        &output_path,
+        &sort_keys,
+        run_rows,
+        batch_size,
+    )
+    .await?;
+    Ok(())
+}
+
+pub(crate) fn read_encryption_key(args: &DataArgs) -> Result<[u8; 32], TblCliError> {
+    let key_env = args
+        .key_env
+        .as_ref()
+        .ok_or_else(|| TblCliError::Arg("missing --key-env".to_string()))?;
+    let key = std::env::var(key_env)
+        .map_err(|_| TblCliError::Arg(format!("environment variable {} is not set", key_env)))?;
+    Ok(tbl_core::parquet::parse_encryption_key(&key)?)
+}
+
+pub(crate) async fn save_encrypted(
+    input_paths: &[PathBuf],
+    output_path: Option<PathBuf>,
+    args: &DataArgs,
+) -> Result<(), TblCliError> {
+    let (input_path, output_path) = match (input_paths, output_path) {
+        ([input_path], Some(output_path)) => (input_path.clone(), output_path),
+        _ => {
+            return Err(TblCliError::Arg(
+                "encryption requires --inplace or --output-dir".to_string(),
+            ))
+        }
+    };
+    let key = read_encryption_key(args)?;
+    let batch_size = 8192;
+    if let Some(columns) = &args.encrypt {
+        let key_id = args.key_id.clone().unwrap_or_default();
+        tbl_core::parquet::encrypt_parquet_columns(
+            input_path,
+            output_path,
+            columns.clone(),
+            key,
+            key_id,
+            batch_size,
+        )
+        .await?;
+    } else if let Some(columns) = &args.decrypt {
+        let columns = if columns.is_empty() {
+            None
+        } else {
+            Some(columns.clone())
+        };
+        tbl_core::parquet::decrypt_parquet_columns(
+            input_path,
+            output_path,
+            columns,
+            key,
+            batch_size,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// whether output can be written by copying the column chunks of parquet inputs
+pub(crate) fn can_splice(
+    input_paths: &[PathBuf],
+    output_path: Option<&std::path::Path>,
+    output_mode: &OutputMode,
+    args: &DataArgs,
+) -> Result<bool, TblCliError> {
+    let is_parquet =
+        |path: &std::path::Path| path.extension().and_then(|s| s.to_str()) == Some("parquet");
+    let writes_parquet = matches!(
+        output_mode,
+        OutputMode::SaveToSingleFile | OutputMode::SaveToDirectory | OutputMode::ModifyInplace
+    ) && output_path.is_some_and(is_parquet)
+        && !args.csv
+        && !args.json;
+    if !writes_parquet
+        || input_paths.is_empty()
+        || !input_paths.iter().all(|path| is_parquet(path))
+        || tbl_core::parquet::has_hive_partitions(input_paths)
+        || !crate::transform::is_splice_only(args)
+    {
+        return Ok(false);
+    }
+
+    // splicing keeps the row group layout of inputs, so it is automatic only when harmless
+    let check = tbl_core::parquet::check_splice_parquets(input_paths)?;
+    if args.splice && !check.compatible {
+        return Err(TblCliError::Arg(
+            "--splice requires inputs with identical schemas".to_string(),
+        ));
+    }
+    Ok(check.compatible && (args.splice || check.is_worthwhile()))
+}
+
+/// write output by copying the compressed column chunks of inputs, without decoding them
+pub(crate) async fn save_spliced(
+    input_paths: &[PathBuf],
+    output_path: Option<PathBuf>,
+    key_values: &[(String, String)],
+    args: &DataArgs,
+) -> Result<(), TblCliError> {
+    let output_path = match output_path {
+        Some(output_path) => output_path,
+        None => return Err(TblCliError::Error("no output path specified".to_string())),
+    };
+    let columns_to_drop = match &args.drop {
+        Some(drop) => {
+            let schema = tbl_core::parquet::get_parquet_schema(&input_paths[0]).await?;
+            tbl_core::schemas::resolve_column_selectors(drop, &schema)?
+        }
+        None => Vec::new(),
+    };
+    let tmp_path = get_tmp_path(&output_path);
+    tbl_core::parquet::splice_parquets(input_paths, &tmp_path, &columns_to_drop)?;
+    if !key_values.is_empty() {
+        let edits = tbl_core::parquet::FooterEdits {
+            set: key_values.to_vec(),
+            ..Default::default()
+        };
+        tbl_core::parquet::edit_unpublished_parquet_metadata(&tmp_path, &edits)?;
+    }
+    std::fs::rename(&tmp_path, &output_path).map_err(|e| TblCliError::Error(e.to_string()))?;
+    Ok(())
+}
+
 fn partition_data(
     _lf: LazyFrame,
     _input_paths: Vec<PathBuf>,
@@ -0,0 +1,64 @@
+use crate::TblCliError;
+use std::path::{Path, PathBuf};
+
+/// name of marker file that protects a directory and its subdirectories from edits
+pub(crate) const PROTECT_MARKER: &str = ".tbl_protected";
+
+/// directories that cannot be edited, from marker files and the `protected` config entry
+pub(crate) struct Protection {
+    configured: Vec<PathBuf>,
+}
+
+impl Protection {
+    pub(crate) fn load() -> Result<Protection, TblCliError> {
+        let config = crate::config::load_config()?;
+        let configured = config
+            .protected
+            .iter()
+            .filter_map(|path| path.canonicalize().ok())
+            .collect();
+        Ok(Protection { configured })
+    }
+
+    /// protected directory that contains path, if any
+    pub(crate) fn protected_dir(&self, path: &Path) -> Option<PathBuf> {
+        // outputs may not exist yet, so start from their nearest existing ancestor
+        let existing = path.ancestors().find_map(|ancestor| {
+            if ancestor.as_os_str().is_empty() {
+                Path::new(".").canonicalize().ok()
+            } else {
+                ancestor.canonicalize().ok()
+            }
+        })?;
+        let start = if existing.is_dir() {
+            existing.as_path()
+        } else {
+            existing.parent()?
+        };
+        start
+            .ancestors()
+            .find(|dir| {
+                dir.join(PROTECT_MARKER).exists() || self.configured.iter().any(|p| p == dir)
+            })
+            .map(|dir| dir.to_path_buf())
+    }
+
+    /// refuse to write any of paths inside a protected directory
+    pub(crate) fn check_writable(&self, paths: &[PathBuf]) -> Result<(), TblCliError> {
+        for path in paths.iter() {
+            if let Some(dir) = self.protected_dir(path) {
+                return Err(TblCliError::Arg(format!(
+                    "{} is in protected directory {}, write outputs elsewhere with --output-dir",
+                    path.to_string_lossy(),
+                    dir.to_string_lossy()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// refuse to write any of paths inside a protected directory
+pub(crate) fn check_not_protected(paths: &[PathBuf]) -> Result<(), TblCliError> {
+    Protection::load()?.check_writable(paths)
+}
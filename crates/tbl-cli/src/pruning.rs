@@ -0,0 +1,583 @@
+use crate::{DataArgs, OutputMode, TblCliError};
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// value bounding a column, comparable only to values of the same kind
+///
+/// integers are kept exact, and compare to floats only within the exact range of f64
+#[derive(Clone, Debug)]
+pub(crate) enum BoundValue {
+    Integer(i128),
+    Number(f64),
+    Text(String),
+}
+
+/// largest magnitude below which every integer is exactly representable as f64
+const MAX_EXACT_F64: i128 = 1 << 53;
+
+impl PartialEq for BoundValue {
+    fn eq(&self, other: &BoundValue) -> bool {
+        self.partial_cmp(other) == Some(std::cmp::Ordering::Equal)
+    }
+}
+
+impl PartialOrd for BoundValue {
+    fn partial_cmp(&self, other: &BoundValue) -> Option<std::cmp::Ordering> {
+        match (self, other) {
+            (BoundValue::Integer(left), BoundValue::Integer(right)) => left.partial_cmp(right),
+            (BoundValue::Number(left), BoundValue::Number(right)) => left.partial_cmp(right),
+            (BoundValue::Text(left), BoundValue::Text(right)) => left.partial_cmp(right),
+            (BoundValue::Integer(left), BoundValue::Number(right)) => (left.abs() < MAX_EXACT_F64)
+                .then_some(*left as f64)?
+                .partial_cmp(right),
+            (BoundValue::Number(left), BoundValue::Integer(right)) => {
+                left.partial_cmp(&(right.abs() < MAX_EXACT_F64).then_some(*right as f64)?)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl BoundValue {
+    fn from_literal(literal: &LiteralValue) -> Option<BoundValue> {
+        match literal {
+            LiteralValue::String(value) | LiteralValue::StrCat(value) => {
+                Some(BoundValue::Text(value.clone()))
+            }
+            LiteralValue::Int(value) => Some(BoundValue::Integer(*value)),
+            LiteralValue::Float(value) => Some(BoundValue::Number(*value)),
+            literal => BoundValue::from_any_value(&literal.to_any_value()?),
+        }
+    }
+
+    /// convert value of a column, keeping integers exact
+    fn from_any_value(value: &AnyValue<'_>) -> Option<BoundValue> {
+        match value {
+            AnyValue::String(value) => Some(BoundValue::Text(value.to_string())),
+            AnyValue::StringOwned(value) => Some(BoundValue::Text(value.to_string())),
+            AnyValue::Int8(value) => Some(BoundValue::Integer(*value as i128)),
+            AnyValue::Int16(value) => Some(BoundValue::Integer(*value as i128)),
+            AnyValue::Int32(value) => Some(BoundValue::Integer(*value as i128)),
+            AnyValue::Int64(value) => Some(BoundValue::Integer(*value as i128)),
+            AnyValue::UInt8(value) => Some(BoundValue::Integer(*value as i128)),
+            AnyValue::UInt16(value) => Some(BoundValue::Integer(*value as i128)),
+            AnyValue::UInt32(value) => Some(BoundValue::Integer(*value as i128)),
+            AnyValue::UInt64(value) => Some(BoundValue::Integer(*value as i128)),
+            AnyValue::Float32(value) => Some(BoundValue::Number(*value as f64)),
+            AnyValue::Float64(value) => Some(BoundValue::Number(*value)),
+            _ => None,
+        }
+    }
+
+    /// parse value displayed for a column of dtype, as stored in index sidecars
+    fn parse(value: &str, dtype: &str) -> Option<BoundValue> {
+        match dtype {
+            "str" => Some(BoundValue::Text(value.to_string())),
+            "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" => {
+                value.parse().ok().map(BoundValue::Integer)
+            }
+            "f32" | "f64" => value.parse().ok().map(BoundValue::Number),
+            _ => None,
+        }
+    }
+}
+
+/// range of values a predicate allows for a column, each bound with whether it is inclusive
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ValueRange {
+    lower: Option<(BoundValue, bool)>,
+    upper: Option<(BoundValue, bool)>,
+}
+
+impl ValueRange {
+    /// whether any value between min and max, inclusive, could be in range
+    pub(crate) fn intersects(&self, min: &BoundValue, max: &BoundValue) -> bool {
+        let comparable =
+            |value: &BoundValue, bound: &BoundValue| value.partial_cmp(bound).is_some();
+        if let Some((lower, inclusive)) = &self.lower {
+            if comparable(max, lower) && (max < lower || (!inclusive && max == lower)) {
+                return false;
+            }
+        }
+        if let Some((upper, inclusive)) = &self.upper {
+            if comparable(min, upper) && (min > upper || (!inclusive && min == upper)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn tighten_lower(&mut self, value: BoundValue, inclusive: bool) {
+        match &self.lower {
+            Some((current, _)) if *current >= value => {}
+            _ => self.lower = Some((value, inclusive)),
+        }
+    }
+
+    fn tighten_upper(&mut self, value: BoundValue, inclusive: bool) {
+        match &self.upper {
+            Some((current, _)) if *current <= value => {}
+            _ => self.upper = Some((value, inclusive)),
+        }
+    }
+}
+
+/// ranges of columns that every row matching predicate must fall in
+///
+/// only comparisons of a column to a literal joined by AND are used, other parts of the
+/// predicate allow any value
+pub(crate) fn predicate_ranges(predicate: &Expr) -> HashMap<String, ValueRange> {
+    let mut ranges = HashMap::new();
+    collect_ranges(predicate, &mut ranges);
+    ranges
+}
+
+fn collect_ranges(expr: &Expr, ranges: &mut HashMap<String, ValueRange>) {
+    let (left, op, right) = match expr {
+        Expr::BinaryExpr { left, op, right } => (left.as_ref(), *op, right.as_ref()),
+        _ => return,
+    };
+    if matches!(op, Operator::And | Operator::LogicalAnd) {
+        collect_ranges(left, ranges);
+        collect_ranges(right, ranges);
+        return;
+    }
+    // put the column on the left, flipping the comparison if needed
+    let (column, op, literal) = match (left, right) {
+        (Expr::Column(column), Expr::Literal(literal)) => (column, op, literal),
+        (Expr::Literal(literal), Expr::Column(column)) => {
+            let flipped = match op {
+                Operator::Lt => Operator::Gt,
+                Operator::LtEq => Operator::GtEq,
+                Operator::Gt => Operator::Lt,
+                Operator::GtEq => Operator::LtEq,
+                op => op,
+            };
+            (column, flipped, literal)
+        }
+        _ => return,
+    };
+    let value = match BoundValue::from_literal(literal) {
+        Some(value) => value,
+        None => return,
+    };
+    let range = ranges.entry(column.to_string()).or_default();
+    match op {
+        Operator::Eq | Operator::EqValidity => {
+            range.tighten_lower(value.clone(), true);
+            range.tighten_upper(value, true);
+        }
+        Operator::Gt => range.tighten_lower(value, false),
+        Operator::GtEq => range.tighten_lower(value, true),
+        Operator::Lt => range.tighten_upper(value, false),
+        Operator::LtEq => range.tighten_upper(value, true),
+        _ => {}
+    }
+}
+
+/// whether inputs of output mode are combined, so that skipping a file only removes its rows
+fn combines_inputs(output_mode: &OutputMode) -> bool {
+    !matches!(
+        output_mode,
+        OutputMode::ModifyInplace | OutputMode::SaveToDirectory
+    )
+}
+
+/// ranges of columns allowed by --where and --filter, if predicates see columns as stored in
+/// files
+fn where_ranges(args: &DataArgs) -> Result<Option<HashMap<String, ValueRange>>, TblCliError> {
+    // columns added before the predicate is applied could shadow a key column, and a row
+    // index would be renumbered by skipping files
+    let adds_columns = args.with_columns.is_some()
+        || !args.parse_json.is_empty()
+        || args.path_column.is_some()
+        || args.with_source
+        || args.with_row_index;
+    if adds_columns || (args.where_.is_none() && args.filter.is_none()) {
+        return Ok(None);
+    }
+    let mut ranges = match &args.where_ {
+        Some(where_) => predicate_ranges(&crate::transform::parse_sql_expr(where_)?),
+        None => HashMap::new(),
+    };
+    for filter in args.filter.iter().flatten() {
+        if let Some((column, value)) = filter_value(filter) {
+            let range = ranges.entry(column.to_string()).or_default();
+            range.tighten_lower(value.clone(), true);
+            range.tighten_upper(value, true);
+        }
+    }
+    Ok(Some(ranges))
+}
+
+/// column and value of an equality filter, syntax COLUMN=VALUE
+///
+/// values that are not plain decimal numbers are text, which bounds only string columns
+fn filter_value(filter: &str) -> Option<(&str, BoundValue)> {
+    let (column, value) = filter.split_once('=')?;
+    if column.ends_with('!') || value.contains('=') {
+        return None;
+    }
+    let value = match (value.parse::<i128>(), value.parse::<f64>()) {
+        (Ok(integer), _) => BoundValue::Integer(integer),
+        (_, Ok(number)) if number.is_finite() => BoundValue::Number(number),
+        _ => BoundValue::Text(value.to_string()),
+    };
+    Some((column, value))
+}
+
+/// skip input files without rows when combining files, returning kept and skipped files
+///
+/// empty placeholder files often have drifted schemas that would otherwise fail the merge,
+/// one file is kept if every file is empty so that outputs still have a schema
+pub(crate) async fn skip_empty_files(
+    input_paths: Vec<PathBuf>,
+    output_mode: &OutputMode,
+) -> Result<(Vec<PathBuf>, Vec<PathBuf>), TblCliError> {
+    if input_paths.len() < 2 || !combines_inputs(output_mode) {
+        return Ok((input_paths, Vec::new()));
+    }
+    let paths: Vec<&std::path::Path> = input_paths.iter().map(|path| path.as_path()).collect();
+    let n_rows = tbl_core::parquet::get_parquet_row_counts(&paths).await?;
+    if n_rows.iter().all(|n| *n > 0) {
+        return Ok((input_paths, Vec::new()));
+    }
+    let (mut kept, mut skipped) = (Vec::new(), Vec::new());
+    for (path, n) in input_paths.into_iter().zip(n_rows) {
+        if n > 0 {
+            kept.push(path)
+        } else {
+            skipped.push(path)
+        }
+    }
+    if kept.is_empty() {
+        kept.push(skipped.remove(0));
+    }
+    Ok((kept, skipped))
+}
+
+/// skip input files that cannot contribute rows according to their index sidecars
+///
+/// files are skipped when the key range of their index falls outside the --where predicate,
+/// or when their rows fall outside --offset and --head, in which case the returned args
+/// have an offset relative to the remaining files
+pub(crate) fn prune_with_indexes(
+    input_paths: Vec<PathBuf>,
+    output_mode: &OutputMode,
+    args: &DataArgs,
+) -> Result<(Vec<PathBuf>, Option<DataArgs>), TblCliError> {
+    if input_paths.len() < 2 || !combines_inputs(output_mode) {
+        return Ok((input_paths, None));
+    }
+    let indexes: Vec<Option<tbl_core::parquet::ParquetIndex>> = input_paths
+        .iter()
+        .map(|path| tbl_core::parquet::read_parquet_index(path))
+        .collect();
+    if indexes.iter().all(|index| index.is_none()) {
+        return Ok((input_paths, None));
+    }
+    let n_inputs = input_paths.len();
+
+    let mut io: Vec<(PathBuf, Option<tbl_core::parquet::ParquetIndex>)> =
+        input_paths.into_iter().zip(indexes).collect();
+    if let Some(ranges) = where_ranges(args)? {
+        let may_match = |index: &Option<tbl_core::parquet::ParquetIndex>| {
+            let key = match index.as_ref().and_then(|index| index.key.as_ref()) {
+                Some(key) => key,
+                None => return true,
+            };
+            let range = match ranges.get(&key.column) {
+                Some(range) => range,
+                None => return true,
+            };
+            match (&key.min, &key.max) {
+                (Some(min), Some(max)) => {
+                    match (
+                        BoundValue::parse(min, &key.dtype),
+                        BoundValue::parse(max, &key.dtype),
+                    ) {
+                        (Some(min), Some(max)) => range.intersects(&min, &max),
+                        _ => true,
+                    }
+                }
+                // bounds are missing when all keys are null or could not be stored
+                _ => true,
+            }
+        };
+        let first = io.first().cloned();
+        io.retain(|(_, index)| may_match(index));
+        // keep one file so that outputs still have a schema
+        if io.is_empty() {
+            io.extend(first);
+        }
+    }
+
+    // rows before offset and after head can be skipped when no rows are removed before slicing
+    let mut pruned_args = None;
+    let slices = args.offset.is_some() || args.head.is_some();
+    let removes_rows = args.where_.is_some()
+        || args.filter.is_some()
+        || args.with_row_index
+        || args.interleave
+        || args.quarantine_dir.is_some();
+    if slices && !removes_rows && io.iter().all(|(_, index)| index.is_some()) {
+        let mut offset = args.offset.unwrap_or(0);
+        let mut kept = Vec::new();
+        let mut n_kept_rows = 0;
+        for (path, index) in io.iter().cloned() {
+            let n_rows = index
+                .as_ref()
+                .map(|index| index.n_rows as usize)
+                .unwrap_or(0);
+            if kept.is_empty() && n_rows <= offset {
+                offset -= n_rows;
+                continue;
+            }
+            if let Some(head) = args.head {
+                if !kept.is_empty() && n_kept_rows >= offset + head {
+                    break;
+                }
+            }
+            n_kept_rows += n_rows;
+            kept.push((path, index));
+        }
+        // offsets past the last row still need one file for the schema of the empty output
+        if !kept.is_empty() {
+            let mut new_args = args.clone();
+            new_args.offset = args.offset.map(|_| offset);
+            pruned_args = Some(new_args);
+            io = kept;
+        }
+    }
+
+    let paths: Vec<PathBuf> = io.into_iter().map(|(path, _)| path).collect();
+    if paths.len() < n_inputs && !args.no_summary {
+        println!(
+            "[skipped {} of {} files using index sidecars]",
+            n_inputs - paths.len(),
+            n_inputs
+        );
+    }
+    Ok((paths, pruned_args))
+}
+
+/// files, row groups, and compressed bytes skipped and scanned by a read
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PruningStats {
+    pub(crate) n_files: u64,
+    pub(crate) n_skipped_files: u64,
+    pub(crate) n_row_groups: u64,
+    pub(crate) n_skipped_row_groups: u64,
+    pub(crate) n_bytes: u64,
+    pub(crate) n_skipped_bytes: u64,
+}
+
+impl PruningStats {
+    /// count files skipped entirely, such as those skipped using index sidecars
+    pub(crate) async fn add_skipped_files(&mut self, paths: &[PathBuf]) -> Result<(), TblCliError> {
+        for path in paths.iter() {
+            let sizes = tbl_core::parquet::get_parquet_row_group_sizes(path).await?;
+            self.add_file(&sizes, &vec![false; sizes.len()], false);
+        }
+        Ok(())
+    }
+
+    /// count file from whether each row group can match and, if known, row group sizes
+    fn add_file(&mut self, sizes: &[u64], matches: &[bool], kept: bool) {
+        self.n_files += 1;
+        self.n_skipped_files += (!kept) as u64;
+        for (i, matches) in matches.iter().enumerate() {
+            let skipped = !kept || !matches;
+            let size = sizes.get(i).copied().unwrap_or_default();
+            self.n_row_groups += 1;
+            self.n_skipped_row_groups += skipped as u64;
+            self.n_bytes += size;
+            self.n_skipped_bytes += if skipped { size } else { 0 };
+        }
+    }
+
+    /// print skipped and scanned amounts of files, row groups, and bytes
+    pub(crate) fn print(&self) {
+        let format = |n: u64, n_skipped: u64, bytes: bool| {
+            let f = |n| {
+                if bytes {
+                    tbl_core::formats::format_bytes(n)
+                } else {
+                    tbl_core::formats::format_with_commas(n)
+                }
+            };
+            format!(
+                "{} scanned, {} skipped ({:.1}%)",
+                f(n - n_skipped),
+                f(n_skipped),
+                100.0 * n_skipped as f64 / (n.max(1) as f64)
+            )
+        };
+        println!();
+        tbl_core::formats::print_header("Pruning");
+        tbl_core::formats::print_bullet("files", format(self.n_files, self.n_skipped_files, false));
+        tbl_core::formats::print_bullet(
+            "row groups",
+            format(self.n_row_groups, self.n_skipped_row_groups, false),
+        );
+        tbl_core::formats::print_bullet("bytes", format(self.n_bytes, self.n_skipped_bytes, true));
+    }
+}
+
+/// skip input files whose parquet statistics show no rows can match --where and --filter
+///
+/// a row group cannot match when the statistics of a column in the predicate fall outside
+/// its range, and a file is skipped when none of its row groups can match, row groups of
+/// remaining files are skipped by the parquet reader
+pub(crate) async fn prune_with_statistics(
+    input_paths: Vec<PathBuf>,
+    output_mode: &OutputMode,
+    args: &DataArgs,
+) -> Result<(Vec<PathBuf>, PruningStats), TblCliError> {
+    let mut stats = PruningStats::default();
+    let skips_files = input_paths.len() >= 2 && combines_inputs(output_mode);
+    if !skips_files && !args.pruning_stats {
+        return Ok((input_paths, stats));
+    }
+    let ranges = where_ranges(args)?.unwrap_or_default();
+    if ranges.is_empty() && !args.pruning_stats {
+        return Ok((input_paths, stats));
+    }
+
+    let n_inputs = input_paths.len();
+    let mut kept = Vec::new();
+    for path in input_paths.iter() {
+        // a row group can match only if every column of the predicate can match
+        let sizes = if args.pruning_stats {
+            tbl_core::parquet::get_parquet_row_group_sizes(path).await?
+        } else {
+            Vec::new()
+        };
+        let mut row_group_matches: Option<Vec<bool>> = None;
+        for (column, range) in ranges.iter() {
+            let row_group_ranges =
+                match tbl_core::parquet::get_parquet_row_group_ranges(path, column).await? {
+                    Some(row_group_ranges) => row_group_ranges,
+                    None => continue,
+                };
+            let matches = row_group_ranges
+                .iter()
+                .map(|row_group_range| match row_group_range {
+                    // null values never satisfy a comparison
+                    tbl_core::parquet::RowGroupRange::Empty => false,
+                    tbl_core::parquet::RowGroupRange::Bounds((min, max)) => match (
+                        BoundValue::from_any_value(min),
+                        BoundValue::from_any_value(max),
+                    ) {
+                        (Some(min), Some(max)) => range.intersects(&min, &max),
+                        _ => true,
+                    },
+                    tbl_core::parquet::RowGroupRange::Unknown => true,
+                });
+            row_group_matches = Some(match row_group_matches {
+                Some(previous) => previous.iter().zip(matches).map(|(a, b)| *a && b).collect(),
+                None => matches.collect(),
+            });
+        }
+        let matches = row_group_matches.unwrap_or_else(|| vec![true; sizes.len()]);
+        let keep = !skips_files || matches.is_empty() || matches.iter().any(|m| *m);
+        if keep {
+            kept.push(path.clone());
+        }
+        stats.add_file(&sizes, &matches, keep);
+    }
+    // keep one file so that outputs still have a schema
+    if kept.is_empty() {
+        kept.extend(input_paths.first().cloned());
+        stats.n_skipped_files -= 1;
+    }
+
+    if stats.n_skipped_row_groups > 0 && skips_files && !args.pruning_stats && !args.no_summary {
+        println!(
+            "[pruned {} of {} files and {} of {} row groups using parquet statistics]",
+            n_inputs - kept.len(),
+            n_inputs,
+            stats.n_skipped_row_groups,
+            stats.n_row_groups
+        );
+    }
+    Ok((kept, stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_file(path: &std::path::Path, mut df: DataFrame) {
+        ParquetWriter::new(std::fs::File::create(path).unwrap())
+            .finish(&mut df)
+            .unwrap();
+    }
+
+    fn where_args(where_: &str) -> DataArgs {
+        DataArgs {
+            where_: Some(where_.to_string()),
+            no_summary: true,
+            ..DataArgs::default()
+        }
+    }
+
+    #[test]
+    fn test_predicate_ranges() {
+        let predicate = crate::transform::parse_sql_expr("a >= 3 AND a < 10 AND 5 > b").unwrap();
+        let ranges = predicate_ranges(&predicate);
+        let a = &ranges["a"];
+        assert!(a.intersects(&BoundValue::Integer(0), &BoundValue::Integer(3)));
+        assert!(!a.intersects(&BoundValue::Integer(10), &BoundValue::Integer(20)));
+        assert!(!a.intersects(&BoundValue::Integer(0), &BoundValue::Integer(2)));
+        let b = &ranges["b"];
+        assert!(!b.intersects(&BoundValue::Number(5.0), &BoundValue::Number(6.0)));
+        assert!(b.intersects(&BoundValue::Number(4.5), &BoundValue::Number(6.0)));
+    }
+
+    #[tokio::test]
+    async fn test_prune_with_indexes_keeps_exact_float_bounds() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut paths = Vec::new();
+        for (i, values) in [[0.1234567891f64, 0.2], [0.3, 0.4], [0.5, 0.6]]
+            .iter()
+            .enumerate()
+        {
+            let path = temp_dir.path().join(format!("{}.parquet", i));
+            write_file(&path, df!("k" => values).unwrap());
+            tbl_core::parquet::write_parquet_index(&path, Some("k"))
+                .await
+                .unwrap();
+            paths.push(path);
+        }
+        let (kept, _) = prune_with_indexes(
+            paths.clone(),
+            &OutputMode::PrintToStdout,
+            &where_args("k < 0.1234569"),
+        )
+        .unwrap();
+        assert_eq!(kept, vec![paths[0].clone()]);
+    }
+
+    #[tokio::test]
+    async fn test_prune_with_indexes_keeps_files_without_bounds() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut paths = Vec::new();
+        for (i, values) in [["a", "b"], ["x\ny", "z"]].iter().enumerate() {
+            let path = temp_dir.path().join(format!("{}.parquet", i));
+            write_file(&path, df!("k" => values).unwrap());
+            tbl_core::parquet::write_parquet_index(&path, Some("k"))
+                .await
+                .unwrap();
+            paths.push(path);
+        }
+        let (kept, _) = prune_with_indexes(
+            paths.clone(),
+            &OutputMode::PrintToStdout,
+            &where_args("k = 'z'"),
+        )
+        .unwrap();
+        assert_eq!(kept, vec![paths[1].clone()]);
+    }
+}
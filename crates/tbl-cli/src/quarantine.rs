@@ -0,0 +1,73 @@
+use crate::TblCliError;
+use polars::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// name of column holding why a row was quarantined
+pub(crate) const REASON_COLUMN: &str = "quarantine_reason";
+
+/// combine row failures into a mask of failing rows and an expression listing their reasons
+pub(crate) fn create_failure_exprs(failures: Vec<(Expr, String)>) -> Option<(Expr, Expr)> {
+    if failures.is_empty() {
+        return None;
+    }
+    let mask = failures
+        .iter()
+        .map(|(failed, _)| failed.clone().fill_null(lit(false)))
+        .reduce(|a, b| a.or(b))?;
+    let reasons: Vec<Expr> = failures
+        .into_iter()
+        .map(|(failed, reason)| {
+            when(failed.fill_null(lit(false)))
+                .then(lit(reason))
+                .otherwise(lit(NULL).cast(DataType::String))
+        })
+        .collect();
+    let reason = concat_str(reasons, "; ", true);
+    Some((mask, reason))
+}
+
+/// path of quarantine file for rows of the given inputs
+///
+/// file names end with a hash of the input paths, so that inputs with the same file name in
+/// different directories, or different groups of inputs, do not overwrite each other
+pub(crate) fn get_quarantine_path(dir: &Path, input_paths: &[PathBuf]) -> PathBuf {
+    let stem = match input_paths {
+        [input_path] => input_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string()),
+        _ => None,
+    };
+    let hash = input_paths
+        .iter()
+        .fold(tbl_core::parquet::FNV_OFFSET_BASIS, |hash, path| {
+            let hash = tbl_core::parquet::fnv1a(hash, path.to_string_lossy().as_bytes());
+            tbl_core::parquet::fnv1a(hash, &[0])
+        });
+    let stem = stem.unwrap_or_else(|| "quarantine".to_string());
+    dir.join(format!("{}_{:08x}.parquet", stem, hash as u32))
+}
+
+/// write rows to a quarantine file, returning the number of rows written
+///
+/// no file is written when there are no rows
+pub(crate) fn write_quarantine(
+    df: &mut DataFrame,
+    dir: &Path,
+    input_paths: &[PathBuf],
+) -> Result<usize, TblCliError> {
+    if df.height() == 0 {
+        return Ok(0);
+    }
+    std::fs::create_dir_all(dir)?;
+    let path = get_quarantine_path(dir, input_paths);
+    let file = std::fs::File::create(&path)?;
+    ParquetWriter::new(file).finish(df)?;
+    tbl_core::formats::print_bullet(
+        path.to_string_lossy(),
+        format!(
+            "quarantined {} rows",
+            tbl_core::formats::format_with_commas(df.height() as u64)
+        ),
+    );
+    Ok(df.height())
+}
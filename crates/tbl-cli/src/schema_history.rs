@@ -0,0 +1,132 @@
+use crate::TblCliError;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+use tbl_core::parquet::TabularSummary;
+
+/// schemas of a dataset seen by one recorded run, stored as one json line of a history file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct SchemaRecord {
+    /// time of run in rfc 3339 format
+    pub(crate) time: String,
+    /// number of files
+    pub(crate) n_files: u64,
+    /// distinct schemas
+    pub(crate) schemas: Vec<RecordedSchema>,
+}
+
+/// schema shared by files of a recorded run
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct RecordedSchema {
+    /// fingerprint of schema
+    pub(crate) fingerprint: String,
+    /// number of files with schema
+    pub(crate) n_files: u64,
+    /// names and dtypes of columns
+    pub(crate) columns: Vec<(String, String)>,
+}
+
+impl SchemaRecord {
+    /// record schemas of file summaries at the current time
+    pub(crate) fn new(summaries: &[TabularSummary]) -> SchemaRecord {
+        let mut schemas: Vec<RecordedSchema> = Vec::new();
+        for summary in summaries.iter() {
+            let fingerprint = tbl_core::parquet::schema_fingerprint(&summary.schema);
+            match schemas.iter_mut().find(|s| s.fingerprint == fingerprint) {
+                Some(schema) => schema.n_files += 1,
+                None => schemas.push(RecordedSchema {
+                    fingerprint,
+                    n_files: 1,
+                    columns: columns_of(&summary.schema),
+                }),
+            }
+        }
+        SchemaRecord {
+            time: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            n_files: summaries.len() as u64,
+            schemas,
+        }
+    }
+
+    /// dtypes of each column across all schemas
+    fn column_dtypes(&self) -> BTreeMap<String, Vec<String>> {
+        let mut dtypes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for schema in self.schemas.iter() {
+            for (name, dtype) in schema.columns.iter() {
+                let column_dtypes = dtypes.entry(name.clone()).or_default();
+                if !column_dtypes.contains(dtype) {
+                    column_dtypes.push(dtype.clone());
+                }
+            }
+        }
+        dtypes
+    }
+}
+
+fn columns_of(schema: &Schema) -> Vec<(String, String)> {
+    schema
+        .iter()
+        .map(|(name, dtype)| (name.to_string(), dtype.to_string()))
+        .collect()
+}
+
+/// append record to history file
+pub(crate) fn append_schema_record(path: &Path, record: &SchemaRecord) -> Result<(), TblCliError> {
+    let line = serde_json::to_string(record).map_err(|e| TblCliError::Error(e.to_string()))?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// read records of history file in the order they were recorded
+pub(crate) fn read_schema_history(path: &Path) -> Result<Vec<SchemaRecord>, TblCliError> {
+    let contents = std::fs::read_to_string(path).map_err(|_| {
+        TblCliError::Arg(format!(
+            "no schema history at {}, record one with --record",
+            path.to_string_lossy()
+        ))
+    })?;
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|e| {
+                TblCliError::Error(format!(
+                    "invalid schema history {}: {}",
+                    path.to_string_lossy(),
+                    e
+                ))
+            })
+        })
+        .collect()
+}
+
+/// changes of columns between two recorded runs
+pub(crate) fn schema_changes(before: &SchemaRecord, after: &SchemaRecord) -> Vec<String> {
+    let before_dtypes = before.column_dtypes();
+    let after_dtypes = after.column_dtypes();
+    let mut changes = Vec::new();
+    for (name, dtypes) in after_dtypes.iter() {
+        match before_dtypes.get(name) {
+            None => changes.push(format!("+ {} ({})", name, dtypes.join(", "))),
+            Some(before) if before != dtypes => changes.push(format!(
+                "~ {} ({} -> {})",
+                name,
+                before.join(", "),
+                dtypes.join(", ")
+            )),
+            Some(_) => {}
+        }
+    }
+    for (name, dtypes) in before_dtypes.iter() {
+        if !after_dtypes.contains_key(name) {
+            changes.push(format!("- {} ({})", name, dtypes.join(", ")));
+        }
+    }
+    changes
+}
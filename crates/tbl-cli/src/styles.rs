@@ -1,13 +1,201 @@
+use crate::config::{Config, ThemeConfig};
+use crate::TblCliError;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
 use toolstr::Colorize;
 use toolstr_colored::ColoredString;
 
+type Rgb = (u8, u8, u8);
+
+/// colors used across tables, summaries, help messages, and prompts
+#[derive(Clone, Debug)]
+pub(crate) struct Theme {
+    pub(crate) title: Rgb,
+    pub(crate) comment: Rgb,
+    pub(crate) string: Rgb,
+    pub(crate) constant: Rgb,
+    pub(crate) function: Rgb,
+    pub(crate) variable: Rgb,
+    pub(crate) background: Rgb,
+    pub(crate) accent: Rgb,
+    pub(crate) text: Rgb,
+    pub(crate) muted: Rgb,
+    pub(crate) error: Rgb,
+}
+
+impl Theme {
+    const fn dark() -> Theme {
+        Theme {
+            title: (206, 147, 249),
+            comment: (98, 114, 164),
+            string: (241, 250, 140),
+            constant: (185, 242, 159),
+            function: (139, 233, 253),
+            variable: (100, 170, 170),
+            background: (40, 42, 54),
+            accent: (0, 225, 0),
+            text: (255, 255, 255),
+            muted: (170, 170, 170),
+            error: (225, 0, 0),
+        }
+    }
+
+    fn light() -> Theme {
+        Theme {
+            title: (136, 57, 239),
+            comment: (108, 111, 133),
+            string: (145, 100, 0),
+            constant: (30, 130, 40),
+            function: (30, 102, 245),
+            variable: (23, 120, 130),
+            background: (239, 241, 245),
+            accent: (0, 140, 0),
+            text: (20, 20, 20),
+            muted: (90, 90, 90),
+            error: (200, 0, 0),
+        }
+    }
+
+    fn builtin(name: &str) -> Option<Theme> {
+        match name {
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            _ => None,
+        }
+    }
+
+    /// resolve theme by name from builtin themes and themes of config file
+    fn resolve(name: &str, config: &Config) -> Result<Theme, TblCliError> {
+        if let Some(theme) = Theme::builtin(name) {
+            return Ok(theme);
+        }
+        match config.themes.get(name) {
+            Some(custom) => Theme::from_config(custom),
+            None => Err(TblCliError::Arg(format!(
+                "unknown theme {}, use dark, light, or a theme from the config file",
+                name
+            ))),
+        }
+    }
+
+    fn from_config(custom: &ThemeConfig) -> Result<Theme, TblCliError> {
+        let base = custom.base.as_deref().unwrap_or("dark");
+        let mut theme = Theme::builtin(base)
+            .ok_or_else(|| TblCliError::Arg(format!("unknown base theme {}", base)))?;
+        let fields = [
+            (&custom.title, &mut theme.title),
+            (&custom.comment, &mut theme.comment),
+            (&custom.string, &mut theme.string),
+            (&custom.constant, &mut theme.constant),
+            (&custom.function, &mut theme.function),
+            (&custom.variable, &mut theme.variable),
+            (&custom.background, &mut theme.background),
+            (&custom.accent, &mut theme.accent),
+            (&custom.text, &mut theme.text),
+            (&custom.muted, &mut theme.muted),
+            (&custom.error, &mut theme.error),
+        ];
+        for (value, color) in fields.into_iter() {
+            if let Some(value) = value {
+                *color = parse_hex_color(value)?;
+            }
+        }
+        Ok(theme)
+    }
+}
+
+fn parse_hex_color(value: &str) -> Result<Rgb, TblCliError> {
+    let hex = value.trim_start_matches('#');
+    let bytes = hex::decode(hex).ok().filter(|bytes| bytes.len() == 3);
+    match bytes {
+        Some(bytes) => Ok((bytes[0], bytes[1], bytes[2])),
+        None => Err(TblCliError::Arg(format!(
+            "invalid color {}, use hex like #ce93f9",
+            value
+        ))),
+    }
+}
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+static COLOR_ENABLED: OnceLock<bool> = OnceLock::new();
+static DEFAULT_THEME: Theme = Theme::dark();
+
+/// theme chosen by init_styles, the default is not stored so that init_styles can still
+/// choose one after the argument pre-pass builds help messages
+pub(crate) fn theme() -> &'static Theme {
+    THEME.get().unwrap_or(&DEFAULT_THEME)
+}
+
+/// whether colors are enabled, chosen by init_styles or taken from `NO_COLOR` until then
+pub(crate) fn color_enabled() -> bool {
+    COLOR_ENABLED
+        .get()
+        .copied()
+        .unwrap_or_else(|| !no_color_requested())
+}
+
+/// whether `NO_COLOR` is set to a non-empty value, see https://no-color.org
+fn no_color_requested() -> bool {
+    matches!(std::env::var("NO_COLOR"), Ok(value) if !value.is_empty())
+}
+
+/// choose colors from --color and --theme before arguments are fully parsed, so that
+/// help messages use the same colors as everything else
+pub(crate) fn init_styles(
+    color: Option<&str>,
+    theme_name: Option<String>,
+) -> Result<(), TblCliError> {
+    let enabled = match color {
+        Some("always") => true,
+        Some("never") => false,
+        _ => !no_color_requested() && std::io::stdout().is_terminal(),
+    };
+    let _ = COLOR_ENABLED.set(enabled);
+    toolstr_colored::control::set_override(enabled);
+    tbl_core::formats::set_color_enabled(enabled);
+
+    // a broken config file should not stop commands that do not need it, including --help
+    let config = crate::config::load_config().unwrap_or_else(|e| {
+        eprintln!("[using default config, {}]", e);
+        Default::default()
+    });
+    let theme = match (theme_name, &config.theme) {
+        (Some(name), _) => Theme::resolve(&name, &config)?,
+        (None, Some(name)) => Theme::resolve(name, &config).unwrap_or_else(|e| {
+            eprintln!("[using default theme, {}]", e);
+            Theme::dark()
+        }),
+        (None, None) => Theme::dark(),
+    };
+    tbl_core::formats::set_format_colors(tbl_core::formats::FormatColors {
+        accent: theme.accent,
+        text: theme.text,
+        muted: theme.muted,
+        error: theme.error,
+    });
+    let _ = THEME.set(theme);
+    Ok(())
+}
+
+pub(crate) fn get_color_choice() -> clap::ColorChoice {
+    if color_enabled() {
+        clap::ColorChoice::Always
+    } else {
+        clap::ColorChoice::Never
+    }
+}
+
 pub(crate) fn get_styles() -> clap::builder::Styles {
-    let white = anstyle::Color::Rgb(anstyle::RgbColor(255, 255, 255));
-    let green = anstyle::Color::Rgb(anstyle::RgbColor(0, 225, 0));
-    let grey = anstyle::Color::Rgb(anstyle::RgbColor(170, 170, 170));
-    let title = anstyle::Style::new().bold().fg_color(Some(green));
-    let arg = anstyle::Style::new().bold().fg_color(Some(white));
-    let comment = anstyle::Style::new().fg_color(Some(grey));
+    if !color_enabled() {
+        return clap::builder::Styles::plain();
+    }
+    let theme = theme();
+    let rgb = |(r, g, b): Rgb| anstyle::Color::Rgb(anstyle::RgbColor(r, g, b));
+    let title = anstyle::Style::new()
+        .bold()
+        .fg_color(Some(rgb(theme.accent)));
+    let arg = anstyle::Style::new().bold().fg_color(Some(rgb(theme.text)));
+    let comment = anstyle::Style::new().fg_color(Some(rgb(theme.muted)));
     clap::builder::Styles::styled()
         .header(title)
         .error(comment)
@@ -18,6 +206,42 @@ pub(crate) fn get_styles() -> clap::builder::Styles {
         .invalid(comment)
 }
 
+/// style help text with the help message styles, lines starting with `## ` become headers
+/// and `backtick` quoted spans become literals
+pub(crate) fn styled_help(text: &str) -> clap::builder::StyledStr {
+    let styles = get_styles();
+    let header = styles.get_header();
+    let literal = styles.get_literal();
+    let mut styled = String::new();
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            styled.push('\n');
+        }
+        if let Some(line) = line.strip_prefix("## ") {
+            styled.push_str(&format!(
+                "{}{}{}",
+                header.render(),
+                line,
+                header.render_reset()
+            ));
+            continue;
+        }
+        for (j, part) in line.split('`').enumerate() {
+            if j % 2 == 1 {
+                styled.push_str(&format!(
+                    "{}{}{}",
+                    literal.render(),
+                    part,
+                    literal.render_reset()
+                ));
+            } else {
+                styled.push_str(part);
+            }
+        }
+    }
+    styled.into()
+}
+
 pub(crate) trait FontStyle {
     fn colorize_background(self) -> ColoredString;
     fn colorize_title(self) -> ColoredString;
@@ -30,44 +254,56 @@ pub(crate) trait FontStyle {
 
 impl FontStyle for &str {
     fn colorize_background(self) -> ColoredString {
-        self.truecolor(40, 42, 54)
+        let (r, g, b) = theme().background;
+        self.truecolor(r, g, b)
     }
 
     fn colorize_title(self) -> ColoredString {
-        self.truecolor(206, 147, 249).bold()
+        let (r, g, b) = theme().title;
+        self.truecolor(r, g, b).bold()
     }
 
     fn colorize_comment(self) -> ColoredString {
-        self.truecolor(98, 114, 164)
+        let (r, g, b) = theme().comment;
+        self.truecolor(r, g, b)
     }
 
     fn colorize_string(self) -> ColoredString {
-        self.truecolor(241, 250, 140)
+        let (r, g, b) = theme().string;
+        self.truecolor(r, g, b)
     }
 
     fn colorize_constant(self) -> ColoredString {
-        self.truecolor(185, 242, 159)
+        let (r, g, b) = theme().constant;
+        self.truecolor(r, g, b)
     }
 
     fn colorize_function(self) -> ColoredString {
-        self.truecolor(139, 233, 253)
+        let (r, g, b) = theme().function;
+        self.truecolor(r, g, b)
     }
 
     fn colorize_variable(self) -> ColoredString {
-        self.truecolor(100, 170, 170)
+        let (r, g, b) = theme().variable;
+        self.truecolor(r, g, b)
     }
 }
 
 use inquire::ui::{Attributes, Color, IndexPrefix, RenderConfig, StyleSheet, Styled};
 
 pub(crate) fn get_render_config() -> RenderConfig<'static> {
-    let highlight_color = Color::DarkGreen;
+    if !color_enabled() {
+        return RenderConfig::empty();
+    }
+    let theme = theme();
+    let rgb = |(r, g, b): Rgb| Color::Rgb { r, g, b };
+    let highlight_color = rgb(theme.accent);
 
     let mut render_config = RenderConfig::default();
     render_config.prompt = StyleSheet::new().with_attr(Attributes::BOLD);
-    render_config.prompt_prefix = Styled::new("").with_fg(Color::LightRed);
-    render_config.answered_prompt_prefix = Styled::new("").with_fg(Color::LightRed);
-    render_config.placeholder = StyleSheet::new().with_fg(Color::LightRed);
+    render_config.prompt_prefix = Styled::new("").with_fg(rgb(theme.error));
+    render_config.answered_prompt_prefix = Styled::new("").with_fg(rgb(theme.error));
+    render_config.placeholder = StyleSheet::new().with_fg(rgb(theme.error));
     render_config.selected_option = Some(StyleSheet::new().with_fg(highlight_color));
     render_config.highlighted_option_prefix = Styled::new("→").with_fg(highlight_color);
     render_config.selected_checkbox = Styled::new("☑").with_fg(highlight_color);
@@ -77,15 +313,11 @@ pub(crate) fn get_render_config() -> RenderConfig<'static> {
     render_config.option_index_prefix = IndexPrefix::Simple;
     render_config.error_message = render_config
         .error_message
-        .with_prefix(Styled::new("❌").with_fg(Color::LightRed));
+        .with_prefix(Styled::new("❌").with_fg(rgb(theme.error)));
     render_config.answer = StyleSheet::new()
         .with_attr(Attributes::BOLD)
         .with_fg(highlight_color);
-    let grey = Color::Rgb {
-        r: 100,
-        g: 100,
-        b: 100,
-    };
+    let grey = rgb(theme.comment);
     render_config.help_message = StyleSheet::new()
         .with_fg(grey)
         .with_attr(Attributes::ITALIC);
@@ -1,4 +1,5 @@
 use crate::{DataArgs, OutputMode, TblCliError};
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 use tbl_core::formats::{print_bullet, print_header};
 
@@ -35,7 +36,7 @@ fn print_input_summary(
     n_input_files: usize,
     input_files: &[&Path],
     n_input_bytes: u64,
-    _args: &DataArgs,
+    args: &DataArgs,
 ) {
     print_header("Inputs");
     print_bullet(
@@ -55,30 +56,125 @@ fn print_input_summary(
     if input_files.len() > n_show_files {
         tbl_core::formats::print_bullet_key_indent("...", 4);
     }
+    if let Some(path_columns) = &args.path_column {
+        print_bullet("path columns", format!("{:?}", path_columns));
+    }
 }
 
 fn print_transform_summary(args: &DataArgs) {
     print_header("Transformations");
+    let mut transforming = print_transform_bullets(args);
+    for step in args.pipeline_steps.iter() {
+        transforming |= print_transform_bullets(step);
+    }
+    if !transforming {
+        println!("[no transformations]");
+    }
+}
+
+/// print a bullet for each transformation, returning whether any were printed
+fn print_transform_bullets(args: &DataArgs) -> bool {
     let mut transforming = false;
     if let Some(with_columns) = &args.with_columns {
         print_bullet("adding columns", format!("{:?}", with_columns));
         transforming = true;
     }
+    if let Some(where_) = &args.where_ {
+        print_bullet("filtering rows", where_);
+        transforming = true;
+    }
     if let Some(filter) = &args.filter {
         print_bullet("filtering rows", format!("{:?}", filter));
         transforming = true;
     }
+    if let Some(update) = &args.update {
+        let update = match &args.update_where {
+            Some(condition) => format!("{:?} where {}", update, condition),
+            None => format!("{:?}", update),
+        };
+        print_bullet("updating values", update);
+        transforming = true;
+    }
+    if args.interleave {
+        print_bullet("interleaving rows", "taking rows from each file in turn");
+        transforming = true;
+    }
     if let Some(drop) = &args.drop {
         print_bullet("dropping columns", format!("{:?}", drop));
         transforming = true;
     }
+    if !args.parse_json.is_empty() {
+        for (column, dtype) in args.parse_json.iter() {
+            print_bullet(format!("parsing json of {}", column), dtype.to_string());
+        }
+        transforming = true;
+    }
+    if let Some(flatten) = &args.flatten {
+        let columns = if flatten.is_empty() {
+            "all struct columns".to_string()
+        } else {
+            format!("{:?}", flatten)
+        };
+        print_bullet("flattening structs", columns);
+        transforming = true;
+    }
     if let Some(cast) = &args.cast {
         print_bullet("casting types", format!("{:?}", cast));
+        if let Some(timezone) = &args.timezone {
+            print_bullet("datetime timezone", timezone);
+        }
+        if let Some(time_format) = &args.time_format {
+            print_bullet("datetime format", time_format);
+        }
         transforming = true;
     }
-    if !transforming {
-        println!("[no transformations]");
+    if let Some(encrypt) = &args.encrypt {
+        let key_id = args.key_id.clone().unwrap_or_default();
+        print_bullet(
+            "encrypting columns",
+            format!("{:?} with key id {}", encrypt, key_id),
+        );
+        transforming = true;
+    }
+    if let Some(decrypt) = &args.decrypt {
+        if decrypt.is_empty() {
+            print_bullet("decrypting columns", "all encrypted columns");
+        } else {
+            print_bullet("decrypting columns", format!("{:?}", decrypt));
+        }
+        transforming = true;
+    }
+    if let Some((case, replacement)) = &args.normalize_names {
+        print_bullet(
+            "normalizing column names",
+            format!(
+                "{} case, replacing invalid characters with {:?}",
+                case, replacement
+            ),
+        );
+        transforming = true;
+    }
+    if let Some(schema) = &args.align_schema {
+        print_bullet(
+            "aligning schemas",
+            format!("to union schema of {} columns", schema.len()),
+        );
+        transforming = true;
+    }
+    if let Some(value_counts) = &args.value_counts {
+        let counts = match (args.top_k, args.approx) {
+            (Some(k), true) => format!("{} (approximate top {})", value_counts, k),
+            (Some(k), false) => format!("{} (top {})", value_counts, k),
+            _ => value_counts.clone(),
+        };
+        let counts = match &args.weight_column {
+            Some(weight_column) => format!("{} weighted by {}", counts, weight_column),
+            None => counts,
+        };
+        print_bullet("counting values", counts);
+        transforming = true;
     }
+    transforming
 }
 
 fn print_output_mode_summary(n_input_files: usize, output_mode: &OutputMode, args: &DataArgs) {
@@ -139,3 +235,178 @@ fn print_output_mode_summary(n_input_files: usize, output_mode: &OutputMode, arg
         }
     }
 }
+
+/// number of files done, rewritten in place on one line of stderr when it is a terminal
+pub(crate) struct ProgressLine {
+    enabled: bool,
+    shown: bool,
+}
+
+impl ProgressLine {
+    /// show progress of batches with more than one file
+    pub(crate) fn new(n_total: usize) -> ProgressLine {
+        ProgressLine {
+            enabled: n_total > 1 && std::io::stderr().is_terminal(),
+            shown: false,
+        }
+    }
+
+    pub(crate) fn update(&mut self, progress: &tbl_core::executor::FileProgress) {
+        if self.enabled {
+            eprint!("\r[{} of {} files done]", progress.n_done, progress.n_total);
+            self.shown = true;
+        }
+    }
+
+    /// erase the progress line, so that other output can be printed in its place
+    pub(crate) fn clear(&mut self) {
+        if self.shown {
+            eprint!("\r\x1b[2K");
+            self.shown = false;
+        }
+    }
+
+    /// end the progress line, so that later output starts on a new line
+    pub(crate) fn finish(&mut self) {
+        if self.shown {
+            eprintln!();
+            self.shown = false;
+        }
+    }
+}
+
+impl Drop for ProgressLine {
+    fn drop(&mut self) {
+        self.finish()
+    }
+}
+
+/// sizes of files read and written by an edit, printed once the edit completes
+#[derive(Debug, Default, serde::Serialize)]
+pub(crate) struct EditStats {
+    n_files_written: u64,
+    n_files_failed: u64,
+    n_rows_processed: u64,
+    n_rows_written: u64,
+    n_bytes_before: u64,
+    n_bytes_after: u64,
+    n_bytes_uncompressed_before: u64,
+    n_bytes_uncompressed_after: u64,
+    elapsed_seconds: f64,
+    #[serde(skip)]
+    start: Option<std::time::Instant>,
+}
+
+impl EditStats {
+    pub(crate) fn start() -> EditStats {
+        EditStats {
+            start: Some(std::time::Instant::now()),
+            ..Default::default()
+        }
+    }
+
+    /// measure inputs of an edit, before they can be overwritten
+    pub(crate) async fn add_inputs(&mut self, input_paths: &[PathBuf]) -> Result<(), TblCliError> {
+        for path in input_paths.iter() {
+            let (n_bytes, n_bytes_uncompressed, n_rows) = measure_file(path).await?;
+            self.n_bytes_before += n_bytes;
+            self.n_bytes_uncompressed_before += n_bytes_uncompressed;
+            self.n_rows_processed += n_rows;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn add_output(&mut self, output_path: &Path) -> Result<(), TblCliError> {
+        let (n_bytes, n_bytes_uncompressed, n_rows) = measure_file(output_path).await?;
+        self.n_files_written += 1;
+        self.n_bytes_after += n_bytes;
+        self.n_bytes_uncompressed_after += n_bytes_uncompressed;
+        self.n_rows_written += n_rows;
+        Ok(())
+    }
+
+    pub(crate) fn add_failure(&mut self) {
+        self.n_files_failed += 1;
+    }
+
+    /// print stats as a summary, or as a json line for other tools to collect
+    pub(crate) fn print(mut self, json: bool) -> Result<(), TblCliError> {
+        if let Some(start) = self.start {
+            self.elapsed_seconds = start.elapsed().as_secs_f64();
+        }
+        if json {
+            let line =
+                serde_json::to_string(&self).map_err(|e| TblCliError::Error(e.to_string()))?;
+            println!("{}", line);
+            return Ok(());
+        }
+        let ratio = |uncompressed: u64, compressed: u64| {
+            if compressed == 0 || uncompressed == 0 {
+                "-".to_string()
+            } else {
+                format!("{:.2}x", uncompressed as f64 / compressed as f64)
+            }
+        };
+        let change = if self.n_bytes_before == 0 {
+            String::new()
+        } else {
+            let change = 100.0 * (self.n_bytes_after as f64 / self.n_bytes_before as f64 - 1.0);
+            format!(" ({:+.1}%)", change)
+        };
+        println!();
+        print_header("Edit summary");
+        print_bullet(
+            "n_files_written",
+            tbl_core::formats::format_with_commas(self.n_files_written),
+        );
+        if self.n_files_failed > 0 {
+            print_bullet(
+                "n_files_failed",
+                tbl_core::formats::format_with_commas(self.n_files_failed),
+            );
+        }
+        print_bullet(
+            "n_rows",
+            format!(
+                "{} processed, {} written",
+                tbl_core::formats::format_with_commas(self.n_rows_processed),
+                tbl_core::formats::format_with_commas(self.n_rows_written)
+            ),
+        );
+        print_bullet(
+            "n_bytes",
+            format!(
+                "{} before, {} after{}",
+                tbl_core::formats::format_bytes(self.n_bytes_before),
+                tbl_core::formats::format_bytes(self.n_bytes_after),
+                change
+            ),
+        );
+        print_bullet(
+            "compression ratio",
+            format!(
+                "{} before, {} after",
+                ratio(self.n_bytes_uncompressed_before, self.n_bytes_before),
+                ratio(self.n_bytes_uncompressed_after, self.n_bytes_after)
+            ),
+        );
+        print_bullet("elapsed", format!("{:.2}s", self.elapsed_seconds));
+        Ok(())
+    }
+}
+
+/// bytes on disk, uncompressed bytes, and rows of file, counting only bytes of non-parquet files
+async fn measure_file(path: &Path) -> Result<(u64, u64, u64), TblCliError> {
+    let n_bytes = std::fs::metadata(path)?.len();
+    if path.extension().and_then(|ext| ext.to_str()) != Some("parquet") {
+        return Ok((n_bytes, 0, 0));
+    }
+    // unreadable files are counted by size, and fail once they are processed
+    let metadata = match tbl_core::parquet::get_parquet_metadata(path).await {
+        Ok(metadata) => metadata,
+        Err(_) => return Ok((n_bytes, 0, 0)),
+    };
+    let n_rows = metadata.file_metadata().num_rows() as u64;
+    let n_bytes_uncompressed = tbl_core::parquet::get_parquet_n_bytes_uncompressed(metadata);
+    Ok((n_bytes, n_bytes_uncompressed, n_rows))
+}
@@ -1,5 +1,7 @@
+use crate::nested::{column_expr, edit_struct_field, project_struct, split_nested_path};
 use crate::{DataArgs, TblCliError};
 use polars::prelude::*;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 pub(crate) fn apply_transformations(
@@ -7,21 +9,86 @@ pub(crate) fn apply_transformations(
     args: &DataArgs,
 ) -> Result<LazyFrame, TblCliError> {
     let lf = apply_with_columns(lf, args.with_columns.as_deref())?;
+    let lf = apply_parse_json(lf, &args.parse_json)?;
+    let lf = apply_where(lf, args.where_.as_deref())?;
     let lf = apply_filter(lf, args.filter.as_deref())?;
     let lf = apply_drop(lf, args.drop.as_deref())?;
-    let lf = apply_cast(lf, args.cast.as_deref())?;
+    let lf = apply_cast(
+        lf,
+        args.cast.as_deref(),
+        args.timezone.as_deref(),
+        args.time_format.as_deref(),
+    )?;
     let lf = apply_set(lf, args.set.as_deref())?;
+    let lf = apply_update(lf, args.update.as_deref(), args.update_where.as_deref())?;
     let lf = apply_nullify(lf, args.nullify.as_deref())?;
+    let lf = apply_flatten(
+        lf,
+        args.flatten.as_deref(),
+        args.flatten_separator.as_deref().unwrap_or("_"),
+        args.flatten_recursive,
+    )?;
     let lf = apply_select(lf, args.columns.as_deref())?;
     let lf = apply_offset(lf, args.offset)?;
     let lf = apply_head(lf, args.head)?;
     let lf = apply_tail(lf, args.tail)?;
-    let lf = apply_value_counts(lf, args.value_counts.as_deref())?;
-    let lf = apply_sort(lf, args.sort.as_deref())?;
+    let lf = apply_value_counts(
+        lf,
+        args.value_counts.as_deref(),
+        args.top_k,
+        args.weight_column.as_deref(),
+    )?;
+    let lf = apply_sort(lf, args.sort.as_deref(), sorts_nulls_last(args))?;
     let lf = apply_rename(lf, args.rename.as_deref())?;
+    let lf = apply_normalize_names(lf, args.normalize_names.as_ref())?;
+    let mut lf = apply_align(lf, args.align_schema.as_deref())?;
+    for step in args.pipeline_steps.iter() {
+        lf = apply_transformations(lf, step)?;
+    }
     Ok(lf)
 }
 
+/// apply only the transformations that choose or reorder rows, in the order used by
+/// apply_transformations, along with the added columns that their predicates can use
+pub(crate) fn apply_row_selection(
+    lf: LazyFrame,
+    args: &DataArgs,
+) -> Result<LazyFrame, TblCliError> {
+    let lf = apply_with_columns(lf, args.with_columns.as_deref())?;
+    let lf = apply_parse_json(lf, &args.parse_json)?;
+    let lf = apply_where(lf, args.where_.as_deref())?;
+    let lf = apply_filter(lf, args.filter.as_deref())?;
+    let lf = apply_offset(lf, args.offset)?;
+    let lf = apply_head(lf, args.head)?;
+    let lf = apply_tail(lf, args.tail)?;
+    apply_sort(lf, args.sort.as_deref(), sorts_nulls_last(args))
+}
+
+/// copy of args without the transformations that choose or reorder rows
+pub(crate) fn row_wise_args(args: &DataArgs) -> DataArgs {
+    DataArgs {
+        where_: None,
+        filter: None,
+        offset: None,
+        head: None,
+        tail: None,
+        sort: None,
+        ..args.clone()
+    }
+}
+
+/// whether transformations choose, reorder, or aggregate rows
+pub(crate) fn selects_rows(args: &DataArgs) -> bool {
+    args.where_.is_some()
+        || args.filter.is_some()
+        || args.offset.is_some()
+        || args.head.is_some()
+        || args.tail.is_some()
+        || args.sort.is_some()
+        || args.value_counts.is_some()
+        || args.pipeline_steps.iter().any(selects_rows)
+}
+
 pub(crate) fn apply_with_columns(
     lf: LazyFrame,
     columns: Option<&[String]>,
@@ -46,8 +113,11 @@ fn parse_new_column_expr(col_spec: &str) -> Result<Expr, TblCliError> {
         ));
     }
     let (name, type_str) = (parts[0], parts[1]);
-    let value_str = parts.get(2).and_then(|s| s.split('=').nth(1));
     let dtype = parse_dtype(type_str)?;
+    if let Some(sql) = parts.get(2).and_then(|s| s.strip_prefix("expr=")) {
+        return Ok(parse_sql_expr(sql)?.cast(dtype).alias(name));
+    }
+    let value_str = parts.get(2).and_then(|s| s.split('=').nth(1));
     let expr = if let Some(value) = value_str {
         create_value_expr(value, &dtype)?
     } else {
@@ -57,7 +127,15 @@ fn parse_new_column_expr(col_spec: &str) -> Result<Expr, TblCliError> {
     Ok(expr)
 }
 
-fn parse_dtype(type_str: &str) -> Result<DataType, TblCliError> {
+pub(crate) fn parse_dtype(type_str: &str) -> Result<DataType, TblCliError> {
+    if let Some((_, timezone)) = parse_datetime_spec(type_str) {
+        return Ok(DataType::Datetime(TimeUnit::Microseconds, timezone));
+    }
+    if let Some(categories) = parse_enum_categories(type_str)? {
+        let categories =
+            polars::export::arrow::array::Utf8ViewArray::from_slice_values(&categories);
+        return Ok(create_enum_data_type(categories));
+    }
     match type_str.to_lowercase().as_str() {
         "i8" => Ok(DataType::Int8),
         "i16" => Ok(DataType::Int16),
@@ -71,12 +149,110 @@ fn parse_dtype(type_str: &str) -> Result<DataType, TblCliError> {
         "f64" => Ok(DataType::Float64),
         "bool" => Ok(DataType::Boolean),
         "str" => Ok(DataType::String),
+        "binary" => Ok(DataType::Binary),
         "date" => Ok(DataType::Date),
         "datetime" => Ok(DataType::Datetime(TimeUnit::Microseconds, None)),
-        _ => Err(TblCliError::Error("invalid data type".to_string())),
+        "categorical" | "cat" => Ok(DataType::Categorical(None, CategoricalOrdering::Physical)),
+        other => match parse_decimal_dtype(other) {
+            Some((precision, scale)) => Ok(DataType::Decimal(Some(precision), Some(scale))),
+            None => Err(TblCliError::Error("invalid data type".to_string())),
+        },
+    }
+}
+
+/// parse format and timezone of datetime type of the form datetime(FORMAT, TIMEZONE)
+///
+/// either part can be left out, a part without `%` directives is taken as the timezone
+pub(crate) fn parse_datetime_spec(type_str: &str) -> Option<(Option<String>, Option<String>)> {
+    let inner = type_str
+        .trim()
+        .strip_prefix("datetime(")
+        .and_then(|s| s.strip_suffix(')'))?;
+    let non_empty = |s: &str| Some(s.trim().to_string()).filter(|s| !s.is_empty());
+    match inner.rsplit_once(',') {
+        Some((format, timezone)) if !timezone.contains('%') => {
+            Some((non_empty(format), non_empty(timezone)))
+        }
+        _ if inner.contains('%') => Some((non_empty(inner), None)),
+        _ => Some((None, non_empty(inner))),
+    }
+}
+
+/// parse categories of enum type, either enum(A,B,C) or enum(@PATH) with one category per line
+fn parse_enum_categories(type_str: &str) -> Result<Option<Vec<String>>, TblCliError> {
+    let inner = match type_str
+        .trim()
+        .strip_prefix("enum(")
+        .and_then(|s| s.strip_suffix(')'))
+    {
+        Some(inner) => inner,
+        None => return Ok(None),
+    };
+    let categories: Vec<String> = match inner.strip_prefix('@') {
+        Some(path) => std::fs::read_to_string(path)?
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect(),
+        None => inner.split(',').map(|s| s.trim().to_string()).collect(),
+    };
+    let mut unique = std::collections::HashSet::new();
+    if categories.is_empty() || !categories.iter().all(|c| unique.insert(c)) {
+        return Err(TblCliError::Arg(format!(
+            "enum categories must be non-empty and unique: {}",
+            type_str
+        )));
+    }
+    Ok(Some(categories))
+}
+
+/// parse decimal type of the form decimal(PRECISION, SCALE)
+fn parse_decimal_dtype(type_str: &str) -> Option<(usize, usize)> {
+    let type_str: String = type_str.chars().filter(|c| !c.is_whitespace()).collect();
+    let (precision, scale) = type_str
+        .strip_prefix("decimal(")?
+        .strip_suffix(')')?
+        .split_once(',')?;
+    let (precision, scale) = (precision.parse().ok()?, scale.parse().ok()?);
+    if (1..=38).contains(&precision) && scale <= precision {
+        Some((precision, scale))
+    } else {
+        None
     }
 }
 
+/// create decimal literal, checking that the value fits the precision and scale
+fn create_decimal_expr(value: &str, dtype: &DataType) -> Result<Expr, TblCliError> {
+    let (precision, scale) = match dtype {
+        DataType::Decimal(Some(precision), Some(scale)) => (*precision, *scale),
+        _ => {
+            return Err(TblCliError::Error(
+                "decimal needs precision and scale".to_string(),
+            ))
+        }
+    };
+    let digits = value.trim().trim_start_matches(['-', '+']);
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    if integer.is_empty() && fraction.is_empty()
+        || !integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(TblCliError::Error(format!(
+            "Invalid decimal value: {}",
+            value
+        )));
+    }
+    if integer.trim_start_matches('0').len() > precision - scale || fraction.len() > scale {
+        return Err(TblCliError::Error(format!(
+            "{} does not fit in decimal({}, {})",
+            value, precision, scale
+        )));
+    }
+    Ok(lit(value.trim().to_string()).strict_cast(dtype.clone()))
+}
+
 fn create_value_expr(value: &str, dtype: &DataType) -> Result<Expr, TblCliError> {
     match dtype {
         DataType::Int8 => Ok(lit(
@@ -129,10 +305,217 @@ fn create_value_expr(value: &str, dtype: &DataType) -> Result<Expr, TblCliError>
                 .map_err(|_| TblCliError::Error(value.to_string()))?;
             Ok(lit(naive_datetime.and_utc().timestamp_millis()))
         }
+        DataType::Decimal(_, _) => create_decimal_expr(value, dtype),
         _ => Err(TblCliError::Error("Unsupported dtype".to_string())),
     }
 }
 
+pub(crate) fn apply_where(lf: LazyFrame, expr: Option<&str>) -> Result<LazyFrame, TblCliError> {
+    match expr {
+        None => Ok(lf),
+        Some(expr) => Ok(lf.filter(parse_sql_expr(expr)?)),
+    }
+}
+
+pub(crate) fn parse_sql_expr(expr: &str) -> Result<Expr, TblCliError> {
+    let expanded = expand_unit_converters(&expand_scientific_notation(expr))?;
+    let parsed = polars::sql::sql_expr(expanded)
+        .map_err(|e| TblCliError::Arg(format!("invalid expression '{}': {}", expr, e)))?;
+    // polars panics when planning subqueries outside of a sql context
+    if (&parsed)
+        .into_iter()
+        .any(|node| matches!(node, Expr::SubPlan(..)))
+    {
+        return Err(TblCliError::Arg(format!(
+            "invalid expression '{}': subqueries are not supported",
+            expr
+        )));
+    }
+    Ok(parsed)
+}
+
+/// unit converters usable as functions in expressions, written as sql with `{x}` as argument
+const UNIT_CONVERTERS: [(&str, &str); 16] = [
+    (
+        "wei_to_eth",
+        "(CAST({x} AS DOUBLE) / 1000000000000000000.0)",
+    ),
+    ("wei_to_gwei", "(CAST({x} AS DOUBLE) / 1000000000.0)"),
+    ("gwei_to_eth", "(CAST({x} AS DOUBLE) / 1000000000.0)"),
+    (
+        "eth_to_wei",
+        "(CAST({x} AS DOUBLE) * 1000000000000000000.0)",
+    ),
+    ("bytes_to_kb", "(CAST({x} AS DOUBLE) / 1000.0)"),
+    ("bytes_to_mb", "(CAST({x} AS DOUBLE) / 1000000.0)"),
+    ("bytes_to_gb", "(CAST({x} AS DOUBLE) / 1000000000.0)"),
+    ("bytes_to_tb", "(CAST({x} AS DOUBLE) / 1000000000000.0)"),
+    ("bytes_to_kib", "(CAST({x} AS DOUBLE) / 1024.0)"),
+    ("bytes_to_mib", "(CAST({x} AS DOUBLE) / 1048576.0)"),
+    ("bytes_to_gib", "(CAST({x} AS DOUBLE) / 1073741824.0)"),
+    ("bytes_to_tib", "(CAST({x} AS DOUBLE) / 1099511627776.0)"),
+    (
+        "epoch_s_to_timestamp",
+        "CAST(CAST({x} * 1000000 AS BIGINT) AS TIMESTAMP)",
+    ),
+    (
+        "epoch_ms_to_timestamp",
+        "CAST(CAST({x} * 1000 AS BIGINT) AS TIMESTAMP)",
+    ),
+    (
+        "epoch_us_to_timestamp",
+        "CAST(CAST({x} AS BIGINT) AS TIMESTAMP)",
+    ),
+    (
+        "epoch_ns_to_timestamp",
+        "CAST(CAST({x} / 1000 AS BIGINT) AS TIMESTAMP)",
+    ),
+];
+
+/// replace calls of unit converters such as `wei_to_eth(value)` with their sql
+fn expand_unit_converters(expr: &str) -> Result<String, TblCliError> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut output = String::new();
+    let mut quote: Option<char> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            output.push(c);
+            i += 1;
+            continue;
+        }
+        if c == '\'' || c == '"' {
+            quote = Some(c);
+            output.push(c);
+            i += 1;
+            continue;
+        }
+        if !(c.is_alphabetic() || c == '_') {
+            output.push(c);
+            i += 1;
+            continue;
+        }
+
+        // read identifier, then look for a converter call
+        let start = i;
+        while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+            i += 1;
+        }
+        let name: String = chars[start..i].iter().collect();
+        let mut open = i;
+        while open < chars.len() && chars[open].is_whitespace() {
+            open += 1;
+        }
+        let template = UNIT_CONVERTERS
+            .iter()
+            .find(|(converter, _)| converter.eq_ignore_ascii_case(&name))
+            .map(|(_, template)| template);
+        match (template, chars.get(open)) {
+            (Some(template), Some('(')) => {
+                let close = find_closing_paren(&chars, open).ok_or_else(|| {
+                    TblCliError::Arg(format!("unclosed call of {} in '{}'", name, expr))
+                })?;
+                let argument: String = chars[open + 1..close].iter().collect();
+                let argument = expand_unit_converters(&argument)?;
+                output.push_str(&template.replace("{x}", &format!("({})", argument)));
+                i = close + 1;
+            }
+            _ => output.push_str(&name),
+        }
+    }
+    Ok(output)
+}
+
+/// index of parenthesis closing the one at open, skipping quoted text
+fn find_closing_paren(chars: &[char], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    for (i, c) in chars.iter().enumerate().skip(open) {
+        match (quote, c) {
+            (Some(q), c) if *c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(*c),
+            (None, '(') => depth += 1,
+            (None, ')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// rewrite numbers like `1e-9` as decimals, which the sql parser cannot read
+///
+/// expanded numbers always keep a decimal point, so `1e3` stays a float rather than
+/// becoming the integer `1000`
+fn expand_scientific_notation(expr: &str) -> String {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut output = String::new();
+    let mut quote: Option<char> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            output.push(c);
+            i += 1;
+            continue;
+        }
+        if c == '\'' || c == '"' {
+            quote = Some(c);
+            output.push(c);
+            i += 1;
+            continue;
+        }
+        let starts_number = c.is_ascii_digit()
+            && (i == 0 || !(chars[i - 1].is_alphanumeric() || chars[i - 1] == '_'));
+        if starts_number {
+            let mut end = i;
+            while end < chars.len() && (chars[end].is_ascii_digit() || chars[end] == '.') {
+                end += 1;
+            }
+            if end < chars.len() && (chars[end] == 'e' || chars[end] == 'E') {
+                let mut exp_end = end + 1;
+                if exp_end < chars.len() && (chars[exp_end] == '-' || chars[exp_end] == '+') {
+                    exp_end += 1;
+                }
+                let digits_start = exp_end;
+                while exp_end < chars.len() && chars[exp_end].is_ascii_digit() {
+                    exp_end += 1;
+                }
+                let token: String = chars[i..exp_end].iter().collect();
+                if exp_end > digits_start {
+                    if let Ok(value) = token.parse::<f64>() {
+                        let value = value.to_string();
+                        output.push_str(&value);
+                        if !value.contains(['.', 'i', 'N']) {
+                            output.push_str(".0");
+                        }
+                        i = exp_end;
+                        continue;
+                    }
+                }
+            }
+            output.extend(&chars[i..end]);
+            i = end;
+            continue;
+        }
+        output.push(c);
+        i += 1;
+    }
+    output
+}
+
 pub(crate) fn apply_filter(
     lf: LazyFrame,
     filters: Option<&[String]>,
@@ -288,35 +671,389 @@ pub(crate) fn apply_rename(
     }
 }
 
+/// rename every column of frame to its normalized name
+pub(crate) fn apply_normalize_names(
+    mut lf: LazyFrame,
+    normalize_names: Option<&(tbl_core::names::NameCase, String)>,
+) -> Result<LazyFrame, TblCliError> {
+    match normalize_names {
+        None => Ok(lf),
+        Some((case, replacement)) => {
+            let (existing, new): (Vec<String>, Vec<String>) = lf
+                .schema()?
+                .iter_names()
+                .map(|name| {
+                    let normalized = tbl_core::names::normalize_name(name, *case, replacement);
+                    (name.to_string(), normalized)
+                })
+                .filter(|(name, normalized)| name != normalized)
+                .unzip();
+            Ok(lf.rename(existing, new))
+        }
+    }
+}
+
+/// select the columns of schema in its order and dtypes, filling missing columns with nulls
+pub(crate) fn apply_align(
+    mut lf: LazyFrame,
+    schema: Option<&Schema>,
+) -> Result<LazyFrame, TblCliError> {
+    match schema {
+        None => Ok(lf),
+        Some(schema) => {
+            let existing = lf.schema()?;
+            let exprs: Vec<Expr> = schema
+                .iter()
+                .map(|(name, dtype)| match existing.get(name) {
+                    Some(current) if current == dtype => col(name),
+                    Some(_) => col(name).strict_cast(dtype.clone()),
+                    None => lit(NULL).cast(dtype.clone()).alias(name),
+                })
+                .collect();
+            Ok(lf.select(exprs))
+        }
+    }
+}
+
 pub(crate) fn apply_drop(
     lf: LazyFrame,
     columns: Option<&[String]>,
 ) -> Result<LazyFrame, TblCliError> {
+    let mut lf = lf;
+    let columns = match columns {
+        None => return Ok(lf),
+        Some(columns) => resolve_columns(&mut lf, columns)?,
+    };
     match columns {
-        None => Ok(lf),
-        Some(columns) => Ok(lf.drop(columns)),
+        columns if !columns.iter().any(|c| c.contains('.')) => Ok(lf.drop(columns)),
+        columns => {
+            // fields of struct columns are dropped by rebuilding the struct without them, each
+            // drop sees the struct as left by previous drops
+            let mut schema = lf.schema()?;
+            let mut flat = Vec::new();
+            for column in columns.iter() {
+                match split_nested_path(&schema, column) {
+                    Some((root, path)) => {
+                        let dtype = schema.get(root).cloned().unwrap_or(DataType::Null);
+                        if let Some(expr) = edit_struct_field(col(root), &dtype, &path, &|_| None)?
+                        {
+                            lf = lf.with_column(expr.alias(root));
+                            schema = lf.schema()?;
+                        }
+                    }
+                    None => flat.push(column.clone()),
+                }
+            }
+            Ok(lf.drop(flat))
+        }
+    }
+}
+
+/// expand column patterns against schema of frame, leaving plain column names as given
+fn resolve_columns(lf: &mut LazyFrame, columns: &[String]) -> Result<Vec<String>, TblCliError> {
+    if !columns
+        .iter()
+        .any(|c| tbl_core::schemas::is_column_pattern(c))
+    {
+        return Ok(columns.to_vec());
     }
+    let schema = lf.schema()?;
+    Ok(tbl_core::schemas::resolve_column_selectors(
+        columns, &schema,
+    )?)
 }
 
-pub(crate) fn apply_cast(lf: LazyFrame, cast: Option<&[String]>) -> Result<LazyFrame, TblCliError> {
+/// expand COLUMN=TYPE casts whose column is a pattern into a cast of each matching column
+pub(crate) fn resolve_cast_columns(
+    schema: &Schema,
+    cast: &[String],
+) -> Result<Vec<String>, TblCliError> {
+    let mut resolved = Vec::new();
+    for c in cast.iter() {
+        // patterns may themselves contain `=`, but types never do
+        match c.rsplit_once('=') {
+            Some((pattern, dtype)) if tbl_core::schemas::is_column_pattern(pattern) => {
+                let columns =
+                    tbl_core::schemas::resolve_column_selectors(&[pattern.to_string()], schema)?;
+                resolved.extend(columns.iter().map(|column| format!("{}={}", column, dtype)));
+            }
+            _ => resolved.push(c.clone()),
+        }
+    }
+    Ok(resolved)
+}
+
+pub(crate) fn apply_cast(
+    lf: LazyFrame,
+    cast: Option<&[String]>,
+    timezone: Option<&str>,
+    time_format: Option<&str>,
+) -> Result<LazyFrame, TblCliError> {
     match cast {
         None => Ok(lf),
         Some(cast) => {
             let mut new_lf = lf;
-            for c in cast {
-                let parts: Vec<&str> = c.split('=').collect();
-                if parts.len() != 2 {
-                    return Err(TblCliError::Error("InvalidCastFormat".to_string()));
-                }
-                let (column, dtype_str) = (parts[0], parts[1]);
+            let schema = new_lf
+                .schema()
+                .map_err(|e| TblCliError::Error(e.to_string()))?;
+            for c in resolve_cast_columns(&schema, cast)?.iter() {
+                // resolved columns may themselves contain `=`, but types never do
+                let (column, dtype_str) = c
+                    .rsplit_once('=')
+                    .ok_or_else(|| TblCliError::Error("InvalidCastFormat".to_string()))?;
                 let dtype = parse_dtype(dtype_str)?;
-                new_lf = new_lf.with_column(col(column).cast(dtype));
+                if let Some((root, path)) = split_nested_path(&schema, column) {
+                    let root_dtype = schema.get(root).cloned().unwrap_or(DataType::Null);
+                    let edit = |expr: Expr| match &dtype {
+                        DataType::Decimal(_, _) | DataType::Enum(_, _) => {
+                            Some(expr.strict_cast(dtype.clone()))
+                        }
+                        dtype => Some(expr.cast(dtype.clone())),
+                    };
+                    if let Some(expr) = edit_struct_field(col(root), &root_dtype, &path, &edit)? {
+                        new_lf = new_lf.with_column(expr.alias(root));
+                    }
+                    continue;
+                }
+                let expr = match (schema.get(column), dtype) {
+                    (Some(source), DataType::Datetime(time_unit, _)) => {
+                        match parse_datetime_spec(dtype_str) {
+                            Some((format, spec_timezone)) => create_datetime_cast_expr(
+                                column,
+                                source,
+                                time_unit,
+                                spec_timezone.as_deref().or(timezone),
+                                DatetimeParsing::Detect(format.as_deref().or(time_format)),
+                            ),
+                            None => create_datetime_cast_expr(
+                                column,
+                                source,
+                                time_unit,
+                                timezone,
+                                DatetimeParsing::Strict(time_format),
+                            ),
+                        }
+                    }
+                    // values that overflow a decimal or fall outside an enum are errors, not nulls
+                    (_, dtype @ (DataType::Decimal(_, _) | DataType::Enum(_, _))) => {
+                        col(column).strict_cast(dtype)
+                    }
+                    (_, dtype) => col(column).cast(dtype),
+                };
+                new_lf = new_lf.with_column(expr);
             }
             Ok(new_lf)
         }
     }
 }
 
+/// rows whose values cannot be cast, paired with a description of the failed cast
+///
+/// only top-level columns are checked, a value fails when it is present but casts to null
+pub(crate) fn get_cast_failures(
+    schema: &Schema,
+    cast: &[String],
+    time_format: Option<&str>,
+) -> Result<Vec<(Expr, String)>, TblCliError> {
+    let mut failures = Vec::new();
+    for c in resolve_cast_columns(schema, cast)?.iter() {
+        let (column, dtype_str) = c
+            .split_once('=')
+            .ok_or_else(|| TblCliError::Error("InvalidCastFormat".to_string()))?;
+        let dtype = parse_dtype(dtype_str)?;
+        let converted = match (schema.get(column), &dtype) {
+            (None, _) => continue,
+            (Some(DataType::String), DataType::Datetime(time_unit, _))
+                if parse_datetime_spec(dtype_str).is_some() =>
+            {
+                let format = parse_datetime_spec(dtype_str).and_then(|(format, _)| format);
+                parse_datetime_strings(column, *time_unit, format.as_deref().or(time_format))
+            }
+            (Some(DataType::String), DataType::Datetime(time_unit, _)) => {
+                let options = StrptimeOptions {
+                    format: time_format.map(|f| f.to_string()),
+                    strict: false,
+                    ..Default::default()
+                };
+                col(column)
+                    .str()
+                    .to_datetime(Some(*time_unit), None, options, lit("raise"))
+            }
+            (Some(_), dtype) => col(column).cast(dtype.clone()),
+        };
+        let failed = col(column).is_not_null().and(converted.is_null());
+        failures.push((failed, format!("cannot cast {} to {}", column, dtype_str)));
+    }
+    Ok(failures)
+}
+
+/// how strings are parsed by datetime casts
+#[derive(Clone, Copy)]
+enum DatetimeParsing<'a> {
+    /// parse with format, or with a format inferred from the first value, failing on mismatches
+    Strict(Option<&'a str>),
+    /// parse with format, then with common formats, leaving unparseable values null
+    Detect(Option<&'a str>),
+}
+
+/// formats tried for strings that do not match the format of a datetime cast
+const FALLBACK_DATETIME_FORMATS: [&str; 8] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%d",
+    "%Y/%m/%d %H:%M:%S",
+    "%d/%m/%Y %H:%M:%S",
+    "%Y%m%d%H%M%S",
+];
+
+/// parse strings as datetimes with format, falling back to common formats
+fn parse_datetime_strings(column: &str, time_unit: TimeUnit, time_format: Option<&str>) -> Expr {
+    let parsed: Vec<Expr> = time_format
+        .into_iter()
+        .chain(FALLBACK_DATETIME_FORMATS)
+        .map(|format| {
+            let options = StrptimeOptions {
+                format: Some(format.to_string()),
+                strict: false,
+                ..Default::default()
+            };
+            col(column)
+                .str()
+                .to_datetime(Some(time_unit), None, options, lit("raise"))
+        })
+        .collect();
+    coalesce(&parsed)
+}
+
+/// fail with examples when string columns cast with datetime(FORMAT) types have unparseable values
+pub(crate) fn check_datetime_parsing(
+    lf: &LazyFrame,
+    cast: &[String],
+    time_format: Option<&str>,
+) -> Result<(), TblCliError> {
+    let schema = lf.clone().schema()?;
+    let mut checks = Vec::new();
+    for c in resolve_cast_columns(&schema, cast)?.iter() {
+        if let (Some((column, dtype_str)), Some(DataType::String)) = (
+            c.split_once('='),
+            c.split_once('=').and_then(|(column, _)| schema.get(column)),
+        ) {
+            if let Some((format, _)) = parse_datetime_spec(dtype_str) {
+                let parsed = parse_datetime_strings(
+                    column,
+                    TimeUnit::Microseconds,
+                    format.as_deref().or(time_format),
+                );
+                let failed = col(column).is_not_null().and(parsed.is_null());
+                checks.push((column.to_string(), dtype_str.to_string(), failed));
+            }
+        }
+    }
+    if checks.is_empty() {
+        return Ok(());
+    }
+    let mut exprs = Vec::new();
+    for (i, (column, _, failed)) in checks.iter().enumerate() {
+        exprs.push(failed.clone().sum().alias(&format!("n_{}", i)));
+        exprs.push(
+            col(column)
+                .filter(failed.clone())
+                .unique_stable()
+                .head(Some(3))
+                .implode()
+                .alias(&format!("examples_{}", i)),
+        );
+    }
+    let report = lf.clone().select(exprs).collect()?;
+    let mut problems = Vec::new();
+    for (i, (column, dtype_str, _)) in checks.iter().enumerate() {
+        let n_failed = report
+            .column(&format!("n_{}", i))?
+            .cast(&DataType::UInt64)?
+            .u64()?
+            .get(0)
+            .unwrap_or(0);
+        if n_failed == 0 {
+            continue;
+        }
+        let examples = report.column(&format!("examples_{}", i))?.get(0)?;
+        problems.push(format!(
+            "{} values of {} do not parse as {}, such as {}",
+            n_failed, column, dtype_str, examples
+        ));
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(TblCliError::Error(format!(
+            "{}; set those rows aside with --quarantine-dir",
+            problems.join("; ")
+        )))
+    }
+}
+
+/// cast to datetime, parsing strings with a format and attaching or converting timezones
+///
+/// naive timestamps are interpreted as being in `timezone`,
+/// timestamps that already have a timezone are converted to `timezone`
+fn create_datetime_cast_expr(
+    column: &str,
+    source: &DataType,
+    time_unit: TimeUnit,
+    timezone: Option<&str>,
+    parsing: DatetimeParsing<'_>,
+) -> Expr {
+    let timezone = timezone.map(|tz| tz.to_string());
+    match source {
+        DataType::String => {
+            let expr = match parsing {
+                DatetimeParsing::Strict(time_format) => {
+                    let options = StrptimeOptions {
+                        format: time_format.map(|f| f.to_string()),
+                        ..Default::default()
+                    };
+                    col(column)
+                        .str()
+                        .to_datetime(Some(time_unit), None, options, lit("raise"))
+                }
+                DatetimeParsing::Detect(time_format) => {
+                    parse_datetime_strings(column, time_unit, time_format)
+                }
+            };
+            match timezone {
+                Some(timezone) => {
+                    expr.dt()
+                        .replace_time_zone(Some(timezone), lit("raise"), NonExistent::Raise)
+                }
+                None => expr,
+            }
+        }
+        DataType::Datetime(_, source_timezone) => {
+            let expr = col(column).cast(DataType::Datetime(time_unit, source_timezone.clone()));
+            match (source_timezone, timezone) {
+                (None, Some(timezone)) => {
+                    expr.dt()
+                        .replace_time_zone(Some(timezone), lit("raise"), NonExistent::Raise)
+                }
+                (Some(_), Some(timezone)) => expr.dt().convert_time_zone(timezone),
+                (_, None) => expr,
+            }
+        }
+        _ => {
+            let expr = col(column).cast(DataType::Datetime(time_unit, None));
+            match timezone {
+                Some(timezone) => {
+                    expr.dt()
+                        .replace_time_zone(Some(timezone), lit("raise"), NonExistent::Raise)
+                }
+                None => expr,
+            }
+        }
+    }
+}
+
 pub(crate) fn apply_set(lf: LazyFrame, set: Option<&[String]>) -> Result<LazyFrame, TblCliError> {
     match set {
         None => Ok(lf),
@@ -407,6 +1144,7 @@ fn create_set_expr(column: &str, value: &str, dtype: &DataType) -> Result<Expr,
                 ));
             }
         }
+        DataType::Decimal(_, _) => create_decimal_expr(value, dtype)?,
         _ => {
             return Err(TblCliError::Error(format!(
                 "Unsupported column type for '{}': {:?}",
@@ -418,6 +1156,42 @@ fn create_set_expr(column: &str, value: &str, dtype: &DataType) -> Result<Expr,
     Ok(lit_value.alias(column))
 }
 
+pub(crate) fn apply_update(
+    lf: LazyFrame,
+    update: Option<&[String]>,
+    condition: Option<&str>,
+) -> Result<LazyFrame, TblCliError> {
+    match update {
+        None => Ok(lf),
+        Some(update) => {
+            let condition = condition.map(parse_sql_expr).transpose()?;
+            let mut exprs = Vec::new();
+            for spec in update {
+                let (column, expr) = parse_update_spec(spec)?;
+                let expr = match &condition {
+                    Some(condition) => when(condition.clone()).then(expr).otherwise(col(&column)),
+                    None => expr,
+                };
+                exprs.push(expr.alias(&column));
+            }
+            Ok(lf.with_columns(exprs))
+        }
+    }
+}
+
+/// parse update spec of the form `COLUMN = EXPR`
+pub(crate) fn parse_update_spec(spec: &str) -> Result<(String, Expr), TblCliError> {
+    match spec.split_once('=') {
+        Some((column, expr)) if !column.trim().is_empty() => {
+            Ok((column.trim().to_string(), parse_sql_expr(expr.trim())?))
+        }
+        _ => Err(TblCliError::Arg(format!(
+            "invalid update '{}', syntax is COLUMN = EXPR",
+            spec
+        ))),
+    }
+}
+
 pub(crate) fn apply_nullify(
     lf: LazyFrame,
     raw_columns: Option<&[String]>,
@@ -445,27 +1219,210 @@ pub(crate) fn apply_nullify(
     }
 }
 
+/// parse json string columns into the given types
+pub(crate) fn apply_parse_json(
+    lf: LazyFrame,
+    columns: &[(String, DataType)],
+) -> Result<LazyFrame, TblCliError> {
+    let exprs: Vec<Expr> = columns
+        .iter()
+        .map(|(column, dtype)| {
+            col(column)
+                .str()
+                .json_decode(Some(dtype.clone()), None)
+                .alias(column)
+        })
+        .collect();
+    if exprs.is_empty() {
+        Ok(lf)
+    } else {
+        Ok(lf.with_columns(exprs))
+    }
+}
+
+/// replace struct columns with one column per field, named STRUCT{separator}FIELD
+pub(crate) fn apply_flatten(
+    lf: LazyFrame,
+    columns: Option<&[String]>,
+    separator: &str,
+    recursive: bool,
+) -> Result<LazyFrame, TblCliError> {
+    let columns = match columns {
+        None => return Ok(lf),
+        Some(columns) => columns,
+    };
+    let mut lf = lf;
+    let schema = lf.schema()?;
+    for column in columns.iter() {
+        match schema.get(column) {
+            Some(DataType::Struct(_)) => {}
+            Some(_) => {
+                return Err(TblCliError::Arg(format!(
+                    "{} is not a struct column",
+                    column
+                )))
+            }
+            None => return Err(TblCliError::Arg(format!("column {} not found", column))),
+        }
+    }
+
+    let mut exprs = Vec::new();
+    let mut names = std::collections::HashSet::new();
+    for (name, dtype) in schema.iter() {
+        let flatten = matches!(dtype, DataType::Struct(_))
+            && (columns.is_empty() || columns.iter().any(|c| c == name.as_str()));
+        let mut column_exprs = Vec::new();
+        if flatten {
+            flatten_struct(
+                col(name),
+                name,
+                dtype,
+                separator,
+                recursive,
+                &mut column_exprs,
+            );
+        } else {
+            column_exprs.push((name.to_string(), col(name)));
+        }
+        for (name, expr) in column_exprs.into_iter() {
+            if !names.insert(name.clone()) {
+                return Err(TblCliError::Arg(format!(
+                    "flattening creates duplicate column {}, use a different separator",
+                    name
+                )));
+            }
+            exprs.push(expr.alias(&name));
+        }
+    }
+    Ok(lf.select(exprs))
+}
+
+fn flatten_struct(
+    expr: Expr,
+    prefix: &str,
+    dtype: &DataType,
+    separator: &str,
+    recursive: bool,
+    output: &mut Vec<(String, Expr)>,
+) {
+    if let DataType::Struct(fields) = dtype {
+        for field in fields.iter() {
+            let name = format!("{}{}{}", prefix, separator, field.name());
+            let field_expr = expr.clone().struct_().field_by_name(field.name());
+            match field.data_type() {
+                DataType::Struct(_) if recursive => flatten_struct(
+                    field_expr,
+                    &name,
+                    field.data_type(),
+                    separator,
+                    recursive,
+                    output,
+                ),
+                _ => output.push((name, field_expr)),
+            }
+        }
+    }
+}
+
+/// parse sort columns of the form COLUMN[:asc|:desc]
+pub(crate) fn parse_sort_columns(raw_columns: &[String]) -> (Vec<String>, Vec<bool>) {
+    let mut columns: Vec<String> = Vec::new();
+    let mut descending: Vec<bool> = Vec::new();
+    for column in raw_columns.iter() {
+        if let Some(column) = column.strip_suffix(":desc") {
+            columns.push(column.to_string());
+            descending.push(true);
+        } else if let Some(column) = column.strip_suffix(":asc") {
+            columns.push(column.to_string());
+            descending.push(false);
+        } else {
+            columns.push(column.to_string());
+            descending.push(false);
+        }
+    }
+    (columns, descending)
+}
+
+/// whether --nulls places nulls after other values of sort columns
+pub(crate) fn sorts_nulls_last(args: &DataArgs) -> bool {
+    args.nulls.as_deref() == Some("last")
+}
+
+/// whether any transformation other than sorting is requested
+pub(crate) fn has_non_sort_transformations(args: &DataArgs) -> bool {
+    args.with_columns.is_some()
+        || args.where_.is_some()
+        || args.filter.is_some()
+        || args.drop.is_some()
+        || args.cast.is_some()
+        || args.set.is_some()
+        || args.update.is_some()
+        || args.nullify.is_some()
+        || args.flatten.is_some()
+        || !args.parse_json.is_empty()
+        || args.columns.is_some()
+        || args.offset.is_some()
+        || args.interleave
+        || args.head.is_some()
+        || args.tail.is_some()
+        || args.value_counts.is_some()
+        || args.rename.is_some()
+        || args.normalize_names.is_some()
+        || args.align_schema.is_some()
+}
+
+/// whether args at most drop top level columns, so parquet column chunks can be copied as is
+///
+/// args are checked against an allow-list, so new args disable splicing until listed here
+/// --prefetch is not listed, since splicing writes outputs while the next files are read
+pub(crate) fn is_splice_only(args: &DataArgs) -> bool {
+    let drops_top_level = args
+        .drop
+        .as_ref()
+        .is_none_or(|columns| !columns.iter().any(|c| c.contains('.')));
+    let allowed = DataArgs {
+        paths: args.paths.clone(),
+        tree: args.tree,
+        drop: args.drop.clone(),
+        n: args.n.clone(),
+        no_summary: args.no_summary,
+        summary_json: args.summary_json,
+        max_xlsx_rows: args.max_xlsx_rows,
+        inplace: args.inplace,
+        output_file: args.output_file.clone(),
+        output_dir: args.output_dir.clone(),
+        output_prefix: args.output_prefix.clone(),
+        output_postfix: args.output_postfix.clone(),
+        output_template: args.output_template.clone(),
+        flat_output: args.flat_output,
+        confirm: args.confirm,
+        dry: args.dry,
+        resume: args.resume,
+        checkpoint: args.checkpoint.clone(),
+        incremental: args.incremental,
+        keep_going: args.keep_going,
+        fail_fast: args.fail_fast,
+        jobs: args.jobs,
+        splice: args.splice,
+        ..DataArgs::default()
+    };
+    drops_top_level && *args == allowed
+}
+
+/// sort rows stably, so rows with equal keys keep the order of input files and their rows
 pub(crate) fn apply_sort(
     lf: LazyFrame,
     raw_columns: Option<&[String]>,
+    nulls_last: bool,
 ) -> Result<LazyFrame, TblCliError> {
     match raw_columns {
         None => Ok(lf),
         Some(raw_columns) => {
-            let mut columns: Vec<String> = Vec::new();
-            let mut descending: Vec<bool> = Vec::new();
-            for column in raw_columns.iter() {
-                let column = column.to_string();
-                if column.ends_with(":desc") {
-                    columns.push(column[..column.len() - 5].to_string());
-                    descending.push(true);
-                } else {
-                    columns.push(column);
-                    descending.push(false);
-                }
-            }
+            let (columns, descending) = parse_sort_columns(raw_columns);
             let options = polars::chunked_array::ops::SortMultipleOptions::default()
-                .with_order_descending_multi(descending);
+                .with_nulls_last_multi(vec![nulls_last; columns.len()])
+                .with_order_descending_multi(descending)
+                .with_maintain_order(true);
             Ok(lf.sort(columns, options))
         }
     }
@@ -475,13 +1432,119 @@ pub(crate) fn apply_select(
     lf: LazyFrame,
     columns: Option<&[String]>,
 ) -> Result<LazyFrame, TblCliError> {
+    let mut lf = lf;
+    let columns = match columns {
+        None => return Ok(lf),
+        Some(columns) => resolve_columns(&mut lf, columns)?,
+    };
     match columns {
-        None => Ok(lf),
-        Some(columns) => {
+        columns if !columns.iter().any(|c| c.contains('.')) => {
             let exprs: Vec<Expr> = columns.iter().map(|c| col(c)).collect();
             Ok(lf.select(&exprs))
         }
+        columns => {
+            // fields selected from the same struct column are kept nested in that struct
+            let schema = lf.schema()?;
+            let mut roots: Vec<(&str, Vec<Vec<&str>>)> = Vec::new();
+            for column in columns.iter() {
+                let (root, path) =
+                    split_nested_path(&schema, column).unwrap_or((column.as_str(), Vec::new()));
+                match roots.iter_mut().find(|(name, _)| *name == root) {
+                    Some((_, paths)) => paths.push(path),
+                    None => roots.push((root, vec![path])),
+                }
+            }
+            let mut exprs = Vec::new();
+            for (root, paths) in roots.iter() {
+                if paths.iter().any(|path| path.is_empty()) {
+                    exprs.push(col(root));
+                } else {
+                    let dtype = schema.get(root).cloned().unwrap_or(DataType::Null);
+                    exprs.push(project_struct(col(root), &dtype, paths)?.alias(root));
+                }
+            }
+            Ok(lf.select(&exprs))
+        }
+    }
+}
+
+/// slice of rows, applied as offset, then head, then tail
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct RowSlice {
+    pub(crate) offset: Option<usize>,
+    pub(crate) head: Option<usize>,
+    pub(crate) tail: Option<usize>,
+}
+
+/// parse row range START..END or START..=END, either side optional
+///
+/// a negative start with an open end, like -100.., selects the last rows. other negative
+/// bounds depend on the number of rows and are rejected
+pub(crate) fn parse_row_range(range: &str) -> Result<RowSlice, TblCliError> {
+    let invalid = || TblCliError::Arg(format!("invalid row range {}, use START..END", range));
+    let (start, end, inclusive) = match range.split_once("..=") {
+        Some((start, end)) => (start, end, true),
+        None => {
+            let (start, end) = range.split_once("..").ok_or_else(invalid)?;
+            (start, end, false)
+        }
+    };
+    let parse = |value: &str| -> Result<Option<i64>, TblCliError> {
+        match value.trim() {
+            "" => Ok(None),
+            value => Ok(Some(value.replace('_', "").parse().map_err(|_| invalid())?)),
+        }
+    };
+    let (start, end) = (parse(start)?, parse(end)?);
+    if end.is_some_and(|end| end < 0) || (start.is_some_and(|start| start < 0) && end.is_some()) {
+        return Err(TblCliError::Arg(format!(
+            "invalid row range {}, only -N.. can count from the end, selecting the last N rows",
+            range
+        )));
+    }
+    if let Some(start) = start.filter(|start| *start < 0) {
+        return Ok(RowSlice {
+            tail: Some(start.unsigned_abs() as usize),
+            ..Default::default()
+        });
+    }
+    let start = start.unwrap_or(0) as usize;
+    let end = match (end, inclusive) {
+        (Some(end), true) => Some(end as usize + 1),
+        (Some(end), false) => Some(end as usize),
+        (None, true) => return Err(invalid()),
+        (None, false) => None,
+    };
+    if end.is_some_and(|end| end < start) {
+        return Err(TblCliError::Arg(format!(
+            "row range {} ends before it starts",
+            range
+        )));
+    }
+    Ok(RowSlice {
+        offset: (start > 0).then_some(start),
+        head: end.map(|end| end - start),
+        tail: None,
+    })
+}
+
+/// convert --rows into --offset, --head, and --tail, so that row ranges are sliced like
+/// other slices
+pub(crate) fn resolve_row_range(args: &mut DataArgs) -> Result<(), TblCliError> {
+    let range = match &args.rows {
+        Some(range) => range.clone(),
+        None => return Ok(()),
+    };
+    if args.offset.is_some() || args.head.is_some() || args.tail.is_some() {
+        return Err(TblCliError::Arg(
+            "--rows cannot be combined with --offset, --head, or --tail".to_string(),
+        ));
     }
+    let slice = parse_row_range(&range)?;
+    args.offset = slice.offset;
+    args.head = slice.head;
+    args.tail = slice.tail;
+    Ok(())
 }
 
 pub(crate) fn apply_head(lf: LazyFrame, n: Option<usize>) -> Result<LazyFrame, TblCliError> {
@@ -505,18 +1568,172 @@ pub(crate) fn apply_offset(lf: LazyFrame, n: Option<usize>) -> Result<LazyFrame,
     }
 }
 
-pub(crate) fn apply_value_counts(lf: LazyFrame, n: Option<&str>) -> Result<LazyFrame, TblCliError> {
+pub(crate) fn apply_value_counts(
+    lf: LazyFrame,
+    n: Option<&str>,
+    top_k: Option<usize>,
+    weight_column: Option<&str>,
+) -> Result<LazyFrame, TblCliError> {
     match n {
         None => Ok(lf),
         Some(column) => {
             // let expr = col(column).value_counts(true, false, "count".to_string(), false);
             // Ok(lf.select([expr]))
+            let mut lf = lf;
+            let expr = if column.contains('.') {
+                column_expr(lf.schema()?.as_ref(), column).alias(column)
+            } else {
+                col(column)
+            };
+            // weighted counts sum the weights of each value, ignoring null weights
+            let count = match weight_column {
+                Some(weight_column) => col(weight_column).sum().alias("count"),
+                None => expr.clone().count().alias("count"),
+            };
             let sort_options = SortMultipleOptions::new().with_order_descending(true);
             let value_counts = lf
-                .group_by(&[col(column)])
-                .agg(&[col(column).count().alias("count")])
+                .group_by(std::slice::from_ref(&expr))
+                .agg(&[count])
                 .sort(["count"], sort_options);
-            Ok(value_counts)
+            match top_k {
+                Some(k) => Ok(value_counts.limit(k as u32)),
+                None => Ok(value_counts),
+            }
+        }
+    }
+}
+
+pub(crate) async fn approx_value_counts(
+    input_paths: &[PathBuf],
+    args: &DataArgs,
+) -> Result<LazyFrame, TblCliError> {
+    let (column, k) = match (args.value_counts.as_deref(), args.top_k) {
+        (Some(column), Some(k)) => (column, k),
+        _ => {
+            return Err(TblCliError::Arg(
+                "--approx requires --value-counts and --top-k".to_string(),
+            ))
+        }
+    };
+    if args.filter.is_some() || args.where_.is_some() || args.with_columns.is_some() {
+        return Err(TblCliError::Arg(
+            "--approx counts raw file values, cannot combine with --filter, --where, or --with-columns"
+                .to_string(),
+        ));
+    }
+
+    // track more counters than reported to tighten error bounds
+    let capacity = std::cmp::max(10 * k, 1000);
+    let sketch =
+        tbl_core::parquet::approx_parquet_value_counts(input_paths, column, capacity, 8192).await?;
+
+    let top_k = sketch.top_k(k);
+    let values: Vec<Option<String>> = top_k.iter().map(|hitter| hitter.item.clone()).collect();
+    let counts: Vec<u64> = top_k.iter().map(|hitter| hitter.count).collect();
+    let errors: Vec<u64> = top_k.iter().map(|hitter| hitter.error).collect();
+    let df = DataFrame::new(vec![
+        Series::new(column, values),
+        Series::new("count", counts),
+        Series::new("count_error", errors),
+    ])?;
+    Ok(df.lazy())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sql_expr_rejects_subqueries() {
+        assert!(parse_sql_expr("a IN (1, 2)").is_ok());
+        for expr in ["a IN (SELECT 1)", "a > 1 AND b = (SELECT 2)"] {
+            assert!(matches!(parse_sql_expr(expr), Err(TblCliError::Arg(_))));
         }
     }
+
+    #[test]
+    fn test_parse_row_range() {
+        let slice = |offset, head, tail| RowSlice { offset, head, tail };
+        assert_eq!(
+            parse_row_range("10..20").unwrap(),
+            slice(Some(10), Some(10), None)
+        );
+        assert_eq!(
+            parse_row_range("10..=20").unwrap(),
+            slice(Some(10), Some(11), None)
+        );
+        assert_eq!(
+            parse_row_range("1_000..").unwrap(),
+            slice(Some(1000), None, None)
+        );
+        assert_eq!(parse_row_range("..5").unwrap(), slice(None, Some(5), None));
+        assert_eq!(parse_row_range("..").unwrap(), slice(None, None, None));
+        assert_eq!(
+            parse_row_range("-100..").unwrap(),
+            slice(None, None, Some(100))
+        );
+        assert!(parse_row_range("-100..-50").is_err());
+        assert!(parse_row_range("..-5").is_err());
+        assert!(parse_row_range("20..10").is_err());
+        assert!(parse_row_range("5..=").is_err());
+        assert!(parse_row_range("5").is_err());
+    }
+
+    #[test]
+    fn test_apply_row_selection() {
+        let df = df!("id" => (0i64..10).collect::<Vec<_>>()).unwrap();
+        let args = DataArgs {
+            where_: Some("id > 2".to_string()),
+            head: Some(3),
+            sort: Some(vec!["id:desc".to_string()]),
+            set: Some(vec!["id=0".to_string()]),
+            ..Default::default()
+        };
+        let selected = apply_row_selection(df.lazy(), &args)
+            .unwrap()
+            .collect()
+            .unwrap();
+        let ids: Vec<Option<i64>> = selected
+            .column("id")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(ids, vec![Some(5), Some(4), Some(3)]);
+
+        let row_wise = row_wise_args(&args);
+        assert!(!selects_rows(&row_wise));
+        assert_eq!(row_wise.set, args.set);
+    }
+
+    #[test]
+    fn test_apply_cast_with_equals_in_pattern() {
+        let df = df!("a=b" => [1i64], "a=c" => [2i64], "d" => [3i64]).unwrap();
+        let cast = ["re:^a=b$=f64".to_string(), "a=c=i32".to_string()];
+        let cast_df = apply_cast(df.lazy(), Some(&cast), None, None)
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_eq!(cast_df.column("a=b").unwrap().dtype(), &DataType::Float64);
+        assert_eq!(cast_df.column("a=c").unwrap().dtype(), &DataType::Int32);
+        assert_eq!(cast_df.column("d").unwrap().dtype(), &DataType::Int64);
+    }
+
+    #[test]
+    fn test_expand_scientific_notation() {
+        assert_eq!(expand_scientific_notation("x > 1e3"), "x > 1000.0");
+        assert_eq!(expand_scientific_notation("x * 2.5E-3"), "x * 0.0025");
+        assert_eq!(
+            expand_scientific_notation("col1e3 = '1e3'"),
+            "col1e3 = '1e3'"
+        );
+        let df = df!("x" => [1i64]).unwrap();
+        let out = df
+            .lazy()
+            .select([parse_sql_expr("x * 1e3").unwrap().alias("y")])
+            .collect()
+            .unwrap();
+        assert_eq!(out.column("y").unwrap().dtype(), &DataType::Float64);
+    }
 }
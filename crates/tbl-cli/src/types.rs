@@ -1,3 +1,5 @@
+use polars::prelude::PolarsError;
+use std::path::PathBuf;
 use tbl_core::TblError;
 use thiserror::Error;
 
@@ -35,11 +37,110 @@ pub(crate) enum TblCliError {
     #[error(transparent)]
     ParseIntError(#[from] std::num::ParseIntError),
 
+    /// Error caused by no input files being found
+    #[error("No inputs: {0}")]
+    NoInputs(String),
+
+    /// Error caused by some operations failing while others succeeded
+    #[error("Partial failure: {0}")]
+    PartialFailure(String),
+
+    /// Error caused by data failing quality checks
+    #[error("Check failed: {0}")]
+    CheckFailed(String),
+
+    /// Error caused by user declining to continue
+    #[error("Aborted by user")]
+    Aborted,
+
     /// General Error
     #[error("Input error: {0}")]
     Error(String),
 }
 
+/// exit code of general errors
+pub(crate) const EXIT_ERROR: i32 = 1;
+/// exit code of invalid arguments
+pub(crate) const EXIT_ARGUMENT_ERROR: i32 = 2;
+/// exit code when no input files are found
+pub(crate) const EXIT_NO_INPUTS: i32 = 3;
+/// exit code when schemas of inputs do not match
+pub(crate) const EXIT_SCHEMA_MISMATCH: i32 = 4;
+/// exit code when some files failed to process
+pub(crate) const EXIT_PARTIAL_FAILURE: i32 = 5;
+/// exit code when data fails quality checks
+pub(crate) const EXIT_CHECK_FAILED: i32 = 6;
+/// exit code when user declines to continue
+pub(crate) const EXIT_USER_ABORT: i32 = 130;
+
+impl TblCliError {
+    /// error for inputs that match no files, naming the search that was used
+    pub(crate) fn no_inputs(inputs: &Option<Vec<PathBuf>>, tree: bool) -> TblCliError {
+        let search = tbl_core::filesystem::describe_input_search(inputs, tree);
+        let searches_dirs = inputs
+            .as_ref()
+            .is_none_or(|paths| paths.iter().any(|path| path.is_dir()));
+        let hint = if searches_dirs && !tree {
+            ", use --tree to search subdirectories"
+        } else {
+            ""
+        };
+        TblCliError::NoInputs(format!("no inputs found matching {}{}", search, hint))
+    }
+
+    /// kind and process exit code of error, classified together so that each
+    /// kind maps to exactly one exit code
+    fn classify(&self) -> (&'static str, i32) {
+        match self {
+            TblCliError::Arg(_) | TblCliError::ParseIntError(_) => {
+                ("argument", EXIT_ARGUMENT_ERROR)
+            }
+            TblCliError::NoInputs(_) => ("no_inputs", EXIT_NO_INPUTS),
+            TblCliError::PartialFailure(_) => ("partial_failure", EXIT_PARTIAL_FAILURE),
+            TblCliError::CheckFailed(_) => ("check_failed", EXIT_CHECK_FAILED),
+            TblCliError::Aborted => ("aborted", EXIT_USER_ABORT),
+            TblCliError::MissingSchemaError(_)
+            | TblCliError::Tbl(TblError::SchemaError(_))
+            | TblCliError::Tbl(TblError::PolarsError(PolarsError::SchemaMismatch(_)))
+            | TblCliError::PolarsError(PolarsError::SchemaMismatch(_)) => {
+                ("schema_mismatch", EXIT_SCHEMA_MISMATCH)
+            }
+            _ => ("error", EXIT_ERROR),
+        }
+    }
+
+    /// category of error, used for json error output
+    pub(crate) fn kind(&self) -> &'static str {
+        self.classify().0
+    }
+
+    /// process exit code of error
+    pub(crate) fn exit_code(&self) -> i32 {
+        self.classify().1
+    }
+
+    /// print error to stderr, or to stdout as a message if no inputs were found
+    pub(crate) fn report(&self, json: bool, exit_code: i32) {
+        if json {
+            let error = serde_json::json!({
+                "error": {
+                    "kind": self.kind(),
+                    "message": self.to_string(),
+                    "exit_code": exit_code,
+                }
+            });
+            eprintln!("{}", error);
+        } else if let TblCliError::NoInputs(message) = self {
+            println!("[{}]", message);
+        } else if let TblCliError::Aborted = self {
+            println!("[exiting]");
+        } else {
+            eprintln!("Error: {:?}", self);
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
 pub(crate) enum OutputMode {
     PrintToStdout,
     SaveToSingleFile,
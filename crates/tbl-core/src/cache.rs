@@ -0,0 +1,142 @@
+use crate::parquet::{fnv1a, FNV_OFFSET_BASIS};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+/// temporary files older than this were left by interrupted writers
+const STALE_TMP_AGE: Duration = Duration::from_secs(3600);
+
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// directory of cached results, evicting least recently used entries beyond a size limit
+///
+/// reads and writes are best effort, io errors and unreadable entries are cache misses
+#[derive(Clone, Debug)]
+pub struct ResultCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl ResultCache {
+    /// create cache in dir holding at most max_bytes of entries
+    pub fn new(dir: &Path, max_bytes: u64) -> ResultCache {
+        ResultCache {
+            dir: dir.to_path_buf(),
+            max_bytes,
+        }
+    }
+
+    /// cached value of key, marking the entry as recently used
+    pub fn get(&self, key: &str) -> Option<String> {
+        let path = self.entry_path(key);
+        let text = std::fs::read_to_string(&path).ok()?;
+        let (stored_key, value) = text.split_once('\n')?;
+        if stored_key != key.escape_default().to_string() {
+            return None;
+        }
+        if let Ok(file) = std::fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(value.to_string())
+    }
+
+    /// store value of key, then evict old entries if the cache is over its size limit
+    ///
+    /// entries are written to a temporary file unique to this writer and moved into place, so
+    /// concurrent readers and writers never see partial entries
+    pub fn put(&self, key: &str, value: &str) {
+        let path = self.entry_path(key);
+        let tmp_path = path.with_extension(format!(
+            "{}_{}.tmp",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let text = format!("{}\n{}", key.escape_default(), value);
+        let written = std::fs::create_dir_all(&self.dir)
+            .and_then(|_| std::fs::write(&tmp_path, text))
+            .and_then(|_| std::fs::rename(&tmp_path, &path));
+        if written.is_err() {
+            let _ = std::fs::remove_file(&tmp_path);
+        }
+        self.evict();
+    }
+
+    /// remove least recently used entries until entries fit within the size limit
+    pub fn evict(&self) {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        let mut files: Vec<(SystemTime, u64, PathBuf)> = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let metadata = match entry.metadata() {
+                Ok(metadata) if metadata.is_file() => metadata,
+                _ => continue,
+            };
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            match path.extension().and_then(|extension| extension.to_str()) {
+                Some("entry") => files.push((modified, metadata.len(), path)),
+                Some("tmp") if modified.elapsed().is_ok_and(|age| age > STALE_TMP_AGE) => {
+                    let _ = std::fs::remove_file(&path);
+                }
+                _ => {}
+            }
+        }
+        files.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.2.cmp(&b.2)));
+        let mut total = 0;
+        for (_, size, path) in files.iter() {
+            total += size;
+            if total > self.max_bytes {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        let hash = fnv1a(FNV_OFFSET_BASIS, key.as_bytes());
+        self.dir.join(format!("{:016x}.entry", hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_result_cache() -> Result<(), crate::TblError> {
+        let temp_dir = TempDir::new()?;
+        let dir = temp_dir.path().join("cache");
+
+        // missing directories and entries are misses
+        let cache = ResultCache::new(&dir, 1_000);
+        assert_eq!(cache.get("a"), None);
+        cache.put("a", "first\nvalue");
+        assert_eq!(cache.get("a").as_deref(), Some("first\nvalue"));
+        assert_eq!(cache.get("b"), None);
+
+        // unreadable entries are misses
+        std::fs::write(cache.entry_path("b"), [0xff, 0xfe])?;
+        assert_eq!(cache.get("b"), None);
+
+        // least recently used entries are evicted first
+        let cache = ResultCache::new(&dir, 100);
+        let old = SystemTime::now() - Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(cache.entry_path("a"))?
+            .set_modified(old)?;
+        cache.put("c", &"x".repeat(90));
+        assert_eq!(cache.get("a"), None);
+        assert!(cache.get("c").is_some());
+        assert_eq!(
+            std::fs::read_dir(&dir)?
+                .flatten()
+                .filter(|entry| entry.path().extension().is_some_and(|e| e == "tmp"))
+                .count(),
+            0
+        );
+        Ok(())
+    }
+}
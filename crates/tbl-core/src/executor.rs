@@ -0,0 +1,334 @@
+use crate::TblError;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::time::{Duration, Instant};
+
+/// options of per-file executor
+#[derive(Clone, Debug)]
+pub struct ExecutorOptions {
+    /// number of files processed at once
+    pub max_concurrent: usize,
+    /// maximum number of files started per second
+    pub max_files_per_second: Option<f64>,
+}
+
+impl Default for ExecutorOptions {
+    fn default() -> ExecutorOptions {
+        ExecutorOptions {
+            max_concurrent: 1,
+            max_files_per_second: None,
+        }
+    }
+}
+
+/// token that stops an executor from starting more files, files already started still finish
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// create new token
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    /// stop starting files
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst)
+    }
+
+    /// whether token was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// progress of executor when a file completes
+#[derive(Clone, Copy, Debug)]
+pub struct FileProgress {
+    /// index of completed job
+    pub index: usize,
+    /// number of completed jobs, including this one
+    pub n_done: usize,
+    /// number of jobs
+    pub n_total: usize,
+}
+
+/// number of jobs that completed, and that were never started because of cancellation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExecutorSummary {
+    /// number of completed jobs
+    pub n_completed: usize,
+    /// number of jobs never started
+    pub n_cancelled: usize,
+}
+
+/// apply operation to each job, such as an input output pair of files
+///
+/// on_complete is called with the result of each job as it completes, in order of completion.
+/// when on_complete returns an error, no more jobs are started, jobs in flight are finished,
+/// and the error is returned
+pub async fn run_per_file<T, R, E, F, Fut, C>(
+    jobs: Vec<T>,
+    options: &ExecutorOptions,
+    cancel: &CancelToken,
+    op: F,
+    mut on_complete: C,
+) -> Result<ExecutorSummary, E>
+where
+    T: Send + 'static,
+    R: Send + 'static,
+    E: From<TblError> + Send + 'static,
+    F: Fn(T) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<R, E>> + Send + 'static,
+    C: FnMut(FileProgress, Result<R, E>) -> Result<(), E>,
+{
+    let n_total = jobs.len();
+    let op = Arc::new(op);
+    let interval = options
+        .max_files_per_second
+        .filter(|rate| *rate > 0.0)
+        .map(|rate| Duration::from_secs_f64(1.0 / rate));
+    let mut pending = jobs.into_iter().enumerate();
+    let mut running = tokio::task::JoinSet::new();
+    let mut next_start = Instant::now();
+    let mut n_started = 0;
+    let mut n_done = 0;
+    let mut first_error = None;
+    loop {
+        // start jobs until workers are busy
+        while first_error.is_none()
+            && !cancel.is_cancelled()
+            && running.len() < options.max_concurrent.max(1)
+        {
+            let (index, job) = match pending.next() {
+                Some(next) => next,
+                None => break,
+            };
+            if let Some(interval) = interval {
+                tokio::time::sleep_until(next_start).await;
+                next_start = next_start.max(Instant::now()) + interval;
+            }
+            let op = op.clone();
+            running.spawn(async move { (index, op(job).await) });
+            n_started += 1;
+        }
+
+        let (index, result) = match running.join_next().await {
+            Some(joined) => joined.map_err(|e| E::from(TblError::from(e)))?,
+            None => break,
+        };
+        n_done += 1;
+        let progress = FileProgress {
+            index,
+            n_done,
+            n_total,
+        };
+        if let Err(e) = on_complete(progress, result) {
+            first_error.get_or_insert(e);
+        }
+    }
+
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(ExecutorSummary {
+            n_completed: n_done,
+            n_cancelled: n_total - n_started,
+        }),
+    }
+}
+
+/// apply two stage operation to each job, reading the next jobs while earlier jobs are written
+///
+/// jobs are read one at a time, with up to depth read jobs waiting to be written, so that
+/// decoding one file overlaps with encoding another. on_complete is called in order of jobs.
+/// when on_complete returns an error, no more jobs are read and the error is returned
+pub async fn run_pipelined<T, M, R, E, FR, FutR, FW, FutW, C>(
+    jobs: Vec<T>,
+    depth: usize,
+    options: &ExecutorOptions,
+    cancel: &CancelToken,
+    read: FR,
+    write: FW,
+    mut on_complete: C,
+) -> Result<ExecutorSummary, E>
+where
+    T: Send + 'static,
+    M: Send + 'static,
+    E: From<TblError> + Send + 'static,
+    FR: Fn(T) -> FutR + Send + 'static,
+    FutR: Future<Output = Result<M, E>> + Send,
+    FW: Fn(M) -> FutW,
+    FutW: Future<Output = Result<R, E>>,
+    C: FnMut(FileProgress, Result<R, E>) -> Result<(), E>,
+{
+    let n_total = jobs.len();
+    let interval = options
+        .max_files_per_second
+        .filter(|rate| *rate > 0.0)
+        .map(|rate| Duration::from_secs_f64(1.0 / rate));
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(depth.max(1));
+    let reader_cancel = cancel.clone();
+    let reader = tokio::spawn(async move {
+        let mut next_start = Instant::now();
+        let mut n_started = 0;
+        for (index, job) in jobs.into_iter().enumerate() {
+            if reader_cancel.is_cancelled() {
+                break;
+            }
+            if let Some(interval) = interval {
+                tokio::time::sleep_until(next_start).await;
+                next_start = next_start.max(Instant::now()) + interval;
+            }
+            n_started += 1;
+            let result = read(job).await;
+            if sender.send((index, result)).await.is_err() {
+                break;
+            }
+        }
+        n_started
+    });
+
+    let mut n_done = 0;
+    let mut first_error = None;
+    while let Some((index, result)) = receiver.recv().await {
+        let result = match result {
+            Ok(read) => write(read).await,
+            Err(e) => Err(e),
+        };
+        n_done += 1;
+        let progress = FileProgress {
+            index,
+            n_done,
+            n_total,
+        };
+        if let Err(e) = on_complete(progress, result) {
+            first_error = Some(e);
+            break;
+        }
+    }
+    drop(receiver);
+    let n_started = reader.await.map_err(|e| E::from(TblError::from(e)))?;
+
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(ExecutorSummary {
+            n_completed: n_done,
+            n_cancelled: n_total - n_started,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_per_file() -> Result<(), TblError> {
+        // all jobs complete, each reported once
+        let options = ExecutorOptions {
+            max_concurrent: 3,
+            max_files_per_second: None,
+        };
+        let mut completed = Vec::new();
+        let summary = run_per_file(
+            (0..10u64).collect(),
+            &options,
+            &CancelToken::new(),
+            |job| async move { Ok::<u64, TblError>(job * 2) },
+            |progress, result| {
+                completed.push((progress.index as u64, result?));
+                Ok(())
+            },
+        )
+        .await?;
+        completed.sort();
+        assert_eq!(completed, (0..10).map(|i| (i, i * 2)).collect::<Vec<_>>());
+        assert_eq!(summary.n_completed, 10);
+
+        // jobs are not started after cancellation
+        let cancel = CancelToken::new();
+        let summary = run_per_file(
+            (0..10u64).collect(),
+            &ExecutorOptions::default(),
+            &cancel.clone(),
+            |job| async move { Ok::<u64, TblError>(job) },
+            |progress, _| {
+                if progress.n_done == 4 {
+                    cancel.cancel();
+                }
+                Ok(())
+            },
+        )
+        .await?;
+        assert_eq!(
+            summary,
+            ExecutorSummary {
+                n_completed: 4,
+                n_cancelled: 6
+            }
+        );
+
+        // errors returned by on_complete stop the executor
+        let result = run_per_file(
+            (0..10u64).collect(),
+            &ExecutorOptions::default(),
+            &CancelToken::new(),
+            |job| async move {
+                if job == 2 {
+                    Err(TblError::Error("failed".to_string()))
+                } else {
+                    Ok(job)
+                }
+            },
+            |_, result| result.map(|_| ()),
+        )
+        .await;
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_pipelined() -> Result<(), TblError> {
+        // jobs are written in order, each after being read
+        let mut completed = Vec::new();
+        let summary = run_pipelined(
+            (0..10u64).collect(),
+            2,
+            &ExecutorOptions::default(),
+            &CancelToken::new(),
+            |job| async move { Ok::<u64, TblError>(job * 2) },
+            |read| async move { Ok::<u64, TblError>(read + 1) },
+            |progress, result| {
+                completed.push((progress.index as u64, result?));
+                Ok(())
+            },
+        )
+        .await?;
+        assert_eq!(
+            completed,
+            (0..10).map(|i| (i, i * 2 + 1)).collect::<Vec<_>>()
+        );
+        assert_eq!(summary.n_completed, 10);
+
+        // errors of either stage stop the executor
+        let result = run_pipelined(
+            (0..10u64).collect(),
+            2,
+            &ExecutorOptions::default(),
+            &CancelToken::new(),
+            |job| async move {
+                if job == 3 {
+                    Err(TblError::Error("failed".to_string()))
+                } else {
+                    Ok(job)
+                }
+            },
+            |read| async move { Ok::<u64, TblError>(read) },
+            |_, result| result.map(|_| ()),
+        )
+        .await;
+        assert!(result.is_err());
+        Ok(())
+    }
+}
@@ -69,3 +69,16 @@ pub async fn count_existing_files(paths: &[PathBuf]) -> usize {
         })
         .await
 }
+
+/// return true if output exists and was modified after every input
+pub fn is_output_up_to_date(input_paths: &[PathBuf], output_path: &Path) -> bool {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let output_modified = match modified(output_path) {
+        Some(output_modified) => output_modified,
+        None => return false,
+    };
+    input_paths.iter().all(|input_path| {
+        input_path != output_path
+            && matches!(modified(input_path), Some(input_modified) if input_modified <= output_modified)
+    })
+}
@@ -1,5 +1,5 @@
 use crate::TblError;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// get file paths
 pub fn get_input_paths(
@@ -16,6 +16,13 @@ pub fn get_input_paths(
     // expand tree if specified
     let mut paths: Vec<PathBuf> = vec![];
     for raw_path in raw_paths.into_iter() {
+        if let Some(scheme) = get_url_scheme(&raw_path) {
+            return Err(TblError::InputError(format!(
+                "{}:// paths are not supported, only local files can be read",
+                scheme
+            )));
+        }
+        check_input_exists(&raw_path)?;
         if raw_path.is_dir() {
             let sub_paths = if tree {
                 super::gather::get_tree_tabular_files(&raw_path)?
@@ -37,3 +44,68 @@ pub fn get_input_paths(
 
     Ok(paths)
 }
+
+/// error naming input path if it does not exist
+pub fn check_input_exists(path: &Path) -> Result<(), TblError> {
+    if path.exists() {
+        Ok(())
+    } else {
+        Err(TblError::InputError(format!(
+            "input path {} does not exist",
+            path.to_string_lossy()
+        )))
+    }
+}
+
+/// describe the files searched for by get_input_paths, e.g. `data/**/*.parquet`
+pub fn describe_input_search(inputs: &Option<Vec<PathBuf>>, tree: bool) -> String {
+    let raw_paths = match inputs {
+        Some(raw_paths) => raw_paths.to_vec(),
+        None => vec![PathBuf::from(".")],
+    };
+    let pattern = if tree { "**/*.parquet" } else { "*.parquet" };
+    raw_paths
+        .iter()
+        .map(|raw_path| {
+            if raw_path.is_dir() {
+                raw_path.join(pattern).to_string_lossy().to_string()
+            } else {
+                raw_path.to_string_lossy().to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// get scheme of object store urls such as `s3://bucket/key`
+pub fn get_url_scheme(path: &Path) -> Option<String> {
+    let path = path.to_str()?;
+    let (scheme, _) = path.split_once("://")?;
+    if !scheme.is_empty() && scheme.chars().all(|c| c.is_ascii_alphanumeric()) {
+        Some(scheme.to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_empty_inputs() -> Result<(), TblError> {
+        let temp_dir = TempDir::new()?;
+        let inputs = Some(vec![temp_dir.path().to_path_buf()]);
+        assert!(get_input_paths(&inputs, true, true)?.is_empty());
+        assert_eq!(
+            describe_input_search(&inputs, true),
+            temp_dir.path().join("**/*.parquet").to_string_lossy()
+        );
+
+        // missing paths are errors rather than empty inputs
+        let missing = Some(vec![temp_dir.path().join("missing")]);
+        assert!(get_input_paths(&missing, false, true).is_err());
+        Ok(())
+    }
+}
@@ -17,3 +17,7 @@ pub use outputs::*;
 /// path size
 pub mod sizes;
 pub use sizes::*;
+
+/// output file name templates
+pub mod templates;
+pub use templates::*;
@@ -17,6 +17,8 @@ pub struct OutputPathSpec {
     pub file_postfix: Option<String>,
     /// sort
     pub sort: bool,
+    /// flat, write tree outputs directly into output_dir instead of their relative subpaths
+    pub flat: bool,
 }
 
 impl OutputPathSpec {
@@ -72,6 +74,12 @@ impl OutputPathSpec {
         self.sort = sort;
         self
     }
+
+    /// set flat
+    pub fn flat(mut self, flat: bool) -> Self {
+        self.flat = flat;
+        self
+    }
 }
 
 /// output dir type
@@ -225,6 +233,7 @@ pub fn get_output_paths(
     let mut return_inputs: Vec<PathBuf> = Vec::new();
     let mut return_outputs: Vec<PathBuf> = Vec::new();
     for input in inputs {
+        super::check_input_exists(&input)?;
         let metadata = std::fs::metadata(&input)?;
         if metadata.is_file() {
             // case 1: input is a file
@@ -237,8 +246,20 @@ pub fn get_output_paths(
             return_inputs.push(input.clone());
             return_outputs.push(output);
         } else if metadata.is_dir() {
-            if !output_spec.tree {
-                // case 2: input is a directory, non-tree mode
+            if output_spec.tree && output_spec.flat && output_dir.is_some() {
+                // case 2: input is a directory, tree mode flattened into output_dir
+                for sub_input in super::gather::get_tree_tabular_files(&input)?.into_iter() {
+                    let output = super::manipulate::convert_file_path(
+                        &sub_input,
+                        &output_dir,
+                        &output_spec.file_prefix,
+                        &output_spec.file_postfix,
+                    )?;
+                    return_inputs.push(sub_input);
+                    return_outputs.push(output);
+                }
+            } else if !output_spec.tree {
+                // case 3: input is a directory, non-tree mode
                 for sub_input in super::gather::get_directory_tabular_files(&input)?.into_iter() {
                     let output = super::manipulate::convert_file_path(
                         &sub_input,
@@ -250,7 +271,7 @@ pub fn get_output_paths(
                     return_outputs.push(output);
                 }
             } else {
-                // case 3: input is a directory, tree mode
+                // case 4: input is a directory, tree mode
                 for sub_input in super::gather::get_tree_tabular_files(&input)?.into_iter() {
                     // use relative path of tree leaf, change root to output_dir if provided
                     let new_path = if let Some(output_dir) = output_dir.clone() {
@@ -293,21 +314,55 @@ pub fn get_output_paths(
         (return_inputs, return_outputs)
     };
 
-    // check that all output paths are unique to avoid collisions
-    let mut count_per_output: HashMap<PathBuf, usize> = HashMap::new();
-    for output in return_outputs.iter() {
-        *count_per_output.entry(output.clone()).or_insert(0) += 1;
-        if count_per_output[output] > 1 {
-            return Err(TblError::Error(format!(
-                "Duplicate output path: {:?}",
-                output
-            )));
-        }
-    }
+    check_output_collisions(&return_inputs, &return_outputs)?;
 
     Ok((return_inputs, return_outputs))
 }
 
+/// check that each output is written by one input and does not overwrite another input
+///
+/// reports every collision at once, so that all of them can be fixed before data is written
+pub fn check_output_collisions(inputs: &[PathBuf], outputs: &[PathBuf]) -> Result<(), TblError> {
+    let mut inputs_per_output: HashMap<&PathBuf, Vec<&PathBuf>> = HashMap::new();
+    for (input, output) in inputs.iter().zip(outputs) {
+        inputs_per_output.entry(output).or_default().push(input);
+    }
+    let mut collisions = Vec::new();
+    for output in outputs.iter() {
+        if let Some(colliding) = inputs_per_output.remove(output) {
+            if colliding.len() > 1 {
+                let colliding: Vec<String> = colliding
+                    .iter()
+                    .map(|input| input.to_string_lossy().to_string())
+                    .collect();
+                collisions.push(format!(
+                    "{} <- {}",
+                    output.to_string_lossy(),
+                    colliding.join(", ")
+                ));
+            }
+        }
+    }
+    let input_set: std::collections::HashSet<&PathBuf> = inputs.iter().collect();
+    for (input, output) in inputs.iter().zip(outputs) {
+        if output != input && input_set.contains(output) {
+            collisions.push(format!(
+                "{} <- {}, overwriting an input",
+                output.to_string_lossy(),
+                input.to_string_lossy()
+            ));
+        }
+    }
+    if collisions.is_empty() {
+        Ok(())
+    } else {
+        Err(TblError::Error(format!(
+            "output paths collide: {}",
+            collisions.join("; ")
+        )))
+    }
+}
+
 /*
 tests
 for the tests, generate the following file tree:
@@ -385,6 +440,29 @@ mod tests {
         Ok(temp_dir)
     }
 
+    #[test]
+    fn test_check_output_collisions() {
+        let paths = |paths: &[&str]| -> Vec<PathBuf> { paths.iter().map(PathBuf::from).collect() };
+        let inputs = paths(&[
+            "a/x.parquet",
+            "b/x.parquet",
+            "c/y.parquet",
+            "c/y_new.parquet",
+        ]);
+        let outputs = paths(&[
+            "o/x.parquet",
+            "o/x.parquet",
+            "c/y_new.parquet",
+            "c/z.parquet",
+        ]);
+        let message = check_output_collisions(&inputs, &outputs)
+            .expect_err("collisions")
+            .to_string();
+        assert!(message.contains("o/x.parquet <- a/x.parquet, b/x.parquet"));
+        assert!(message.contains("c/y_new.parquet <- c/y.parquet, overwriting an input"));
+        assert!(check_output_collisions(&inputs, &inputs).is_ok());
+    }
+
     struct TestCase {
         name: &'static str,
         spec: OutputPathSpec,
@@ -680,6 +758,20 @@ mod tests {
                 "other_root/super_data_a.parquet",
             ],
         },
+        test_root_input_output_dir_tree_flat: TestCase {
+            name: "Root input with other output dir tree flattened",
+            spec: OutputPathSpec::new().inputs(vec!["root"]).output_dir("other_root").tree(true).flat(true),
+            expected_outputs: vec![
+                "other_root/super_data_a.parquet",
+                "other_root/super_data_b.parquet",
+                "other_root/data1_a.parquet",
+                "other_root/data1_b.parquet",
+                "other_root/sub_data1_a.parquet",
+                "other_root/sub_data1_b.parquet",
+                "other_root/data2_a.parquet",
+                "other_root/data2_b.parquet",
+            ],
+        },
 
     }
 }
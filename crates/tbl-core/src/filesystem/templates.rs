@@ -0,0 +1,206 @@
+use crate::TblError;
+use std::collections::HashMap;
+
+/// template of output file names, such as "{stem}_{date}_{part:04}.parquet"
+///
+/// a variable can take a format spec after a colon, where a spec starting with 0 pads values
+/// with leading zeros to its width and any other number truncates values to its width. a spec
+/// containing % is a strftime format of dates and times, which is applied when values are
+/// computed, so values of such variables are looked up by `name:spec`
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutputTemplate {
+    pieces: Vec<TemplatePiece>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TemplatePiece {
+    Text(String),
+    Variable { name: String, spec: Option<String> },
+}
+
+impl OutputTemplate {
+    /// parse template, with variables in braces
+    pub fn parse(template: &str) -> Result<OutputTemplate, TblError> {
+        let mut pieces = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                pieces.push(TemplatePiece::Text(rest[..start].to_string()));
+            }
+            let end = rest[start..].find('}').ok_or_else(|| {
+                TblError::Error(format!("unclosed variable in template: {}", template))
+            })? + start;
+            let variable = &rest[start + 1..end];
+            let (name, spec) = match variable.split_once(':') {
+                Some((name, spec)) => {
+                    if spec.parse::<usize>().is_err() && !is_time_spec(spec) {
+                        return Err(TblError::Error(format!(
+                            "invalid format spec of template variable: {}",
+                            variable
+                        )));
+                    }
+                    (name, Some(spec.to_string()))
+                }
+                None => (variable, None),
+            };
+            if name.is_empty() || name.contains('{') || name.contains('/') {
+                return Err(TblError::Error(format!(
+                    "invalid template variable: {{{}}}",
+                    variable
+                )));
+            }
+            pieces.push(TemplatePiece::Variable {
+                name: name.to_string(),
+                spec,
+            });
+            rest = &rest[end + 1..];
+        }
+        if rest.contains('}') {
+            return Err(TblError::Error(format!(
+                "unopened variable in template: {}",
+                template
+            )));
+        }
+        if !rest.is_empty() {
+            pieces.push(TemplatePiece::Text(rest.to_string()));
+        }
+        if pieces.is_empty() {
+            return Err(TblError::Error("template is empty".to_string()));
+        }
+        Ok(OutputTemplate { pieces })
+    }
+
+    /// whether template uses variable
+    pub fn uses(&self, variable: &str) -> bool {
+        self.pieces
+            .iter()
+            .any(|piece| matches!(piece, TemplatePiece::Variable { name, .. } if name == variable))
+    }
+
+    /// names and format specs of variables, in order of first use
+    pub fn variables(&self) -> Vec<(String, Option<String>)> {
+        let mut variables: Vec<(String, Option<String>)> = Vec::new();
+        for piece in self.pieces.iter() {
+            if let TemplatePiece::Variable { name, spec } = piece {
+                let variable = (name.clone(), spec.clone());
+                if !variables.contains(&variable) {
+                    variables.push(variable);
+                }
+            }
+        }
+        variables
+    }
+
+    /// render template with values of variables
+    ///
+    /// variables listed in deferred are kept as written when they have no value, so that they
+    /// can be rendered once their values are known
+    pub fn render(
+        &self,
+        values: &HashMap<String, String>,
+        deferred: &[&str],
+    ) -> Result<String, TblError> {
+        let mut rendered = String::new();
+        for piece in self.pieces.iter() {
+            match piece {
+                TemplatePiece::Text(text) => rendered.push_str(text),
+                TemplatePiece::Variable { name, spec } => {
+                    match values.get(&value_key(name, spec)) {
+                        Some(value) => rendered.push_str(&format_value(value, spec.as_deref())),
+                        None if deferred.contains(&name.as_str()) => {
+                            rendered.push('{');
+                            rendered.push_str(name);
+                            if let Some(spec) = spec {
+                                rendered.push(':');
+                                rendered.push_str(spec);
+                            }
+                            rendered.push('}');
+                        }
+                        None => {
+                            return Err(TblError::Error(format!(
+                                "no value for template variable {{{}}}",
+                                name
+                            )))
+                        }
+                    }
+                }
+            }
+        }
+        Ok(rendered)
+    }
+}
+
+/// whether format spec is a strftime format
+fn is_time_spec(spec: &str) -> bool {
+    spec.contains('%')
+}
+
+/// key of variable in values, which includes strftime specs
+fn value_key(name: &str, spec: &Option<String>) -> String {
+    match spec {
+        Some(spec) if is_time_spec(spec) => format!("{}:{}", name, spec),
+        _ => name.to_string(),
+    }
+}
+
+fn format_value(value: &str, spec: Option<&str>) -> String {
+    match spec {
+        Some(spec) if is_time_spec(spec) => value.to_string(),
+        Some(spec) if spec.starts_with('0') => {
+            let width = spec.parse::<usize>().unwrap_or_default();
+            format!("{:0>width$}", value, width = width)
+        }
+        Some(spec) => {
+            let width = spec.parse::<usize>().unwrap_or(usize::MAX);
+            value.chars().take(width).collect()
+        }
+        None => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_output_template() {
+        let template =
+            OutputTemplate::parse("{stem}_{chain_id}_{part:04}_{hash:4}.parquet").expect("parse");
+        let mut values: HashMap<String, String> = [("stem", "blocks"), ("chain_id", "1")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        values.insert("part".to_string(), "7".to_string());
+        assert_eq!(
+            template.render(&values, &["hash"]).expect("render"),
+            "blocks_1_0007_{hash:4}.parquet"
+        );
+        assert!(template.render(&values, &[]).is_err());
+        values.insert("hash".to_string(), "abcdef".to_string());
+        assert_eq!(
+            template.render(&values, &[]).expect("render"),
+            "blocks_1_0007_abcd.parquet"
+        );
+
+        // strftime specs are applied when values are computed
+        let template = OutputTemplate::parse("{min(ts):%Y-%m-%d}_{row_count}").expect("parse");
+        assert_eq!(
+            template.variables(),
+            vec![
+                ("min(ts)".to_string(), Some("%Y-%m-%d".to_string())),
+                ("row_count".to_string(), None)
+            ]
+        );
+        let values: HashMap<String, String> =
+            [("min(ts):%Y-%m-%d", "2024-01-02"), ("row_count", "5")]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+        assert_eq!(
+            template.render(&values, &[]).expect("render"),
+            "2024-01-02_5"
+        );
+        assert!(OutputTemplate::parse("{stem").is_err());
+        assert!(OutputTemplate::parse("{part:x}").is_err());
+    }
+}
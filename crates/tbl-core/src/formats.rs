@@ -1,4 +1,5 @@
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
+use std::sync::OnceLock;
 
 /// format bytes
 pub fn format_bytes(bytes: u64) -> String {
@@ -32,63 +33,115 @@ pub fn format_with_commas(number: u64) -> String {
     result.chars().rev().collect()
 }
 
-const TITLE_R: u8 = 0;
-const TITLE_G: u8 = 225;
-const TITLE_B: u8 = 0;
-const ERROR_R: u8 = 225;
-const ERROR_G: u8 = 0;
-const ERROR_B: u8 = 0;
+/// format float with up to 4 decimals, or in scientific notation if very large or small
+pub fn format_float(value: f64) -> String {
+    if value != 0.0 && (value.abs() >= 1e9 || value.abs() < 1e-3) {
+        format!("{:.3e}", value)
+    } else {
+        let formatted = format!("{:.4}", value);
+        formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    }
+}
+
+/// colors used when printing headers and bullets
+#[derive(Clone, Copy, Debug)]
+pub struct FormatColors {
+    /// color of header underlines and bullets
+    pub accent: (u8, u8, u8),
+    /// color of headers and keys
+    pub text: (u8, u8, u8),
+    /// color of values
+    pub muted: (u8, u8, u8),
+    /// color of error header underlines
+    pub error: (u8, u8, u8),
+}
+
+impl Default for FormatColors {
+    fn default() -> Self {
+        FormatColors {
+            accent: (0, 225, 0),
+            text: (255, 255, 255),
+            muted: (170, 170, 170),
+            error: (225, 0, 0),
+        }
+    }
+}
+
+static FORMAT_COLORS: OnceLock<FormatColors> = OnceLock::new();
+
+/// set colors used when printing, has no effect after colors are first used
+pub fn set_format_colors(colors: FormatColors) {
+    let _ = FORMAT_COLORS.set(colors);
+}
+
+/// enable or disable colored output regardless of terminal and environment
+pub fn set_color_enabled(enabled: bool) {
+    colored::control::set_override(enabled);
+}
+
+fn colors() -> &'static FormatColors {
+    FORMAT_COLORS.get_or_init(FormatColors::default)
+}
+
+trait Paint {
+    fn paint(&self, color: (u8, u8, u8)) -> ColoredString;
+}
+
+impl Paint for str {
+    fn paint(&self, (r, g, b): (u8, u8, u8)) -> ColoredString {
+        self.truecolor(r, g, b)
+    }
+}
 
 /// print header
 pub fn print_header<A: AsRef<str>>(header: A) {
-    let header_str = header.as_ref().white().bold();
-    let underline = "─"
-        .repeat(header_str.len())
-        .truecolor(TITLE_R, TITLE_G, TITLE_B);
+    let header_str = header.as_ref().paint(colors().text).bold();
+    let underline = "─".repeat(header_str.len()).paint(colors().accent);
     println!("{}", header_str);
     println!("{}", underline);
 }
 
 /// print header error
 pub fn print_header_error<A: AsRef<str>>(header: A) {
-    let header_str = header.as_ref().white().bold();
-    let underline = "─"
-        .repeat(header_str.len())
-        .truecolor(ERROR_R, ERROR_G, ERROR_B);
+    let header_str = header.as_ref().paint(colors().text).bold();
+    let underline = "─".repeat(header_str.len()).paint(colors().error);
     println!("{}", header_str);
     println!("{}", underline);
 }
 
 /// print bullet as `- key`
 pub fn print_bullet_key<A: AsRef<str>>(key: A) {
-    let bullet_str = "- ".truecolor(TITLE_R, TITLE_G, TITLE_B);
-    let key_str = key.as_ref().white().bold();
+    let bullet_str = "- ".paint(colors().accent);
+    let key_str = key.as_ref().paint(colors().text).bold();
     println!("{}{}", bullet_str, key_str);
 }
 
 /// print bullet as `- key: value`
 pub fn print_bullet<A: AsRef<str>, B: AsRef<str>>(key: A, value: B) {
-    let bullet_str = "- ".truecolor(TITLE_R, TITLE_G, TITLE_B);
-    let key_str = key.as_ref().white().bold();
-    let value_str = value.as_ref().truecolor(170, 170, 170);
-    let colon_str = ": ".truecolor(TITLE_R, TITLE_G, TITLE_B);
+    let bullet_str = "- ".paint(colors().accent);
+    let key_str = key.as_ref().paint(colors().text).bold();
+    let value_str = value.as_ref().paint(colors().muted);
+    let colon_str = ": ".paint(colors().accent);
     println!("{}{}{}{}", bullet_str, key_str, colon_str, value_str);
 }
 
 /// print bullet as `- key (value)`
 pub fn print_bullet_parenthetical<A: AsRef<str>, B: AsRef<str>>(key: A, value: B) {
-    let bullet_str = "- ".truecolor(TITLE_R, TITLE_G, TITLE_B);
-    let key_str = key.as_ref().white().bold();
-    let value_str = value.as_ref().truecolor(170, 170, 170);
+    let bullet_str = "- ".paint(colors().accent);
+    let key_str = key.as_ref().paint(colors().text).bold();
+    let value_str = value.as_ref().paint(colors().muted);
     println!("{}{} ({})", bullet_str, key_str, value_str);
 }
 
 /// print bullet as `    - key: value`
 pub fn print_bullet_indent<A: AsRef<str>, B: AsRef<str>>(key: A, value: B, indent: usize) {
-    let bullet_str = "- ".truecolor(TITLE_R, TITLE_G, TITLE_B);
-    let key_str = key.as_ref().white().bold();
-    let value_str = value.as_ref().truecolor(170, 170, 170);
-    let colon_str = ": ".truecolor(TITLE_R, TITLE_G, TITLE_B);
+    let bullet_str = "- ".paint(colors().accent);
+    let key_str = key.as_ref().paint(colors().text).bold();
+    let value_str = value.as_ref().paint(colors().muted);
+    let colon_str = ": ".paint(colors().accent);
     println!(
         "{}{}{}{}{}",
         " ".repeat(indent),
@@ -101,7 +154,7 @@ pub fn print_bullet_indent<A: AsRef<str>, B: AsRef<str>>(key: A, value: B, inden
 
 /// print bullet as `    - key`
 pub fn print_bullet_key_indent<A: AsRef<str>>(key: A, indent: usize) {
-    let bullet_str = "- ".truecolor(TITLE_R, TITLE_G, TITLE_B);
-    let key_str = key.as_ref().white().bold();
+    let bullet_str = "- ".paint(colors().accent);
+    let key_str = key.as_ref().paint(colors().text).bold();
     println!("{}{}{}", " ".repeat(indent), bullet_str, key_str,);
 }
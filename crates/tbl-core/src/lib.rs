@@ -14,10 +14,34 @@ pub mod filesystem;
 /// parquet utilities
 pub mod parquet;
 
+/// column name utilities
+pub mod names;
+
+/// pii detection
+pub mod pii;
+
+/// schema utilities
+pub mod schemas;
+
+/// sketches
+pub mod sketches;
+
 /// types
 pub mod types;
 
 /// formats
 pub mod formats;
 
+/// excel workbooks
+pub mod xlsx;
+
+/// per-file executor
+pub mod executor;
+
+/// cache of computed results
+pub mod cache;
+
 pub use types::*;
+
+// only used to enable a feature required by polars categorical types
+use hashbrown as _;
@@ -0,0 +1,140 @@
+use crate::TblError;
+
+/// naming convention of column names
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameCase {
+    /// words joined by the replacement, lowercased
+    Snake,
+    /// lowercased, invalid characters replaced
+    Lower,
+    /// uppercased, invalid characters replaced
+    Upper,
+    /// words joined without separator, each after the first capitalized
+    Camel,
+}
+
+impl std::str::FromStr for NameCase {
+    type Err = TblError;
+
+    fn from_str(s: &str) -> Result<NameCase, TblError> {
+        match s {
+            "snake" => Ok(NameCase::Snake),
+            "lower" => Ok(NameCase::Lower),
+            "upper" => Ok(NameCase::Upper),
+            "camel" => Ok(NameCase::Camel),
+            _ => Err(TblError::InputError(format!(
+                "invalid case {}, use snake, lower, upper, or camel",
+                s
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for NameCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            NameCase::Snake => "snake",
+            NameCase::Lower => "lower",
+            NameCase::Upper => "upper",
+            NameCase::Camel => "camel",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// split name into words at characters other than ascii letters and digits and at case changes
+///
+/// runs of capitals stay together, so `HTTPServer` splits into `HTTP` and `Server`
+fn split_words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        let previous = if i > 0 { chars.get(i - 1) } else { None };
+        let next = chars.get(i + 1);
+        let starts_word = c.is_ascii_uppercase()
+            && match previous {
+                Some(p) if p.is_ascii_lowercase() || p.is_ascii_digit() => true,
+                Some(p) if p.is_ascii_uppercase() => next.is_some_and(|n| n.is_ascii_lowercase()),
+                _ => false,
+            };
+        if starts_word && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(*c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// normalize column name to a naming convention
+///
+/// characters other than ascii letters and digits become the replacement, which also
+/// separates snake case words
+pub fn normalize_name(name: &str, case: NameCase, replacement: &str) -> String {
+    match case {
+        NameCase::Snake => split_words(name)
+            .iter()
+            .map(|word| word.to_lowercase())
+            .collect::<Vec<_>>()
+            .join(replacement),
+        NameCase::Camel => split_words(name)
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                let word = word.to_lowercase();
+                if i == 0 {
+                    word
+                } else {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                        None => word,
+                    }
+                }
+            })
+            .collect(),
+        NameCase::Lower | NameCase::Upper => {
+            let replaced = name
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join(replacement);
+            if case == NameCase::Lower {
+                replaced.to_lowercase()
+            } else {
+                replaced.to_uppercase()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_name() {
+        let snake = |name| normalize_name(name, NameCase::Snake, "_");
+        assert_eq!(snake("User Id"), "user_id");
+        assert_eq!(snake("userId"), "user_id");
+        assert_eq!(snake("HTTPServer"), "http_server");
+        assert_eq!(snake("  total (usd) "), "total_usd");
+        assert_eq!(snake("block_number"), "block_number");
+        assert_eq!(snake("value2Eth"), "value2_eth");
+        assert_eq!(normalize_name("User Id", NameCase::Camel, "_"), "userId");
+        assert_eq!(normalize_name("user-id", NameCase::Upper, "_"), "USER_ID");
+        assert_eq!(
+            normalize_name("Total (USD)", NameCase::Lower, ""),
+            "totalusd"
+        );
+    }
+}
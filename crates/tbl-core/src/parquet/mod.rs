@@ -14,6 +14,10 @@ pub use parquet_cast::*;
 pub mod parquet_merge;
 pub use parquet_merge::*;
 
+/// parquet column chunk splicing functions
+pub mod parquet_splice;
+pub use parquet_splice::*;
+
 /// parquet insert functions
 pub mod parquet_insert;
 pub use parquet_insert::*;
@@ -21,3 +25,51 @@ pub use parquet_insert::*;
 /// parquet parquet_scan
 pub mod parquet_scan;
 pub use parquet_scan::*;
+
+/// parquet value count functions
+pub mod parquet_value_counts;
+pub use parquet_value_counts::*;
+
+/// parquet schema fingerprint functions
+pub mod parquet_fingerprint;
+pub use parquet_fingerprint::*;
+
+/// parquet external sort functions
+pub mod parquet_sort;
+pub use parquet_sort::*;
+
+/// parquet column encryption functions
+pub mod parquet_encrypt;
+pub use parquet_encrypt::*;
+
+/// parquet quantile sketch functions
+pub mod parquet_quantiles;
+pub use parquet_quantiles::*;
+
+/// parquet column profile functions
+pub mod parquet_profile;
+pub use parquet_profile::*;
+
+/// parquet column liveness functions
+pub mod parquet_liveness;
+pub use parquet_liveness::*;
+
+/// parquet content hash functions
+pub mod parquet_content_hash;
+pub use parquet_content_hash::*;
+
+/// parquet index sidecar functions
+pub mod parquet_index;
+pub use parquet_index::*;
+
+/// parquet key range functions
+pub mod parquet_key_range;
+pub use parquet_key_range::*;
+
+/// parquet footer metadata functions
+pub mod parquet_footer;
+pub use parquet_footer::*;
+
+/// parquet legacy layout functions
+pub mod parquet_legacy;
+pub use parquet_legacy::*;
@@ -0,0 +1,144 @@
+use super::parquet_fingerprint::{fnv1a, FNV_OFFSET_BASIS};
+use crate::TblError;
+use arrow::row::{RowConverter, SortField};
+use futures::StreamExt;
+use parquet::arrow::async_reader::ParquetRecordBatchStreamBuilder;
+use std::path::Path;
+
+/// hash of the logical content of a parquet file
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ContentHash {
+    /// hex digest of schema and rows
+    pub hash: String,
+    /// number of rows hashed
+    pub n_rows: u64,
+}
+
+/// hash logical content of parquet file, independent of encoding, compression, and row order
+///
+/// each row is hashed from its arrow row encoding, and row hashes are summed so that files
+/// holding the same rows in a different order or row group layout hash equally
+pub async fn parquet_content_hash(path: &Path, batch_size: usize) -> Result<ContentHash, TblError> {
+    let file = tokio::fs::File::open(path).await?;
+    let builder = ParquetRecordBatchStreamBuilder::new(file)
+        .await?
+        .with_batch_size(batch_size);
+    let schema = builder.schema().clone();
+    let converter = RowConverter::new(
+        schema
+            .fields()
+            .iter()
+            .map(|field| SortField::new(field.data_type().clone()))
+            .collect(),
+    )?;
+
+    let mut schema_hash = FNV_OFFSET_BASIS;
+    for field in schema.fields().iter() {
+        let entry = format!("{}:{}\n", field.name(), field.data_type());
+        schema_hash = fnv1a(schema_hash, entry.as_bytes());
+    }
+
+    let mut rows_hash: u64 = 0;
+    let mut n_rows: u64 = 0;
+    let mut reader_stream = builder.build()?;
+    while let Some(batch) = reader_stream.next().await {
+        let rows = converter.convert_columns(batch?.columns())?;
+        for row in rows.iter() {
+            rows_hash = rows_hash.wrapping_add(fnv1a(FNV_OFFSET_BASIS, row.as_ref()));
+            n_rows += 1;
+        }
+    }
+
+    let mut hash = fnv1a(schema_hash, &rows_hash.to_le_bytes());
+    hash = fnv1a(hash, &n_rows.to_le_bytes());
+    Ok(ContentHash {
+        hash: format!("{:016x}", hash),
+        n_rows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn write_file(
+        dir: &TempDir,
+        name: &str,
+        column_name: &str,
+        rows: &[(i64, &str)],
+        row_group_size: usize,
+    ) -> Result<PathBuf, TblError> {
+        let path = dir.path().join(name);
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(column_name, DataType::Int64, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let ids = Int64Array::from(rows.iter().map(|(id, _)| *id).collect::<Vec<_>>());
+        let names = StringArray::from(rows.iter().map(|(_, name)| *name).collect::<Vec<_>>());
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(ids), Arc::new(names)])?;
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(row_group_size)
+            .build();
+        let mut writer = ArrowWriter::try_new(std::fs::File::create(&path)?, schema, Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(path)
+    }
+
+    const ROWS: [(i64, &str); 4] = [(1, "a"), (2, "b"), (3, "c"), (4, "d")];
+
+    #[tokio::test]
+    async fn test_hash_independent_of_row_order() -> Result<(), TblError> {
+        let dir = TempDir::new()?;
+        let mut reversed = ROWS;
+        reversed.reverse();
+        let path = write_file(&dir, "a.parquet", "id", &ROWS, 1024)?;
+        let reversed_path = write_file(&dir, "b.parquet", "id", &reversed, 1024)?;
+
+        let hash = parquet_content_hash(&path, 1024).await?;
+        assert_eq!(hash, parquet_content_hash(&reversed_path, 1024).await?);
+        assert_eq!(hash.n_rows, 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hash_independent_of_row_groups() -> Result<(), TblError> {
+        let dir = TempDir::new()?;
+        let path = write_file(&dir, "a.parquet", "id", &ROWS, 1024)?;
+        let split_path = write_file(&dir, "b.parquet", "id", &ROWS, 1)?;
+
+        let hash = parquet_content_hash(&path, 1024).await?;
+        assert_eq!(hash, parquet_content_hash(&split_path, 1024).await?);
+        assert_eq!(hash, parquet_content_hash(&split_path, 3).await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_different_files_hash_differently() -> Result<(), TblError> {
+        let dir = TempDir::new()?;
+        let path = write_file(&dir, "a.parquet", "id", &ROWS, 1024)?;
+        let changed_value = write_file(
+            &dir,
+            "b.parquet",
+            "id",
+            &[(1, "a"), (2, "b"), (3, "c"), (4, "e")],
+            1024,
+        )?;
+        let missing_row = write_file(&dir, "c.parquet", "id", &ROWS[..3], 1024)?;
+        let renamed_column = write_file(&dir, "d.parquet", "key", &ROWS, 1024)?;
+
+        let hash = parquet_content_hash(&path, 1024).await?.hash;
+        for other in [changed_value, missing_row, renamed_column] {
+            assert_ne!(hash, parquet_content_hash(&other, 1024).await?.hash);
+        }
+        Ok(())
+    }
+}
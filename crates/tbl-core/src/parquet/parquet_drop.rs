@@ -101,25 +101,19 @@ pub async fn drop_parquets_columns(
     batch_size: usize,
     max_concurrent: usize,
 ) -> Result<(), crate::TblError> {
-    let semaphore = Arc::new(tokio::sync::Semaphore::new(max_concurrent));
-
-    let results = futures::stream::iter(input_output_paths)
-        .map(|(input, output)| {
-            let columns_to_drop = columns_to_drop.clone();
-            let sem = Arc::clone(&semaphore);
-            async move {
-                let _permit = sem.acquire().await?;
-                drop_parquet_columns(input, output, columns_to_drop, batch_size).await
-            }
-        })
-        .buffer_unordered(max_concurrent)
-        .collect::<Vec<_>>()
-        .await;
-
-    // Check if any operations failed
-    for result in results {
-        result?;
-    }
-
+    let options = crate::executor::ExecutorOptions {
+        max_concurrent,
+        ..Default::default()
+    };
+    crate::executor::run_per_file(
+        input_output_paths,
+        &options,
+        &crate::executor::CancelToken::new(),
+        move |(input, output)| {
+            drop_parquet_columns(input, output, columns_to_drop.clone(), batch_size)
+        },
+        |_, result| result,
+    )
+    .await?;
     Ok(())
 }
@@ -0,0 +1,638 @@
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use arrow::array::{Array, ArrayRef, BinaryArray, BinaryBuilder, StringBuilder};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use futures::stream::StreamExt;
+use parquet::arrow::arrow_writer::ArrowWriter;
+use parquet::arrow::async_reader::ParquetRecordBatchStreamBuilder;
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::fs::File;
+
+use crate::TblError;
+
+/// algorithm used to encrypt column values
+pub const ENCRYPTION_ALGORITHM: &str = "AES-256-GCM";
+
+/// prefix of key-value metadata entries describing encrypted columns
+pub const ENCRYPTION_METADATA_PREFIX: &str = "tbl.encryption.";
+
+const NONCE_SIZE: usize = 12;
+
+/// encryption metadata stored for each encrypted column
+///
+/// values are authenticated with the random column id instead of the column name, so a renamed
+/// column can be decrypted once its metadata entry is moved to the new name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnEncryption {
+    /// encryption algorithm
+    pub algorithm: String,
+    /// identifier of the key used to encrypt the column
+    pub key_id: String,
+    /// arrow type of the column before encryption, such as "utf8" or "large_binary"
+    pub dtype: String,
+    /// random id used as associated data, none for files that authenticate the column name
+    pub column_id: Option<String>,
+}
+
+/// names under which string and binary arrow types are recorded in encryption metadata
+const ENCRYPTED_DTYPES: [(&str, DataType); 6] = [
+    ("utf8", DataType::Utf8),
+    ("large_utf8", DataType::LargeUtf8),
+    ("utf8_view", DataType::Utf8View),
+    ("binary", DataType::Binary),
+    ("large_binary", DataType::LargeBinary),
+    ("binary_view", DataType::BinaryView),
+];
+
+impl ColumnEncryption {
+    fn to_metadata_value(&self) -> String {
+        let mut value = format!(
+            "algorithm={};key_id={};dtype={}",
+            self.algorithm, self.key_id, self.dtype
+        );
+        if let Some(column_id) = &self.column_id {
+            value.push_str(&format!(";column_id={}", column_id));
+        }
+        value
+    }
+
+    /// arrow type of the column before encryption
+    fn original_dtype(&self, column: &str) -> Result<DataType, TblError> {
+        ENCRYPTED_DTYPES
+            .iter()
+            .find(|(name, _)| *name == self.dtype)
+            .map(|(_, dtype)| dtype.clone())
+            .ok_or_else(|| {
+                TblError::Error(format!(
+                    "column {} has unsupported encrypted dtype {}",
+                    column, self.dtype
+                ))
+            })
+    }
+
+    /// associated data authenticated with each value of column
+    fn aad<'a>(&'a self, column: &'a str) -> &'a [u8] {
+        self.column_id.as_deref().unwrap_or(column).as_bytes()
+    }
+
+    fn from_metadata_value(value: &str) -> Result<ColumnEncryption, TblError> {
+        let mut algorithm = None;
+        let mut key_id = None;
+        let mut dtype = None;
+        let mut column_id = None;
+        for entry in value.split(';') {
+            match entry.split_once('=') {
+                Some(("algorithm", v)) => algorithm = Some(v.to_string()),
+                Some(("key_id", v)) => key_id = Some(v.to_string()),
+                Some(("dtype", v)) => dtype = Some(v.to_string()),
+                Some(("column_id", v)) => column_id = Some(v.to_string()),
+                _ => {}
+            }
+        }
+        match (algorithm, key_id, dtype) {
+            (Some(algorithm), Some(key_id), Some(dtype)) => Ok(ColumnEncryption {
+                algorithm,
+                key_id,
+                dtype,
+                column_id,
+            }),
+            _ => Err(TblError::Error(format!(
+                "invalid encryption metadata: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// parse a 256-bit encryption key from a hex string
+pub fn parse_encryption_key(key: &str) -> Result<[u8; 32], TblError> {
+    let key = key.trim();
+    let key = key.strip_prefix("0x").unwrap_or(key);
+    let bytes = hex::decode(key)
+        .map_err(|_| TblError::InputError("encryption key must be hex encoded".to_string()))?;
+    bytes.try_into().map_err(|_| {
+        TblError::InputError("encryption key must be 32 bytes (64 hex characters)".to_string())
+    })
+}
+
+/// get encryption metadata of each encrypted column in parquet file
+pub async fn get_encrypted_columns(
+    path: &std::path::Path,
+) -> Result<Vec<(String, ColumnEncryption)>, TblError> {
+    let file = File::open(path).await?;
+    let builder = ParquetRecordBatchStreamBuilder::new(file).await?;
+    let key_values = builder
+        .metadata()
+        .file_metadata()
+        .key_value_metadata()
+        .cloned()
+        .unwrap_or_default();
+    parse_encrypted_columns(&key_values)
+}
+
+fn parse_encrypted_columns(
+    key_values: &[KeyValue],
+) -> Result<Vec<(String, ColumnEncryption)>, TblError> {
+    let mut columns = Vec::new();
+    for kv in key_values.iter() {
+        if let (Some(column), Some(value)) =
+            (kv.key.strip_prefix(ENCRYPTION_METADATA_PREFIX), &kv.value)
+        {
+            columns.push((
+                column.to_string(),
+                ColumnEncryption::from_metadata_value(value)?,
+            ));
+        }
+    }
+    Ok(columns)
+}
+
+/// encrypt columns of parquet file, replacing each value with nonce + ciphertext
+pub async fn encrypt_parquet_columns(
+    input_path: PathBuf,
+    output_path: PathBuf,
+    columns: Vec<String>,
+    key: [u8; 32],
+    key_id: String,
+    batch_size: usize,
+) -> Result<(), TblError> {
+    if key_id.contains(';') || key_id.contains('=') {
+        return Err(TblError::InputError(
+            "key id cannot contain ';' or '='".to_string(),
+        ));
+    }
+    let cipher = Aes256Gcm::new(&key.into());
+
+    let input_file = File::open(&input_path).await?;
+    let builder = ParquetRecordBatchStreamBuilder::new(input_file)
+        .await?
+        .with_batch_size(batch_size);
+    let mut key_values = get_key_values(&builder);
+    let already_encrypted = parse_encrypted_columns(&key_values)?;
+    let original_schema = builder.schema().clone();
+
+    // replace encrypted columns with binary columns
+    let mut fields = Vec::new();
+    let mut encryptions = Vec::new();
+    for column in columns.iter() {
+        if already_encrypted.iter().any(|(name, _)| name == column) {
+            return Err(TblError::InputError(format!(
+                "column {} is already encrypted",
+                column
+            )));
+        }
+        let field = original_schema.field_with_name(column).map_err(|_| {
+            TblError::SchemaError(format!("column {} not in {:?}", column, input_path))
+        })?;
+        let dtype = match ENCRYPTED_DTYPES
+            .iter()
+            .find(|(_, dtype)| dtype == field.data_type())
+        {
+            Some((name, _)) => name,
+            None => {
+                return Err(TblError::SchemaError(format!(
+                    "can only encrypt string or binary columns, {} has dtype {}",
+                    column,
+                    field.data_type()
+                )))
+            }
+        };
+        let mut column_id = [0u8; 16];
+        OsRng.fill_bytes(&mut column_id);
+        let encryption = ColumnEncryption {
+            algorithm: ENCRYPTION_ALGORITHM.to_string(),
+            key_id: key_id.clone(),
+            dtype: dtype.to_string(),
+            column_id: Some(hex::encode(column_id)),
+        };
+        key_values.push(KeyValue::new(
+            format!("{}{}", ENCRYPTION_METADATA_PREFIX, column),
+            encryption.to_metadata_value(),
+        ));
+        encryptions.push((column.clone(), encryption));
+    }
+    for field in original_schema.fields().iter() {
+        if columns.contains(field.name()) {
+            fields.push(rebuild_field(field, DataType::Binary));
+        } else {
+            fields.push(field.as_ref().clone());
+        }
+    }
+    let new_schema = Arc::new(Schema::new(fields));
+
+    let mut reader_stream = builder.build()?;
+    let writer_props = WriterProperties::builder()
+        .set_key_value_metadata(Some(key_values))
+        .build();
+    let tmp_output_path = super::parquet_drop::create_tmp_target(output_path.as_path());
+    // tmp file is removed if encryption fails partway
+    let result = async {
+        let mut arrow_writer = ArrowWriter::try_new(
+            BufWriter::new(std::fs::File::create(&tmp_output_path)?),
+            new_schema.clone(),
+            Some(writer_props),
+        )?;
+        while let Some(batch) = reader_stream.next().await {
+            let batch = batch?;
+            let mut new_columns = Vec::new();
+            for (field, array) in original_schema.fields().iter().zip(batch.columns()) {
+                match encryptions.iter().find(|(name, _)| name == field.name()) {
+                    Some((column, encryption)) => {
+                        let binary = cast(array, &DataType::Binary)?;
+                        let binary = binary
+                            .as_any()
+                            .downcast_ref::<BinaryArray>()
+                            .ok_or_else(|| TblError::Error("could not read column".to_string()))?;
+                        new_columns.push(encrypt_array(&cipher, binary, column, encryption)?);
+                    }
+                    None => new_columns.push(array.clone()),
+                }
+            }
+            arrow_writer.write(&RecordBatch::try_new(new_schema.clone(), new_columns)?)?;
+        }
+
+        arrow_writer.close()?;
+        Ok::<(), TblError>(())
+    }
+    .await;
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&tmp_output_path);
+        return Err(e);
+    }
+    std::fs::rename(tmp_output_path, output_path)?;
+    Ok(())
+}
+
+/// decrypt encrypted columns of parquet file, or all encrypted columns if none given
+pub async fn decrypt_parquet_columns(
+    input_path: PathBuf,
+    output_path: PathBuf,
+    columns: Option<Vec<String>>,
+    key: [u8; 32],
+    batch_size: usize,
+) -> Result<(), TblError> {
+    let cipher = Aes256Gcm::new(&key.into());
+
+    let input_file = File::open(&input_path).await?;
+    let builder = ParquetRecordBatchStreamBuilder::new(input_file)
+        .await?
+        .with_batch_size(batch_size);
+    let key_values = get_key_values(&builder);
+    let encrypted = parse_encrypted_columns(&key_values)?;
+    let original_schema = builder.schema().clone();
+
+    // select columns to decrypt
+    let to_decrypt: Vec<(String, ColumnEncryption)> = match &columns {
+        Some(columns) => {
+            let mut to_decrypt = Vec::new();
+            for column in columns.iter() {
+                match encrypted.iter().find(|(name, _)| name == column) {
+                    Some(entry) => to_decrypt.push(entry.clone()),
+                    None => {
+                        return Err(TblError::InputError(format!(
+                            "column {} is not encrypted in {:?}",
+                            column, input_path
+                        )))
+                    }
+                }
+            }
+            to_decrypt
+        }
+        None => encrypted,
+    };
+    for (column, encryption) in to_decrypt.iter() {
+        if encryption.algorithm != ENCRYPTION_ALGORITHM {
+            return Err(TblError::Error(format!(
+                "column {} uses unsupported algorithm {}",
+                column, encryption.algorithm
+            )));
+        }
+        encryption.original_dtype(column)?;
+    }
+
+    // restore original dtypes and drop metadata of decrypted columns
+    let key_values: Vec<KeyValue> = key_values
+        .into_iter()
+        .filter(|kv| match kv.key.strip_prefix(ENCRYPTION_METADATA_PREFIX) {
+            Some(column) => !to_decrypt.iter().any(|(name, _)| name == column),
+            None => true,
+        })
+        .collect();
+    let fields: Vec<Field> = original_schema
+        .fields()
+        .iter()
+        .map(
+            |field| match to_decrypt.iter().find(|(name, _)| name == field.name()) {
+                Some((column, encryption)) => {
+                    Ok(rebuild_field(field, encryption.original_dtype(column)?))
+                }
+                None => Ok(field.as_ref().clone()),
+            },
+        )
+        .collect::<Result<_, TblError>>()?;
+    let new_schema = Arc::new(Schema::new(fields));
+
+    let mut reader_stream = builder.build()?;
+    let writer_props = WriterProperties::builder()
+        .set_key_value_metadata(Some(key_values))
+        .build();
+    let tmp_output_path = super::parquet_drop::create_tmp_target(output_path.as_path());
+    // tmp file is removed if decryption fails partway, such as with the wrong key
+    let result = async {
+        let mut arrow_writer = ArrowWriter::try_new(
+            BufWriter::new(std::fs::File::create(&tmp_output_path)?),
+            new_schema.clone(),
+            Some(writer_props),
+        )?;
+        while let Some(batch) = reader_stream.next().await {
+            let batch = batch?;
+            let mut new_columns = Vec::new();
+            for (field, array) in original_schema.fields().iter().zip(batch.columns()) {
+                match to_decrypt.iter().find(|(name, _)| name == field.name()) {
+                    Some((column, encryption)) => {
+                        let binary = cast(array, &DataType::Binary)?;
+                        let binary = binary
+                            .as_any()
+                            .downcast_ref::<BinaryArray>()
+                            .ok_or_else(|| TblError::Error("could not read column".to_string()))?;
+                        new_columns.push(decrypt_array(&cipher, binary, column, encryption)?);
+                    }
+                    None => new_columns.push(array.clone()),
+                }
+            }
+            arrow_writer.write(&RecordBatch::try_new(new_schema.clone(), new_columns)?)?;
+        }
+
+        arrow_writer.close()?;
+        Ok::<(), TblError>(())
+    }
+    .await;
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&tmp_output_path);
+        return Err(e);
+    }
+    std::fs::rename(tmp_output_path, output_path)?;
+    Ok(())
+}
+
+/// field with a new dtype, keeping the name, nullability, and metadata of field
+fn rebuild_field(field: &Field, dtype: DataType) -> Field {
+    Field::new(field.name(), dtype, field.is_nullable()).with_metadata(field.metadata().clone())
+}
+
+fn get_key_values(builder: &ParquetRecordBatchStreamBuilder<File>) -> Vec<KeyValue> {
+    // the writer adds its own arrow schema entry
+    builder
+        .metadata()
+        .file_metadata()
+        .key_value_metadata()
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|kv| kv.key != parquet::arrow::ARROW_SCHEMA_META_KEY)
+        .collect()
+}
+
+fn encrypt_array(
+    cipher: &Aes256Gcm,
+    array: &BinaryArray,
+    column: &str,
+    encryption: &ColumnEncryption,
+) -> Result<ArrayRef, TblError> {
+    let mut builder = BinaryBuilder::new();
+    for value in array.iter() {
+        match value {
+            Some(value) => {
+                let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+                let payload = Payload {
+                    msg: value,
+                    aad: encryption.aad(column),
+                };
+                let ciphertext = cipher
+                    .encrypt(&nonce, payload)
+                    .map_err(|_| TblError::Error(format!("could not encrypt {}", column)))?;
+                let mut encrypted = nonce.to_vec();
+                encrypted.extend(ciphertext);
+                builder.append_value(encrypted);
+            }
+            None => builder.append_null(),
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+fn decrypt_array(
+    cipher: &Aes256Gcm,
+    array: &BinaryArray,
+    column: &str,
+    encryption: &ColumnEncryption,
+) -> Result<ArrayRef, TblError> {
+    let mut values = Vec::with_capacity(array.len());
+    for value in array.iter() {
+        match value {
+            Some(value) if value.len() >= NONCE_SIZE => {
+                let (nonce, ciphertext) = value.split_at(NONCE_SIZE);
+                let payload = Payload {
+                    msg: ciphertext,
+                    aad: encryption.aad(column),
+                };
+                let plaintext = cipher
+                    .decrypt(Nonce::from_slice(nonce), payload)
+                    .map_err(|_| {
+                        TblError::InputError(format!(
+                            "could not decrypt {}, wrong key? (column was encrypted with key id {})",
+                            column, encryption.key_id
+                        ))
+                    })?;
+                values.push(Some(plaintext));
+            }
+            Some(_) => {
+                return Err(TblError::Error(format!(
+                    "invalid encrypted value in {}",
+                    column
+                )))
+            }
+            None => values.push(None),
+        }
+    }
+
+    let dtype = encryption.original_dtype(column)?;
+    let decrypted: ArrayRef = if matches!(
+        dtype,
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+    ) {
+        let mut builder = StringBuilder::new();
+        for value in values.into_iter() {
+            match value {
+                Some(value) => {
+                    let value = String::from_utf8(value).map_err(|_| {
+                        TblError::Error(format!("decrypted {} is not valid utf8", column))
+                    })?;
+                    builder.append_value(value);
+                }
+                None => builder.append_null(),
+            }
+        }
+        Arc::new(builder.finish())
+    } else {
+        Arc::new(BinaryArray::from_iter(values))
+    };
+    Ok(cast(&decrypted, &dtype)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, StringArray};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use tempfile::TempDir;
+
+    fn read_column(path: &std::path::Path, index: usize) -> Result<ArrayRef, TblError> {
+        let reader =
+            ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(path)?)?.build()?;
+        let batches = reader.collect::<Result<Vec<_>, _>>()?;
+        Ok(batches[0].column(index).clone())
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt_roundtrip() -> Result<(), TblError> {
+        let temp_dir = TempDir::new()?;
+        let plain = temp_dir.path().join("plain.parquet");
+        let encrypted = temp_dir.path().join("encrypted.parquet");
+        let decrypted = temp_dir.path().join("decrypted.parquet");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("ssn", DataType::Utf8, true),
+            Field::new("name", DataType::Utf8, false).with_metadata(
+                [("comment".to_string(), "legal name".to_string())]
+                    .into_iter()
+                    .collect(),
+            ),
+        ]));
+        let ssn = StringArray::from(vec![Some("123-45-6789"), None, Some("987-65-4321")]);
+        let name = StringArray::from(vec!["a", "b", "c"]);
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3])),
+                Arc::new(ssn.clone()),
+                Arc::new(name.clone()),
+            ],
+        )?;
+        let mut writer =
+            ArrowWriter::try_new(std::fs::File::create(&plain)?, schema.clone(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let key = [7u8; 32];
+        let columns = vec!["ssn".to_string(), "name".to_string()];
+        encrypt_parquet_columns(plain, encrypted.clone(), columns, key, "k1".to_string(), 2)
+            .await?;
+        let metadata = get_encrypted_columns(&encrypted).await?;
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata[0].0, "ssn");
+        assert_eq!(metadata[0].1.key_id, "k1");
+        assert_eq!(read_column(&encrypted, 1)?.data_type(), &DataType::Binary);
+
+        let wrong_key = [8u8; 32];
+        let result =
+            decrypt_parquet_columns(encrypted.clone(), decrypted.clone(), None, wrong_key, 2).await;
+        assert!(result.is_err());
+        assert!(!decrypted.exists());
+        let tmp_files: Vec<_> = std::fs::read_dir(temp_dir.path())?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .ends_with("_tmp.parquet")
+            })
+            .collect();
+        assert!(tmp_files.is_empty());
+
+        decrypt_parquet_columns(encrypted, decrypted.clone(), None, key, 2).await?;
+        assert!(get_encrypted_columns(&decrypted).await?.is_empty());
+        let column = read_column(&decrypted, 1)?;
+        let column = column.as_any().downcast_ref::<StringArray>().expect("utf8");
+        assert_eq!(column, &ssn);
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&decrypted)?)?;
+        assert_eq!(reader.schema().fields(), schema.fields());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_restores_type_of_renamed_column() -> Result<(), TblError> {
+        let temp_dir = TempDir::new()?;
+        let plain = temp_dir.path().join("plain.parquet");
+        let encrypted = temp_dir.path().join("encrypted.parquet");
+        let renamed = temp_dir.path().join("renamed.parquet");
+        let decrypted = temp_dir.path().join("decrypted.parquet");
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "ssn",
+            DataType::LargeUtf8,
+            true,
+        )]));
+        let ssn: ArrayRef = Arc::new(arrow::array::LargeStringArray::from(vec![
+            Some("123-45-6789"),
+            None,
+        ]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![ssn.clone()])?;
+        let mut writer = ArrowWriter::try_new(std::fs::File::create(&plain)?, schema, None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let key = [7u8; 32];
+        let columns = vec!["ssn".to_string()];
+        encrypt_parquet_columns(plain, encrypted.clone(), columns, key, "k1".to_string(), 2)
+            .await?;
+
+        // rename the column, moving its encryption metadata to the new name
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&encrypted)?)?;
+        let key_values: Vec<KeyValue> = reader
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|kv| kv.key != parquet::arrow::ARROW_SCHEMA_META_KEY)
+            .map(|kv| match kv.key.strip_prefix(ENCRYPTION_METADATA_PREFIX) {
+                Some(_) => KeyValue::new(format!("{}tax_id", ENCRYPTION_METADATA_PREFIX), kv.value),
+                None => kv,
+            })
+            .collect();
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "tax_id",
+            DataType::Binary,
+            true,
+        )]));
+        let props = WriterProperties::builder()
+            .set_key_value_metadata(Some(key_values))
+            .build();
+        let mut writer = ArrowWriter::try_new(
+            std::fs::File::create(&renamed)?,
+            schema.clone(),
+            Some(props),
+        )?;
+        for batch in reader.build()? {
+            writer.write(&RecordBatch::try_new(
+                schema.clone(),
+                batch?.columns().to_vec(),
+            )?)?;
+        }
+        writer.close()?;
+
+        decrypt_parquet_columns(renamed, decrypted.clone(), None, key, 2).await?;
+        let column = read_column(&decrypted, 0)?;
+        assert_eq!(column.data_type(), &DataType::LargeUtf8);
+        assert_eq!(&column, &ssn);
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+use polars::prelude::*;
+
+/// initial value of 64-bit FNV-1a hashes
+pub const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// extend 64-bit FNV-1a hash with bytes
+pub fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for byte in bytes.iter() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// compute short stable hash of schema, identical across machines and versions
+///
+/// hashes the ordered column names and dtypes using 64-bit FNV-1a, with dtypes encoded by
+/// canonical_dtype rather than by polars, whose dtype names change between releases
+pub fn schema_fingerprint(schema: &Schema) -> String {
+    let mut hash = FNV_OFFSET_BASIS;
+    for (name, dtype) in schema.iter() {
+        hash = fnv1a(
+            hash,
+            format!("{}:{}\n", name, canonical_dtype(dtype)).as_bytes(),
+        );
+    }
+    format!("{:016x}", hash)[..12].to_string()
+}
+
+/// encode dtype as a string that only changes when the dtype does
+pub fn canonical_dtype(dtype: &DataType) -> String {
+    let time_unit = |unit: &TimeUnit| match unit {
+        TimeUnit::Nanoseconds => "ns",
+        TimeUnit::Microseconds => "us",
+        TimeUnit::Milliseconds => "ms",
+    };
+    match dtype {
+        DataType::Boolean => "bool".to_string(),
+        DataType::UInt8 => "u8".to_string(),
+        DataType::UInt16 => "u16".to_string(),
+        DataType::UInt32 => "u32".to_string(),
+        DataType::UInt64 => "u64".to_string(),
+        DataType::Int8 => "i8".to_string(),
+        DataType::Int16 => "i16".to_string(),
+        DataType::Int32 => "i32".to_string(),
+        DataType::Int64 => "i64".to_string(),
+        DataType::Float32 => "f32".to_string(),
+        DataType::Float64 => "f64".to_string(),
+        DataType::Decimal(precision, scale) => format!(
+            "decimal({},{})",
+            precision.map_or("-".to_string(), |p| p.to_string()),
+            scale.map_or("-".to_string(), |s| s.to_string())
+        ),
+        DataType::String => "str".to_string(),
+        DataType::Binary => "binary".to_string(),
+        DataType::BinaryOffset => "binary_offset".to_string(),
+        DataType::Date => "date".to_string(),
+        DataType::Datetime(unit, time_zone) => match time_zone {
+            Some(time_zone) => format!("datetime({},{})", time_unit(unit), time_zone),
+            None => format!("datetime({})", time_unit(unit)),
+        },
+        DataType::Duration(unit) => format!("duration({})", time_unit(unit)),
+        DataType::Time => "time".to_string(),
+        DataType::List(inner) => format!("list({})", canonical_dtype(inner)),
+        DataType::Null => "null".to_string(),
+        DataType::Categorical(_, _) => "categorical".to_string(),
+        DataType::Enum(_, _) => "enum".to_string(),
+        DataType::Struct(fields) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|field| format!("{}:{}", field.name(), canonical_dtype(field.data_type())))
+                .collect();
+            format!("struct({})", fields.join(","))
+        }
+        dtype => format!("other({})", dtype),
+    }
+}
+
+/// compute hash of parquet file from its size and raw footer, without reading data pages
+///
+/// footers record the offsets, sizes, and statistics of every column chunk, so rewriting a
+/// file changes its footer hash while renaming or copying it does not
+pub fn parquet_footer_fingerprint(path: &std::path::Path) -> Result<String, crate::TblError> {
+    use std::io::{Read, Seek, SeekFrom};
+    let mut file = std::fs::File::open(path)?;
+    let n_bytes = file.metadata()?.len();
+    if n_bytes < 12 {
+        return Err(crate::TblError::Error(format!(
+            "{} is too small to be a parquet file",
+            path.to_string_lossy()
+        )));
+    }
+    let mut footer = [0u8; 8];
+    file.seek(SeekFrom::End(-8))?;
+    file.read_exact(&mut footer)?;
+    let metadata_len = u32::from_le_bytes([footer[0], footer[1], footer[2], footer[3]]) as u64;
+    let metadata_len = metadata_len.min(n_bytes - 8);
+    let mut metadata = vec![0u8; metadata_len as usize];
+    file.seek(SeekFrom::Start(n_bytes - 8 - metadata_len))?;
+    file.read_exact(&mut metadata)?;
+
+    let mut hash = fnv1a(FNV_OFFSET_BASIS, &n_bytes.to_le_bytes());
+    hash = fnv1a(hash, &metadata);
+    Ok(format!("{:016x}", hash))
+}
+
+/// compute hash of the raw bytes of a file, changing whenever any byte of the file changes
+pub fn file_content_fingerprint(path: &std::path::Path) -> Result<String, crate::TblError> {
+    use std::io::Read;
+    let mut file = std::fs::File::open(path)?;
+    let mut buffer = vec![0u8; 1 << 20];
+    let mut hash = FNV_OFFSET_BASIS;
+    loop {
+        let n_read = file.read(&mut buffer)?;
+        if n_read == 0 {
+            break;
+        }
+        hash = fnv1a(hash, &buffer[..n_read]);
+    }
+    Ok(format!("{:016x}", hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_fingerprint() {
+        let schema = Schema::from_iter(vec![
+            Field::new("a", DataType::Int64),
+            Field::new("b", DataType::Binary),
+        ]);
+        let reordered = Schema::from_iter(vec![
+            Field::new("b", DataType::Binary),
+            Field::new("a", DataType::Int64),
+        ]);
+        assert_eq!(
+            schema_fingerprint(&schema),
+            schema_fingerprint(&schema.clone())
+        );
+        assert_ne!(schema_fingerprint(&schema), schema_fingerprint(&reordered));
+        assert_eq!(schema_fingerprint(&schema).len(), 12);
+        // pinned so that changes to the encoding are noticed
+        assert_eq!(schema_fingerprint(&schema), "718d2e58c0ec");
+    }
+
+    #[test]
+    fn test_canonical_dtype() {
+        assert_eq!(canonical_dtype(&DataType::String), "str");
+        assert_eq!(
+            canonical_dtype(&DataType::Datetime(
+                TimeUnit::Microseconds,
+                Some("UTC".to_string())
+            )),
+            "datetime(us,UTC)"
+        );
+        assert_eq!(
+            canonical_dtype(&DataType::List(Box::new(DataType::Int64))),
+            "list(i64)"
+        );
+        assert_eq!(
+            canonical_dtype(&DataType::Struct(vec![
+                Field::new("a", DataType::UInt8),
+                Field::new("b", DataType::Date),
+            ])),
+            "struct(a:u8,b:date)"
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use crate::TblError;
+use parquet::errors::ParquetError;
+use parquet::format::{FileMetaData, KeyValue};
+use parquet::thrift::TSerializable;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use thrift::protocol::{TCompactInputProtocol, TCompactOutputProtocol};
+
+const PARQUET_MAGIC: &[u8; 4] = b"PAR1";
+
+/// edits of the footer metadata of a parquet file
+#[derive(Clone, Debug, Default)]
+pub struct FooterEdits {
+    /// new created_by string
+    pub created_by: Option<String>,
+    /// key value pairs to add or replace
+    pub set: Vec<(String, String)>,
+    /// keys to remove
+    pub remove: Vec<String>,
+}
+
+/// rewrite the footer metadata of parquet file, keeping its data pages byte for byte
+///
+/// the edited file is written next to the original and then renamed over it
+pub fn touch_parquet_metadata(path: &Path, edits: &FooterEdits) -> Result<(), TblError> {
+    let mut file = std::fs::File::open(path)?;
+    let (footer_start, mut metadata) = read_footer(&mut file, path)?;
+    let footer = encode_footer(&mut metadata, edits)?;
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tbl_tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    let result = (|| -> Result<(), TblError> {
+        let mut tmp = std::fs::File::create(&tmp_path)?;
+        file.seek(SeekFrom::Start(0))?;
+        std::io::copy(&mut (&mut file).take(footer_start), &mut tmp)?;
+        tmp.write_all(&footer)?;
+        tmp.sync_all()?;
+        Ok(())
+    })();
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// rewrite the footer metadata of parquet file in place, replacing its old footer
+///
+/// only for files that readers cannot see yet, such as outputs before they are moved into place,
+/// since the file is briefly without a valid footer
+pub fn edit_unpublished_parquet_metadata(path: &Path, edits: &FooterEdits) -> Result<(), TblError> {
+    let mut file = std::fs::File::options().read(true).write(true).open(path)?;
+    let (footer_start, mut metadata) = read_footer(&mut file, path)?;
+    let footer = encode_footer(&mut metadata, edits)?;
+    file.set_len(footer_start)?;
+    file.seek(SeekFrom::Start(footer_start))?;
+    file.write_all(&footer)?;
+    Ok(())
+}
+
+/// apply edits to footer metadata, then encode it with its length and magic bytes
+fn encode_footer(metadata: &mut FileMetaData, edits: &FooterEdits) -> Result<Vec<u8>, TblError> {
+    if let Some(created_by) = &edits.created_by {
+        metadata.created_by = Some(created_by.clone());
+    }
+    let mut key_values = metadata.key_value_metadata.take().unwrap_or_default();
+    key_values.retain(|kv| !edits.remove.contains(&kv.key));
+    for (key, value) in edits.set.iter() {
+        match key_values.iter_mut().find(|kv| &kv.key == key) {
+            Some(kv) => kv.value = Some(value.clone()),
+            None => key_values.push(KeyValue::new(key.clone(), value.clone())),
+        }
+    }
+    metadata.key_value_metadata = if key_values.is_empty() {
+        None
+    } else {
+        Some(key_values)
+    };
+
+    let mut footer = Vec::new();
+    metadata
+        .write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut footer))
+        .map_err(ParquetError::from)?;
+    let length = footer.len() as u32;
+    footer.extend_from_slice(&length.to_le_bytes());
+    footer.extend_from_slice(PARQUET_MAGIC);
+    Ok(footer)
+}
+
+/// whether statistics of each column chunk record a null count, by row group and leaf column
+///
+/// decoded statistics report a missing null count as zero, so presence is read from the footer
+pub(crate) fn get_null_count_presence(path: &Path) -> Result<Vec<Vec<bool>>, TblError> {
+    let (_, metadata) = read_footer(&mut std::fs::File::open(path)?, path)?;
+    Ok(metadata
+        .row_groups
+        .iter()
+        .map(|rg| {
+            rg.columns
+                .iter()
+                .map(|column| {
+                    column
+                        .meta_data
+                        .as_ref()
+                        .and_then(|meta| meta.statistics.as_ref())
+                        .is_some_and(|statistics| statistics.null_count.is_some())
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// offset where the footer of parquet file starts, and its decoded metadata
+fn read_footer(file: &mut std::fs::File, path: &Path) -> Result<(u64, FileMetaData), TblError> {
+    let invalid =
+        |reason: &str| TblError::InputError(format!("{}: {}", path.to_string_lossy(), reason));
+    let file_len = file.metadata()?.len();
+    if file_len < 12 {
+        return Err(invalid("file is too small to be parquet"));
+    }
+    let mut tail = [0u8; 8];
+    file.seek(SeekFrom::End(-8))?;
+    file.read_exact(&mut tail)?;
+    match &tail[4..] {
+        magic if magic == PARQUET_MAGIC => {}
+        b"PARE" => return Err(invalid("encrypted footers cannot be edited")),
+        _ => return Err(invalid("file does not end with parquet magic bytes")),
+    }
+    let footer_len = u32::from_le_bytes([tail[0], tail[1], tail[2], tail[3]]) as u64;
+    if footer_len + 12 > file_len {
+        return Err(invalid("footer length exceeds file size"));
+    }
+    let footer_start = file_len - 8 - footer_len;
+    let mut footer = vec![0u8; footer_len as usize];
+    file.seek(SeekFrom::Start(footer_start))?;
+    file.read_exact(&mut footer)?;
+    let metadata =
+        FileMetaData::read_from_in_protocol(&mut TCompactInputProtocol::new(footer.as_slice()))
+            .map_err(ParquetError::from)?;
+    Ok((footer_start, metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_touch_parquet_metadata() -> Result<(), TblError> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("data.parquet");
+        let mut df = df!("block" => [7u32, 3, 9], "name" => ["b", "a", "c"])?;
+        ParquetWriter::new(std::fs::File::create(&path)?).finish(&mut df)?;
+        let before = std::fs::read(&path)?;
+        let (footer_start, _) = read_footer(&mut std::fs::File::open(&path)?, &path)?;
+
+        let edits = FooterEdits {
+            created_by: Some("migration v2".to_string()),
+            set: vec![("source".to_string(), "chain".to_string())],
+            remove: vec![],
+        };
+        touch_parquet_metadata(&path, &edits)?;
+
+        let after = std::fs::read(&path)?;
+        let footer_start = footer_start as usize;
+        assert_eq!(before[..footer_start], after[..footer_start]);
+        let metadata = super::super::get_parquet_metadata(&path).await?;
+        let file_metadata = metadata.file_metadata();
+        assert_eq!(file_metadata.created_by(), Some("migration v2"));
+        let key_values = file_metadata.key_value_metadata().expect("key values");
+        assert!(key_values
+            .iter()
+            .any(|kv| kv.key == "source" && kv.value.as_deref() == Some("chain")));
+        let read = ParquetReader::new(std::fs::File::open(&path)?).finish()?;
+        assert!(read.equals(&df));
+
+        // in place edits replace the footer without copying data pages
+        let edits = FooterEdits {
+            set: vec![("source".to_string(), "lake".to_string())],
+            ..Default::default()
+        };
+        edit_unpublished_parquet_metadata(&path, &edits)?;
+        let edited = std::fs::read(&path)?;
+        assert_eq!(before[..footer_start], edited[..footer_start]);
+        let metadata = super::super::get_parquet_metadata(&path).await?;
+        let key_values = metadata.file_metadata().key_value_metadata().expect("kv");
+        assert!(key_values
+            .iter()
+            .any(|kv| kv.key == "source" && kv.value.as_deref() == Some("lake")));
+        let read = ParquetReader::new(std::fs::File::open(&path)?).finish()?;
+        assert!(read.equals(&df));
+        Ok(())
+    }
+}
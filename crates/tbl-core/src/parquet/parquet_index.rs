@@ -0,0 +1,207 @@
+use crate::TblError;
+use polars::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// extension appended to a parquet path to name its index sidecar
+pub const INDEX_EXTENSION: &str = "tblindex";
+
+/// summary of a parquet file stored in a sidecar, readable without opening the file footer
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParquetIndex {
+    /// size of the indexed file, used to detect files rewritten after indexing
+    pub n_bytes: u64,
+    /// number of rows
+    pub n_rows: u64,
+    /// fingerprint of schema
+    pub schema_fingerprint: String,
+    /// range of key column
+    pub key: Option<KeyRange>,
+}
+
+/// inclusive range of values of a key column, as displayed by polars
+///
+/// floats are written in their shortest round trip form, so that bounds are exact
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyRange {
+    /// name of key column
+    pub column: String,
+    /// dtype of key column
+    pub dtype: String,
+    /// smallest non-null value, none if all values are null or the value cannot be stored
+    pub min: Option<String>,
+    /// largest non-null value, none if all values are null or the value cannot be stored
+    pub max: Option<String>,
+}
+
+/// path of index sidecar of parquet file
+pub fn index_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(INDEX_EXTENSION);
+    PathBuf::from(name)
+}
+
+impl ParquetIndex {
+    fn to_text(&self) -> String {
+        let mut lines = vec![
+            format!("n_bytes={}", self.n_bytes),
+            format!("n_rows={}", self.n_rows),
+            format!("schema={}", self.schema_fingerprint),
+        ];
+        if let Some(key) = &self.key {
+            lines.push(format!("key={}", key.column));
+            lines.push(format!("key_dtype={}", key.dtype));
+            if let (Some(min), Some(max)) = (&key.min, &key.max) {
+                lines.push(format!("min={}", min));
+                lines.push(format!("max={}", max));
+            }
+        }
+        lines.join("\n") + "\n"
+    }
+
+    fn from_text(text: &str) -> Option<ParquetIndex> {
+        let mut fields = std::collections::HashMap::new();
+        for line in text.lines() {
+            let (name, value) = line.split_once('=')?;
+            fields.insert(name, value);
+        }
+        let key = match fields.get("key") {
+            Some(column) => Some(KeyRange {
+                column: column.to_string(),
+                dtype: fields.get("key_dtype")?.to_string(),
+                min: fields.get("min").map(|value| value.to_string()),
+                max: fields.get("max").map(|value| value.to_string()),
+            }),
+            None => None,
+        };
+        Some(ParquetIndex {
+            n_bytes: fields.get("n_bytes")?.parse().ok()?,
+            n_rows: fields.get("n_rows")?.parse().ok()?,
+            schema_fingerprint: fields.get("schema")?.to_string(),
+            key,
+        })
+    }
+}
+
+/// write index sidecar of parquet file, including the range of key column if given
+pub async fn write_parquet_index(path: &Path, key: Option<&str>) -> Result<ParquetIndex, TblError> {
+    let summary = super::get_parquet_summary(path).await?;
+    let key = match key {
+        Some(column) => {
+            let dtype = summary.schema.get(column).ok_or_else(|| {
+                TblError::SchemaError(format!(
+                    "index key {} not in {}",
+                    column,
+                    path.to_string_lossy()
+                ))
+            })?;
+            let df = super::create_lazyframe(&[path.to_path_buf()])?
+                .select([
+                    col(column).min().alias("min"),
+                    col(column).max().alias("max"),
+                ])
+                .collect()?;
+            let display = |name: &str| -> Result<Option<String>, TblError> {
+                let value = df.column(name)?.get(0)?;
+                let value = match value {
+                    AnyValue::Null => None,
+                    AnyValue::String(value) => Some(value.to_string()),
+                    // polars displays floats with 6 significant digits
+                    AnyValue::Float32(value) => Some(format!("{:?}", value as f64)),
+                    AnyValue::Float64(value) => Some(format!("{:?}", value)),
+                    value => Some(value.to_string()),
+                };
+                // values spanning lines cannot be stored in the line based format
+                Ok(value.filter(|value| !value.contains('\n')))
+            };
+            Some(KeyRange {
+                column: column.to_string(),
+                dtype: dtype.to_string(),
+                min: display("min")?,
+                max: display("max")?,
+            })
+        }
+        None => None,
+    };
+    let index = ParquetIndex {
+        n_bytes: std::fs::metadata(path)?.len(),
+        n_rows: summary.n_rows,
+        schema_fingerprint: super::schema_fingerprint(&summary.schema),
+        key,
+    };
+    std::fs::write(index_path(path), index.to_text())?;
+    Ok(index)
+}
+
+/// read index sidecar of parquet file, if one exists and is not older than the file
+pub fn read_parquet_index(path: &Path) -> Option<ParquetIndex> {
+    let sidecar = index_path(path);
+    let file_metadata = std::fs::metadata(path).ok()?;
+    let sidecar_metadata = std::fs::metadata(&sidecar).ok()?;
+    if sidecar_metadata.modified().ok()? < file_metadata.modified().ok()? {
+        return None;
+    }
+    let index = ParquetIndex::from_text(&std::fs::read_to_string(sidecar).ok()?)?;
+    (index.n_bytes == file_metadata.len()).then_some(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_file(path: &Path, mut df: DataFrame) -> Result<(), TblError> {
+        ParquetWriter::new(std::fs::File::create(path)?).finish(&mut df)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_parquet_index() -> Result<(), TblError> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("data.parquet");
+        write_file(
+            &path,
+            df!("block" => [5i64, 3, 9], "name" => ["a", "b", "c"])?,
+        )?;
+        assert_eq!(read_parquet_index(&path), None);
+
+        let index = write_parquet_index(&path, Some("block")).await?;
+        assert_eq!(index.n_rows, 3);
+        let key = index.key.clone().expect("key range");
+        assert_eq!(key.min.as_deref(), Some("3"));
+        assert_eq!(key.max.as_deref(), Some("9"));
+        assert_eq!(read_parquet_index(&path), Some(index));
+
+        let index = write_parquet_index(&path, Some("name")).await?;
+        let key = index.key.expect("key range");
+        assert_eq!(key.min.as_deref(), Some("a"));
+        assert_eq!(key.max.as_deref(), Some("c"));
+
+        // rewriting the file invalidates its index
+        write_file(&path, df!("block" => [1i64])?)?;
+        assert_eq!(read_parquet_index(&path), None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_float_bounds_round_trip() -> Result<(), TblError> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("data.parquet");
+        write_file(
+            &path,
+            df!("f64" => [0.1234567891f64, 2.5], "f32" => [0.1f32, 1.0])?,
+        )?;
+        let key = write_parquet_index(&path, Some("f64"))
+            .await?
+            .key
+            .expect("key");
+        assert_eq!(key.min.as_deref(), Some("0.1234567891"));
+        let key = write_parquet_index(&path, Some("f32"))
+            .await?
+            .key
+            .expect("key");
+        let min: f64 = key.min.expect("min").parse().expect("float");
+        assert_eq!(min, 0.1f32 as f64);
+        Ok(())
+    }
+}
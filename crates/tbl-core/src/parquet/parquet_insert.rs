@@ -3,7 +3,6 @@ use arrow::array::{ArrayRef, StringArray};
 use arrow::array::{BinaryArray, BooleanArray, UInt32Array, UInt64Array};
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
-use futures::stream::{self};
 use futures::StreamExt;
 use hex;
 use parquet::arrow::arrow_writer::ArrowWriter;
@@ -14,7 +13,6 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
-use tokio::sync::Semaphore;
 
 /// insert columns into multiple parquet files
 #[allow(clippy::too_many_arguments)]
@@ -34,25 +32,28 @@ pub async fn insert_parquets_columns(
         ));
     }
 
-    let semaphore = Arc::new(Semaphore::new(max_concurrent));
-
-    let results = stream::iter(inputs.iter().zip(outputs.iter()))
-        .map(|(input, output)| {
-            let sem_clone = semaphore.clone();
+    let options = crate::executor::ExecutorOptions {
+        max_concurrent,
+        ..Default::default()
+    };
+    let jobs: Vec<(PathBuf, PathBuf)> = inputs
+        .iter()
+        .cloned()
+        .zip(outputs.iter().cloned())
+        .collect();
+    crate::executor::run_per_file(
+        jobs,
+        &options,
+        &crate::executor::CancelToken::new(),
+        move |(input, output)| {
             let column_names = column_names.clone();
             let column_dtypes = column_dtypes.clone();
             let default_values = default_values.clone();
             let index = index.clone();
-
             async move {
-                let _permit = sem_clone
-                    .acquire()
-                    .await
-                    .map_err(|e| TblError::Error(e.to_string()))?;
-
                 insert_parquet_columns(
-                    input,
-                    output,
+                    &input,
+                    &output,
                     column_names,
                     column_dtypes,
                     default_values,
@@ -61,16 +62,10 @@ pub async fn insert_parquets_columns(
                 )
                 .await
             }
-        })
-        .buffer_unordered(max_concurrent)
-        .collect::<Vec<_>>()
-        .await;
-
-    // Check if any of the operations resulted in an error
-    for result in results {
-        result?;
-    }
-
+        },
+        |_, result| result,
+    )
+    .await?;
     Ok(())
 }
 
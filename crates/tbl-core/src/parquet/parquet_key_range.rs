@@ -0,0 +1,204 @@
+use crate::TblError;
+use parquet::file::statistics::Statistics;
+use polars::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// min and max of a column
+pub type KeyBounds = (AnyValue<'static>, AnyValue<'static>);
+
+/// range of a column within a row group according to its statistics
+#[derive(Clone, Debug, PartialEq)]
+pub enum RowGroupRange {
+    /// row group has no non-null values
+    Empty,
+    /// min and max of non-null values
+    Bounds(KeyBounds),
+    /// statistics are missing or cannot be decoded
+    Unknown,
+}
+
+/// get range of a top-level column in each row group of a parquet file from statistics
+///
+/// returns none when the column is missing or nested
+pub async fn get_parquet_row_group_ranges(
+    path: &Path,
+    column: &str,
+) -> Result<Option<Vec<RowGroupRange>>, TblError> {
+    let schema = super::get_parquet_schema(path).await?;
+    let dtype = match schema.get(column) {
+        Some(dtype) if !dtype.is_nested() => dtype.clone(),
+        _ => return Ok(None),
+    };
+    let metadata = super::get_parquet_metadata(path).await?;
+    let index = metadata
+        .file_metadata()
+        .schema_descr()
+        .columns()
+        .iter()
+        .position(|leaf| leaf.path().parts() == [column.to_string()]);
+    let index = match index {
+        Some(index) => index,
+        None => return Ok(None),
+    };
+
+    let mut ranges = Vec::new();
+    for rg in metadata.row_groups() {
+        let statistics = rg.column(index).statistics();
+        let range = if rg.num_rows() == 0
+            || statistics.is_some_and(|s| s.null_count() == rg.num_rows() as u64)
+        {
+            RowGroupRange::Empty
+        } else {
+            match statistics.and_then(|statistics| decode_statistics(statistics, &dtype)) {
+                Some(bounds) => RowGroupRange::Bounds(bounds),
+                None => RowGroupRange::Unknown,
+            }
+        };
+        ranges.push(range);
+    }
+    Ok(Some(ranges))
+}
+
+/// get min and max of a top-level column in each parquet file from row group statistics
+///
+/// a file has no bounds when the column is missing or nested, when its values are all null,
+/// or when some row group lacks statistics of a dtype that can be decoded
+pub async fn get_parquet_key_ranges(
+    paths: &[PathBuf],
+    column: &str,
+) -> Result<Vec<Option<KeyBounds>>, TblError> {
+    let mut ranges = Vec::new();
+    for path in paths.iter() {
+        let row_group_ranges = get_parquet_row_group_ranges(path, column).await?;
+        let mut bounds: Option<KeyBounds> = None;
+        for range in row_group_ranges.iter().flatten() {
+            match range {
+                RowGroupRange::Empty => {}
+                RowGroupRange::Bounds((min, max)) => {
+                    bounds = Some(match bounds {
+                        Some((current_min, current_max)) => (
+                            if *min < current_min {
+                                min.clone()
+                            } else {
+                                current_min
+                            },
+                            if *max > current_max {
+                                max.clone()
+                            } else {
+                                current_max
+                            },
+                        ),
+                        None => (min.clone(), max.clone()),
+                    })
+                }
+                RowGroupRange::Unknown => {
+                    bounds = None;
+                    break;
+                }
+            }
+        }
+        ranges.push(bounds);
+    }
+    Ok(ranges)
+}
+
+/// decode min and max of row group statistics into values of dtype
+fn decode_statistics(statistics: &Statistics, dtype: &DataType) -> Option<KeyBounds> {
+    // inexact statistics of truncated byte arrays still bound the values
+    if !statistics.has_min_max_set() {
+        return None;
+    }
+    // build physical values, then cast them to the logical dtype of the column
+    let physical = match statistics {
+        Statistics::Boolean(s) => Series::new("", [*s.min(), *s.max()]),
+        Statistics::Int32(s) if dtype.is_unsigned_integer() => {
+            Series::new("", [*s.min() as u32, *s.max() as u32])
+        }
+        Statistics::Int32(s) if dtype.is_integer() || *dtype == DataType::Date => {
+            Series::new("", [*s.min(), *s.max()])
+        }
+        Statistics::Int64(s) if dtype.is_unsigned_integer() => {
+            Series::new("", [*s.min() as u64, *s.max() as u64])
+        }
+        Statistics::Int64(s) if dtype.is_integer() || dtype.is_temporal() => {
+            Series::new("", [*s.min(), *s.max()])
+        }
+        Statistics::Float(s) => Series::new("", [*s.min(), *s.max()]),
+        Statistics::Double(s) => Series::new("", [*s.min(), *s.max()]),
+        Statistics::ByteArray(s) if *dtype == DataType::String => Series::new(
+            "",
+            [
+                std::str::from_utf8(s.min().data()).ok()?,
+                std::str::from_utf8(s.max().data()).ok()?,
+            ],
+        ),
+        _ => return None,
+    };
+    let values = physical.cast(dtype).ok()?;
+    let min = into_static_value(values.get(0).ok()?).ok()?;
+    let max = into_static_value(values.get(1).ok()?).ok()?;
+    Some((min, max))
+}
+
+/// convert value into an owned value, dropping time zones of datetimes, which keeps their order
+pub fn into_static_value(value: AnyValue<'_>) -> Result<AnyValue<'static>, TblError> {
+    match value {
+        AnyValue::Datetime(value, time_unit, _) => Ok(AnyValue::Datetime(value, time_unit, &None)),
+        AnyValue::Duration(value, time_unit) => Ok(AnyValue::Duration(value, time_unit)),
+        value => Ok(value.into_static()?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_parquet_key_ranges() -> Result<(), TblError> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("data.parquet");
+        let mut df = df!(
+            "block" => [7u32, 3, 9],
+            "name" => ["b", "a", "c"],
+            "empty" => [None::<i64>, None, None],
+        )?;
+        df.with_column(
+            Series::new("ts", [2_000i64, 1_000, 3_000])
+                .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?,
+        )?;
+        ParquetWriter::new(std::fs::File::create(&path)?)
+            .with_statistics(StatisticsOptions::full())
+            .finish(&mut df)?;
+        let paths = vec![path];
+
+        let ranges = get_parquet_key_ranges(&paths, "block").await?;
+        assert_eq!(
+            ranges,
+            vec![Some((AnyValue::UInt32(3), AnyValue::UInt32(9)))]
+        );
+        let ranges = get_parquet_key_ranges(&paths, "name").await?;
+        assert_eq!(
+            ranges,
+            vec![Some((
+                AnyValue::StringOwned("a".into()),
+                AnyValue::StringOwned("c".into())
+            ))]
+        );
+        let ranges = get_parquet_key_ranges(&paths, "ts").await?;
+        assert_eq!(
+            ranges,
+            vec![Some((
+                AnyValue::Datetime(1_000, TimeUnit::Milliseconds, &None),
+                AnyValue::Datetime(3_000, TimeUnit::Milliseconds, &None)
+            ))]
+        );
+        assert_eq!(get_parquet_key_ranges(&paths, "empty").await?, vec![None]);
+        assert_eq!(
+            get_parquet_row_group_ranges(&paths[0], "empty").await?,
+            Some(vec![RowGroupRange::Empty])
+        );
+        assert_eq!(get_parquet_key_ranges(&paths, "missing").await?, vec![None]);
+        Ok(())
+    }
+}
@@ -0,0 +1,374 @@
+use crate::TblError;
+use arrow::array::{ArrayRef, AsArray};
+use arrow::datatypes::{DataType, Field, Int64Type, TimeUnit};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::arrow_writer::{compute_leaves, get_column_writers, ArrowColumnWriter};
+use parquet::arrow::{arrow_to_parquet_schema, ARROW_SCHEMA_META_KEY};
+use parquet::basic::{Compression, LogicalType, Type as PhysicalType};
+use parquet::data_type::{Int96, Int96Type};
+use parquet::file::properties::{EnabledStatistics, WriterProperties, WriterVersion};
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::types::{ColumnPath, SchemaDescriptor, Type};
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+/// physical layout of parquet files written for engines that predate newer encodings
+#[derive(Clone, Debug)]
+pub struct LegacyLayout {
+    /// store decimals as fixed-length byte arrays at their declared precision
+    pub fixed_decimals: bool,
+    /// store top-level timestamps as int96 julian day and nanosecond of day
+    pub int96_timestamps: bool,
+    /// compress data pages with snappy instead of zstd
+    pub snappy: bool,
+}
+
+/// rewrite parquet file into a layout that older engines can read
+///
+/// batches are streamed from input to output, holding at most one row group in memory, and
+/// data pages are always written in the version 1 format
+pub fn rewrite_parquet_legacy(
+    input_path: &Path,
+    output_path: &Path,
+    layout: &LegacyLayout,
+) -> Result<(), TblError> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(input_path)?)?;
+    let arrow_schema = builder.schema().clone();
+    // the arrow schema of the input describes the old physical types, so it is not copied
+    let key_values: Vec<_> = builder
+        .metadata()
+        .file_metadata()
+        .key_value_metadata()
+        .into_iter()
+        .flatten()
+        .filter(|kv| kv.key != ARROW_SCHEMA_META_KEY)
+        .cloned()
+        .collect();
+    let reader = builder.build()?;
+
+    let root = Arc::new(legacy_root(
+        arrow_to_parquet_schema(&arrow_schema)?.root_schema(),
+        arrow_schema.fields(),
+        layout,
+    )?);
+    let descriptor = SchemaDescriptor::new(root.clone());
+    let mut props = WriterProperties::builder().set_writer_version(WriterVersion::PARQUET_1_0);
+    for field in arrow_schema.fields() {
+        // engines that read int96 ignore its statistics, whose ordering is not defined
+        if is_int96(field, layout) {
+            props = props.set_column_statistics_enabled(
+                ColumnPath::from(field.name().as_str()),
+                EnabledStatistics::None,
+            );
+        }
+    }
+    let props = props
+        .set_compression(if layout.snappy {
+            Compression::SNAPPY
+        } else {
+            Compression::ZSTD(Default::default())
+        })
+        .set_key_value_metadata((!key_values.is_empty()).then_some(key_values))
+        .build();
+    let max_row_group_size = props.max_row_group_size();
+    let props = Arc::new(props);
+    let mut writer = SerializedFileWriter::new(File::create(output_path)?, root, props.clone())?;
+
+    let mut column_writers: Option<Vec<LeafWriter>> = None;
+    let mut n_buffered_rows = 0;
+    for batch in reader {
+        let batch = batch?;
+        let writers = match column_writers.as_mut() {
+            Some(writers) => writers,
+            None => column_writers.insert(leaf_writers(&descriptor, &props, &arrow_schema)?),
+        };
+        let mut writers = writers.iter_mut();
+        for (field, column) in arrow_schema.fields().iter().zip(batch.columns()) {
+            if is_int96(field, layout) {
+                match writers.next() {
+                    Some(LeafWriter::Int96 { values, def_levels }) => {
+                        buffer_int96(column, values, def_levels)?
+                    }
+                    _ => return Err(TblError::SchemaError("missing int96 writer".to_string())),
+                }
+                continue;
+            }
+            for leaf in compute_leaves(field, column)? {
+                match writers.next() {
+                    Some(LeafWriter::Arrow(writer)) => writer.write(&leaf)?,
+                    _ => return Err(TblError::SchemaError("too few column writers".to_string())),
+                }
+            }
+        }
+        n_buffered_rows += batch.num_rows();
+        if n_buffered_rows >= max_row_group_size {
+            if let Some(writers) = column_writers.take() {
+                write_row_group(&mut writer, writers)?;
+            }
+            n_buffered_rows = 0;
+        }
+    }
+    if let Some(writers) = column_writers.take() {
+        write_row_group(&mut writer, writers)?;
+    }
+    writer.close()?;
+    Ok(())
+}
+
+/// writer of one leaf column of a row group
+///
+/// the arrow writer cannot encode int96, so int96 columns are buffered and written directly
+enum LeafWriter {
+    Arrow(Box<ArrowColumnWriter>),
+    Int96 {
+        values: Vec<Int96>,
+        def_levels: Option<Vec<i16>>,
+    },
+}
+
+fn leaf_writers(
+    descriptor: &SchemaDescriptor,
+    props: &Arc<WriterProperties>,
+    arrow_schema: &arrow::datatypes::SchemaRef,
+) -> Result<Vec<LeafWriter>, TblError> {
+    let writers = get_column_writers(descriptor, props, arrow_schema)?;
+    Ok(writers
+        .into_iter()
+        .zip(descriptor.columns())
+        .map(|(writer, column)| match column.physical_type() {
+            PhysicalType::INT96 => LeafWriter::Int96 {
+                values: Vec::new(),
+                def_levels: (column.max_def_level() > 0).then(Vec::new),
+            },
+            _ => LeafWriter::Arrow(Box::new(writer)),
+        })
+        .collect())
+}
+
+fn write_row_group(
+    writer: &mut SerializedFileWriter<File>,
+    column_writers: Vec<LeafWriter>,
+) -> Result<(), TblError> {
+    let mut row_group = writer.next_row_group()?;
+    for column_writer in column_writers.into_iter() {
+        match column_writer {
+            LeafWriter::Arrow(column_writer) => {
+                column_writer.close()?.append_to_row_group(&mut row_group)?
+            }
+            LeafWriter::Int96 { values, def_levels } => {
+                let mut column = row_group
+                    .next_column()?
+                    .ok_or_else(|| TblError::SchemaError("missing int96 column".to_string()))?;
+                column
+                    .typed::<Int96Type>()
+                    .write_batch(&values, def_levels.as_deref(), None)?;
+                column.close()?;
+            }
+        }
+    }
+    row_group.close()?;
+    Ok(())
+}
+
+/// whether field is stored as int96 under layout
+fn is_int96(field: &Field, layout: &LegacyLayout) -> bool {
+    layout.int96_timestamps && matches!(field.data_type(), DataType::Timestamp(..))
+}
+
+/// append timestamps of column to buffered int96 values and definition levels
+fn buffer_int96(
+    column: &ArrayRef,
+    values: &mut Vec<Int96>,
+    def_levels: &mut Option<Vec<i16>>,
+) -> Result<(), TblError> {
+    const NANOS_PER_DAY: i128 = 86_400_000_000_000;
+    const JULIAN_DAY_OF_EPOCH: i128 = 2_440_588;
+    let nanos_per_unit: i128 = match column.data_type() {
+        DataType::Timestamp(TimeUnit::Second, _) => 1_000_000_000,
+        DataType::Timestamp(TimeUnit::Millisecond, _) => 1_000_000,
+        DataType::Timestamp(TimeUnit::Microsecond, _) => 1_000,
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => 1,
+        dtype => {
+            return Err(TblError::SchemaError(format!(
+                "cannot store {} as int96",
+                dtype
+            )))
+        }
+    };
+    // timestamps of every unit are stored as i64 counts of that unit
+    let counts = arrow::compute::cast(column, &DataType::Int64)?;
+    for count in counts.as_primitive::<Int64Type>().iter() {
+        if let Some(def_levels) = def_levels.as_mut() {
+            def_levels.push(count.is_some() as i16);
+        }
+        let Some(count) = count else { continue };
+        let nanos = count as i128 * nanos_per_unit;
+        let nanos_of_day = nanos.rem_euclid(NANOS_PER_DAY) as u64;
+        let julian_day = u32::try_from(nanos.div_euclid(NANOS_PER_DAY) + JULIAN_DAY_OF_EPOCH)
+            .map_err(|_| TblError::SchemaError("timestamp out of int96 range".to_string()))?;
+        let mut value = Int96::new();
+        value.set_data(nanos_of_day as u32, (nanos_of_day >> 32) as u32, julian_day);
+        values.push(value);
+    }
+    Ok(())
+}
+
+/// convert root of parquet schema into its legacy physical types
+fn legacy_root(
+    root: &Type,
+    fields: &arrow::datatypes::Fields,
+    layout: &LegacyLayout,
+) -> Result<Type, TblError> {
+    let Type::GroupType {
+        basic_info,
+        fields: columns,
+    } = root
+    else {
+        return Err(TblError::SchemaError(
+            "parquet root is not a group".to_string(),
+        ));
+    };
+    let mut legacy_columns = Vec::new();
+    for (field, column) in fields.iter().zip(columns) {
+        let legacy_column = if is_int96(field, layout) {
+            let info = column.get_basic_info();
+            Type::primitive_type_builder(info.name(), PhysicalType::INT96)
+                .with_repetition(info.repetition())
+                .build()?
+        } else {
+            legacy_type(column, layout)
+        };
+        legacy_columns.push(Arc::new(legacy_column));
+    }
+    Ok(Type::GroupType {
+        basic_info: basic_info.clone(),
+        fields: legacy_columns,
+    })
+}
+
+/// convert parquet type, and the types nested inside it, into their legacy physical types
+fn legacy_type(parquet_type: &Type, layout: &LegacyLayout) -> Type {
+    match parquet_type {
+        Type::GroupType { basic_info, fields } => {
+            let fields = fields
+                .iter()
+                .map(|field| Arc::new(legacy_type(field, layout)))
+                .collect();
+            Type::GroupType {
+                basic_info: basic_info.clone(),
+                fields,
+            }
+        }
+        Type::PrimitiveType {
+            basic_info,
+            precision,
+            scale,
+            ..
+        } if layout.fixed_decimals
+            && matches!(basic_info.logical_type(), Some(LogicalType::Decimal { .. })) =>
+        {
+            Type::PrimitiveType {
+                basic_info: basic_info.clone(),
+                physical_type: PhysicalType::FIXED_LEN_BYTE_ARRAY,
+                type_length: decimal_length_from_precision(*precision) as i32,
+                precision: *precision,
+                scale: *scale,
+            }
+        }
+        parquet_type => parquet_type.clone(),
+    }
+}
+
+/// number of bytes of the fixed-length byte arrays of decimals, as computed by the arrow writer
+fn decimal_length_from_precision(precision: i32) -> usize {
+    (((10.0_f64.powi(precision) + 1.0).log2() + 1.0) / 8.0).ceil() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::*;
+
+    #[test]
+    fn test_rewrite_parquet_legacy_decimals() {
+        let dir = tempfile::tempdir().unwrap();
+        let input_path = dir.path().join("input.parquet");
+        let output_path = dir.path().join("output.parquet");
+        let mut df = df!("x" => [1i64, 2, 3]).unwrap();
+        df.with_column(
+            df.column("x")
+                .unwrap()
+                .cast(&polars::prelude::DataType::Decimal(Some(9), Some(2)))
+                .unwrap(),
+        )
+        .unwrap();
+        ParquetWriter::new(File::create(&input_path).unwrap())
+            .finish(&mut df)
+            .unwrap();
+
+        let layout = LegacyLayout {
+            fixed_decimals: true,
+            int96_timestamps: false,
+            snappy: true,
+        };
+        rewrite_parquet_legacy(&input_path, &output_path, &layout).unwrap();
+
+        let reader =
+            parquet::file::reader::SerializedFileReader::new(File::open(&output_path).unwrap())
+                .unwrap();
+        let metadata = parquet::file::reader::FileReader::metadata(&reader);
+        let column = metadata.file_metadata().schema_descr().column(0);
+        assert_eq!(column.physical_type(), PhysicalType::FIXED_LEN_BYTE_ARRAY);
+        assert_eq!(column.type_length(), 4);
+        assert_eq!(column.type_precision(), 9);
+        assert_eq!(column.type_scale(), 2);
+        assert_eq!(metadata.file_metadata().num_rows(), 3);
+    }
+
+    #[test]
+    fn test_rewrite_parquet_legacy_int96() {
+        let dir = tempfile::tempdir().unwrap();
+        let input_path = dir.path().join("input.parquet");
+        let output_path = dir.path().join("output.parquet");
+        let nanos = [
+            Some(-1_500_000_000_123i64),
+            None,
+            Some(1_700_000_000_123_456_789),
+        ];
+        let mut df = df!("t" => nanos).unwrap();
+        df.with_column(
+            df.column("t")
+                .unwrap()
+                .cast(&polars::prelude::DataType::Datetime(
+                    polars::prelude::TimeUnit::Nanoseconds,
+                    None,
+                ))
+                .unwrap(),
+        )
+        .unwrap();
+        ParquetWriter::new(File::create(&input_path).unwrap())
+            .finish(&mut df)
+            .unwrap();
+
+        let layout = LegacyLayout {
+            fixed_decimals: false,
+            int96_timestamps: true,
+            snappy: true,
+        };
+        rewrite_parquet_legacy(&input_path, &output_path, &layout).unwrap();
+
+        let builder =
+            ParquetRecordBatchReaderBuilder::try_new(File::open(&output_path).unwrap()).unwrap();
+        let column = builder.metadata().file_metadata().schema_descr().column(0);
+        assert_eq!(column.physical_type(), PhysicalType::INT96);
+        let batches: Vec<_> = builder.build().unwrap().map(|b| b.unwrap()).collect();
+        let counts =
+            arrow::compute::cast(batches[0].column(0), &arrow::datatypes::DataType::Int64).unwrap();
+        let counts: Vec<_> = counts
+            .as_primitive::<arrow::datatypes::Int64Type>()
+            .iter()
+            .collect();
+        assert_eq!(counts, nanos);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::TblError;
+use parquet::file::statistics::Statistics;
+use std::path::PathBuf;
+
+/// whether a column carries information, as determined from parquet statistics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnLiveness {
+    /// every value is null
+    AllNull,
+    /// every value is the same non-null value, stored as its plain encoded bytes
+    Constant(Vec<u8>),
+    /// column holds at least two distinct values, or both nulls and non-nulls
+    Live,
+    /// statistics are missing or inexact, so the column must be scanned
+    Unknown,
+}
+
+impl ColumnLiveness {
+    /// whether column is entirely null or constant
+    pub fn is_dead(&self) -> bool {
+        matches!(self, ColumnLiveness::AllNull | ColumnLiveness::Constant(_))
+    }
+
+    /// combine liveness of two disjoint sets of rows
+    pub fn combine(self, other: ColumnLiveness) -> ColumnLiveness {
+        use ColumnLiveness::*;
+        match (self, other) {
+            (Live, _) | (_, Live) => Live,
+            (Unknown, _) | (_, Unknown) => Unknown,
+            (AllNull, AllNull) => AllNull,
+            (Constant(a), Constant(b)) if a == b => Constant(a),
+            _ => Live,
+        }
+    }
+
+    /// liveness of a row group from its statistics, and whether they record a null count
+    ///
+    /// without a null count, only differing bounds show anything about the column
+    pub fn from_statistics(
+        n_rows: u64,
+        statistics: Option<&Statistics>,
+        null_count_known: bool,
+    ) -> ColumnLiveness {
+        let statistics = match statistics {
+            Some(statistics) => statistics,
+            None => return ColumnLiveness::Unknown,
+        };
+        let bounds_differ = statistics.has_min_max_set()
+            && statistics.min_is_exact()
+            && statistics.max_is_exact()
+            && statistics.min_bytes() != statistics.max_bytes();
+        if !null_count_known {
+            return if bounds_differ {
+                ColumnLiveness::Live
+            } else {
+                ColumnLiveness::Unknown
+            };
+        }
+        let null_count = statistics.null_count();
+        if null_count == n_rows {
+            ColumnLiveness::AllNull
+        } else if null_count > 0 {
+            ColumnLiveness::Live
+        } else if !statistics.has_min_max_set()
+            || !statistics.min_is_exact()
+            || !statistics.max_is_exact()
+        {
+            // truncated bounds cannot show whether values are equal
+            ColumnLiveness::Unknown
+        } else if statistics.min_bytes() == statistics.max_bytes() {
+            ColumnLiveness::Constant(statistics.min_bytes().to_vec())
+        } else {
+            ColumnLiveness::Live
+        }
+    }
+}
+
+/// get liveness of each top-level column across parquet files using only their statistics
+///
+/// nested columns and columns missing from some files are reported as unknown
+pub async fn get_parquet_column_liveness(
+    paths: &[PathBuf],
+) -> Result<Vec<(String, ColumnLiveness)>, TblError> {
+    // columns without any rows yet have no liveness
+    let mut columns: Vec<(String, Option<ColumnLiveness>)> = Vec::new();
+    for (p, path) in paths.iter().enumerate() {
+        let metadata = super::get_parquet_metadata(path).await?;
+        let schema = metadata.file_metadata().schema_descr();
+        // footers that cannot be decoded directly leave every null count unknown
+        let null_counts = super::parquet_footer::get_null_count_presence(path).unwrap_or_default();
+        if p == 0 {
+            columns = schema
+                .root_schema()
+                .get_fields()
+                .iter()
+                .map(|field| (field.name().to_string(), None))
+                .collect();
+        }
+
+        // map each top-level column to its leaf index when it is not nested
+        let leaves: std::collections::HashMap<&str, usize> = schema
+            .columns()
+            .iter()
+            .enumerate()
+            .filter(|(_, leaf)| leaf.path().parts().len() == 1)
+            .map(|(index, leaf)| (leaf.path().parts()[0].as_str(), index))
+            .collect();
+
+        for (name, liveness) in columns.iter_mut() {
+            let index = match leaves.get(name.as_str()) {
+                Some(index) => *index,
+                None => {
+                    *liveness = Some(ColumnLiveness::Unknown);
+                    continue;
+                }
+            };
+            // required columns cannot hold nulls
+            let required = schema.column(index).max_def_level() == 0;
+            for (r, rg) in metadata.row_groups().iter().enumerate() {
+                if rg.num_rows() == 0 {
+                    continue;
+                }
+                let null_count_known = required
+                    || null_counts
+                        .get(r)
+                        .and_then(|columns| columns.get(index))
+                        .copied()
+                        .unwrap_or(false);
+                let rg_liveness = ColumnLiveness::from_statistics(
+                    rg.num_rows() as u64,
+                    rg.column(index).statistics(),
+                    null_count_known,
+                );
+                *liveness = Some(match liveness.take() {
+                    Some(current) => current.combine(rg_liveness),
+                    None => rg_liveness,
+                });
+                if *liveness == Some(ColumnLiveness::Live) {
+                    break;
+                }
+            }
+        }
+    }
+
+    // columns of datasets without rows are vacuously null
+    Ok(columns
+        .into_iter()
+        .map(|(name, liveness)| (name, liveness.unwrap_or(ColumnLiveness::AllNull)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_liveness() {
+        let constant = Statistics::int64(Some(3), Some(3), None, 0, false);
+        let varied = Statistics::int64(Some(1), Some(3), None, 0, false);
+        let partly_null = Statistics::int64(Some(3), Some(3), None, 2, false);
+        let all_null = Statistics::int64(None, None, None, 10, false);
+
+        let from =
+            |statistics: &Statistics| ColumnLiveness::from_statistics(10, Some(statistics), true);
+        assert!(matches!(from(&constant), ColumnLiveness::Constant(_)));
+        assert_eq!(from(&varied), ColumnLiveness::Live);
+        assert_eq!(from(&partly_null), ColumnLiveness::Live);
+        assert_eq!(from(&all_null), ColumnLiveness::AllNull);
+        assert_eq!(
+            ColumnLiveness::from_statistics(10, None, true),
+            ColumnLiveness::Unknown
+        );
+
+        // constant bounds without a null count may hide nulls
+        let without_null_count =
+            |statistics: &Statistics| ColumnLiveness::from_statistics(10, Some(statistics), false);
+        assert_eq!(without_null_count(&constant), ColumnLiveness::Unknown);
+        assert_eq!(without_null_count(&all_null), ColumnLiveness::Unknown);
+        assert_eq!(without_null_count(&varied), ColumnLiveness::Live);
+
+        assert!(from(&constant).combine(from(&constant)).is_dead());
+        assert_eq!(
+            from(&constant).combine(from(&all_null)),
+            ColumnLiveness::Live
+        );
+        assert_eq!(
+            ColumnLiveness::Unknown.combine(from(&varied)),
+            ColumnLiveness::Live
+        );
+    }
+}
@@ -21,6 +21,15 @@ pub async fn merge_parquets(
     }
 
     let tmp_output_path = super::parquet_drop::create_tmp_target(output_path.as_path());
+
+    // files with identical schemas and large row groups are merged by copying their compressed
+    // column chunks
+    if super::check_splice_parquets(input_paths)?.is_worthwhile() {
+        super::splice_parquets(input_paths, &tmp_output_path, &[])?;
+        tokio::fs::rename(tmp_output_path, output_path).await?;
+        return Ok(());
+    }
+
     let mut output_file = File::create(&tmp_output_path).await?;
     let mut buffer = Vec::new();
 
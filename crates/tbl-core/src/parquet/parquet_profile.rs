@@ -0,0 +1,234 @@
+use crate::cache::ResultCache;
+use crate::sketches::{HllSketch, KllSketch};
+use crate::TblError;
+use arrow::array::{Array, ArrayRef, Float64Array, Int64Array, UInt64Array};
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use futures::StreamExt;
+use parquet::arrow::async_reader::ParquetRecordBatchStreamBuilder;
+use parquet::arrow::ProjectionMask;
+use std::path::{Path, PathBuf};
+
+/// registers of distinct count sketches are 2^HLL_PRECISION, for about 1.6% relative error
+const HLL_PRECISION: u8 = 12;
+
+/// sketches of the values of a numeric column, excluding nulls
+#[derive(Clone, Debug)]
+pub struct ColumnProfile {
+    /// quantiles of values
+    pub quantiles: KllSketch,
+    /// number of distinct values
+    pub distinct: HllSketch,
+}
+
+impl ColumnProfile {
+    /// create empty profile with quantile accuracy parameter `k`
+    pub fn new(k: usize) -> ColumnProfile {
+        ColumnProfile {
+            quantiles: KllSketch::new(k),
+            distinct: HllSketch::new(HLL_PRECISION),
+        }
+    }
+
+    /// merge values of another profile into this profile
+    pub fn merge(&mut self, other: &ColumnProfile) {
+        self.quantiles.merge(&other.quantiles);
+        self.distinct.merge(&other.distinct);
+    }
+
+    fn to_text(&self) -> String {
+        format!("{}\n{}", self.quantiles.to_text(), self.distinct.to_text())
+    }
+
+    fn from_text(text: &str) -> Option<ColumnProfile> {
+        let (quantiles, distinct) = text.split_once("\n\n")?;
+        Some(ColumnProfile {
+            quantiles: KllSketch::from_text(quantiles)?,
+            distinct: HllSketch::from_text(distinct)?,
+        })
+    }
+}
+
+/// profile numeric columns across parquet files, one profile per column
+///
+/// files are profiled concurrently and their profiles merged, files without a column or where
+/// it is not numeric are skipped for that column. with a cache, profiles are cached per file
+/// and column, keyed by a hash of the file contents, so only new or changed files are read.
+/// returns profiles and number of files read from cache
+pub async fn parquet_column_profiles(
+    paths: &[PathBuf],
+    columns: &[String],
+    k: usize,
+    batch_size: usize,
+    max_concurrent: usize,
+    cache: Option<&ResultCache>,
+) -> Result<(Vec<ColumnProfile>, usize), TblError> {
+    let file_profiles = futures::stream::iter(paths)
+        .map(|path| async move {
+            // files that cannot be hashed are read without the cache
+            let keys: Vec<String> = match cache.and(super::file_content_fingerprint(path).ok()) {
+                Some(hash) => columns
+                    .iter()
+                    .map(|column| format!("profile\t{}\t{}\t{}", hash, k, column))
+                    .collect(),
+                None => Vec::new(),
+            };
+            if let (Some(cache), false) = (cache, keys.is_empty()) {
+                let cached: Option<Vec<ColumnProfile>> = keys
+                    .iter()
+                    .map(|key| ColumnProfile::from_text(&cache.get(key)?))
+                    .collect();
+                if let Some(cached) = cached {
+                    return Ok((cached, true));
+                }
+            }
+            let profiles = parquet_file_column_profiles(path, columns, k, batch_size).await?;
+            if let Some(cache) = cache {
+                for (key, profile) in keys.iter().zip(profiles.iter()) {
+                    cache.put(key, &profile.to_text());
+                }
+            }
+            Ok::<_, TblError>((profiles, false))
+        })
+        .buffer_unordered(max_concurrent.max(1))
+        .collect::<Vec<_>>()
+        .await;
+    let mut profiles: Vec<ColumnProfile> = columns.iter().map(|_| ColumnProfile::new(k)).collect();
+    let mut n_cached = 0;
+    for file_profile in file_profiles.into_iter() {
+        let (file_profile, is_cached) = file_profile?;
+        n_cached += is_cached as usize;
+        for (profile, file_profile) in profiles.iter_mut().zip(file_profile.iter()) {
+            profile.merge(file_profile);
+        }
+    }
+    Ok((profiles, n_cached))
+}
+
+async fn parquet_file_column_profiles(
+    path: &Path,
+    columns: &[String],
+    k: usize,
+    batch_size: usize,
+) -> Result<Vec<ColumnProfile>, TblError> {
+    let mut profiles: Vec<ColumnProfile> = columns.iter().map(|_| ColumnProfile::new(k)).collect();
+    let file = tokio::fs::File::open(path).await?;
+    let builder = ParquetRecordBatchStreamBuilder::new(file)
+        .await?
+        .with_batch_size(batch_size);
+    let schema = builder.schema().clone();
+    let indices: Vec<Option<usize>> = columns
+        .iter()
+        .map(|column| {
+            schema
+                .index_of(column)
+                .ok()
+                .filter(|index| schema.field(*index).data_type().is_numeric())
+        })
+        .collect();
+    let mut roots: Vec<usize> = indices.iter().flatten().copied().collect();
+    if roots.is_empty() {
+        return Ok(profiles);
+    }
+    roots.sort();
+    roots.dedup();
+    let mask = ProjectionMask::roots(builder.parquet_schema(), roots.clone());
+    let mut reader_stream = builder.with_projection(mask).build()?;
+
+    while let Some(batch) = reader_stream.next().await {
+        let batch = batch?;
+        for (profile, index) in profiles.iter_mut().zip(indices.iter()) {
+            // projected columns keep their relative order
+            let position = match index.and_then(|index| roots.iter().position(|r| *r == index)) {
+                Some(position) => position,
+                None => continue,
+            };
+            insert_values(profile, batch.column(position))?;
+        }
+    }
+    Ok(profiles)
+}
+
+/// add values of a numeric array to a profile
+///
+/// distinct values of integers are counted exactly as integers, since integers beyond 2^53
+/// would collide as floats
+fn insert_values(profile: &mut ColumnProfile, array: &ArrayRef) -> Result<(), TblError> {
+    let downcast_error = || TblError::Error("could not cast numeric column".to_string());
+    let floats = cast(array, &DataType::Float64)?;
+    let floats = floats
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .ok_or_else(downcast_error)?;
+    for value in floats.iter().flatten() {
+        profile.quantiles.insert(value);
+    }
+    match array.data_type() {
+        DataType::UInt64 => {
+            let values = array
+                .as_any()
+                .downcast_ref::<UInt64Array>()
+                .ok_or_else(downcast_error)?;
+            values
+                .iter()
+                .flatten()
+                .for_each(|v| profile.distinct.insert(v));
+        }
+        dtype if dtype.is_integer() => {
+            let values = cast(array, &DataType::Int64)?;
+            let values = values
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .ok_or_else(downcast_error)?;
+            values
+                .iter()
+                .flatten()
+                .for_each(|v| profile.distinct.insert(v as u64));
+        }
+        _ => floats
+            .iter()
+            .flatten()
+            .for_each(|v| profile.distinct.insert_f64(v)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_parquet_column_profiles() -> Result<(), TblError> {
+        let temp_dir = TempDir::new()?;
+        let cache = ResultCache::new(&temp_dir.path().join("cache"), 1 << 20);
+        let path = temp_dir.path().join("data.parquet");
+        let big = 1u64 << 60;
+        let mut df = df!("id" => [big, big + 1, big + 2, big + 2], "x" => [1.0, 2.0, 2.0, 3.0])?;
+        ParquetWriter::new(std::fs::File::create(&path)?).finish(&mut df)?;
+        let paths = vec![path.clone()];
+        let columns = vec!["id".to_string(), "x".to_string()];
+
+        let (profiles, n_cached) =
+            parquet_column_profiles(&paths, &columns, 200, 1024, 2, Some(&cache)).await?;
+        assert_eq!(n_cached, 0);
+        assert_eq!(profiles[0].distinct.estimate(), 3);
+        assert_eq!(profiles[1].distinct.estimate(), 3);
+        assert_eq!(profiles[1].quantiles.n_items(), 4);
+
+        let (profiles, n_cached) =
+            parquet_column_profiles(&paths, &columns, 200, 1024, 2, Some(&cache)).await?;
+        assert_eq!(n_cached, 1);
+        assert_eq!(profiles[1].quantiles.quantile(1.0), Some(3.0));
+
+        // changed contents are read again
+        let mut df = df!("id" => [big, big + 1, big + 2, big + 3], "x" => [1.0, 2.0, 2.0, 3.0])?;
+        ParquetWriter::new(std::fs::File::create(&path)?).finish(&mut df)?;
+        let (profiles, n_cached) =
+            parquet_column_profiles(&paths, &columns, 200, 1024, 2, Some(&cache)).await?;
+        assert_eq!(n_cached, 0);
+        assert_eq!(profiles[0].distinct.estimate(), 4);
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use crate::sketches::KllSketch;
+use crate::TblError;
+use arrow::array::{Array, Float64Array};
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use futures::StreamExt;
+use parquet::arrow::async_reader::ParquetRecordBatchStreamBuilder;
+use parquet::arrow::ProjectionMask;
+use std::path::{Path, PathBuf};
+
+/// build a quantile sketch of a numeric column across parquet files
+///
+/// streams only the requested column, null values are skipped
+pub async fn parquet_quantile_sketch(
+    paths: &[PathBuf],
+    column: &str,
+    k: usize,
+    batch_size: usize,
+) -> Result<KllSketch, TblError> {
+    let mut sketch = KllSketch::new(k);
+    for path in paths.iter() {
+        let file = tokio::fs::File::open(path).await?;
+        let builder = ParquetRecordBatchStreamBuilder::new(file)
+            .await?
+            .with_batch_size(batch_size);
+        let index = builder.schema().index_of(column).map_err(|_| {
+            TblError::SchemaError(format!(
+                "column '{}' not found in {}",
+                column,
+                path.to_string_lossy()
+            ))
+        })?;
+        let dtype = builder.schema().field(index).data_type().clone();
+        if !dtype.is_numeric() {
+            return Err(TblError::SchemaError(format!(
+                "column '{}' is not numeric ({})",
+                column, dtype
+            )));
+        }
+        let mask = ProjectionMask::roots(builder.parquet_schema(), [index]);
+        let mut reader_stream = builder.with_projection(mask).build()?;
+
+        while let Some(batch) = reader_stream.next().await {
+            let batch = batch?;
+            let values = cast(batch.column(0), &DataType::Float64)?;
+            let values = values
+                .as_any()
+                .downcast_ref::<Float64Array>()
+                .ok_or_else(|| TblError::Error("could not cast column to f64".to_string()))?;
+            for value in values.iter().flatten() {
+                sketch.insert(value);
+            }
+        }
+    }
+    Ok(sketch)
+}
+
+/// build quantile sketches of numeric columns across parquet files, one per column
+///
+/// files are sketched concurrently and their sketches merged, files without a column or
+/// where it is not numeric are skipped for that column
+pub async fn parquet_quantile_sketches(
+    paths: &[PathBuf],
+    columns: &[String],
+    k: usize,
+    batch_size: usize,
+    max_concurrent: usize,
+) -> Result<Vec<KllSketch>, TblError> {
+    let file_sketches = futures::stream::iter(paths)
+        .map(|path| parquet_file_quantile_sketches(path, columns, k, batch_size))
+        .buffer_unordered(max_concurrent.max(1))
+        .collect::<Vec<_>>()
+        .await;
+    let mut sketches: Vec<KllSketch> = columns.iter().map(|_| KllSketch::new(k)).collect();
+    for file_sketch in file_sketches.into_iter() {
+        for (sketch, file_sketch) in sketches.iter_mut().zip(file_sketch?.iter()) {
+            sketch.merge(file_sketch);
+        }
+    }
+    Ok(sketches)
+}
+
+async fn parquet_file_quantile_sketches(
+    path: &Path,
+    columns: &[String],
+    k: usize,
+    batch_size: usize,
+) -> Result<Vec<KllSketch>, TblError> {
+    let mut sketches: Vec<KllSketch> = columns.iter().map(|_| KllSketch::new(k)).collect();
+    let file = tokio::fs::File::open(path).await?;
+    let builder = ParquetRecordBatchStreamBuilder::new(file)
+        .await?
+        .with_batch_size(batch_size);
+    let schema = builder.schema().clone();
+    let indices: Vec<Option<usize>> = columns
+        .iter()
+        .map(|column| {
+            schema
+                .index_of(column)
+                .ok()
+                .filter(|index| schema.field(*index).data_type().is_numeric())
+        })
+        .collect();
+    let mut roots: Vec<usize> = indices.iter().flatten().copied().collect();
+    if roots.is_empty() {
+        return Ok(sketches);
+    }
+    roots.sort();
+    roots.dedup();
+    let mask = ProjectionMask::roots(builder.parquet_schema(), roots.clone());
+    let mut reader_stream = builder.with_projection(mask).build()?;
+
+    while let Some(batch) = reader_stream.next().await {
+        let batch = batch?;
+        for (sketch, index) in sketches.iter_mut().zip(indices.iter()) {
+            // projected columns keep their relative order
+            let position = match index.and_then(|index| roots.iter().position(|r| *r == index)) {
+                Some(position) => position,
+                None => continue,
+            };
+            let values = cast(batch.column(position), &DataType::Float64)?;
+            let values = values
+                .as_any()
+                .downcast_ref::<Float64Array>()
+                .ok_or_else(|| TblError::Error("could not cast column to f64".to_string()))?;
+            for value in values.iter().flatten() {
+                sketch.insert(value);
+            }
+        }
+    }
+    Ok(sketches)
+}
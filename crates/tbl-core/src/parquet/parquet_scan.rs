@@ -1,10 +1,207 @@
 use crate::TblError;
 use polars::prelude::*;
-use std::path::PathBuf;
+use regex::Regex;
+use std::path::{Path, PathBuf};
 
 /// create lazy frame by scanning input paths
 pub fn create_lazyframe(paths: &[PathBuf]) -> Result<LazyFrame, TblError> {
-    let scan_args = polars::prelude::ScanArgsParquet::default();
+    let scan_args = create_scan_args(paths);
     let arc_paths = Arc::from(paths.to_vec().into_boxed_slice());
     Ok(LazyFrame::scan_parquet_files(arc_paths, scan_args)?)
 }
+
+/// column whose values are extracted from input file paths
+#[derive(Clone, Debug)]
+pub struct PathColumn {
+    /// name of column
+    pub name: String,
+    /// pattern matched against each path, using the first capture group if present
+    pub pattern: Regex,
+}
+
+impl PathColumn {
+    /// parse path column from syntax NAME:REGEX
+    pub fn parse(spec: &str) -> Result<PathColumn, TblError> {
+        let (name, pattern) = spec.split_once(':').ok_or_else(|| {
+            TblError::InputError(format!("path column {} must be NAME:REGEX", spec))
+        })?;
+        let pattern = Regex::new(pattern)
+            .map_err(|e| TblError::InputError(format!("invalid path column regex: {}", e)))?;
+        Ok(PathColumn {
+            name: name.to_string(),
+            pattern,
+        })
+    }
+
+    /// column holding the full path of each file
+    pub fn full_path(name: &str) -> PathColumn {
+        PathColumn {
+            name: name.to_string(),
+            pattern: Regex::new("^.*$").expect("valid regex"),
+        }
+    }
+
+    /// extract value of column from path
+    pub fn extract(&self, path: &Path) -> Option<String> {
+        let path = path.to_string_lossy();
+        let captures = self.pattern.captures(&path)?;
+        captures
+            .get(1)
+            .or_else(|| captures.get(0))
+            .map(|m| m.as_str().to_string())
+    }
+}
+
+/// create lazy frame by scanning input paths, adding columns extracted from each path
+pub fn create_lazyframe_with_path_columns(
+    paths: &[PathBuf],
+    path_columns: &[PathColumn],
+) -> Result<LazyFrame, TblError> {
+    if path_columns.is_empty() {
+        return create_lazyframe(paths);
+    }
+    let lfs = paths
+        .iter()
+        .map(|path| {
+            let exprs: Vec<Expr> = path_columns
+                .iter()
+                .map(|column| {
+                    let value = match column.extract(path) {
+                        Some(value) => lit(value),
+                        None => lit(NULL).cast(DataType::String),
+                    };
+                    value.alias(&column.name)
+                })
+                .collect();
+            Ok(create_lazyframe(std::slice::from_ref(path))?.with_columns(exprs))
+        })
+        .collect::<Result<Vec<_>, TblError>>()?;
+    Ok(concat(lfs, UnionArgs::default())?)
+}
+
+/// interleave rows of lazyframes, taking one row from each frame in turn
+///
+/// each frame is first limited to limit rows when given, which still yields the first limit
+/// rows of the interleaved result
+pub fn interleave_lazyframes(
+    lfs: Vec<LazyFrame>,
+    limit: Option<usize>,
+) -> Result<LazyFrame, TblError> {
+    const ROW: &str = "_interleave_row";
+    const FRAME: &str = "_interleave_frame";
+    let lfs: Vec<LazyFrame> = lfs
+        .into_iter()
+        .enumerate()
+        .map(|(i, lf)| {
+            let lf = match limit {
+                Some(limit) => lf.limit(limit as IdxSize),
+                None => lf,
+            };
+            lf.with_row_index(ROW, None)
+                .with_column(lit(i as u64).alias(FRAME))
+        })
+        .collect();
+    Ok(concat(lfs, UnionArgs::default())?
+        .sort([ROW, FRAME], SortMultipleOptions::default())
+        .drop([ROW, FRAME]))
+}
+
+/// create scan arguments for input paths
+///
+/// hive partition columns are enabled when every path shares the same `key=value` directories,
+/// filters on these columns then skip files without reading them
+pub fn create_scan_args(paths: &[PathBuf]) -> ScanArgsParquet {
+    let mut scan_args = ScanArgsParquet::default();
+    scan_args.hive_options.enabled = Some(has_hive_partitions(paths));
+    scan_args
+}
+
+/// whether all paths have the same hive partition keys
+pub fn has_hive_partitions(paths: &[PathBuf]) -> bool {
+    let keys = |path: &PathBuf| -> Vec<String> {
+        get_hive_partitions(path)
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect()
+    };
+    match paths.first().map(keys) {
+        Some(first_keys) if !first_keys.is_empty() => {
+            paths.iter().all(|path| keys(path) == first_keys)
+        }
+        _ => false,
+    }
+}
+
+/// get hive partition keys and values from the `key=value` directories of a path
+pub fn get_hive_partitions(path: &Path) -> Vec<(String, String)> {
+    let parent = match path.parent() {
+        Some(parent) => parent,
+        None => return Vec::new(),
+    };
+    parent
+        .components()
+        .filter_map(|component| {
+            let component = component.as_os_str().to_str()?;
+            let (key, value) = component.split_once('=')?;
+            if key.is_empty() {
+                None
+            } else {
+                Some((key.to_string(), value.to_string()))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_hive_partitions() {
+        let path = PathBuf::from("data/year=2024/month=01/part=0.parquet");
+        assert_eq!(
+            get_hive_partitions(&path),
+            vec![
+                ("year".to_string(), "2024".to_string()),
+                ("month".to_string(), "01".to_string())
+            ]
+        );
+        let other = PathBuf::from("data/year=2023/month=12/part.parquet");
+        assert!(has_hive_partitions(&[path.clone(), other]));
+        let flat = PathBuf::from("data/part.parquet");
+        assert!(get_hive_partitions(&flat).is_empty());
+        assert!(!has_hive_partitions(&[path, flat]));
+    }
+
+    #[test]
+    fn test_interleave_lazyframes() -> Result<(), TblError> {
+        let a = df!("x" => [1i64, 2, 3])?.lazy();
+        let b = df!("x" => [10i64])?.lazy();
+        let c = df!("x" => [20i64, 21])?.lazy();
+        let df = interleave_lazyframes(vec![a.clone(), b.clone(), c.clone()], None)?.collect()?;
+        let values: Vec<Option<i64>> = df.column("x")?.i64()?.into_iter().collect();
+        let expected = [1, 10, 20, 2, 21, 3];
+        assert_eq!(values, expected.map(Some).to_vec());
+        let df = interleave_lazyframes(vec![a, b, c], Some(1))?.collect()?;
+        assert_eq!(
+            df.column("x")?.i64()?.into_iter().collect::<Vec<_>>().len(),
+            3
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_path_column() {
+        let column = PathColumn::parse(r"date:(\d{4}-\d{2}-\d{2})").unwrap();
+        assert_eq!(column.name, "date");
+        let path = PathBuf::from("logs/events_2024-03-01.parquet");
+        assert_eq!(column.extract(&path), Some("2024-03-01".to_string()));
+        assert_eq!(column.extract(&PathBuf::from("logs/events.parquet")), None);
+        assert!(PathColumn::parse("date").is_err());
+        let source = PathColumn::full_path("source");
+        assert_eq!(
+            source.extract(&path),
+            Some("logs/events_2024-03-01.parquet".to_string())
+        );
+    }
+}
@@ -0,0 +1,406 @@
+use crate::TblError;
+use arrow::array::ArrayRef;
+use arrow::compute::SortOptions;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::{RecordBatch, RecordBatchReader};
+use arrow::row::{OwnedRow, RowConverter, SortField};
+use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
+use parquet::arrow::arrow_writer::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::path::{Path, PathBuf};
+
+/// sort key of external sort
+#[derive(Clone, Debug)]
+pub struct SortKey {
+    /// column name
+    pub column: String,
+    /// sort in descending order
+    pub descending: bool,
+    /// place nulls before other values
+    pub nulls_first: bool,
+}
+
+/// totally order rows of multiple parquet files into a single output file
+///
+/// rows are read in sorted runs of at most `run_rows` rows that are spilled to
+/// temporary files next to the output, then k-way merged. memory use is bounded
+/// by the run size plus one batch per run during the merge. the sort is stable,
+/// rows with equal keys keep the order of input files and of rows within them
+pub async fn external_sort_parquets(
+    input_paths: &[PathBuf],
+    output_path: &Path,
+    sort_keys: &[SortKey],
+    run_rows: usize,
+    batch_size: usize,
+) -> Result<(), TblError> {
+    let input_paths = input_paths.to_vec();
+    let output_path = output_path.to_path_buf();
+    let sort_keys = sort_keys.to_vec();
+    tokio::task::spawn_blocking(move || {
+        external_sort(&input_paths, &output_path, &sort_keys, run_rows, batch_size)
+    })
+    .await?
+}
+
+fn external_sort(
+    input_paths: &[PathBuf],
+    output_path: &Path,
+    sort_keys: &[SortKey],
+    run_rows: usize,
+    batch_size: usize,
+) -> Result<(), TblError> {
+    if input_paths.is_empty() {
+        return Err(TblError::Error("No input files provided".to_string()));
+    }
+    if sort_keys.is_empty() {
+        return Err(TblError::Error("No sort columns provided".to_string()));
+    }
+
+    // spill sorted runs next to the output
+    let run_dir = output_path.with_file_name(format!(
+        ".tbl_sort_{}_{}",
+        std::process::id(),
+        output_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    ));
+    std::fs::create_dir_all(&run_dir)?;
+    let result = write_sorted_runs(input_paths, &run_dir, sort_keys, run_rows, batch_size)
+        .and_then(|(schema, run_paths)| {
+            merge_sorted_runs(&schema, &run_paths, output_path, sort_keys, batch_size)
+        });
+    std::fs::remove_dir_all(&run_dir)?;
+    result
+}
+
+fn write_sorted_runs(
+    input_paths: &[PathBuf],
+    run_dir: &Path,
+    sort_keys: &[SortKey],
+    run_rows: usize,
+    batch_size: usize,
+) -> Result<(SchemaRef, Vec<PathBuf>), TblError> {
+    let mut schema: Option<SchemaRef> = None;
+    let mut run_paths = Vec::new();
+    let mut pending: Vec<RecordBatch> = Vec::new();
+    let mut n_pending = 0;
+
+    for input_path in input_paths.iter() {
+        let reader = open_reader(input_path, batch_size)?;
+        match &schema {
+            None => schema = Some(reader.schema()),
+            Some(schema) if schema != &reader.schema() => {
+                return Err(TblError::SchemaError(format!(
+                    "schema of {} does not match schema of {}",
+                    input_path.to_string_lossy(),
+                    input_paths[0].to_string_lossy()
+                )))
+            }
+            Some(_) => {}
+        }
+        for batch in reader {
+            let batch = batch?;
+            n_pending += batch.num_rows();
+            pending.push(batch);
+            if n_pending >= run_rows {
+                let run_path = run_dir.join(format!("run_{}.parquet", run_paths.len()));
+                write_sorted_run(&pending, &run_path, sort_keys)?;
+                run_paths.push(run_path);
+                pending.clear();
+                n_pending = 0;
+            }
+        }
+    }
+
+    let schema = schema.ok_or_else(|| TblError::Error("no input schema".to_string()))?;
+    if !pending.is_empty() {
+        let run_path = run_dir.join(format!("run_{}.parquet", run_paths.len()));
+        write_sorted_run(&pending, &run_path, sort_keys)?;
+        run_paths.push(run_path);
+    }
+    Ok((schema, run_paths))
+}
+
+fn write_sorted_run(
+    batches: &[RecordBatch],
+    run_path: &Path,
+    sort_keys: &[SortKey],
+) -> Result<(), TblError> {
+    let schema = batches[0].schema();
+    let batch = arrow::compute::concat_batches(&schema, batches)?;
+
+    // lexsort is unstable, so rows are compared in their row format and sorted stably
+    let mut key_columns = Vec::new();
+    let mut fields = Vec::new();
+    for key in sort_keys.iter() {
+        let column = batch.column(schema.index_of(&key.column)?).clone();
+        fields.push(SortField::new_with_options(
+            column.data_type().clone(),
+            sort_options(key),
+        ));
+        key_columns.push(column);
+    }
+    let rows = RowConverter::new(fields)?.convert_columns(&key_columns)?;
+    let mut order: Vec<u32> = (0..batch.num_rows() as u32).collect();
+    order.sort_by(|a, b| rows.row(*a as usize).cmp(&rows.row(*b as usize)));
+    let indices = arrow::array::UInt32Array::from(order);
+    let batch = arrow::compute::take_record_batch(&batch, &indices)?;
+
+    let file = std::fs::File::create(run_path)?;
+    let mut writer = ArrowWriter::try_new(file, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+fn merge_sorted_runs(
+    schema: &SchemaRef,
+    run_paths: &[PathBuf],
+    output_path: &Path,
+    sort_keys: &[SortKey],
+    batch_size: usize,
+) -> Result<(), TblError> {
+    let key_indices = sort_keys
+        .iter()
+        .map(|key| schema.index_of(&key.column))
+        .collect::<Result<Vec<_>, _>>()?;
+    let converter = RowConverter::new(
+        sort_keys
+            .iter()
+            .zip(key_indices.iter())
+            .map(|(key, index)| {
+                SortField::new_with_options(
+                    schema.field(*index).data_type().clone(),
+                    sort_options(key),
+                )
+            })
+            .collect(),
+    )?;
+
+    // current batch of each run, stored in a pool that is compacted after each flush
+    let mut readers = Vec::new();
+    let mut pool: Vec<RecordBatch> = Vec::new();
+    let mut cursors: Vec<Option<RunCursor>> = Vec::new();
+    let mut heap = BinaryHeap::new();
+    for (run, run_path) in run_paths.iter().enumerate() {
+        let mut reader = open_reader(run_path, batch_size)?;
+        let cursor = next_cursor(&mut reader, &mut pool, &converter, &key_indices)?;
+        if let Some(cursor) = &cursor {
+            heap.push(Reverse((cursor.rows[0].clone(), run)));
+        }
+        readers.push(reader);
+        cursors.push(cursor);
+    }
+
+    let tmp_output_path = super::parquet_drop::create_tmp_target(output_path);
+    let file = std::fs::File::create(&tmp_output_path)?;
+    let writer_props = WriterProperties::builder().build();
+    let mut writer = ArrowWriter::try_new(file, schema.clone(), Some(writer_props))?;
+    let mut buffer: Vec<(usize, usize)> = Vec::with_capacity(batch_size);
+
+    while let Some(Reverse((_, run))) = heap.pop() {
+        let cursor = cursors[run]
+            .as_mut()
+            .ok_or_else(|| TblError::Error("sort run exhausted".to_string()))?;
+        buffer.push((cursor.pool_index, cursor.position));
+        cursor.position += 1;
+
+        if buffer.len() >= batch_size {
+            flush_buffer(&mut writer, schema, &pool, &mut buffer)?;
+        }
+
+        // advance run, loading its next batch if needed
+        let cursor = cursors[run].as_mut().expect("cursor checked above");
+        if cursor.position < cursor.rows.len() {
+            heap.push(Reverse((cursor.rows[cursor.position].clone(), run)));
+        } else {
+            flush_buffer(&mut writer, schema, &pool, &mut buffer)?;
+            compact_pool(&mut pool, &mut cursors, run);
+            cursors[run] = next_cursor(&mut readers[run], &mut pool, &converter, &key_indices)?;
+            if let Some(cursor) = &cursors[run] {
+                heap.push(Reverse((cursor.rows[0].clone(), run)));
+            }
+        }
+    }
+    flush_buffer(&mut writer, schema, &pool, &mut buffer)?;
+    writer.close()?;
+    std::fs::rename(tmp_output_path, output_path)?;
+
+    Ok(())
+}
+
+struct RunCursor {
+    pool_index: usize,
+    position: usize,
+    rows: Vec<OwnedRow>,
+}
+
+fn next_cursor(
+    reader: &mut ParquetRecordBatchReader,
+    pool: &mut Vec<RecordBatch>,
+    converter: &RowConverter,
+    key_indices: &[usize],
+) -> Result<Option<RunCursor>, TblError> {
+    for batch in reader.by_ref() {
+        let batch = batch?;
+        if batch.num_rows() == 0 {
+            continue;
+        }
+        let key_columns: Vec<ArrayRef> = key_indices
+            .iter()
+            .map(|index| batch.column(*index).clone())
+            .collect();
+        let rows = converter.convert_columns(&key_columns)?;
+        let rows = rows.iter().map(|row| row.owned()).collect();
+        pool.push(batch);
+        return Ok(Some(RunCursor {
+            pool_index: pool.len() - 1,
+            position: 0,
+            rows,
+        }));
+    }
+    Ok(None)
+}
+
+/// drop the exhausted batch of `run` from the pool, must be called on an empty buffer
+fn compact_pool(pool: &mut Vec<RecordBatch>, cursors: &mut [Option<RunCursor>], run: usize) {
+    if let Some(cursor) = cursors[run].take() {
+        let removed = cursor.pool_index;
+        pool.remove(removed);
+        for cursor in cursors.iter_mut().flatten() {
+            if cursor.pool_index > removed {
+                cursor.pool_index -= 1;
+            }
+        }
+    }
+}
+
+fn flush_buffer(
+    writer: &mut ArrowWriter<std::fs::File>,
+    schema: &SchemaRef,
+    pool: &[RecordBatch],
+    buffer: &mut Vec<(usize, usize)>,
+) -> Result<(), TblError> {
+    if buffer.is_empty() {
+        return Ok(());
+    }
+    let columns = (0..schema.fields().len())
+        .map(|c| {
+            let arrays: Vec<&dyn arrow::array::Array> =
+                pool.iter().map(|batch| batch.column(c).as_ref()).collect();
+            arrow::compute::interleave(&arrays, buffer)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    writer.write(&RecordBatch::try_new(schema.clone(), columns)?)?;
+    buffer.clear();
+    Ok(())
+}
+
+fn open_reader(path: &Path, batch_size: usize) -> Result<ParquetRecordBatchReader, TblError> {
+    let file = std::fs::File::open(path)?;
+    Ok(ParquetRecordBatchReaderBuilder::try_new(file)?
+        .with_batch_size(batch_size)
+        .build()?)
+}
+
+fn sort_options(key: &SortKey) -> SortOptions {
+    SortOptions {
+        descending: key.descending,
+        nulls_first: key.nulls_first,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn write_file(path: &Path, values: Vec<i64>) -> Result<(), TblError> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("value", DataType::Int64, false),
+            Field::new("label", DataType::Utf8, false),
+        ]));
+        let labels: Vec<String> = values.iter().map(|v| format!("row_{}", v)).collect();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(values)),
+                Arc::new(StringArray::from(labels)),
+            ],
+        )?;
+        let mut writer = ArrowWriter::try_new(std::fs::File::create(path)?, schema, None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+
+    fn read_values(path: &Path) -> Result<Vec<i64>, TblError> {
+        let mut values = Vec::new();
+        for batch in open_reader(path, 1024)? {
+            let batch = batch?;
+            let column = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .expect("int64 column")
+                .clone();
+            values.extend(column.iter().flatten());
+        }
+        Ok(values)
+    }
+
+    #[test]
+    fn test_external_sort_merges_runs() -> Result<(), TblError> {
+        let temp_dir = TempDir::new()?;
+        let a = temp_dir.path().join("a.parquet");
+        let b = temp_dir.path().join("b.parquet");
+        let output = temp_dir.path().join("sorted.parquet");
+        write_file(&a, (0..50).map(|i| (i * 37) % 101).collect())?;
+        write_file(&b, (0..50).map(|i| (i * 53) % 97).collect())?;
+
+        let keys = vec![SortKey {
+            column: "value".to_string(),
+            descending: true,
+            nulls_first: true,
+        }];
+        external_sort(&[a.clone(), b.clone()], &output, &keys, 7, 5)?;
+
+        let mut expected = read_values(&a)?;
+        expected.extend(read_values(&b)?);
+        expected.sort_by(|x, y| y.cmp(x));
+        assert_eq!(read_values(&output)?, expected);
+        assert_eq!(std::fs::read_dir(temp_dir.path())?.count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_external_sort_is_stable() -> Result<(), TblError> {
+        use polars::prelude::*;
+        let temp_dir = TempDir::new()?;
+        let a = temp_dir.path().join("a.parquet");
+        let b = temp_dir.path().join("b.parquet");
+        let output = temp_dir.path().join("sorted.parquet");
+        let mut df_a =
+            df!("key" => [Some(1i64), None, Some(0), Some(1)], "order" => [0i64, 1, 2, 3])?;
+        let mut df_b = df!("key" => [Some(0i64), Some(1), None], "order" => [4i64, 5, 6])?;
+        ParquetWriter::new(std::fs::File::create(&a)?).finish(&mut df_a)?;
+        ParquetWriter::new(std::fs::File::create(&b)?).finish(&mut df_b)?;
+
+        let keys = vec![SortKey {
+            column: "key".to_string(),
+            descending: false,
+            nulls_first: false,
+        }];
+        external_sort(&[a, b], &output, &keys, 3, 2)?;
+        let df = ParquetReader::new(std::fs::File::open(&output)?).finish()?;
+        let order: Vec<Option<i64>> = df.column("order")?.i64()?.into_iter().collect();
+        assert_eq!(order, [2, 4, 0, 3, 5, 1, 6].map(Some).to_vec());
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+use crate::TblError;
+use arrow::datatypes::Schema;
+use parquet::arrow::arrow_writer::ArrowWriter;
+use parquet::arrow::{parquet_to_arrow_schema, ARROW_SCHEMA_META_KEY};
+use parquet::column::writer::ColumnCloseResult;
+use parquet::errors::ParquetError;
+use parquet::file::footer::parse_metadata;
+use parquet::file::metadata::ParquetMetaData;
+use parquet::file::properties::WriterProperties;
+use parquet::file::reader::ChunkReader;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::types::Type;
+use parquet::thrift::TSerializable;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use thrift::protocol::TCompactInputProtocol;
+
+/// fewest rows per row group for splicing to be chosen without being asked, since spliced
+/// outputs keep the row groups of their inputs
+pub const MIN_SPLICE_ROW_GROUP_ROWS: u64 = 100_000;
+
+/// whether and how well the column chunks of parquet files can be spliced
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpliceCheck {
+    /// files have identical schemas
+    pub compatible: bool,
+    /// no bloom filters or differing key value metadata would be dropped
+    pub lossless: bool,
+    /// fewest rows in any row group
+    pub min_row_group_rows: u64,
+}
+
+impl SpliceCheck {
+    /// whether splicing is compatible, lossless, and keeps row groups of a useful size
+    pub fn is_worthwhile(&self) -> bool {
+        self.compatible && self.lossless && self.min_row_group_rows >= MIN_SPLICE_ROW_GROUP_ROWS
+    }
+}
+
+/// check whether parquet files can be spliced and what splicing would lose
+pub fn check_splice_parquets(paths: &[PathBuf]) -> Result<SpliceCheck, TblError> {
+    let mut check = SpliceCheck {
+        compatible: true,
+        lossless: true,
+        min_row_group_rows: u64::MAX,
+    };
+    let mut first = None;
+    for path in paths.iter() {
+        let metadata = parse_metadata(&std::fs::File::open(path)?)?;
+        let schema = get_splice_schema(&metadata);
+        let key_values = metadata.file_metadata().key_value_metadata().cloned();
+        match &first {
+            None => first = Some((schema, key_values)),
+            Some((first_schema, first_key_values)) => {
+                check.compatible &= first_schema == &schema;
+                check.lossless &= first_key_values == &key_values;
+            }
+        }
+        for row_group in metadata.row_groups().iter() {
+            check.min_row_group_rows = check.min_row_group_rows.min(row_group.num_rows() as u64);
+            check.lossless &= row_group
+                .columns()
+                .iter()
+                .all(|column| column.bloom_filter_offset().is_none());
+        }
+    }
+    Ok(check)
+}
+
+/// copy the compressed column chunks of parquet files into one file, without decoding pages
+///
+/// files must have identical schemas, top level columns in columns_to_drop are left out, page
+/// indexes are copied, bloom filters are dropped, and only key value metadata of the first file
+/// is kept
+pub fn splice_parquets(
+    input_paths: &[PathBuf],
+    output_path: &Path,
+    columns_to_drop: &[String],
+) -> Result<(), TblError> {
+    let first_path = match input_paths.first() {
+        Some(first_path) => first_path,
+        None => return Err(TblError::Error("No input files provided".to_string())),
+    };
+    let first = parse_metadata(&std::fs::File::open(first_path)?)?;
+    let first_schema = get_splice_schema(&first);
+    let descr = first.file_metadata().schema_descr();
+    let is_kept = |name: &str| !columns_to_drop.iter().any(|column| column == name);
+
+    // output schema keeps the parquet types of kept columns as they are
+    let root = descr.root_schema();
+    let fields = root
+        .get_fields()
+        .iter()
+        .filter(|field| is_kept(field.name()))
+        .cloned()
+        .collect();
+    let schema = Type::group_type_builder(root.name())
+        .with_fields(fields)
+        .build()?;
+
+    // the arrow schema stored in key value metadata must describe only the kept columns
+    let mut key_values = first
+        .file_metadata()
+        .key_value_metadata()
+        .cloned()
+        .unwrap_or_default();
+    if !columns_to_drop.is_empty() {
+        if let Some(kv) = key_values
+            .iter_mut()
+            .find(|kv| kv.key == ARROW_SCHEMA_META_KEY)
+        {
+            let arrow_schema =
+                parquet_to_arrow_schema(descr, first.file_metadata().key_value_metadata())?;
+            let arrow_fields: Vec<_> = arrow_schema
+                .fields()
+                .iter()
+                .filter(|field| is_kept(field.name()))
+                .cloned()
+                .collect();
+            let arrow_schema =
+                Schema::new_with_metadata(arrow_fields, arrow_schema.metadata().clone());
+            kv.value = encode_arrow_schema(Arc::new(arrow_schema))?;
+        }
+    }
+    let props = WriterProperties::builder()
+        .set_key_value_metadata(Some(key_values))
+        .build();
+    let output_file = std::fs::File::create(output_path)?;
+    let mut writer = SerializedFileWriter::new(output_file, Arc::new(schema), Arc::new(props))?;
+
+    for input_path in input_paths.iter() {
+        let input_file = std::fs::File::open(input_path)?;
+        let metadata = parse_metadata(&input_file)?;
+        if get_splice_schema(&metadata) != first_schema {
+            return Err(TblError::SchemaError(format!(
+                "schema of {} differs from schema of {}",
+                input_path.to_string_lossy(),
+                first_path.to_string_lossy()
+            )));
+        }
+        for row_group in metadata.row_groups().iter() {
+            let mut row_group_writer = writer.next_row_group()?;
+            for (i, column) in row_group.columns().iter().enumerate() {
+                if !is_kept(descr.get_column_root(i).name()) {
+                    continue;
+                }
+                // the writer moves offset index page locations along with the column chunk
+                let close = ColumnCloseResult {
+                    bytes_written: column.compressed_size() as u64,
+                    rows_written: row_group.num_rows() as u64,
+                    metadata: column.clone(),
+                    bloom_filter: None,
+                    column_index: read_page_index(
+                        &input_file,
+                        column.column_index_offset(),
+                        column.column_index_length(),
+                    )?,
+                    offset_index: read_page_index(
+                        &input_file,
+                        column.offset_index_offset(),
+                        column.offset_index_length(),
+                    )?,
+                };
+                row_group_writer.append_column(&input_file, close)?;
+            }
+            row_group_writer.close()?;
+        }
+    }
+    writer.close()?;
+    Ok(())
+}
+
+/// page index structure stored at an offset of a parquet file
+fn read_page_index<T: TSerializable>(
+    file: &std::fs::File,
+    offset: Option<i64>,
+    length: Option<i32>,
+) -> Result<Option<T>, TblError> {
+    let (offset, length) = match (offset, length) {
+        (Some(offset), Some(length)) => (offset as u64, length as usize),
+        _ => return Ok(None),
+    };
+    let bytes = file.get_bytes(offset, length)?;
+    let index = T::read_from_in_protocol(&mut TCompactInputProtocol::new(bytes.as_ref()))
+        .map_err(ParquetError::from)?;
+    Ok(Some(index))
+}
+
+/// parquet schema and stored arrow schema, which must both match for files to be spliced
+fn get_splice_schema(metadata: &ParquetMetaData) -> (Type, Option<String>) {
+    let file_metadata = metadata.file_metadata();
+    let arrow_schema = file_metadata.key_value_metadata().and_then(|key_values| {
+        key_values
+            .iter()
+            .find(|kv| kv.key == ARROW_SCHEMA_META_KEY)
+            .and_then(|kv| kv.value.clone())
+    });
+    (
+        file_metadata.schema_descr().root_schema().clone(),
+        arrow_schema,
+    )
+}
+
+/// arrow schema encoded the way arrow writers store it in key value metadata
+fn encode_arrow_schema(schema: arrow::datatypes::SchemaRef) -> Result<Option<String>, TblError> {
+    let writer = ArrowWriter::try_new(Vec::new(), schema, None)?;
+    let metadata = writer.close()?;
+    Ok(metadata
+        .key_value_metadata
+        .unwrap_or_default()
+        .into_iter()
+        .find(|kv| kv.key == ARROW_SCHEMA_META_KEY)
+        .and_then(|kv| kv.value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_splice_parquets() -> Result<(), TblError> {
+        let temp_dir = TempDir::new()?;
+        let mut paths = Vec::new();
+        for (i, blocks) in [[1u32, 2, 3], [4, 5, 6]].iter().enumerate() {
+            let path = temp_dir.path().join(format!("{}.parquet", i));
+            let mut df = df!("block" => blocks, "name" => ["a", "b", "c"], "x" => [1.0, 2.0, 3.0])?;
+            ParquetWriter::new(std::fs::File::create(&path)?).finish(&mut df)?;
+            paths.push(path);
+        }
+        let check = check_splice_parquets(&paths)?;
+        assert!(check.compatible && check.lossless);
+        assert_eq!(check.min_row_group_rows, 3);
+        assert!(!check.is_worthwhile());
+
+        // merged rows are read back in file order, without dropped columns
+        let output_path = temp_dir.path().join("merged.parquet");
+        splice_parquets(&paths, &output_path, &["x".to_string()])?;
+        let read = ParquetReader::new(std::fs::File::open(&output_path)?).finish()?;
+        let expected = df!(
+            "block" => [1u32, 2, 3, 4, 5, 6],
+            "name" => ["a", "b", "c", "a", "b", "c"]
+        )?;
+        assert!(read.equals(&expected));
+
+        // page indexes are copied, with page locations moved to where the chunks were written
+        let options = parquet::file::serialized_reader::ReadOptionsBuilder::new()
+            .with_page_index()
+            .build();
+        let reader = parquet::file::serialized_reader::SerializedFileReader::new_with_options(
+            std::fs::File::open(&output_path)?,
+            options,
+        )?;
+        let metadata = parquet::file::reader::FileReader::metadata(&reader);
+        let column = metadata.row_group(1).column(0);
+        let first_page = column
+            .dictionary_page_offset()
+            .unwrap_or(column.data_page_offset());
+        let offset_index = metadata.offset_index().expect("offset index");
+        assert_eq!(offset_index[1][0][0].offset, first_page);
+
+        // files with different schemas cannot be spliced
+        paths.push(output_path);
+        assert!(!check_splice_parquets(&paths)?.compatible);
+        Ok(())
+    }
+}
@@ -4,8 +4,11 @@ use parquet::arrow::async_reader::ParquetRecordBatchStreamBuilder;
 use polars::prelude::*;
 use std::collections::HashMap;
 
-/// get the number of rows in a parquet file
+/// get the number of rows in a parquet file, from its index sidecar if one is up to date
 pub async fn get_parquet_row_count(path: &std::path::Path) -> Result<u64, TblError> {
+    if let Some(index) = super::read_parquet_index(path) {
+        return Ok(index.n_rows);
+    }
     let file = tokio::fs::File::open(path).await?;
     let builder = ParquetRecordBatchStreamBuilder::new(file)
         .await?
@@ -16,8 +19,9 @@ pub async fn get_parquet_row_count(path: &std::path::Path) -> Result<u64, TblErr
 
 /// get the number of rows in multiple parquet files
 pub async fn get_parquet_row_counts(paths: &[&std::path::Path]) -> Result<Vec<u64>, TblError> {
+    let paths: Vec<std::path::PathBuf> = paths.iter().map(|path| path.to_path_buf()).collect();
     let row_counts = stream::iter(paths)
-        .map(|path| get_parquet_row_count(path))
+        .map(|path| async move { get_parquet_row_count(&path).await })
         .buffered(10)
         .collect::<Vec<Result<u64, TblError>>>()
         .await;
@@ -31,7 +35,7 @@ pub async fn get_parquet_row_counts(paths: &[&std::path::Path]) -> Result<Vec<u6
 pub async fn get_parquet_schema(path: &std::path::Path) -> Result<Arc<Schema>, TblError> {
     let path = path.to_path_buf();
     tokio::task::spawn_blocking(move || {
-        let scan_args = ScanArgsParquet::default();
+        let scan_args = crate::parquet::create_scan_args(std::slice::from_ref(&path));
         let mut lf = LazyFrame::scan_parquet(path, scan_args)?;
         let schema = lf.schema()?;
         Ok(schema)
@@ -129,6 +133,16 @@ pub fn get_parquet_n_bytes_uncompressed(
         .sum::<u64>()
 }
 
+/// get compressed bytes of each row group of parquet file
+pub async fn get_parquet_row_group_sizes(path: &std::path::Path) -> Result<Vec<u64>, TblError> {
+    let metadata = get_parquet_metadata(path).await?;
+    Ok(metadata
+        .row_groups()
+        .iter()
+        .map(|rg| rg.compressed_size() as u64)
+        .collect())
+}
+
 /// get column summaries for parquet file
 pub async fn get_parquet_column_summaries(
     metadata: Arc<parquet::file::metadata::ParquetMetaData>,
@@ -148,6 +162,33 @@ pub async fn get_parquet_column_summaries(
     Ok(columns)
 }
 
+/// get compressed and uncompressed bytes of each top-level column of parquet file
+///
+/// nested columns are stored as several leaf columns, whose sizes are added together
+pub async fn get_parquet_top_level_column_sizes(
+    path: &std::path::Path,
+) -> Result<Vec<(String, u64, u64)>, TblError> {
+    let metadata = get_parquet_metadata(path).await?;
+    let schema_descr = metadata.file_metadata().schema_descr();
+    let mut sizes: Vec<(String, u64, u64)> = Vec::new();
+    for i in 0..schema_descr.num_columns() {
+        let root = schema_descr.get_column_root(i).name().to_string();
+        if !sizes.iter().any(|(name, _, _)| name == &root) {
+            sizes.push((root, 0, 0));
+        }
+    }
+    for rg in metadata.row_groups() {
+        for (i, column_metadata) in rg.columns().iter().enumerate() {
+            let root = schema_descr.get_column_root(i).name();
+            if let Some(size) = sizes.iter_mut().find(|(name, _, _)| name == root) {
+                size.1 += column_metadata.compressed_size() as u64;
+                size.2 += column_metadata.uncompressed_size() as u64;
+            }
+        }
+    }
+    Ok(sizes)
+}
+
 /// get parquet schemas
 pub async fn get_parquet_summaries(
     paths: &[std::path::PathBuf],
@@ -0,0 +1,102 @@
+use crate::sketches::SpaceSaving;
+use crate::TblError;
+use arrow::array::Array;
+use arrow::datatypes::DataType;
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+use futures::StreamExt;
+use parquet::arrow::async_reader::ParquetRecordBatchStreamBuilder;
+use parquet::arrow::ProjectionMask;
+use std::path::PathBuf;
+
+/// approximate the most frequent values of a column across parquet files
+///
+/// streams only the requested column and keeps at most `capacity` counters in memory. null
+/// values are counted as `None`, apart from any string values that read "null"
+pub async fn approx_parquet_value_counts(
+    paths: &[PathBuf],
+    column: &str,
+    capacity: usize,
+    batch_size: usize,
+) -> Result<SpaceSaving<Option<String>>, TblError> {
+    let mut sketch = SpaceSaving::new(capacity);
+    for path in paths.iter() {
+        let file = tokio::fs::File::open(path).await?;
+        let builder = ParquetRecordBatchStreamBuilder::new(file)
+            .await?
+            .with_batch_size(batch_size);
+        let index = builder.schema().index_of(column).map_err(|_| {
+            TblError::SchemaError(format!(
+                "column '{}' not found in {}",
+                column,
+                path.to_string_lossy()
+            ))
+        })?;
+        let mask = ProjectionMask::roots(builder.parquet_schema(), [index]);
+        let mut reader_stream = builder.with_projection(mask).build()?;
+
+        while let Some(batch) = reader_stream.next().await {
+            let batch = batch?;
+            insert_array_values(&mut sketch, batch.column(0).as_ref())?;
+        }
+    }
+    Ok(sketch)
+}
+
+fn insert_array_values(
+    sketch: &mut SpaceSaving<Option<String>>,
+    array: &dyn Array,
+) -> Result<(), TblError> {
+    let options = FormatOptions::default();
+    let formatter = ArrayFormatter::try_new(array, &options)?;
+    let prefix = match array.data_type() {
+        DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_) => "0x",
+        _ => "",
+    };
+    for i in 0..array.len() {
+        if array.is_null(i) {
+            sketch.insert(None);
+        } else {
+            sketch.insert(Some(format!("{}{}", prefix, formatter.value(i))));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::StringArray;
+    use arrow::datatypes::{Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_nulls_counted_apart_from_null_strings() -> Result<(), TblError> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("data.parquet");
+        let schema = Arc::new(Schema::new(vec![Field::new("x", DataType::Utf8, true)]));
+        let values = StringArray::from(vec![Some("null"), None, None, Some("a"), None]);
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(values)])?;
+        let mut writer = ArrowWriter::try_new(std::fs::File::create(&path)?, schema, None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let sketch = approx_parquet_value_counts(&[path], "x", 10, 2).await?;
+        let top: Vec<(Option<String>, u64)> = sketch
+            .top_k(3)
+            .into_iter()
+            .map(|hitter| (hitter.item, hitter.count))
+            .collect();
+        assert_eq!(
+            top,
+            vec![
+                (None, 3),
+                (Some("null".to_string()), 1),
+                (Some("a".to_string()), 1)
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,317 @@
+use crate::TblError;
+use polars::prelude::*;
+use regex::Regex;
+
+/// smallest dtype that can hold values of both dtypes, if any
+///
+/// integers widen to the narrowest type holding both, mixing signed and unsigned integers
+/// gives a signed type, integers and floats give a float, dates and datetimes give the finer
+/// datetime, and strings hold any other primitive
+///
+/// 64-bit integers with floats, and u64 with signed integers, only promote when allow_lossy is
+/// set, since values beyond 2^53 or i64::MAX do not survive the cast
+pub fn promote_dtypes(left: &DataType, right: &DataType, allow_lossy: bool) -> Option<DataType> {
+    use DataType::*;
+    if left == right {
+        return Some(left.clone());
+    }
+    if !allow_lossy && is_lossy_pair(left, right) {
+        return None;
+    }
+    let promoted = match (left, right) {
+        (Null, other) | (other, Null) => other.clone(),
+        (l, r) if l.is_integer() && r.is_integer() => promote_integers(l, r),
+        (l, r) if l.is_float() && r.is_float() => Float64,
+        (Float32, r) | (r, Float32) if is_small_integer(r) => Float32,
+        (l, r) if l.is_numeric() && r.is_numeric() && !l.is_decimal() && !r.is_decimal() => Float64,
+        (Decimal(lp, ls), Decimal(rp, rs)) => {
+            let (lp, ls, rp, rs) = (
+                lp.unwrap_or(38),
+                ls.unwrap_or(0),
+                rp.unwrap_or(38),
+                rs.unwrap_or(0),
+            );
+            let scale = ls.max(rs);
+            let integer_digits = (lp - ls).max(rp - rs);
+            Decimal(Some((integer_digits + scale).min(38)), Some(scale))
+        }
+        (Datetime(lu, ltz), Datetime(ru, rtz)) if ltz == rtz => {
+            Datetime(finer_time_unit(*lu, *ru), ltz.clone())
+        }
+        (Date, Datetime(unit, tz)) | (Datetime(unit, tz), Date) => Datetime(*unit, tz.clone()),
+        (Duration(lu), Duration(ru)) => Duration(finer_time_unit(*lu, *ru)),
+        (List(l), List(r)) => List(Box::new(promote_dtypes(l, r, allow_lossy)?)),
+        (Binary, String) | (String, Binary) => Binary,
+        (String, other) | (other, String) if !other.is_nested() && *other != Binary => String,
+        (Categorical(..) | Enum(..), Categorical(..) | Enum(..)) => String,
+        _ => return None,
+    };
+    Some(promoted)
+}
+
+/// whether promoting the pair loses integer precision
+fn is_lossy_pair(left: &DataType, right: &DataType) -> bool {
+    let is_wide = |dtype: &DataType| matches!(dtype, DataType::Int64 | DataType::UInt64);
+    let wide_with_float =
+        (is_wide(left) && right.is_float()) || (is_wide(right) && left.is_float());
+    let u64_with_signed = (*left == DataType::UInt64 && right.is_signed_integer())
+        || (*right == DataType::UInt64 && left.is_signed_integer());
+    wide_with_float || u64_with_signed
+}
+
+fn is_small_integer(dtype: &DataType) -> bool {
+    matches!(
+        dtype,
+        DataType::Int8 | DataType::Int16 | DataType::UInt8 | DataType::UInt16
+    )
+}
+
+fn integer_bits(dtype: &DataType) -> u32 {
+    match dtype {
+        DataType::Int8 | DataType::UInt8 => 8,
+        DataType::Int16 | DataType::UInt16 => 16,
+        DataType::Int32 | DataType::UInt32 => 32,
+        _ => 64,
+    }
+}
+
+fn promote_integers(left: &DataType, right: &DataType) -> DataType {
+    let signed = left.is_signed_integer() || right.is_signed_integer();
+    let unsigned_bits = |dtype: &DataType| {
+        if dtype.is_unsigned_integer() && signed {
+            // an unsigned type needs one more bit once signed
+            (integer_bits(dtype) * 2).min(64)
+        } else {
+            integer_bits(dtype)
+        }
+    };
+    let bits = unsigned_bits(left).max(unsigned_bits(right));
+    match (signed, bits) {
+        (true, 8) => DataType::Int8,
+        (true, 16) => DataType::Int16,
+        (true, 32) => DataType::Int32,
+        (true, _) => DataType::Int64,
+        (false, 8) => DataType::UInt8,
+        (false, 16) => DataType::UInt16,
+        (false, 32) => DataType::UInt32,
+        (false, _) => DataType::UInt64,
+    }
+}
+
+fn finer_time_unit(left: TimeUnit, right: TimeUnit) -> TimeUnit {
+    match (left, right) {
+        (TimeUnit::Nanoseconds, _) | (_, TimeUnit::Nanoseconds) => TimeUnit::Nanoseconds,
+        (TimeUnit::Microseconds, _) | (_, TimeUnit::Microseconds) => TimeUnit::Microseconds,
+        _ => TimeUnit::Milliseconds,
+    }
+}
+
+/// union of schemas, ordering columns by first appearance and promoting differing dtypes
+pub fn union_schemas(schemas: &[Arc<Schema>], allow_lossy: bool) -> Result<Schema, TblError> {
+    let mut union = Schema::new();
+    for schema in schemas.iter() {
+        for (name, dtype) in schema.iter() {
+            let promoted = match union.get(name) {
+                Some(existing) => match promote_dtypes(existing, dtype, allow_lossy) {
+                    Some(promoted) => promoted,
+                    None => {
+                        let reason = match promote_dtypes(existing, dtype, true) {
+                            Some(lossy) => format!("only promote lossily to {}", lossy),
+                            None => "are incompatible".to_string(),
+                        };
+                        return Err(TblError::SchemaError(format!(
+                            "column {} has types {} and {}, which {}",
+                            name, existing, dtype, reason
+                        )));
+                    }
+                },
+                None => dtype.clone(),
+            };
+            union.with_column(name.clone(), promoted);
+        }
+    }
+    Ok(union)
+}
+
+/// expand column selectors against schema into column names
+///
+/// `re:PATTERN` selects columns matching a regex, `glob:PATTERN` columns matching a glob
+/// of `*` and `?` wildcards, and `dtype:PATTERN` columns whose dtype matches a glob such as
+/// `binary` or `list<*>`, in schema order, while other selectors are column names kept as
+/// given; names selected more than once are kept once
+pub fn resolve_column_selectors(
+    selectors: &[String],
+    schema: &Schema,
+) -> Result<Vec<String>, TblError> {
+    let mut names: Vec<String> = Vec::new();
+    for selector in selectors.iter() {
+        let pattern = if let Some(pattern) = selector.strip_prefix("re:") {
+            Some(Regex::new(pattern).map_err(|e| {
+                TblError::InputError(format!("invalid column regex {}: {}", pattern, e))
+            })?)
+        } else if let Some(pattern) = selector.strip_prefix("glob:") {
+            Some(glob_to_regex(pattern)?)
+        } else {
+            None
+        };
+        let matched: Vec<String> = match (pattern, selector.strip_prefix("dtype:")) {
+            (Some(pattern), _) => schema
+                .iter_names()
+                .filter(|name| pattern.is_match(name))
+                .map(|name| name.to_string())
+                .collect(),
+            (None, Some(dtype_pattern)) => {
+                let pattern = glob_to_regex(&normalize_dtype_name(dtype_pattern))?;
+                schema
+                    .iter()
+                    .filter(|(_, dtype)| dtype_matches(&pattern, dtype))
+                    .map(|(name, _)| name.to_string())
+                    .collect()
+            }
+            (None, None) => vec![selector.clone()],
+        };
+        for name in matched.into_iter() {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// whether selector is a pattern rather than a column name
+pub fn is_column_pattern(selector: &str) -> bool {
+    selector.starts_with("re:") || selector.starts_with("glob:") || selector.starts_with("dtype:")
+}
+
+/// whether dtype matches a dtype glob, where a bare outer type such as `list` or `datetime`
+/// matches every parameterization of that type
+fn dtype_matches(pattern: &Regex, dtype: &DataType) -> bool {
+    let name = normalize_dtype_name(&dtype.to_string());
+    let outer = name.split('<').next().unwrap_or(&name);
+    pattern.is_match(&name) || pattern.is_match(outer)
+}
+
+/// lowercase dtype name using angle brackets for parameters and polars names for arrow aliases
+fn normalize_dtype_name(name: &str) -> String {
+    let name = name
+        .trim()
+        .to_lowercase()
+        .replace('[', "<")
+        .replace(']', ">")
+        .replace(' ', "");
+    let (outer, rest) = match name.find('<') {
+        Some(index) => name.split_at(index),
+        None => (name.as_str(), ""),
+    };
+    // polars stores binary, string, and list columns with 64 bit offsets, so large types match
+    let outer = match outer {
+        "large_binary" => "binary",
+        "string" | "utf8" | "large_string" | "large_utf8" => "str",
+        "large_list" => "list",
+        "boolean" => "bool",
+        "int8" => "i8",
+        "int16" => "i16",
+        "int32" => "i32",
+        "int64" => "i64",
+        "uint8" => "u8",
+        "uint16" => "u16",
+        "uint32" => "u32",
+        "uint64" => "u64",
+        "float32" => "f32",
+        "float64" => "f64",
+        other => other,
+    };
+    format!("{}{}", outer, rest)
+}
+
+fn glob_to_regex(pattern: &str) -> Result<Regex, TblError> {
+    let mut regex = String::from("^");
+    for c in pattern.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex)
+        .map_err(|e| TblError::InputError(format!("invalid column glob {}: {}", pattern, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_promote_dtypes() {
+        use DataType::*;
+        let promote_dtypes = |left: &DataType, right: &DataType| promote_dtypes(left, right, false);
+        assert_eq!(promote_dtypes(&Int32, &Int64), Some(Int64));
+        assert_eq!(promote_dtypes(&UInt8, &Int8), Some(Int16));
+        assert_eq!(promote_dtypes(&UInt32, &UInt16), Some(UInt32));
+        assert_eq!(promote_dtypes(&Int16, &Float32), Some(Float32));
+        assert_eq!(promote_dtypes(&Int32, &Float32), Some(Float64));
+        assert_eq!(promote_dtypes(&Int64, &Float64), None);
+        assert_eq!(promote_dtypes(&UInt64, &Int64), None);
+        assert_eq!(super::promote_dtypes(&Int64, &Float32, true), Some(Float64));
+        assert_eq!(promote_dtypes(&Null, &String), Some(String));
+        assert_eq!(promote_dtypes(&Int64, &String), Some(String));
+        assert_eq!(
+            promote_dtypes(&Decimal(Some(10), Some(2)), &Decimal(Some(12), Some(4))),
+            Some(Decimal(Some(14), Some(4)))
+        );
+        assert_eq!(
+            promote_dtypes(
+                &Datetime(TimeUnit::Milliseconds, None),
+                &Datetime(TimeUnit::Microseconds, None)
+            ),
+            Some(Datetime(TimeUnit::Microseconds, None))
+        );
+        assert_eq!(promote_dtypes(&Boolean, &Int64), None);
+
+        let a = Schema::from_iter(vec![Field::new("x", Int32), Field::new("y", String)]);
+        let b = Schema::from_iter(vec![Field::new("z", Boolean), Field::new("x", Float64)]);
+        let union = union_schemas(&[Arc::new(a), Arc::new(b)], false).unwrap();
+        let names: Vec<&str> = union.iter_names().map(|name| name.as_str()).collect();
+        assert_eq!(names, vec!["x", "y", "z"]);
+        assert_eq!(union.get("x"), Some(&Float64));
+    }
+
+    #[test]
+    fn test_resolve_column_selectors() {
+        let schema = Schema::from_iter(vec![
+            Field::new("trace_id", DataType::Int64),
+            Field::new("fee_base", DataType::Int64),
+            Field::new("fee_priority", DataType::Int64),
+            Field::new("trace_address", DataType::String),
+        ]);
+        let resolve = |selectors: &[&str]| {
+            let selectors: Vec<String> = selectors.iter().map(|s| s.to_string()).collect();
+            resolve_column_selectors(&selectors, &schema).unwrap()
+        };
+        assert_eq!(resolve(&["re:^trace_"]), vec!["trace_id", "trace_address"]);
+        assert_eq!(resolve(&["glob:fee_*"]), vec!["fee_base", "fee_priority"]);
+        assert_eq!(
+            resolve(&["fee_base", "glob:fee_*", "other"]),
+            vec!["fee_base", "fee_priority", "other"]
+        );
+        assert!(resolve(&["glob:fee?"]).is_empty());
+        assert_eq!(resolve(&["dtype:str"]), vec!["trace_address"]);
+        assert_eq!(resolve(&["dtype:large_utf8"]), vec!["trace_address"]);
+
+        let nested = Schema::from_iter(vec![
+            Field::new("blob", DataType::Binary),
+            Field::new("values", DataType::List(Box::new(DataType::Int64))),
+            Field::new("ts", DataType::Datetime(TimeUnit::Milliseconds, None)),
+        ]);
+        let resolve =
+            |selector: &str| resolve_column_selectors(&[selector.to_string()], &nested).unwrap();
+        assert_eq!(resolve("dtype:large_binary"), vec!["blob"]);
+        assert_eq!(resolve("dtype:list<*>"), vec!["values"]);
+        assert_eq!(resolve("dtype:list<i64>"), vec!["values"]);
+        assert_eq!(resolve("dtype:list"), vec!["values"]);
+        assert_eq!(resolve("dtype:datetime"), vec!["ts"]);
+        assert!(resolve("dtype:list<str>").is_empty());
+    }
+}
@@ -0,0 +1,144 @@
+/// hyperloglog sketch for approximating the number of distinct values in a stream
+///
+/// each value is hashed to one of 2^precision registers, which keep the longest run of leading
+/// zeros seen. relative error is roughly 1.04 / sqrt(2^precision)
+#[derive(Clone, Debug, PartialEq)]
+pub struct HllSketch {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HllSketch {
+    /// create new sketch with 2^precision registers, precision is clamped to 4..=16
+    pub fn new(precision: u8) -> Self {
+        let precision = precision.clamp(4, 16);
+        HllSketch {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    /// add a value given by its 64 bit representation
+    pub fn insert(&mut self, bits: u64) {
+        let hash = mix(bits);
+        let index = (hash >> (64 - self.precision)) as usize;
+        let rest = hash << self.precision;
+        let rank = (rest.leading_zeros() as u8).min(64 - self.precision) + 1;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    /// add a float value, treating -0.0 as 0.0 and ignoring NaN values
+    pub fn insert_f64(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        let value = if value == 0.0 { 0.0 } else { value };
+        self.insert(value.to_bits());
+    }
+
+    /// merge values of another sketch into this sketch
+    ///
+    /// sketches of different precisions cannot be merged, returns whether merge happened
+    pub fn merge(&mut self, other: &HllSketch) -> bool {
+        if other.precision != self.precision {
+            return false;
+        }
+        for (register, other) in self.registers.iter_mut().zip(other.registers.iter()) {
+            *register = (*register).max(*other);
+        }
+        true
+    }
+
+    /// approximate number of distinct values inserted
+    pub fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|register| 2f64.powi(-(*register as i32)))
+            .sum();
+        let estimate = alpha * m * m / sum;
+        let n_zeros = self
+            .registers
+            .iter()
+            .filter(|register| **register == 0)
+            .count();
+        // linear counting is more accurate while many registers are empty
+        if estimate <= 2.5 * m && n_zeros > 0 {
+            (m * (m / n_zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+
+    /// encode sketch as text, registers are hex encoded
+    pub fn to_text(&self) -> String {
+        format!(
+            "precision={}\nregisters={}\n",
+            self.precision,
+            hex::encode(&self.registers)
+        )
+    }
+
+    /// decode sketch encoded by `to_text`
+    pub fn from_text(text: &str) -> Option<HllSketch> {
+        let mut lines = text.lines();
+        let precision: u8 = lines.next()?.strip_prefix("precision=")?.parse().ok()?;
+        let registers = hex::decode(lines.next()?.strip_prefix("registers=")?).ok()?;
+        if !(4..=16).contains(&precision) || registers.len() != 1 << precision {
+            return None;
+        }
+        Some(HllSketch {
+            precision,
+            registers,
+        })
+    }
+}
+
+/// splitmix64 finalizer, spreading similar inputs such as consecutive integers across all bits
+fn mix(bits: u64) -> u64 {
+    let mut z = bits.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hll_sketch() {
+        let mut sketch = HllSketch::new(12);
+        for i in 0..100_000u64 {
+            sketch.insert(i % 50_000);
+        }
+        let estimate = sketch.estimate() as f64;
+        assert!((estimate - 50_000.0).abs() < 2_500.0);
+
+        // small counts are exact or nearly so
+        let mut small = HllSketch::new(12);
+        for i in 0..10u64 {
+            small.insert(i);
+            small.insert(i);
+        }
+        assert_eq!(small.estimate(), 10);
+
+        // merging overlapping sketches counts shared values once
+        let mut other = HllSketch::new(12);
+        for i in 25_000..75_000u64 {
+            other.insert(i);
+        }
+        assert!(sketch.merge(&other));
+        let estimate = sketch.estimate() as f64;
+        assert!((estimate - 75_000.0).abs() < 3_750.0);
+        assert!(!sketch.merge(&HllSketch::new(10)));
+
+        let decoded = HllSketch::from_text(&sketch.to_text()).unwrap();
+        assert_eq!(decoded, sketch);
+        assert!(HllSketch::from_text("precision=12\nregisters=00\n").is_none());
+    }
+}
@@ -0,0 +1,296 @@
+/// kll sketch for approximating quantiles of a stream of numbers
+///
+/// items are kept in a hierarchy of compactors, where items at level h stand in
+/// for 2^h inserted items. memory is O(k log(n / k)) and rank error is roughly
+/// proportional to 1 / k
+#[derive(Clone, Debug)]
+pub struct KllSketch {
+    k: usize,
+    compactors: Vec<Vec<f64>>,
+    n_items: u64,
+    min: f64,
+    max: f64,
+    flip: bool,
+}
+
+impl KllSketch {
+    /// create new sketch with accuracy parameter `k`
+    pub fn new(k: usize) -> Self {
+        KllSketch {
+            k: k.max(8),
+            compactors: vec![Vec::new()],
+            n_items: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            flip: false,
+        }
+    }
+
+    /// add value to sketch, NaN values are ignored
+    pub fn insert(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        self.n_items += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.compactors[0].push(value);
+        let size: usize = self.compactors.iter().map(|c| c.len()).sum();
+        let max_size: usize = (0..self.compactors.len())
+            .map(|level| self.level_capacity(level))
+            .sum();
+        if size >= max_size {
+            self.compress();
+        }
+    }
+
+    /// merge values of another sketch into this sketch
+    pub fn merge(&mut self, other: &KllSketch) {
+        if other.n_items == 0 {
+            return;
+        }
+        self.n_items += other.n_items;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        while self.compactors.len() < other.compactors.len() {
+            self.compactors.push(Vec::new());
+        }
+        for (level, items) in other.compactors.iter().enumerate() {
+            self.compactors[level].extend(items.iter().copied());
+        }
+        loop {
+            let size: usize = self.compactors.iter().map(|c| c.len()).sum();
+            let max_size: usize = (0..self.compactors.len())
+                .map(|level| self.level_capacity(level))
+                .sum();
+            if size < max_size {
+                break;
+            }
+            self.compress();
+        }
+    }
+
+    /// number of values inserted into sketch
+    pub fn n_items(&self) -> u64 {
+        self.n_items
+    }
+
+    /// smallest inserted value
+    pub fn min(&self) -> Option<f64> {
+        (self.n_items > 0).then_some(self.min)
+    }
+
+    /// largest inserted value
+    pub fn max(&self) -> Option<f64> {
+        (self.n_items > 0).then_some(self.max)
+    }
+
+    /// approximate value at quantile `q` in [0, 1]
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.n_items == 0 {
+            return None;
+        }
+        if q <= 0.0 {
+            return Some(self.min);
+        }
+        if q >= 1.0 {
+            return Some(self.max);
+        }
+        let target = q * self.n_items as f64;
+        let mut cumulative = 0u64;
+        for (value, weight) in self.weighted_items() {
+            cumulative += weight;
+            if cumulative as f64 >= target {
+                return Some(value);
+            }
+        }
+        Some(self.max)
+    }
+
+    /// encode sketch as lines of text, one line per compactor level
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![
+            format!("k={}", self.k),
+            format!("n_items={}", self.n_items),
+            format!("min={}", self.min),
+            format!("max={}", self.max),
+            format!("flip={}", self.flip),
+        ];
+        for items in self.compactors.iter() {
+            let items: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+            lines.push(format!("level={}", items.join(",")));
+        }
+        lines.join("\n") + "\n"
+    }
+
+    /// decode sketch encoded by `to_text`
+    pub fn from_text(text: &str) -> Option<KllSketch> {
+        let mut lines = text.lines().map(|line| line.split_once('='));
+        let mut field = |name: &str| match lines.next()? {
+            Some((key, value)) if key == name => Some(value.to_string()),
+            _ => None,
+        };
+        let k = field("k")?.parse().ok()?;
+        let n_items = field("n_items")?.parse().ok()?;
+        let min = field("min")?.parse().ok()?;
+        let max = field("max")?.parse().ok()?;
+        let flip = field("flip")?.parse().ok()?;
+        let mut compactors = Vec::new();
+        while let Some(items) = field("level") {
+            let items = items
+                .split(',')
+                .filter(|item| !item.is_empty())
+                .map(|item| item.parse().ok())
+                .collect::<Option<Vec<f64>>>()?;
+            compactors.push(items);
+        }
+        if compactors.is_empty() {
+            return None;
+        }
+        Some(KllSketch {
+            k,
+            compactors,
+            n_items,
+            min,
+            max,
+            flip,
+        })
+    }
+
+    /// approximate number of inserted values less than `value`
+    pub fn rank(&self, value: f64) -> u64 {
+        self.compactors
+            .iter()
+            .enumerate()
+            .map(|(level, items)| (items.iter().filter(|x| **x < value).count() as u64) << level)
+            .sum()
+    }
+
+    /// approximate counts of `n_bins` equal width bins spanning min to max
+    ///
+    /// returns (lower bound, upper bound, count) of each bin
+    pub fn histogram(&self, n_bins: usize) -> Vec<(f64, f64, u64)> {
+        if self.n_items == 0 || n_bins == 0 {
+            return Vec::new();
+        }
+        let width = (self.max - self.min) / n_bins as f64;
+        let mut bins = Vec::with_capacity(n_bins);
+        let mut previous_rank = 0;
+        for i in 0..n_bins {
+            let lower = self.min + width * i as f64;
+            let upper = if i + 1 == n_bins {
+                self.max
+            } else {
+                self.min + width * (i + 1) as f64
+            };
+            let rank = if i + 1 == n_bins {
+                self.n_items
+            } else {
+                self.rank(upper)
+            };
+            bins.push((lower, upper, rank.saturating_sub(previous_rank)));
+            previous_rank = rank.max(previous_rank);
+        }
+        bins
+    }
+
+    fn weighted_items(&self) -> Vec<(f64, u64)> {
+        let mut items: Vec<(f64, u64)> = self
+            .compactors
+            .iter()
+            .enumerate()
+            .flat_map(|(level, items)| items.iter().map(move |x| (*x, 1u64 << level)))
+            .collect();
+        items.sort_by(|a, b| a.0.total_cmp(&b.0));
+        items
+    }
+
+    fn level_capacity(&self, level: usize) -> usize {
+        let depth = self.compactors.len() - level - 1;
+        let capacity = self.k as f64 * (2.0f64 / 3.0).powi(depth as i32);
+        (capacity.ceil() as usize).max(2)
+    }
+
+    /// compact the lowest full level, promoting every other item to the next level
+    fn compress(&mut self) {
+        for level in 0..self.compactors.len() {
+            if self.compactors[level].len() >= self.level_capacity(level) {
+                if level + 1 == self.compactors.len() {
+                    self.compactors.push(Vec::new());
+                }
+                let mut items = std::mem::take(&mut self.compactors[level]);
+                items.sort_by(|a, b| a.total_cmp(b));
+                if items.len() % 2 == 1 {
+                    // keep one item at this level so that total weight is preserved
+                    if let Some(item) = items.pop() {
+                        self.compactors[level].push(item);
+                    }
+                }
+                let offset = self.flip as usize;
+                self.flip = !self.flip;
+                let promoted: Vec<f64> = items.into_iter().skip(offset).step_by(2).collect();
+                self.compactors[level + 1].extend(promoted);
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kll_quantiles_and_histogram() {
+        let mut sketch = KllSketch::new(200);
+        let n = 100_000;
+        for i in 0..n {
+            // insert in a scrambled order
+            sketch.insert(((i * 7919) % n) as f64);
+        }
+        assert_eq!(sketch.n_items(), n as u64);
+        assert_eq!(sketch.min(), Some(0.0));
+        assert_eq!(sketch.max(), Some((n - 1) as f64));
+        for q in [0.1, 0.25, 0.5, 0.75, 0.9] {
+            let estimate = sketch.quantile(q).unwrap();
+            let error = (estimate - q * n as f64).abs() / n as f64;
+            assert!(error < 0.02, "q={} estimate={}", q, estimate);
+        }
+
+        let bins = sketch.histogram(10);
+        assert_eq!(bins.len(), 10);
+        assert_eq!(
+            bins.iter().map(|(_, _, count)| count).sum::<u64>(),
+            n as u64
+        );
+        for (_, _, count) in bins.iter() {
+            assert!((*count as f64 - 10_000.0).abs() < 2_000.0);
+        }
+
+        let decoded = KllSketch::from_text(&sketch.to_text()).unwrap();
+        assert_eq!(decoded.n_items(), sketch.n_items());
+        assert_eq!(decoded.quantile(0.5), sketch.quantile(0.5));
+        assert!(KllSketch::from_text(&KllSketch::new(200).to_text()).is_some());
+    }
+
+    #[test]
+    fn test_kll_merge() {
+        let n = 100_000;
+        let mut sketches = [KllSketch::new(200), KllSketch::new(200)];
+        for i in 0..n {
+            sketches[i % 2].insert(((i * 7919) % n) as f64);
+        }
+        let mut merged = KllSketch::new(200);
+        for sketch in sketches.iter() {
+            merged.merge(sketch);
+        }
+        assert_eq!(merged.n_items(), n as u64);
+        assert_eq!(merged.min(), Some(0.0));
+        assert_eq!(merged.max(), Some((n - 1) as f64));
+        for q in [0.01, 0.25, 0.5, 0.75, 0.99] {
+            let estimate = merged.quantile(q).unwrap();
+            let error = (estimate - q * n as f64).abs() / n as f64;
+            assert!(error < 0.02, "q={} estimate={}", q, estimate);
+        }
+    }
+}
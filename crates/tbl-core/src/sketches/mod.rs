@@ -0,0 +1,11 @@
+/// space saving heavy hitters sketch
+pub mod space_saving;
+pub use space_saving::*;
+
+/// kll quantile sketch
+pub mod kll;
+pub use kll::*;
+
+/// hyperloglog distinct count sketch
+pub mod hll;
+pub use hll::*;
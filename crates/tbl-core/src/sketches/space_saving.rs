@@ -0,0 +1,170 @@
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hash;
+
+/// space saving sketch for finding the most frequent items of a stream
+///
+/// memory is bounded by `capacity` counters. each reported count overestimates
+/// the true count by at most its reported error, and any item whose true count
+/// exceeds `n_items / capacity` is guaranteed to be tracked
+///
+/// counters are ordered by count, so the smallest counter is replaced in O(log capacity). among
+/// counters with equal counts, the one tracked longest is replaced first
+#[derive(Clone, Debug)]
+pub struct SpaceSaving<K> {
+    capacity: usize,
+    n_items: u64,
+    /// count, error, and sequence number of each tracked item
+    counters: HashMap<K, Counter>,
+    /// count and sequence number of each tracked item, smallest first
+    order: BTreeSet<(u64, u64)>,
+    /// item of each sequence number
+    items: HashMap<u64, K>,
+    next_seq: u64,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Counter {
+    count: u64,
+    error: u64,
+    seq: u64,
+}
+
+/// heavy hitter reported by a SpaceSaving sketch
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeavyHitter<K> {
+    /// item
+    pub item: K,
+    /// estimated count, an upper bound of the true count
+    pub count: u64,
+    /// maximum overestimation of count
+    pub error: u64,
+}
+
+impl<K: Hash + Eq + Clone> SpaceSaving<K> {
+    /// create new sketch that tracks at most `capacity` items
+    pub fn new(capacity: usize) -> Self {
+        SpaceSaving {
+            capacity: capacity.max(1),
+            n_items: 0,
+            counters: HashMap::with_capacity(capacity),
+            order: BTreeSet::new(),
+            items: HashMap::with_capacity(capacity),
+            next_seq: 0,
+        }
+    }
+
+    /// add one occurrence of item
+    pub fn insert(&mut self, item: K) {
+        self.n_items += 1;
+        if let Some(counter) = self.counters.get_mut(&item) {
+            self.order.remove(&(counter.count, counter.seq));
+            counter.count += 1;
+            self.order.insert((counter.count, counter.seq));
+            return;
+        }
+        let (count, error) = if self.counters.len() < self.capacity {
+            (1, 0)
+        } else {
+            // replace the item with the smallest count
+            let (min_count, min_seq) = self.order.pop_first().expect("sketch capacity is nonzero");
+            if let Some(min_item) = self.items.remove(&min_seq) {
+                self.counters.remove(&min_item);
+            }
+            (min_count + 1, min_count)
+        };
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.order.insert((count, seq));
+        self.items.insert(seq, item.clone());
+        self.counters.insert(item, Counter { count, error, seq });
+    }
+
+    /// number of items inserted into sketch
+    pub fn n_items(&self) -> u64 {
+        self.n_items
+    }
+
+    /// maximum number of items tracked by sketch
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// upper bound on the count error of any reported item
+    pub fn error_bound(&self) -> u64 {
+        self.n_items / (self.capacity as u64)
+    }
+
+    /// get the k most frequent items, sorted by descending count
+    ///
+    /// items with equal counts are ordered by when they were first tracked
+    pub fn top_k(&self, k: usize) -> Vec<HeavyHitter<K>> {
+        self.order
+            .iter()
+            .rev()
+            .map(|(count, seq)| (std::cmp::Reverse(*count), *seq))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .take(k)
+            .filter_map(|(_, seq)| {
+                let item = self.items.get(&seq)?;
+                let counter = self.counters.get(item)?;
+                Some(HeavyHitter {
+                    item: item.clone(),
+                    count: counter.count,
+                    error: counter.error,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_when_under_capacity() {
+        let mut sketch = SpaceSaving::new(10);
+        for item in ["a", "b", "a", "c", "a", "b"] {
+            sketch.insert(item);
+        }
+        let top = sketch.top_k(2);
+        assert_eq!(top[0].item, "a");
+        assert_eq!(top[0].count, 3);
+        assert_eq!(top[0].error, 0);
+        assert_eq!(top[1].item, "b");
+        assert_eq!(top[1].count, 2);
+        assert_eq!(sketch.n_items(), 6);
+    }
+
+    #[test]
+    fn test_heavy_hitters_survive_eviction() {
+        let mut sketch = SpaceSaving::new(4);
+        for i in 0..1000u64 {
+            sketch.insert(if i % 2 == 0 { 0 } else { i });
+        }
+        let top = sketch.top_k(1);
+        assert_eq!(top[0].item, 0);
+        assert!(top[0].count >= 500);
+        assert!(top[0].count - top[0].error <= 500);
+        assert!(top[0].error <= sketch.error_bound());
+    }
+
+    #[test]
+    fn test_ties_are_deterministic() {
+        // the longest tracked of the smallest counters is replaced, whatever the hash order
+        let mut sketch = SpaceSaving::new(3);
+        for item in ["a", "b", "c", "c", "d"] {
+            sketch.insert(item);
+        }
+        let top = sketch.top_k(3);
+        let items: Vec<&str> = top.iter().map(|hitter| hitter.item).collect();
+        assert_eq!(items, vec!["c", "d", "b"]);
+        assert_eq!(top[1].count, 2);
+        assert_eq!(top[1].error, 1);
+
+        sketch.insert("e");
+        let items: Vec<&str> = sketch.top_k(3).iter().map(|hitter| hitter.item).collect();
+        assert_eq!(items, vec!["c", "d", "e"]);
+    }
+}
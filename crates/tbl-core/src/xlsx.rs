@@ -0,0 +1,584 @@
+use crate::TblError;
+use polars::prelude::*;
+use std::io::Write;
+use std::path::Path;
+
+/// most rows of a sheet, including its header row
+pub const XLSX_MAX_ROWS: usize = 1_048_576;
+
+/// most columns of a sheet
+pub const XLSX_MAX_COLUMNS: usize = 16_384;
+
+/// most characters of a cell
+const XLSX_MAX_CELL_CHARS: usize = 32_767;
+
+/// integers below this have at most the 15 significant digits that excel keeps of numbers
+const MAX_EXACT_INTEGER: u64 = 1_000_000_000_000_000;
+
+/// days between the excel epoch of 1899-12-30 and the unix epoch
+const EXCEL_UNIX_EPOCH_DAYS: f64 = 25_569.0;
+
+/// check that a sheet name is valid, names have 1 to 31 characters and none of `[]:*?/\`
+pub fn validate_sheet_name(name: &str) -> Result<(), TblError> {
+    let n_chars = name.chars().count();
+    if n_chars == 0 || n_chars > 31 {
+        return Err(TblError::InputError(format!(
+            "sheet name {} must have 1 to 31 characters",
+            name
+        )));
+    }
+    if name.contains(['[', ']', ':', '*', '?', '/', '\\']) || name.starts_with('\'') {
+        return Err(TblError::InputError(format!(
+            "sheet name {} cannot contain []:*?/\\ or start with '",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// sheet name derived from a file name, replacing invalid characters and truncating
+pub fn default_sheet_name(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name: String = stem
+        .trim_start_matches('\'')
+        .chars()
+        .map(|c| if "[]:*?/\\".contains(c) { '_' } else { c })
+        .take(31)
+        .collect();
+    if name.is_empty() {
+        "Sheet1".to_string()
+    } else {
+        name
+    }
+}
+
+/// write dataframe to a single sheet of an excel workbook
+///
+/// numbers, booleans, dates, datetimes, and times are stored as typed cells, other columns
+/// as text, with binary values encoded as 0x-prefixed hex and null values left empty
+pub fn write_xlsx(df: &DataFrame, path: &Path, sheet_name: &str) -> Result<(), TblError> {
+    validate_sheet_name(sheet_name)?;
+    let columns = sheet_columns(df)?;
+    let workbook = format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" "#,
+            r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">"#,
+            r#"<sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets></workbook>"#
+        ),
+        escape_xml(sheet_name)
+    );
+    let parts = [
+        ("[Content_Types].xml", CONTENT_TYPES),
+        ("_rels/.rels", ROOT_RELS),
+        ("xl/workbook.xml", workbook.as_str()),
+        ("xl/_rels/workbook.xml.rels", WORKBOOK_RELS),
+        ("xl/styles.xml", STYLES),
+    ];
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut zip = ZipWriter::new(file);
+    for (name, contents) in parts.iter() {
+        zip.add_file(name, |writer| Ok(writer.write_all(contents.as_bytes())?))?;
+    }
+    // the sheet is deflated as it is rendered, so it is never held in memory whole
+    zip.add_file("xl/worksheets/sheet1.xml", |writer| {
+        write_sheet_xml(df, &columns, writer)
+    })?;
+    zip.finish()?.flush()?;
+    Ok(())
+}
+
+/// columns of a dataframe converted to sheet cells, checking that they fit in a sheet
+fn sheet_columns(df: &DataFrame) -> Result<Vec<SheetColumn>, TblError> {
+    if df.height() + 1 > XLSX_MAX_ROWS {
+        return Err(TblError::InputError(format!(
+            "{} rows exceed the limit of {} rows of a sheet",
+            df.height(),
+            XLSX_MAX_ROWS - 1
+        )));
+    }
+    if df.width() > XLSX_MAX_COLUMNS {
+        return Err(TblError::InputError(format!(
+            "{} columns exceed the limit of {} columns of a sheet",
+            df.width(),
+            XLSX_MAX_COLUMNS
+        )));
+    }
+    df.get_columns().iter().map(SheetColumn::new).collect()
+}
+
+/// write worksheet xml of a dataframe, with a header row of column names
+fn write_sheet_xml(
+    df: &DataFrame,
+    columns: &[SheetColumn],
+    sheet: &mut dyn Write,
+) -> Result<(), TblError> {
+    let letters: Vec<String> = (0..df.width()).map(column_letters).collect();
+
+    // header row, then one row per record
+    sheet.write_all(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+            r#"<sheetData><row r="1">"#
+        )
+        .as_bytes(),
+    )?;
+    for (name, letter) in df.get_column_names().iter().zip(letters.iter()) {
+        write_text_cell(sheet, &format!("{}1", letter), name)?;
+    }
+    sheet.write_all(b"</row>")?;
+    for i in 0..df.height() {
+        let row = i + 2;
+        write!(sheet, r#"<row r="{}">"#, row)?;
+        for (column, letter) in columns.iter().zip(letters.iter()) {
+            column.write_cell(sheet, &format!("{}{}", letter, row), i)?;
+        }
+        sheet.write_all(b"</row>")?;
+    }
+    sheet.write_all(b"</sheetData></worksheet>")?;
+    Ok(())
+}
+
+/// cell style indices into the cellXfs of the stylesheet
+#[derive(Clone, Copy)]
+enum Style {
+    General = 0,
+    Date = 1,
+    Datetime = 2,
+    Time = 3,
+}
+
+/// values of a column converted to the kinds of cells a sheet holds
+///
+/// integers are written in their own digits rather than through floats, and integers of more
+/// than 15 digits are written as text, because excel rounds numbers to 15 significant digits
+enum SheetColumn {
+    Int(Int64Chunked),
+    UInt(UInt64Chunked),
+    Number(Float64Chunked, Style),
+    Boolean(BooleanChunked),
+    Text(StringChunked),
+}
+
+impl SheetColumn {
+    fn new(series: &Series) -> Result<SheetColumn, TblError> {
+        let column = match series.dtype() {
+            DataType::Boolean => SheetColumn::Boolean(series.bool()?.clone()),
+            DataType::Date => {
+                let days = series.cast(&DataType::Int32)?.cast(&DataType::Float64)?;
+                let serial = days.f64()? + EXCEL_UNIX_EPOCH_DAYS;
+                SheetColumn::Number(serial, Style::Date)
+            }
+            DataType::Datetime(unit, _) => {
+                let per_day = match unit {
+                    TimeUnit::Nanoseconds => 86_400e9,
+                    TimeUnit::Microseconds => 86_400e6,
+                    TimeUnit::Milliseconds => 86_400e3,
+                };
+                let values = series.cast(&DataType::Int64)?.cast(&DataType::Float64)?;
+                let serial = values.f64()? / per_day + EXCEL_UNIX_EPOCH_DAYS;
+                SheetColumn::Number(serial, Style::Datetime)
+            }
+            DataType::Time => {
+                let nanoseconds = series.cast(&DataType::Int64)?.cast(&DataType::Float64)?;
+                SheetColumn::Number(nanoseconds.f64()? / 86_400e9, Style::Time)
+            }
+            DataType::UInt64 => SheetColumn::UInt(series.u64()?.clone()),
+            dtype if dtype.is_integer() => {
+                let values = series.cast(&DataType::Int64)?;
+                SheetColumn::Int(values.i64()?.clone())
+            }
+            dtype if dtype.is_numeric() => {
+                let values = series.cast(&DataType::Float64)?;
+                SheetColumn::Number(values.f64()?.clone(), Style::General)
+            }
+            DataType::Binary => {
+                let hex = series
+                    .binary()?
+                    .into_iter()
+                    .map(|value| value.map(|value| format!("0x{}", hex::encode(value))))
+                    .collect::<StringChunked>();
+                SheetColumn::Text(hex)
+            }
+            DataType::String => SheetColumn::Text(series.str()?.clone()),
+            _ => {
+                let text = series.cast(&DataType::String)?;
+                SheetColumn::Text(text.str()?.clone())
+            }
+        };
+        Ok(column)
+    }
+
+    fn write_cell(&self, sheet: &mut dyn Write, reference: &str, i: usize) -> std::io::Result<()> {
+        match self {
+            SheetColumn::Int(values) => match values.get(i) {
+                Some(value) if value.unsigned_abs() < MAX_EXACT_INTEGER => {
+                    write_number_cell(sheet, reference, value)
+                }
+                Some(value) => write_text_cell(sheet, reference, &value.to_string()),
+                None => Ok(()),
+            },
+            SheetColumn::UInt(values) => match values.get(i) {
+                Some(value) if value < MAX_EXACT_INTEGER => {
+                    write_number_cell(sheet, reference, value)
+                }
+                Some(value) => write_text_cell(sheet, reference, &value.to_string()),
+                None => Ok(()),
+            },
+            SheetColumn::Number(values, style) => match values.get(i) {
+                Some(value) if value.is_finite() => {
+                    let style = match style {
+                        Style::General => String::new(),
+                        style => format!(r#" s="{}""#, *style as u8),
+                    };
+                    write!(
+                        sheet,
+                        r#"<c r="{}"{}><v>{}</v></c>"#,
+                        reference, style, value
+                    )
+                }
+                // sheets have no representation of nan or infinity
+                Some(value) => write_text_cell(sheet, reference, &value.to_string()),
+                None => Ok(()),
+            },
+            SheetColumn::Boolean(values) => match values.get(i) {
+                Some(value) => write!(
+                    sheet,
+                    r#"<c r="{}" t="b"><v>{}</v></c>"#,
+                    reference, value as u8
+                ),
+                None => Ok(()),
+            },
+            SheetColumn::Text(values) => match values.get(i) {
+                Some(value) => write_text_cell(sheet, reference, value),
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+fn write_number_cell(
+    sheet: &mut dyn Write,
+    reference: &str,
+    value: impl std::fmt::Display,
+) -> std::io::Result<()> {
+    write!(sheet, r#"<c r="{}"><v>{}</v></c>"#, reference, value)
+}
+
+fn write_text_cell(sheet: &mut dyn Write, reference: &str, value: &str) -> std::io::Result<()> {
+    let value: String = value.chars().take(XLSX_MAX_CELL_CHARS).collect();
+    write!(
+        sheet,
+        r#"<c r="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+        reference,
+        escape_xml(&value)
+    )
+}
+
+/// letters naming a zero-based column index, e.g. A, Z, AA
+fn column_letters(index: usize) -> String {
+    let mut letters = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        let remainder = (n - 1) % 26;
+        letters.push((b'A' + remainder as u8) as char);
+        n = (n - 1) / 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// escape text for xml, dropping control characters that xml cannot hold
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// zip archive written file by file, deflating each file as it is written
+///
+/// sizes and checksums of each file follow its data in a data descriptor, so that files can
+/// be streamed into the archive without knowing their size in advance
+struct ZipWriter<W: Write> {
+    writer: CountingWriter<W>,
+    central: Vec<u8>,
+    n_files: u16,
+}
+
+impl<W: Write> ZipWriter<W> {
+    fn new(writer: W) -> ZipWriter<W> {
+        ZipWriter {
+            writer: CountingWriter {
+                inner: writer,
+                n_bytes: 0,
+            },
+            central: Vec::new(),
+            n_files: 0,
+        }
+    }
+
+    /// add file whose contents are written by write_contents
+    fn add_file(
+        &mut self,
+        name: &str,
+        write_contents: impl FnOnce(&mut dyn Write) -> Result<(), TblError>,
+    ) -> Result<(), TblError> {
+        let too_large = || TblError::InputError("workbook exceeds 4 GB".to_string());
+        let offset = u32::try_from(self.writer.n_bytes).map_err(|_| too_large())?;
+
+        // version needed, flags with sizes in a data descriptor, deflate method, time, and
+        // date of 1980-01-01, followed by crc and sizes that are zero in the local header
+        let mut fields = Vec::new();
+        fields.extend_from_slice(&20u16.to_le_bytes());
+        fields.extend_from_slice(&0x08u16.to_le_bytes());
+        fields.extend_from_slice(&8u16.to_le_bytes());
+        fields.extend_from_slice(&0u16.to_le_bytes());
+        fields.extend_from_slice(&0x21u16.to_le_bytes());
+        let name_fields = |fields: &mut Vec<u8>| {
+            fields.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            fields.extend_from_slice(&0u16.to_le_bytes());
+        };
+
+        let mut local = Vec::new();
+        local.extend_from_slice(&0x04034b50u32.to_le_bytes());
+        local.extend_from_slice(&fields);
+        local.extend_from_slice(&[0u8; 12]);
+        name_fields(&mut local);
+        local.extend_from_slice(name.as_bytes());
+        self.writer.write_all(&local)?;
+
+        let data_start = self.writer.n_bytes;
+        let (crc, size) = {
+            let mut contents = ChecksumWriter {
+                inner: flate2::write::DeflateEncoder::new(
+                    &mut self.writer,
+                    flate2::Compression::default(),
+                ),
+                hasher: crc32fast::Hasher::new(),
+                n_bytes: 0,
+            };
+            write_contents(&mut contents)?;
+            contents.inner.finish()?;
+            (contents.hasher.finalize(), contents.n_bytes)
+        };
+        let size = u32::try_from(size).map_err(|_| too_large())?;
+        let compressed_size =
+            u32::try_from(self.writer.n_bytes - data_start).map_err(|_| too_large())?;
+        let mut sizes = Vec::new();
+        sizes.extend_from_slice(&crc.to_le_bytes());
+        sizes.extend_from_slice(&compressed_size.to_le_bytes());
+        sizes.extend_from_slice(&size.to_le_bytes());
+
+        let mut descriptor = 0x08074b50u32.to_le_bytes().to_vec();
+        descriptor.extend_from_slice(&sizes);
+        self.writer.write_all(&descriptor)?;
+
+        let central = &mut self.central;
+        central.extend_from_slice(&0x02014b50u32.to_le_bytes());
+        central.extend_from_slice(&20u16.to_le_bytes());
+        central.extend_from_slice(&fields);
+        central.extend_from_slice(&sizes);
+        name_fields(central);
+        // comment length, disk number, internal and external attributes, local header offset
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&0u32.to_le_bytes());
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+        self.n_files += 1;
+        Ok(())
+    }
+
+    /// write central directory, returning the underlying writer
+    fn finish(mut self) -> Result<W, TblError> {
+        let offset = u32::try_from(self.writer.n_bytes)
+            .map_err(|_| TblError::InputError("workbook exceeds 4 GB".to_string()))?;
+        self.writer.write_all(&self.central)?;
+
+        // end of central directory
+        let mut end = Vec::new();
+        end.extend_from_slice(&0x06054b50u32.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes());
+        end.extend_from_slice(&self.n_files.to_le_bytes());
+        end.extend_from_slice(&self.n_files.to_le_bytes());
+        end.extend_from_slice(&(self.central.len() as u32).to_le_bytes());
+        end.extend_from_slice(&offset.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes());
+        self.writer.write_all(&end)?;
+        Ok(self.writer.inner)
+    }
+}
+
+/// writer that counts the bytes written through it
+struct CountingWriter<W: Write> {
+    inner: W,
+    n_bytes: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n_written = self.inner.write(buf)?;
+        self.n_bytes += n_written as u64;
+        Ok(n_written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// writer that computes the crc and size of the uncompressed bytes written through it
+struct ChecksumWriter<W: Write> {
+    inner: W,
+    hasher: crc32fast::Hasher,
+    n_bytes: u64,
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n_written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n_written]);
+        self.n_bytes += n_written as u64;
+        Ok(n_written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+const CONTENT_TYPES: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+    r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+    r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+    r#"<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+    r#"<Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+    r#"<Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>"#,
+    r#"</Types>"#
+);
+
+const ROOT_RELS: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>"#,
+    r#"</Relationships>"#
+);
+
+const WORKBOOK_RELS: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>"#,
+    r#"<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>"#,
+    r#"</Relationships>"#
+);
+
+// cell formats in the order of Style
+const STYLES: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+    r#"<numFmts count="2">"#,
+    r#"<numFmt numFmtId="164" formatCode="yyyy-mm-dd hh:mm:ss"/>"#,
+    r#"<numFmt numFmtId="165" formatCode="hh:mm:ss"/>"#,
+    r#"</numFmts>"#,
+    r#"<fonts count="1"><font><sz val="11"/><name val="Calibri"/></font></fonts>"#,
+    r#"<fills count="1"><fill><patternFill patternType="none"/></fill></fills>"#,
+    r#"<borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders>"#,
+    r#"<cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs>"#,
+    r#"<cellXfs count="4">"#,
+    r#"<xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/>"#,
+    r#"<xf numFmtId="14" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/>"#,
+    r#"<xf numFmtId="164" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/>"#,
+    r#"<xf numFmtId="165" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/>"#,
+    r#"</cellXfs></styleSheet>"#
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_xlsx() -> Result<(), TblError> {
+        assert_eq!(column_letters(0), "A");
+        assert_eq!(column_letters(25), "Z");
+        assert_eq!(column_letters(26), "AA");
+        assert_eq!(column_letters(XLSX_MAX_COLUMNS - 1), "XFD");
+        assert!(validate_sheet_name("blocks").is_ok());
+        assert!(validate_sheet_name("a/b").is_err());
+        assert_eq!(default_sheet_name(Path::new("dir/a:b.xlsx")), "a_b");
+
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("data.xlsx");
+        let df = df!(
+            "block" => [1u32, 2],
+            "name" => [Some("a & b"), None],
+            "flag" => [true, false],
+        )?;
+        write_xlsx(&df, &path, "data")?;
+        let bytes = std::fs::read(&path)?;
+        assert_eq!(&bytes[..4], &0x04034b50u32.to_le_bytes());
+        let end = &bytes[bytes.len() - 22..];
+        assert_eq!(&end[..4], &0x06054b50u32.to_le_bytes());
+        // six parts are stored in the archive
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 6);
+
+        // integers beyond 15 digits are text, nulls are left empty, and text is escaped
+        let big = (1u64 << 60) + 1;
+        let df = df!(
+            "id" => [big, 2],
+            "delta" => [-3i32, 4],
+            "price" => [Some(1.5), None],
+            "name" => [Some("a & b"), None],
+            "flag" => [true, false],
+            "day" => [1i32, 1],
+        )?
+        .lazy()
+        .with_column(col("day").cast(DataType::Date))
+        .collect()?;
+        let mut sheet = Vec::new();
+        write_sheet_xml(&df, &sheet_columns(&df)?, &mut sheet)?;
+        let sheet = String::from_utf8(sheet).unwrap();
+        let rows: Vec<&str> = sheet
+            .split("<row ")
+            .skip(1)
+            .map(|row| row.trim_end_matches("</sheetData></worksheet>"))
+            .collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0]
+            .contains(r#"<c r="A1" t="inlineStr"><is><t xml:space="preserve">id</t></is></c>"#));
+        assert_eq!(
+            rows[1],
+            concat!(
+                r#"r="2"><c r="A2" t="inlineStr"><is><t xml:space="preserve">"#,
+                r#"1152921504606846977</t></is></c><c r="B2"><v>-3</v></c>"#,
+                r#"<c r="C2"><v>1.5</v></c>"#,
+                r#"<c r="D2" t="inlineStr"><is><t xml:space="preserve">a &amp; b</t></is></c>"#,
+                r#"<c r="E2" t="b"><v>1</v></c><c r="F2" s="1"><v>25570</v></c></row>"#,
+            )
+        );
+        assert_eq!(
+            rows[2],
+            concat!(
+                r#"r="3"><c r="A3"><v>2</v></c><c r="B3"><v>4</v></c>"#,
+                r#"<c r="E3" t="b"><v>0</v></c><c r="F3" s="1"><v>25570</v></c></row>"#,
+            )
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+//! python bindings for tbl
+
+#![warn(missing_docs, unreachable_pub, unused_crate_dependencies)]
+#![deny(unused_must_use, rust_2018_idioms)]
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::path::PathBuf;
+use tbl_core::TblError;
+
+create_exception!(pytbl, TblException, PyException, "error raised by tbl");
+
+fn to_py_err(e: TblError) -> PyErr {
+    TblException::new_err(e.to_string())
+}
+
+/// run future created by f on a new runtime, releasing the gil while it runs
+fn block_on<F, Fut, T>(py: Python<'_>, f: F) -> PyResult<T>
+where
+    F: FnOnce() -> Fut + Send,
+    Fut: std::future::Future<Output = Result<T, TblError>>,
+    T: Send,
+{
+    py.allow_threads(|| {
+        let runtime = tokio::runtime::Runtime::new()?;
+        runtime.block_on(f()).map_err(to_py_err)
+    })
+}
+
+/// find tabular files, expanding directories, optionally recursing into subdirectories
+#[pyfunction]
+#[pyo3(signature = (paths=None, tree=false))]
+fn get_input_paths(paths: Option<Vec<PathBuf>>, tree: bool) -> PyResult<Vec<PathBuf>> {
+    tbl_core::filesystem::get_input_paths(&paths, tree, true).map_err(to_py_err)
+}
+
+/// pair tabular inputs with the output paths that batch edits would write
+///
+/// without an output directory, each output path is its input path
+#[pyfunction]
+#[pyo3(signature = (paths=None, output_dir=None, tree=false, prefix=None, postfix=None, flat=false))]
+fn get_output_paths(
+    paths: Option<Vec<PathBuf>>,
+    output_dir: Option<PathBuf>,
+    tree: bool,
+    prefix: Option<String>,
+    postfix: Option<String>,
+    flat: bool,
+) -> PyResult<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let spec = tbl_core::filesystem::OutputPathSpec {
+        inputs: paths,
+        output_dir,
+        tree,
+        file_prefix: prefix,
+        file_postfix: postfix,
+        sort: true,
+        flat,
+    };
+    tbl_core::filesystem::get_output_paths(spec).map_err(to_py_err)
+}
+
+/// summarize each parquet file as a dict of row count, byte sizes, and schema
+#[pyfunction]
+fn summarize(py: Python<'_>, paths: Vec<PathBuf>) -> PyResult<Vec<Bound<'_, PyDict>>> {
+    let summaries = block_on(py, || tbl_core::parquet::get_parquet_summaries(&paths))?;
+    paths
+        .iter()
+        .zip(summaries.iter())
+        .map(|(path, summary)| {
+            let schema = PyDict::new(py);
+            for (name, dtype) in summary.schema.iter() {
+                schema.set_item(name.as_str(), dtype.to_string())?;
+            }
+            let dict = PyDict::new(py);
+            dict.set_item("path", path)?;
+            dict.set_item("n_rows", summary.n_rows)?;
+            dict.set_item("n_bytes_compressed", summary.n_bytes_compressed)?;
+            dict.set_item("n_bytes_uncompressed", summary.n_bytes_uncompressed)?;
+            dict.set_item("schema", schema)?;
+            Ok(dict)
+        })
+        .collect()
+}
+
+/// rewrite each input to its output without the given columns
+#[pyfunction]
+#[pyo3(signature = (inputs, outputs, columns, batch_size=8192, max_concurrent=16))]
+fn drop_columns(
+    py: Python<'_>,
+    inputs: Vec<PathBuf>,
+    outputs: Vec<PathBuf>,
+    columns: Vec<String>,
+    batch_size: usize,
+    max_concurrent: usize,
+) -> PyResult<()> {
+    check_io_lengths(&inputs, &outputs)?;
+    let io = inputs.into_iter().zip(outputs).collect();
+    block_on(py, || {
+        tbl_core::parquet::drop_parquets_columns(io, columns, batch_size, max_concurrent)
+    })
+}
+
+/// merge inputs with a shared schema into a single output file
+#[pyfunction]
+#[pyo3(signature = (inputs, output, batch_size=8192))]
+fn merge(py: Python<'_>, inputs: Vec<PathBuf>, output: PathBuf, batch_size: usize) -> PyResult<()> {
+    block_on(py, || {
+        tbl_core::parquet::merge_parquets(&inputs, &output, batch_size)
+    })
+}
+
+fn check_io_lengths(inputs: &[PathBuf], outputs: &[PathBuf]) -> PyResult<()> {
+    if inputs.len() != outputs.len() {
+        return Err(TblException::new_err(format!(
+            "got {} inputs but {} outputs",
+            inputs.len(),
+            outputs.len()
+        )));
+    }
+    Ok(())
+}
+
+/// python module of tbl functions
+#[pymodule]
+fn pytbl(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("TblException", m.py().get_type::<TblException>())?;
+    m.add_function(wrap_pyfunction!(get_input_paths, m)?)?;
+    m.add_function(wrap_pyfunction!(get_output_paths, m)?)?;
+    m.add_function(wrap_pyfunction!(summarize, m)?)?;
+    m.add_function(wrap_pyfunction!(drop_columns, m)?)?;
+    m.add_function(wrap_pyfunction!(merge, m)?)?;
+    Ok(())
+}
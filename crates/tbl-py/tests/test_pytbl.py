@@ -0,0 +1,85 @@
+"""smoke tests of the pytbl bindings, run with `python -m unittest discover tests` after
+`maturin develop`"""
+
+import os
+import tempfile
+import unittest
+
+import pytbl
+
+try:
+    import pyarrow
+    import pyarrow.parquet
+except ImportError:
+    pyarrow = None
+
+
+class TestPaths(unittest.TestCase):
+    def test_get_input_paths(self):
+        with tempfile.TemporaryDirectory() as root:
+            os.makedirs(os.path.join(root, 'sub'))
+            for name in ['b.parquet', 'a.parquet', os.path.join('sub', 'c.parquet')]:
+                open(os.path.join(root, name), 'wb').close()
+
+            paths = pytbl.get_input_paths([root])
+            self.assertEqual(
+                [os.path.basename(path) for path in paths], ['a.parquet', 'b.parquet']
+            )
+            paths = pytbl.get_input_paths([root], tree=True)
+            self.assertEqual(len(paths), 3)
+
+    def test_get_output_paths(self):
+        with tempfile.TemporaryDirectory() as root:
+            path = os.path.join(root, 'a.parquet')
+            open(path, 'wb').close()
+            output_dir = os.path.join(root, 'out')
+
+            inputs, outputs = pytbl.get_output_paths([path])
+            self.assertEqual(outputs, inputs)
+            inputs, outputs = pytbl.get_output_paths(
+                [path], output_dir=output_dir, prefix='new_'
+            )
+            self.assertEqual(outputs, [os.path.join(output_dir, 'new_a.parquet')])
+
+    def test_missing_path_raises(self):
+        with self.assertRaises(pytbl.TblException):
+            pytbl.get_input_paths(['/nonexistent/pytbl/path.parquet'])
+
+
+@unittest.skipIf(pyarrow is None, 'pyarrow is needed to write parquet files')
+class TestParquet(unittest.TestCase):
+    def setUp(self):
+        self.tempdir = tempfile.TemporaryDirectory()
+        self.root = self.tempdir.name
+        self.paths = []
+        for i in range(2):
+            table = pyarrow.table({'id': [2 * i, 2 * i + 1], 'name': ['x', 'y']})
+            path = os.path.join(self.root, 'part{}.parquet'.format(i))
+            pyarrow.parquet.write_table(table, path)
+            self.paths.append(path)
+
+    def tearDown(self):
+        self.tempdir.cleanup()
+
+    def test_summarize(self):
+        summaries = pytbl.summarize(self.paths)
+        self.assertEqual([summary['n_rows'] for summary in summaries], [2, 2])
+        self.assertEqual(list(summaries[0]['schema'].keys()), ['id', 'name'])
+
+    def test_drop_columns(self):
+        outputs = [os.path.join(self.root, 'dropped{}.parquet'.format(i)) for i in range(2)]
+        pytbl.drop_columns(self.paths, outputs, ['name'])
+        for output in outputs:
+            self.assertEqual(pyarrow.parquet.read_table(output).column_names, ['id'])
+        with self.assertRaises(pytbl.TblException):
+            pytbl.drop_columns(self.paths, outputs[:1], ['name'])
+
+    def test_merge(self):
+        output = os.path.join(self.root, 'merged.parquet')
+        pytbl.merge(self.paths, output)
+        table = pyarrow.parquet.read_table(output)
+        self.assertEqual(table.column('id').to_pylist(), [0, 1, 2, 3])
+
+
+if __name__ == '__main__':
+    unittest.main()